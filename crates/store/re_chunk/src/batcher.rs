@@ -1,4 +1,5 @@
 use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
     hash::{Hash as _, Hasher as _},
     sync::Arc,
     time::{Duration, Instant},
@@ -8,6 +9,7 @@ use arrow::array::{Array as ArrowArray, ArrayRef};
 use arrow::buffer::ScalarBuffer as ArrowScalarBuffer;
 use crossbeam::channel::{Receiver, Sender};
 use nohash_hasher::IntMap;
+use parking_lot::Mutex;
 
 use re_arrow_util::arrays_to_list_array_opt;
 use re_byte_size::SizeBytes as _;
@@ -154,6 +156,8 @@ pub struct ChunkBatcherConfig {
     ///
     /// Unbounded if left unspecified.
     /// Once a batcher is created, this property cannot be changed.
+    ///
+    /// What happens once it is full is decided by [`Self::backpressure`].
     pub max_commands_in_flight: Option<u64>,
 
     /// Size of the internal channel of [`Chunk`]s.
@@ -161,6 +165,37 @@ pub struct ChunkBatcherConfig {
     /// Unbounded if left unspecified.
     /// Once a batcher is created, this property cannot be changed.
     pub max_chunks_in_flight: Option<u64>,
+
+    /// What to do with new rows once [`Self::max_commands_in_flight`] rows are waiting to be
+    /// batched.
+    ///
+    /// Has no effect if [`Self::max_commands_in_flight`] is unbounded.
+    /// Once a batcher is created, this property cannot be changed.
+    pub backpressure: BackpressurePolicy,
+}
+
+/// What a [`ChunkBatcher`] does with new rows when it cannot keep up, see
+/// [`ChunkBatcherConfig::backpressure`].
+///
+/// This only applies to rows ([`ChunkBatcher::push_row`]), chunks always block.
+/// Dropped rows are counted per entity, see [`ChunkBatcher::dropped_rows`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BackpressurePolicy {
+    /// Block the logging thread until there is room.
+    #[default]
+    Block,
+
+    /// Drop the new row.
+    DropNewest,
+
+    /// Drop the oldest row waiting to be batched, to make room for the new one.
+    DropOldest,
+
+    /// Drop an older row of an entity that has newer rows waiting to be batched, so that the
+    /// latest row of every entity makes it through.
+    ///
+    /// Falls back to [`Self::DropOldest`] if every waiting row is the latest of its entity.
+    KeepLatestPerEntity,
 }
 
 impl Default for ChunkBatcherConfig {
@@ -178,6 +213,7 @@ impl ChunkBatcherConfig {
         chunk_max_rows_if_unsorted: 256,
         max_commands_in_flight: None,
         max_chunks_in_flight: None,
+        backpressure: BackpressurePolicy::Block,
     };
 
     /// Low-latency configuration, preferred when streaming directly to a viewer.
@@ -194,6 +230,7 @@ impl ChunkBatcherConfig {
         chunk_max_rows_if_unsorted: 256,
         max_commands_in_flight: None,
         max_chunks_in_flight: None,
+        backpressure: BackpressurePolicy::Block,
     };
 
     /// Never flushes unless manually told to (or hitting one the builtin invariants).
@@ -204,6 +241,7 @@ impl ChunkBatcherConfig {
         chunk_max_rows_if_unsorted: 256,
         max_commands_in_flight: None,
         max_chunks_in_flight: None,
+        backpressure: BackpressurePolicy::Block,
     };
 
     /// Environment variable to configure [`Self::flush_tick`].
@@ -394,6 +432,177 @@ struct ChunkBatcherInner {
     // NOTE: Option so we can make shutdown non-blocking even with bounded channels.
     rx_chunks: Option<Receiver<Chunk>>,
     cmds_to_chunks_handle: Option<std::thread::JoinHandle<()>>,
+
+    /// Where rows go instead of the command channel, if they must never block.
+    row_queue: Option<Arc<RowQueue>>,
+    dropped_rows: DroppedRows,
+}
+
+/// The rows waiting to be batched when using a non-blocking [`BackpressurePolicy`].
+///
+/// The batching thread drains it before handling any command, which preserves the ordering
+/// guarantees of the command channel.
+struct RowQueue {
+    policy: BackpressurePolicy,
+    capacity: usize,
+    rows: Mutex<QueuedRows>,
+}
+
+impl RowQueue {
+    /// Queues a new row, returning the entity path of the row that had to be dropped, if any.
+    fn push(&self, entity_path: EntityPath, row: PendingRow) -> Option<EntityPath> {
+        let mut rows = self.rows.lock();
+
+        let mut dropped = None;
+        if rows.len() >= self.capacity {
+            let entity_to_drop = match self.policy {
+                // NOTE: Blocking never goes through the queue in the first place.
+                BackpressurePolicy::Block | BackpressurePolicy::DropNewest => {
+                    return Some(entity_path);
+                }
+
+                BackpressurePolicy::DropOldest => rows.oldest_entity(),
+
+                BackpressurePolicy::KeepLatestPerEntity => rows
+                    .oldest_entity_with_newer_row(&entity_path)
+                    .or_else(|| rows.oldest_entity()),
+            };
+
+            dropped = entity_to_drop.and_then(|entity_path| rows.remove_oldest(&entity_path));
+        }
+
+        rows.push(entity_path, row);
+
+        dropped
+    }
+
+    fn take(&self) -> VecDeque<(EntityPath, PendingRow)> {
+        std::mem::take(&mut *self.rows.lock()).into_rows()
+    }
+}
+
+/// The rows of a [`RowQueue`], indexed so that picking the one to drop never requires going
+/// through all of them.
+#[derive(Default)]
+struct QueuedRows {
+    /// All the rows, keyed by the order in which they were queued.
+    rows: BTreeMap<u64, (EntityPath, PendingRow)>,
+    next_row_index: u64,
+
+    /// The keys of the rows of each entity, oldest first.
+    row_indices_per_entity: IntMap<EntityPath, VecDeque<u64>>,
+
+    /// The key of the oldest row of each entity that has more than one row.
+    oldest_row_index_with_newer_row: BTreeSet<u64>,
+}
+
+impl QueuedRows {
+    #[inline]
+    fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn push(&mut self, entity_path: EntityPath, row: PendingRow) {
+        let row_index = self.next_row_index;
+        self.next_row_index += 1;
+
+        let row_indices = self
+            .row_indices_per_entity
+            .entry(entity_path.clone())
+            .or_default();
+        row_indices.push_back(row_index);
+        if row_indices.len() == 2 {
+            self.oldest_row_index_with_newer_row.insert(row_indices[0]);
+        }
+
+        self.rows.insert(row_index, (entity_path, row));
+    }
+
+    /// The entity of the oldest row.
+    fn oldest_entity(&self) -> Option<EntityPath> {
+        self.rows
+            .first_key_value()
+            .map(|(_, (entity_path, _))| entity_path.clone())
+    }
+
+    /// The entity of the oldest row that has a newer row of the same entity, counting a row of
+    /// `new_entity_path` that is about to be queued.
+    fn oldest_entity_with_newer_row(&self, new_entity_path: &EntityPath) -> Option<EntityPath> {
+        let new_entity_oldest = self
+            .row_indices_per_entity
+            .get(new_entity_path)
+            .and_then(|row_indices| row_indices.front());
+
+        let row_index = match (
+            self.oldest_row_index_with_newer_row.first(),
+            new_entity_oldest,
+        ) {
+            (Some(lhs), Some(rhs)) => lhs.min(rhs),
+            (Some(row_index), None) | (None, Some(row_index)) => row_index,
+            (None, None) => return None,
+        };
+
+        self.rows
+            .get(row_index)
+            .map(|(entity_path, _)| entity_path.clone())
+    }
+
+    /// Removes the oldest row of the given entity, returning its entity path.
+    fn remove_oldest(&mut self, entity_path: &EntityPath) -> Option<EntityPath> {
+        let row_indices = self.row_indices_per_entity.get_mut(entity_path)?;
+        let row_index = row_indices.pop_front()?;
+
+        self.oldest_row_index_with_newer_row.remove(&row_index);
+        match row_indices.len() {
+            0 => {
+                self.row_indices_per_entity.remove(entity_path);
+            }
+            1 => {}
+            _ => {
+                self.oldest_row_index_with_newer_row.insert(row_indices[0]);
+            }
+        }
+
+        self.rows
+            .remove(&row_index)
+            .map(|(entity_path, _)| entity_path)
+    }
+
+    /// All the rows, in the order they were queued.
+    fn into_rows(self) -> VecDeque<(EntityPath, PendingRow)> {
+        self.rows.into_values().collect()
+    }
+}
+
+/// The number of rows dropped by a [`ChunkBatcher`] because of its [`BackpressurePolicy`], per
+/// entity.
+///
+/// This is a cheap handle that is shared with the batcher: it keeps counting.
+#[derive(Default, Clone)]
+pub struct DroppedRows(Arc<Mutex<IntMap<EntityPath, u64>>>);
+
+impl DroppedRows {
+    /// How many rows were dropped so far, per entity.
+    ///
+    /// Entities that never had rows dropped are not included.
+    pub fn counts(&self) -> IntMap<EntityPath, u64> {
+        self.0.lock().clone()
+    }
+
+    /// How many rows were dropped so far, across all entities.
+    pub fn total(&self) -> u64 {
+        self.0.lock().values().sum()
+    }
+
+    fn add(&self, entity_path: EntityPath) {
+        *self.0.lock().entry(entity_path).or_default() += 1;
+    }
+}
+
+impl std::fmt::Debug for DroppedRows {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("DroppedRows").field(&*self.0.lock()).finish()
+    }
 }
 
 impl Drop for ChunkBatcherInner {
@@ -418,6 +627,7 @@ impl Drop for ChunkBatcherInner {
 enum Command {
     AppendChunk(Chunk),
     AppendRow(EntityPath, PendingRow),
+    PopRows, // Wakes up the batching thread so that it drains the `RowQueue`.
     Flush { on_done: Sender<()> },
     UpdateConfig(ChunkBatcherConfig),
     Shutdown,
@@ -448,13 +658,23 @@ impl ChunkBatcher {
             None => crossbeam::channel::unbounded(),
         };
 
+        let row_queue = match (config.backpressure, config.max_commands_in_flight) {
+            (BackpressurePolicy::Block, _) | (_, None) => None,
+            (policy, Some(cap)) => Some(Arc::new(RowQueue {
+                policy,
+                capacity: cap as _,
+                rows: Default::default(),
+            })),
+        };
+
         let cmds_to_chunks_handle = {
             const NAME: &str = "ChunkBatcher::cmds_to_chunks";
             std::thread::Builder::new()
                 .name(NAME.into())
                 .spawn({
                     let config = config.clone();
-                    move || batching_thread(config, hooks, rx_cmd, tx_chunk)
+                    let row_queue = row_queue.clone();
+                    move || batching_thread(config, hooks, rx_cmd, row_queue, tx_chunk)
                 })
                 .map_err(|err| ChunkBatcherError::SpawnThread {
                     name: NAME,
@@ -468,6 +688,8 @@ impl ChunkBatcher {
             tx_cmds,
             rx_chunks: Some(rx_chunks),
             cmds_to_chunks_handle: Some(cmds_to_chunks_handle),
+            row_queue,
+            dropped_rows: DroppedRows::default(),
         };
 
         Ok(Self {
@@ -485,12 +707,23 @@ impl ChunkBatcher {
     ///
     /// This will computea the size of the row from the batching thread!
     ///
+    /// Depending on [`ChunkBatcherConfig::backpressure`], this might block or drop data if the
+    /// batcher cannot keep up.
+    ///
     /// See [`ChunkBatcher`] docs for ordering semantics and multithreading guarantees.
     #[inline]
     pub fn push_row(&self, entity_path: EntityPath, row: PendingRow) {
         self.inner.push_row(entity_path, row);
     }
 
+    /// The rows dropped so far because of [`ChunkBatcherConfig::backpressure`], per entity.
+    ///
+    /// The returned handle keeps counting.
+    #[inline]
+    pub fn dropped_rows(&self) -> DroppedRows {
+        self.inner.dropped_rows.clone()
+    }
+
     /// Initiates a flush of the pipeline and returns immediately.
     ///
     /// This does **not** wait for the flush to propagate (see [`Self::flush_blocking`]).
@@ -534,7 +767,18 @@ impl ChunkBatcherInner {
     }
 
     fn push_row(&self, entity_path: EntityPath, row: PendingRow) {
-        self.send_cmd(Command::AppendRow(entity_path, row));
+        let Some(row_queue) = self.row_queue.as_ref() else {
+            self.send_cmd(Command::AppendRow(entity_path, row));
+            return;
+        };
+
+        if let Some(dropped) = row_queue.push(entity_path, row) {
+            re_log::warn_once!("The batcher cannot keep up, dropping rows");
+            self.dropped_rows.add(dropped);
+        }
+
+        // NOTE: If the channel is full, the batching thread has wake ups pending already.
+        self.tx_cmds.try_send(Command::PopRows).ok();
     }
 
     fn flush_async(&self) {
@@ -570,6 +814,7 @@ fn batching_thread(
     mut config: ChunkBatcherConfig,
     hooks: BatcherHooks,
     rx_cmd: Receiver<Command>,
+    row_queue: Option<Arc<RowQueue>>,
     tx_chunk: Sender<Chunk>,
 ) {
     let mut rx_tick = crossbeam::channel::tick(config.flush_tick);
@@ -650,6 +895,35 @@ fn batching_thread(
         acc.reset();
     }
 
+    /// Returns `true` if the row triggered a flush.
+    fn do_append_row(
+        accs: &mut IntMap<EntityPath, Accumulator>,
+        hooks: &BatcherHooks,
+        config: &ChunkBatcherConfig,
+        tx_chunk: &Sender<Chunk>,
+        entity_path: EntityPath,
+        row: PendingRow,
+    ) -> bool {
+        let acc = accs
+            .entry(entity_path.clone())
+            .or_insert_with(|| Accumulator::new(entity_path));
+        do_push_row(acc, row);
+
+        if let Some(config) = hooks.on_insert.as_ref() {
+            config(&acc.pending_rows);
+        }
+
+        if acc.pending_rows.len() as u64 >= config.flush_num_rows {
+            do_flush_all(acc, tx_chunk, "rows", config.chunk_max_rows_if_unsorted);
+            true
+        } else if acc.pending_num_bytes >= config.flush_num_bytes {
+            do_flush_all(acc, tx_chunk, "bytes", config.chunk_max_rows_if_unsorted);
+            true
+        } else {
+            false
+        }
+    }
+
     re_log::trace!(
         "Flushing every: {:.2}s, {} rows, {}",
         config.flush_tick.as_secs_f64(),
@@ -675,6 +949,12 @@ fn batching_thread(
                     break;
                 };
 
+                // NOTE: Queued rows were pushed before this command was sent, so they always go first.
+                if let Some(row_queue) = row_queue.as_ref() {
+                    for (entity_path, row) in row_queue.take() {
+                        skip_next_tick |= do_append_row(&mut accs, &hooks, &config, &tx_chunk, entity_path, row);
+                    }
+                }
 
                 match cmd {
                     Command::AppendChunk(chunk) => {
@@ -692,23 +972,11 @@ fn batching_thread(
                         }
                     },
                     Command::AppendRow(entity_path, row) => {
-                        let acc = accs.entry(entity_path.clone())
-                            .or_insert_with(|| Accumulator::new(entity_path));
-                        do_push_row(acc, row);
-
-                        if let Some(config) = hooks.on_insert.as_ref() {
-                            config(&acc.pending_rows);
-                        }
-
-                        if acc.pending_rows.len() as u64 >= config.flush_num_rows {
-                            do_flush_all(acc, &tx_chunk, "rows", config.chunk_max_rows_if_unsorted);
-                            skip_next_tick = true;
-                        } else if acc.pending_num_bytes >= config.flush_num_bytes {
-                            do_flush_all(acc, &tx_chunk, "bytes", config.chunk_max_rows_if_unsorted);
-                            skip_next_tick = true;
-                        }
+                        skip_next_tick |= do_append_row(&mut accs, &hooks, &config, &tx_chunk, entity_path, row);
                     },
 
+                    Command::PopRows => {}, // The row queue was drained already.
+
                     Command::Flush{ on_done } => {
                         skip_next_tick = true;
                         for acc in accs.values_mut() {
@@ -724,6 +992,10 @@ fn batching_thread(
                             re_log::warn!("Cannot change max commands/chunks in flight after batcher has been created. Previous max commands/chunks: {:?}/{:?}, new max commands/chunks: {:?}/{:?}",
                                             config.max_commands_in_flight, config.max_chunks_in_flight, new_config.max_commands_in_flight, new_config.max_chunks_in_flight);
                         }
+                        if config.backpressure != new_config.backpressure {
+                            re_log::warn!("Cannot change backpressure policy after batcher has been created. Previous policy: {:?}, new policy: {:?}",
+                                            config.backpressure, new_config.backpressure);
+                        }
 
                         re_log::trace!("Updated batcher config: {:?}", new_config);
                         if let Some(on_config_change) = hooks.on_config_change.as_ref() {
@@ -752,6 +1024,11 @@ fn batching_thread(
     }
 
     drop(rx_cmd);
+    if let Some(row_queue) = row_queue.as_ref() {
+        for (entity_path, row) in row_queue.take() {
+            do_append_row(&mut accs, &hooks, &config, &tx_chunk, entity_path, row);
+        }
+    }
     for acc in accs.values_mut() {
         do_flush_all(
             acc,
@@ -1966,4 +2243,88 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn backpressure_policies() {
+        let [a, b, c] = ["a", "b", "c"].map(EntityPath::from);
+        let row = || PendingRow::new(TimePoint::default(), IntMap::default());
+
+        let queued = |policy, entity_paths: &[&EntityPath]| {
+            let queue = RowQueue {
+                policy,
+                capacity: 3,
+                rows: Default::default(),
+            };
+            let dropped = entity_paths
+                .iter()
+                .filter_map(|entity_path| queue.push((*entity_path).clone(), row()))
+                .collect::<Vec<_>>();
+            let queued = queue
+                .take()
+                .into_iter()
+                .map(|(entity_path, _)| entity_path)
+                .collect::<Vec<_>>();
+            (queued, dropped)
+        };
+
+        assert_eq!(
+            (vec![a.clone(), b.clone(), a.clone()], vec![c.clone()]),
+            queued(BackpressurePolicy::DropNewest, &[&a, &b, &a, &c]),
+        );
+        assert_eq!(
+            (vec![b.clone(), a.clone(), c.clone()], vec![a.clone()]),
+            queued(BackpressurePolicy::DropOldest, &[&a, &b, &a, &c]),
+        );
+
+        // `c` has no older row, the older `a` makes room for it.
+        assert_eq!(
+            (vec![b.clone(), a.clone(), c.clone()], vec![a.clone()]),
+            queued(BackpressurePolicy::KeepLatestPerEntity, &[&a, &b, &a, &c]),
+        );
+        // The new `b` replaces the older `b`, even though `a` is older still.
+        assert_eq!(
+            (vec![a.clone(), c.clone(), b.clone()], vec![b.clone()]),
+            queued(BackpressurePolicy::KeepLatestPerEntity, &[&a, &b, &c, &b]),
+        );
+        // Every row is the latest of its entity: fall back to dropping the oldest.
+        let d = EntityPath::from("d");
+        assert_eq!(
+            (vec![b.clone(), c.clone(), d.clone()], vec![a.clone()]),
+            queued(BackpressurePolicy::KeepLatestPerEntity, &[&a, &b, &c, &d]),
+        );
+        // Rows of other entities that have newer rows go first, if they are older.
+        assert_eq!(
+            (vec![a.clone(), b.clone(), b.clone()], vec![a.clone()]),
+            queued(BackpressurePolicy::KeepLatestPerEntity, &[&a, &a, &b, &b]),
+        );
+
+        // Same as checking the number of rows of every entity on every push, which is what the
+        // index is for.
+        let entity_paths = [&a, &b, &c, &d];
+        let pushed = (0..1000_usize)
+            .map(|i| entity_paths[(i * 7 + i / 5) % entity_paths.len()])
+            .collect::<Vec<_>>();
+        let mut expected_queued: VecDeque<EntityPath> = VecDeque::new();
+        let mut expected_dropped = Vec::new();
+        for &entity_path in &pushed {
+            if expected_queued.len() >= 3 {
+                let num_rows = |entity_path: &EntityPath| {
+                    expected_queued
+                        .iter()
+                        .filter(|queued| *queued == entity_path)
+                        .count()
+                };
+                let index = expected_queued
+                    .iter()
+                    .position(|queued| num_rows(queued) + usize::from(queued == entity_path) > 1)
+                    .unwrap_or(0);
+                expected_dropped.extend(expected_queued.remove(index));
+            }
+            expected_queued.push_back(entity_path.clone());
+        }
+        assert_eq!(
+            (Vec::from(expected_queued), expected_dropped),
+            queued(BackpressurePolicy::KeepLatestPerEntity, pushed.as_slice()),
+        );
+    }
 }
@@ -1,4 +1,7 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
 
 use ahash::HashMap;
 use anyhow::Context as _;
@@ -22,7 +25,7 @@ use re_types_core::{
     SerializationError, SerializedComponentColumn,
 };
 
-use crate::{ChunkId, RowId};
+use crate::{ChunkId, ChunkStatistics, RowId};
 
 // ---
 
@@ -185,6 +188,13 @@ pub struct Chunk {
     /// hot path (e.g. during garbage collection).
     pub(crate) heap_size_bytes: AtomicU64,
 
+    /// Per-component statistics, if they were computed.
+    ///
+    /// Accounted for in [`Self::heap_size_bytes`].
+    ///
+    /// See [`Self::compute_statistics`].
+    pub(crate) statistics: Option<Arc<ChunkStatistics>>,
+
     /// Is the chunk as a whole sorted by [`RowId`]?
     pub(crate) is_sorted: bool,
 
@@ -213,6 +223,7 @@ impl PartialEq for Chunk {
             id,
             entity_path,
             heap_size_bytes: _,
+            statistics: _,
             is_sorted,
             row_ids,
             timelines,
@@ -255,6 +266,7 @@ impl Chunk {
             id: _,
             entity_path,
             heap_size_bytes: _,
+            statistics: _,
             is_sorted: _,
             row_ids: _,
             timelines,
@@ -321,6 +333,7 @@ impl Chunk {
             id,
             entity_path,
             heap_size_bytes: _,
+            statistics: _,
             is_sorted,
             row_ids,
             timelines,
@@ -354,6 +367,7 @@ impl Clone for Chunk {
             id: self.id,
             entity_path: self.entity_path.clone(),
             heap_size_bytes: AtomicU64::new(self.heap_size_bytes.load(Ordering::Relaxed)),
+            statistics: self.statistics.clone(),
             is_sorted: self.is_sorted,
             row_ids: self.row_ids.clone(),
             timelines: self.timelines.clone(),
@@ -715,6 +729,7 @@ impl Chunk {
             id,
             entity_path,
             heap_size_bytes: AtomicU64::new(0),
+            statistics: Default::default(),
             is_sorted: false,
             row_ids,
             timelines,
@@ -810,6 +825,7 @@ impl Chunk {
             id,
             entity_path,
             heap_size_bytes: Default::default(),
+            statistics: Default::default(),
             is_sorted: true,
             row_ids: RowId::arrow_from_slice(&[]),
             timelines: Default::default(),
@@ -829,6 +845,15 @@ impl Chunk {
         list_array: ArrowListArray,
     ) -> ChunkResult<()> {
         self.components.insert(component_desc, list_array);
+        if self.statistics.is_some() {
+            // Statistics are accounted for in the heap size.
+            let heap_size_bytes = self.heap_size_bytes.load(Ordering::Relaxed);
+            self.heap_size_bytes.store(
+                heap_size_bytes.saturating_sub(self.statistics_heap_size_bytes()),
+                Ordering::Relaxed,
+            );
+            self.statistics = None;
+        }
         self.sanity_check()
     }
 
@@ -1113,6 +1138,7 @@ impl Chunk {
             id: _,
             entity_path: _, // not an actual column
             heap_size_bytes: _,
+            statistics: _,
             is_sorted: _,
             row_ids: _,
             timelines,
@@ -1375,6 +1401,7 @@ impl re_byte_size::SizeBytes for Chunk {
             id,
             entity_path,
             heap_size_bytes,
+            statistics,
             is_sorted,
             row_ids,
             timelines,
@@ -1386,6 +1413,9 @@ impl re_byte_size::SizeBytes for Chunk {
         if size_bytes == 0 {
             size_bytes = id.heap_size_bytes()
                 + entity_path.heap_size_bytes()
+                + statistics
+                    .as_deref()
+                    .map_or(0, |statistics| statistics.heap_size_bytes())
                 + is_sorted.heap_size_bytes()
                 + row_ids.heap_size_bytes()
                 + timelines.heap_size_bytes()
@@ -1428,6 +1458,7 @@ impl Chunk {
             id: _,
             entity_path: _,
             heap_size_bytes,
+            statistics: _,
             is_sorted,
             row_ids,
             timelines,
@@ -13,6 +13,7 @@ mod merge;
 mod range;
 mod shuffle;
 mod slice;
+mod statistics;
 mod transport;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -28,11 +29,12 @@ pub use self::iter::{
 };
 pub use self::latest_at::LatestAtQuery;
 pub use self::range::{RangeQuery, RangeQueryOptions};
+pub use self::statistics::{ChunkStatistics, ComponentStatistics, ValueBounds};
 
 #[cfg(not(target_arch = "wasm32"))]
 pub use self::batcher::{
-    BatcherFlushError, BatcherHooks, ChunkBatcher, ChunkBatcherConfig, ChunkBatcherError,
-    ChunkBatcherResult, PendingRow,
+    BackpressurePolicy, BatcherFlushError, BatcherHooks, ChunkBatcher, ChunkBatcherConfig,
+    ChunkBatcherError, ChunkBatcherResult, DroppedRows, PendingRow,
 };
 
 // Re-exports
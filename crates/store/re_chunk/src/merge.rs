@@ -159,6 +159,7 @@ impl Chunk {
             id: ChunkId::new(),
             entity_path: cl.entity_path.clone(),
             heap_size_bytes: Default::default(),
+            statistics: Default::default(),
             is_sorted,
             row_ids,
             timelines,
@@ -213,6 +213,7 @@ impl Chunk {
             id: _,
             entity_path: _,
             heap_size_bytes: _,
+            statistics: _,
             is_sorted: _,
             row_ids: _,
             timelines,
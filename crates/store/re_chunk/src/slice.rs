@@ -5,7 +5,7 @@ use arrow::array::{
 use itertools::Itertools as _;
 use nohash_hasher::IntSet;
 
-use re_log_types::TimelineName;
+use re_log_types::{AbsoluteTimeRange, TimelineName};
 use re_types_core::ComponentDescriptor;
 
 use crate::{Chunk, RowId, TimeColumn};
@@ -56,6 +56,7 @@ impl Chunk {
             id,
             entity_path,
             heap_size_bytes: _,
+            statistics: _,
             is_sorted,
             row_ids,
             timelines,
@@ -82,6 +83,7 @@ impl Chunk {
             id: *id,
             entity_path: entity_path.clone(),
             heap_size_bytes: Default::default(),
+            statistics: Default::default(),
             is_sorted,
             row_ids: row_ids.clone().slice(index, len),
             timelines: timelines
@@ -140,6 +142,7 @@ impl Chunk {
             id,
             entity_path,
             heap_size_bytes: _,
+            statistics: _,
             is_sorted,
             row_ids,
             timelines,
@@ -150,6 +153,7 @@ impl Chunk {
             id: *id,
             entity_path: entity_path.clone(),
             heap_size_bytes: Default::default(),
+            statistics: Default::default(),
             is_sorted: *is_sorted,
             row_ids: row_ids.clone(),
             timelines: timelines
@@ -183,6 +187,7 @@ impl Chunk {
             id,
             entity_path,
             heap_size_bytes: _,
+            statistics: _,
             is_sorted,
             row_ids,
             timelines,
@@ -193,6 +198,7 @@ impl Chunk {
             id: *id,
             entity_path: entity_path.clone(),
             heap_size_bytes: Default::default(),
+            statistics: Default::default(),
             is_sorted: *is_sorted,
             row_ids: row_ids.clone(),
             timelines: timelines.clone(),
@@ -228,6 +234,7 @@ impl Chunk {
             id,
             entity_path,
             heap_size_bytes: _,
+            statistics: _,
             is_sorted,
             row_ids,
             timelines,
@@ -238,6 +245,7 @@ impl Chunk {
             id: *id,
             entity_path: entity_path.clone(),
             heap_size_bytes: Default::default(),
+            statistics: Default::default(),
             is_sorted: *is_sorted,
             row_ids: row_ids.clone(),
             timelines: timelines
@@ -255,6 +263,89 @@ impl Chunk {
         chunk
     }
 
+    /// Slices the [`Chunk`] horizontally by keeping only the selected components.
+    ///
+    /// The result is a new [`Chunk`] with the same rows and (at-most) the selected component columns.
+    /// All non-component columns will be kept as-is.
+    ///
+    /// If none of the selected components exist in the [`Chunk`], the end result will be the same as the
+    /// current chunk but without any component column.
+    ///
+    /// WARNING: the returned chunk has the same old [`crate::ChunkId`]! Change it with [`Self::with_id`].
+    #[must_use]
+    #[inline]
+    pub fn components_sliced(&self, components_to_keep: &IntSet<ComponentDescriptor>) -> Self {
+        let Self {
+            id,
+            entity_path,
+            heap_size_bytes: _,
+            statistics: _,
+            is_sorted,
+            row_ids,
+            timelines,
+            components,
+        } = self;
+
+        let chunk = Self {
+            id: *id,
+            entity_path: entity_path.clone(),
+            heap_size_bytes: Default::default(),
+            statistics: Default::default(),
+            is_sorted: *is_sorted,
+            row_ids: row_ids.clone(),
+            timelines: timelines.clone(),
+            components: components
+                .iter()
+                .filter(|(component_descr, _)| components_to_keep.contains(component_descr))
+                .map(|(component_descr, list_array)| (component_descr.clone(), list_array.clone()))
+                .collect(),
+        };
+
+        #[cfg(debug_assertions)]
+        #[allow(clippy::unwrap_used)] // debug-only
+        chunk.sanity_check().unwrap();
+
+        chunk
+    }
+
+    /// Slices the [`Chunk`] vertically by keeping only the rows whose index value on `timeline`
+    /// falls within `range`.
+    ///
+    /// The result is a new [`Chunk`] with the same columns and (potentially) less rows, sorted
+    /// on `timeline`.
+    ///
+    /// If `timeline` is not found within the [`Chunk`] (e.g. static chunks), the end result will
+    /// be empty.
+    ///
+    /// WARNING: the returned chunk has the same old [`crate::ChunkId`]! Change it with [`Self::with_id`].
+    #[must_use]
+    #[inline]
+    pub fn time_range_sliced(&self, timeline: &TimelineName, range: AbsoluteTimeRange) -> Self {
+        let Some(time_column) = self.timelines.get(timeline) else {
+            return self.emptied();
+        };
+
+        let chunk_range = time_column.time_range();
+        if range.contains_range(chunk_range) {
+            return self.clone();
+        }
+        if !range.intersects(chunk_range) {
+            return self.emptied();
+        }
+
+        re_tracing::profile_function!();
+
+        let chunk = self.sorted_by_timeline_if_unsorted(timeline);
+
+        #[allow(clippy::unwrap_used)] // We already know the chunk has the timeline
+        let times = chunk.timelines.get(timeline).unwrap().times_raw();
+
+        let min_idx = times.partition_point(|&time| time < range.min().as_i64());
+        let max_idx = times.partition_point(|&time| time <= range.max().as_i64());
+
+        chunk.row_sliced(min_idx, max_idx.saturating_sub(min_idx))
+    }
+
     /// Densifies the [`Chunk`] vertically based on the `component_descriptor` column.
     ///
     /// Densifying here means dropping all rows where the associated value in the `component_descriptor`
@@ -273,6 +364,7 @@ impl Chunk {
             id,
             entity_path,
             heap_size_bytes: _,
+            statistics: _,
             is_sorted,
             row_ids,
             timelines,
@@ -301,6 +393,7 @@ impl Chunk {
             id: *id,
             entity_path: entity_path.clone(),
             heap_size_bytes: Default::default(),
+            statistics: Default::default(),
             is_sorted,
             row_ids: re_arrow_util::filter_array(row_ids, &validity_filter),
             timelines: timelines
@@ -363,6 +456,7 @@ impl Chunk {
             id,
             entity_path,
             heap_size_bytes: _,
+            statistics: _,
             is_sorted: _,
             row_ids: _,
             timelines,
@@ -375,6 +469,7 @@ impl Chunk {
             id: *id,
             entity_path: entity_path.clone(),
             heap_size_bytes: Default::default(),
+            statistics: Default::default(),
             is_sorted: true,
             row_ids: RowId::arrow_from_slice(&[]),
             timelines: timelines
@@ -407,6 +502,7 @@ impl Chunk {
             id,
             entity_path,
             heap_size_bytes: _,
+            statistics: _,
             is_sorted,
             row_ids,
             timelines,
@@ -417,6 +513,7 @@ impl Chunk {
             id,
             entity_path,
             heap_size_bytes: Default::default(), // (!) lazily recompute
+            statistics: Default::default(),
             is_sorted,
             row_ids,
             timelines,
@@ -472,6 +569,7 @@ impl Chunk {
             id: self.id,
             entity_path: self.entity_path.clone(),
             heap_size_bytes: Default::default(),
+            statistics: Default::default(),
             is_sorted: self.is_sorted,
             row_ids: re_arrow_util::take_array(
                 &self.row_ids,
@@ -520,6 +618,7 @@ impl Chunk {
             id,
             entity_path,
             heap_size_bytes: _,
+            statistics: _,
             is_sorted,
             row_ids,
             timelines,
@@ -548,6 +647,7 @@ impl Chunk {
             id: *id,
             entity_path: entity_path.clone(),
             heap_size_bytes: Default::default(),
+            statistics: Default::default(),
             is_sorted,
             row_ids: re_arrow_util::filter_array(row_ids, filter),
             timelines: timelines
@@ -606,6 +706,7 @@ impl Chunk {
             id,
             entity_path,
             heap_size_bytes: _,
+            statistics: _,
             is_sorted,
             row_ids,
             timelines,
@@ -628,6 +729,7 @@ impl Chunk {
             id: *id,
             entity_path: entity_path.clone(),
             heap_size_bytes: Default::default(),
+            statistics: Default::default(),
             is_sorted,
             row_ids: re_arrow_util::take_array(
                 row_ids,
@@ -1497,4 +1599,174 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn components_sliced() -> anyhow::Result<()> {
+        let entity_path = "my/entity";
+
+        let row_id1 = RowId::new();
+        let row_id2 = RowId::new();
+
+        let timepoint1 = [(Timeline::new_sequence("frame"), 1)];
+        let timepoint2 = [(Timeline::new_sequence("frame"), 2)];
+
+        let points1 = &[MyPoint::new(1.0, 1.0)];
+        let colors2 = &[MyColor::from_rgb(1, 1, 1)];
+        let labels1 = &[MyLabel("a".into())];
+        let labels2 = &[MyLabel("b".into())];
+
+        let chunk = Chunk::builder(entity_path)
+            .with_sparse_component_batches(
+                row_id1,
+                timepoint1,
+                [
+                    (MyPoints::descriptor_points(), Some(points1 as _)),
+                    (MyPoints::descriptor_colors(), None),
+                    (MyPoints::descriptor_labels(), Some(labels1 as _)),
+                ],
+            )
+            .with_sparse_component_batches(
+                row_id2,
+                timepoint2,
+                [
+                    (MyPoints::descriptor_points(), None),
+                    (MyPoints::descriptor_colors(), Some(colors2 as _)),
+                    (MyPoints::descriptor_labels(), Some(labels2 as _)),
+                ],
+            )
+            .build()?;
+
+        eprintln!("chunk:\n{chunk}");
+
+        // basic
+        {
+            let components_to_keep = [MyPoints::descriptor_points(), MyPoints::descriptor_labels()]
+                .into_iter()
+                .collect();
+            let got = chunk.components_sliced(&components_to_keep);
+            eprintln!("got:\n{got}");
+
+            assert_eq!(chunk.num_rows(), got.num_rows());
+            assert_eq!(chunk.timelines(), got.timelines());
+            assert_eq!(
+                components_to_keep,
+                got.component_descriptors().collect::<IntSet<_>>()
+            );
+
+            let expectations: &[(_, _, Option<&dyn re_types_core::ComponentBatch>)] = &[
+                (row_id1, MyPoints::descriptor_points(), Some(points1 as _)),
+                (row_id1, MyPoints::descriptor_labels(), Some(labels1 as _)),
+                (row_id2, MyPoints::descriptor_points(), None),
+                (row_id2, MyPoints::descriptor_labels(), Some(labels2 as _)),
+            ];
+
+            for (row_id, component_desc, expected) in expectations {
+                let expected = expected
+                    .and_then(|expected| re_types_core::ComponentBatch::to_arrow(expected).ok());
+                eprintln!("{component_desc} @ {row_id}");
+                similar_asserts::assert_eq!(expected, got.cell(*row_id, component_desc));
+            }
+            assert!(got.cell(row_id2, &MyPoints::descriptor_colors()).is_none());
+        }
+
+        // unknown components
+        {
+            let components_to_keep =
+                std::iter::once(ComponentDescriptor::partial("unknown")).collect();
+            let got = chunk.components_sliced(&components_to_keep);
+            eprintln!("got:\n{got}");
+
+            assert_eq!(chunk.num_rows(), got.num_rows());
+            assert_eq!(0, got.num_components());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn time_range_sliced() -> anyhow::Result<()> {
+        let entity_path = "my/entity";
+        let frame = Timeline::new_sequence("frame");
+
+        let row_ids = std::iter::repeat_with(RowId::new).take(5).collect_vec();
+        let labels = ["a", "b", "c", "d", "e"].map(|label| [MyLabel(label.into())]);
+
+        // Deliberately unsorted on `frame`.
+        let frames = [5_i64, 1, 9, 3, 7];
+
+        let mut builder = Chunk::builder(entity_path);
+        for ((row_id, frame_nr), labels) in row_ids.iter().zip(frames).zip(&labels) {
+            builder = builder.with_component_batches(
+                *row_id,
+                [(frame, frame_nr), (Timeline::log_time(), 1000 + frame_nr)],
+                [(
+                    MyPoints::descriptor_labels(),
+                    labels as &dyn re_types_core::ComponentBatch,
+                )],
+            );
+        }
+        let chunk = builder.build()?;
+
+        eprintln!("chunk:\n{chunk}");
+
+        let frames_of = |chunk: &Chunk| -> Vec<i64> {
+            chunk
+                .timelines()
+                .get(frame.name())
+                .map(|column| column.times_raw().to_vec())
+                .unwrap_or_default()
+        };
+
+        // basic
+        {
+            let got = chunk.time_range_sliced(frame.name(), AbsoluteTimeRange::new(3, 7));
+            eprintln!("got:\n{got}");
+
+            assert_eq!(vec![3, 5, 7], frames_of(&got));
+            assert_eq!(
+                vec![row_ids[3], row_ids[0], row_ids[4]],
+                got.row_ids().collect_vec()
+            );
+
+            // The other timelines and the components follow along.
+            assert_eq!(
+                &[1003, 1005, 1007],
+                got.timelines()
+                    .get(&TimelineName::log_time())
+                    .unwrap()
+                    .times_raw()
+            );
+            similar_asserts::assert_eq!(
+                re_types_core::ComponentBatch::to_arrow(
+                    &labels[0] as &dyn re_types_core::ComponentBatch
+                )
+                .ok(),
+                got.cell(row_ids[0], &MyPoints::descriptor_labels())
+            );
+        }
+
+        // everything
+        {
+            let got = chunk.time_range_sliced(frame.name(), AbsoluteTimeRange::EVERYTHING);
+            assert_eq!(chunk, got);
+        }
+
+        // nothing
+        {
+            let got = chunk.time_range_sliced(frame.name(), AbsoluteTimeRange::new(10, 20));
+            assert!(got.is_empty());
+
+            let got = chunk.time_range_sliced(frame.name(), AbsoluteTimeRange::new(2, 2));
+            assert!(got.is_empty());
+        }
+
+        // unknown timeline
+        {
+            let got = chunk
+                .time_range_sliced(&TimelineName::new("unknown"), AbsoluteTimeRange::EVERYTHING);
+            assert!(got.is_empty());
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,496 @@
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, atomic::Ordering},
+};
+
+use arrow::{
+    array::{Array as _, ArrayRef as ArrowArrayRef, AsArray as _, ListArray as ArrowListArray},
+    datatypes::{DataType as ArrowDataType, Float64Type as ArrowFloat64Type, Int64Type},
+};
+use itertools::Itertools as _;
+use nohash_hasher::IntMap;
+
+use re_byte_size::SizeBytes as _;
+use re_sorbet::ArrowFieldMetadata;
+use re_types_core::ComponentDescriptor;
+
+use crate::Chunk;
+
+// ---
+
+/// Per-component statistics about the data of a [`Chunk`].
+///
+/// See [`Chunk::compute_statistics`].
+pub type ChunkStatistics = IntMap<ComponentDescriptor, ComponentStatistics>;
+
+/// Statistics about the data of a single component column.
+///
+/// These are what allow value-based filters to skip entire chunks without looking at their data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentStatistics {
+    /// How many rows have no data for this component.
+    pub null_count: u64,
+
+    /// The smallest and largest values in the column, if it holds comparable scalars.
+    ///
+    /// Nulls and NaNs are ignored. `None` if there are no such values.
+    pub bounds: Option<ValueBounds>,
+
+    /// All the distinct values of a string column, as long as there are at most
+    /// [`Self::MAX_DISTINCT_VALUES`] of them, none of which is longer than
+    /// [`Self::MAX_STRING_LEN`].
+    pub distinct_values: Option<BTreeSet<String>>,
+}
+
+/// The smallest and largest values in a column.
+///
+/// All integer types are widened to `i64`, and all floating point types to `f64`.
+///
+/// String bounds are kept short (see [`ComponentStatistics::MAX_STRING_LEN`]): the minimum gets
+/// truncated, which still makes for a valid lower bound, while columns whose maximum is too long
+/// to be kept have no bounds at all.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueBounds {
+    Bool { min: bool, max: bool },
+    Int { min: i64, max: i64 },
+    Float { min: f64, max: f64 },
+    String { min: String, max: String },
+}
+
+impl ComponentStatistics {
+    /// String columns with more distinct values than this only keep track of their bounds.
+    pub const MAX_DISTINCT_VALUES: usize = 32;
+
+    /// The longest string, in bytes, that statistics keep track of.
+    ///
+    /// This bounds the size of the statistics, no matter what the data looks like.
+    pub const MAX_STRING_LEN: usize = 64;
+
+    const METADATA_KEY_NULL_COUNT: &'static str = "rerun:stats:null_count";
+    const METADATA_KEY_BOUNDS_TYPE: &'static str = "rerun:stats:type";
+    const METADATA_KEY_MIN: &'static str = "rerun:stats:min";
+    const METADATA_KEY_MAX: &'static str = "rerun:stats:max";
+    const METADATA_KEY_DISTINCT_VALUES: &'static str = "rerun:stats:distinct_values";
+
+    /// Computes the statistics of a sparse component column.
+    pub fn from_list_array(list_array: &ArrowListArray) -> Self {
+        re_tracing::profile_function!();
+
+        // NOTE: The list array might be a slice of a larger one: only look at the values
+        // that are actually referenced.
+        let offsets = list_array.value_offsets();
+        let (start, end) = match (offsets.first(), offsets.last()) {
+            (Some(&start), Some(&end)) => (start as usize, end as usize),
+            _ => (0, 0),
+        };
+        let values = list_array.values().slice(start, end - start);
+
+        let (bounds, distinct_values) = value_statistics(&values);
+
+        Self {
+            null_count: list_array.null_count() as u64,
+            bounds,
+            distinct_values,
+        }
+    }
+
+    /// The number of distinct values in the column, if known.
+    ///
+    /// See [`Self::distinct_values`].
+    #[inline]
+    pub fn num_distinct_values(&self) -> Option<usize> {
+        self.distinct_values.as_ref().map(|values| values.len())
+    }
+
+    /// Encodes these statistics as Arrow field metadata, for transport.
+    pub fn to_arrow_metadata(&self) -> ArrowFieldMetadata {
+        let Self {
+            null_count,
+            bounds,
+            distinct_values,
+        } = self;
+
+        let mut metadata = ArrowFieldMetadata::from([(
+            Self::METADATA_KEY_NULL_COUNT.to_owned(),
+            null_count.to_string(),
+        )]);
+
+        if let Some(bounds) = bounds {
+            let (bounds_type, min, max) = match bounds {
+                ValueBounds::Bool { min, max } => ("bool", min.to_string(), max.to_string()),
+                ValueBounds::Int { min, max } => ("int", min.to_string(), max.to_string()),
+                ValueBounds::Float { min, max } => ("float", min.to_string(), max.to_string()),
+                ValueBounds::String { min, max } => ("string", min.clone(), max.clone()),
+            };
+
+            metadata.insert(
+                Self::METADATA_KEY_BOUNDS_TYPE.to_owned(),
+                bounds_type.to_owned(),
+            );
+            metadata.insert(Self::METADATA_KEY_MIN.to_owned(), min);
+            metadata.insert(Self::METADATA_KEY_MAX.to_owned(), max);
+        }
+
+        if let Some(distinct_values) = distinct_values {
+            // Every value is terminated by a newline, so that an empty set and a set containing
+            // only the empty string can be told apart.
+            let encoded = distinct_values
+                .iter()
+                .map(|value| format!("{}\n", escape_newlines(value)))
+                .collect();
+            metadata.insert(Self::METADATA_KEY_DISTINCT_VALUES.to_owned(), encoded);
+        }
+
+        metadata
+    }
+
+    /// Does this field metadata contain statistics encoded with [`Self::to_arrow_metadata`]?
+    pub(crate) fn is_in_arrow_metadata(metadata: &ArrowFieldMetadata) -> bool {
+        metadata.contains_key(Self::METADATA_KEY_NULL_COUNT)
+    }
+
+    /// Decodes statistics that were encoded with [`Self::to_arrow_metadata`].
+    ///
+    /// Returns `None` if the metadata doesn't contain any valid statistics.
+    ///
+    /// Nothing guarantees that these describe the data they come with: [`Chunk::from_record_batch`]
+    /// recomputes statistics from the data rather than trusting the ones it received.
+    pub fn from_arrow_metadata(metadata: &ArrowFieldMetadata) -> Option<Self> {
+        let null_count = metadata.get(Self::METADATA_KEY_NULL_COUNT)?.parse().ok()?;
+
+        let bounds = if let Some(bounds_type) = metadata.get(Self::METADATA_KEY_BOUNDS_TYPE) {
+            let min = metadata.get(Self::METADATA_KEY_MIN)?;
+            let max = metadata.get(Self::METADATA_KEY_MAX)?;
+
+            Some(match bounds_type.as_str() {
+                "bool" => ValueBounds::Bool {
+                    min: min.parse().ok()?,
+                    max: max.parse().ok()?,
+                },
+                "int" => ValueBounds::Int {
+                    min: min.parse().ok()?,
+                    max: max.parse().ok()?,
+                },
+                "float" => ValueBounds::Float {
+                    min: min.parse().ok()?,
+                    max: max.parse().ok()?,
+                },
+                "string" => ValueBounds::String {
+                    min: min.clone(),
+                    max: max.clone(),
+                },
+                _ => return None,
+            })
+        } else {
+            None
+        };
+
+        let distinct_values = metadata
+            .get(Self::METADATA_KEY_DISTINCT_VALUES)
+            .map(|encoded| {
+                encoded
+                    .split_terminator('\n')
+                    .map(unescape_newlines)
+                    .collect()
+            });
+
+        Some(Self {
+            null_count,
+            bounds,
+            distinct_values,
+        })
+    }
+}
+
+/// Computes the bounds and, for strings, the distinct values of a flat array of values.
+fn value_statistics(values: &ArrowArrayRef) -> (Option<ValueBounds>, Option<BTreeSet<String>>) {
+    let datatype = values.data_type();
+
+    // Integers are widened the same way value filters do, see `re_dataframe`.
+    if datatype.is_integer() {
+        let Ok(values) = arrow::compute::cast(values, &ArrowDataType::Int64) else {
+            return (None, None);
+        };
+        let bounds = values
+            .as_primitive::<Int64Type>()
+            .iter()
+            .flatten()
+            .minmax()
+            .into_option()
+            .map(|(min, max)| ValueBounds::Int { min, max });
+        return (bounds, None);
+    }
+
+    if datatype.is_floating() {
+        let Ok(values) = arrow::compute::cast(values, &ArrowDataType::Float64) else {
+            return (None, None);
+        };
+        let bounds = values
+            .as_primitive::<ArrowFloat64Type>()
+            .iter()
+            .flatten()
+            .filter(|value| !value.is_nan())
+            .minmax_by(f64::total_cmp)
+            .into_option()
+            .map(|(min, max)| ValueBounds::Float { min, max });
+        return (bounds, None);
+    }
+
+    match datatype {
+        ArrowDataType::Boolean => {
+            let bounds = values
+                .as_boolean()
+                .iter()
+                .flatten()
+                .minmax()
+                .into_option()
+                .map(|(min, max)| ValueBounds::Bool { min, max });
+            (bounds, None)
+        }
+
+        ArrowDataType::Utf8 => string_statistics(values.as_string::<i32>().iter()),
+        ArrowDataType::LargeUtf8 => string_statistics(values.as_string::<i64>().iter()),
+        ArrowDataType::Utf8View => string_statistics(values.as_string_view().iter()),
+
+        _ => (None, None),
+    }
+}
+
+fn string_statistics<'a>(
+    values: impl Iterator<Item = Option<&'a str>>,
+) -> (Option<ValueBounds>, Option<BTreeSet<String>>) {
+    let mut bounds: Option<(&str, &str)> = None;
+    let mut distinct_values = Some(BTreeSet::new());
+
+    for value in values.flatten() {
+        bounds = Some(match bounds {
+            Some((min, max)) => (min.min(value), max.max(value)),
+            None => (value, value),
+        });
+
+        if let Some(values) = distinct_values.as_mut() {
+            values.insert(value);
+            if values.len() > ComponentStatistics::MAX_DISTINCT_VALUES
+                || value.len() > ComponentStatistics::MAX_STRING_LEN
+            {
+                distinct_values = None;
+            }
+        }
+    }
+
+    // A prefix of the minimum is still a lower bound, but there is no such thing for the maximum.
+    let bounds = bounds
+        .filter(|(_, max)| max.len() <= ComponentStatistics::MAX_STRING_LEN)
+        .map(|(min, max)| ValueBounds::String {
+            min: truncate_str(min, ComponentStatistics::MAX_STRING_LEN).to_owned(),
+            max: max.to_owned(),
+        });
+
+    (
+        bounds,
+        distinct_values.map(|values| values.into_iter().map(ToOwned::to_owned).collect()),
+    )
+}
+
+/// The longest prefix of `value` that is at most `max_len` bytes long.
+fn truncate_str(value: &str, max_len: usize) -> &str {
+    let mut len = value.len().min(max_len);
+    while !value.is_char_boundary(len) {
+        len -= 1;
+    }
+    &value[..len]
+}
+
+fn escape_newlines(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape_newlines(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => unescaped.push('\n'),
+                Some(c) => unescaped.push(c),
+                None => unescaped.push('\\'),
+            }
+        } else {
+            unescaped.push(c);
+        }
+    }
+
+    unescaped
+}
+
+impl re_byte_size::SizeBytes for ComponentStatistics {
+    #[inline]
+    fn heap_size_bytes(&self) -> u64 {
+        let Self {
+            null_count: _,
+            bounds,
+            distinct_values,
+        } = self;
+
+        bounds.heap_size_bytes() + distinct_values.heap_size_bytes()
+    }
+}
+
+impl re_byte_size::SizeBytes for ValueBounds {
+    #[inline]
+    fn heap_size_bytes(&self) -> u64 {
+        match self {
+            Self::Bool { .. } | Self::Int { .. } | Self::Float { .. } => 0,
+            Self::String { min, max } => min.heap_size_bytes() + max.heap_size_bytes(),
+        }
+    }
+}
+
+impl Chunk {
+    /// Computes per-component statistics about the data in this chunk, unless that was already
+    /// done.
+    ///
+    /// This is opt-in, since it requires going through all the data: see e.g.
+    /// `ChunkStoreConfig::compute_statistics`. Statistics are accounted for in
+    /// [`re_byte_size::SizeBytes::heap_size_bytes`], and are preserved through transport (see
+    /// [`Self::to_record_batch`]).
+    ///
+    /// Chunks derived from this one (slices, filters, etc.) start with no statistics.
+    pub fn compute_statistics(&mut self) {
+        if self.statistics.is_some() {
+            return;
+        }
+
+        re_tracing::profile_function!();
+
+        let statistics: ChunkStatistics = self
+            .components
+            .iter()
+            .map(|(component_desc, list_array)| {
+                (
+                    component_desc.clone(),
+                    ComponentStatistics::from_list_array(list_array),
+                )
+            })
+            .collect();
+
+        // Keep the cached size, if any, up to date.
+        let heap_size_bytes = self.heap_size_bytes.load(Ordering::Relaxed);
+        if heap_size_bytes != 0 {
+            self.heap_size_bytes.store(
+                heap_size_bytes + statistics.heap_size_bytes(),
+                Ordering::Relaxed,
+            );
+        }
+
+        self.statistics = Some(Arc::new(statistics));
+    }
+
+    /// Per-component statistics about the data in this chunk, if they were computed.
+    ///
+    /// See [`Self::compute_statistics`].
+    #[inline]
+    pub fn statistics(&self) -> Option<&ChunkStatistics> {
+        self.statistics.as_deref()
+    }
+
+    /// How much of [`re_byte_size::SizeBytes::heap_size_bytes`] is taken by [`Self::statistics`].
+    #[inline]
+    pub(crate) fn statistics_heap_size_bytes(&self) -> u64 {
+        self.statistics()
+            .map_or(0, |statistics| statistics.heap_size_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Float64Array, StringArray};
+
+    use super::*;
+
+    #[test]
+    fn metadata_roundtrip() {
+        let list_array = |values: ArrowArrayRef, lengths: &[usize]| {
+            ArrowListArray::new(
+                std::sync::Arc::new(arrow::datatypes::Field::new_list_field(
+                    values.data_type().clone(),
+                    true,
+                )),
+                arrow::buffer::OffsetBuffer::from_lengths(lengths.iter().copied()),
+                values,
+                None,
+            )
+        };
+
+        let floats = list_array(
+            std::sync::Arc::new(Float64Array::from(vec![
+                Some(1.5),
+                None,
+                Some(f64::NAN),
+                Some(-3.0),
+            ])),
+            &[2, 2],
+        );
+        let strings = list_array(
+            std::sync::Arc::new(StringArray::from(vec!["b", "", "a\nb", "b", "c\\n"])),
+            &[1, 4],
+        );
+
+        let float_stats = ComponentStatistics::from_list_array(&floats);
+        assert_eq!(
+            Some(ValueBounds::Float {
+                min: -3.0,
+                max: 1.5
+            }),
+            float_stats.bounds,
+        );
+        assert_eq!(None, float_stats.distinct_values);
+
+        let string_stats = ComponentStatistics::from_list_array(&strings);
+        assert_eq!(
+            Some(ValueBounds::String {
+                min: String::new(),
+                max: "c\\n".to_owned(),
+            }),
+            string_stats.bounds,
+        );
+        assert_eq!(Some(4), string_stats.num_distinct_values());
+
+        // Slicing must only account for the values that are still referenced.
+        let sliced = strings.slice(0, 1);
+        assert_eq!(
+            Some(1),
+            ComponentStatistics::from_list_array(&sliced).num_distinct_values()
+        );
+
+        // Statistics stay small, no matter how long the strings are.
+        let long_string = "x".repeat(ComponentStatistics::MAX_STRING_LEN + 1);
+        let long_strings = list_array(
+            std::sync::Arc::new(StringArray::from(vec![long_string.as_str(), "a"])),
+            &[2],
+        );
+        let long_string_stats = ComponentStatistics::from_list_array(&long_strings);
+        assert_eq!(None, long_string_stats.bounds);
+        assert_eq!(None, long_string_stats.distinct_values);
+
+        let long_strings = list_array(
+            std::sync::Arc::new(StringArray::from(vec![long_string.as_str(), "y"])),
+            &[2],
+        );
+        assert_eq!(
+            Some(ValueBounds::String {
+                min: long_string[..ComponentStatistics::MAX_STRING_LEN].to_owned(),
+                max: "y".to_owned(),
+            }),
+            ComponentStatistics::from_list_array(&long_strings).bounds,
+        );
+
+        for stats in [float_stats, string_stats] {
+            assert_eq!(
+                Some(&stats),
+                ComponentStatistics::from_arrow_metadata(&stats.to_arrow_metadata()).as_ref(),
+            );
+        }
+    }
+}
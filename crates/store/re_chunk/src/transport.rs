@@ -6,7 +6,9 @@ use re_arrow_util::{ArrowArrayDowncastRef as _, into_arrow_ref};
 use re_byte_size::SizeBytes as _;
 use re_types_core::{ComponentDescriptor, arrow_helpers::as_array_ref};
 
-use crate::{Chunk, ChunkError, ChunkResult, TimeColumn, chunk::ChunkComponents};
+use crate::{
+    Chunk, ChunkError, ChunkResult, ComponentStatistics, TimeColumn, chunk::ChunkComponents,
+};
 
 // ---
 
@@ -34,11 +36,14 @@ impl Chunk {
             self.num_rows()
         ));
 
-        let heap_size_bytes = self.heap_size_bytes();
+        // The statistics aren't trusted on the receiving end, and get recomputed there.
+        let heap_size_bytes = self.heap_size_bytes() - self.statistics_heap_size_bytes();
+        let statistics = self.statistics();
         let Self {
             id,
             entity_path,
             heap_size_bytes: _, // use the method instead because of lazy initialization
+            statistics: _,
             is_sorted,
             row_ids,
             timelines,
@@ -78,7 +83,7 @@ impl Chunk {
             timelines.into_iter().unzip()
         };
 
-        let (data_schemas, data_arrays): (Vec<_>, Vec<_>) = {
+        let (data_schemas, data_arrays, data_statistics): (Vec<_>, Vec<_>, Vec<_>) = {
             re_tracing::profile_scope!("components");
 
             let mut components = components
@@ -110,13 +115,18 @@ impl Chunk {
                         is_tombstone: false,
                         is_semantically_empty: false,
                     };
-                    (schema, into_arrow_ref(list_array))
+
+                    let statistics = statistics
+                        .and_then(|statistics| statistics.get(component_desc))
+                        .map(|statistics| statistics.to_arrow_metadata());
+
+                    (schema, into_arrow_ref(list_array), statistics)
                 })
                 .collect_vec();
 
-            components.sort_by(|(schema_a, _), (schema_b, _)| schema_a.cmp(schema_b));
+            components.sort_by(|(schema_a, _, _), (schema_b, _, _)| schema_a.cmp(schema_b));
 
-            components.into_iter().unzip()
+            components.into_iter().multiunzip()
         };
 
         let schema = re_sorbet::ChunkSchema::new(
@@ -129,12 +139,20 @@ impl Chunk {
         )
         .with_heap_size_bytes(heap_size_bytes);
 
+        // Statistics are not part of the Rerun schema: they travel as extra field metadata.
+        let first_data_column = 1 /* row_ids */ + index_arrays.len();
+        let data_statistics = data_statistics
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, metadata)| Some((first_data_column + i, metadata?)));
+
         Ok(re_sorbet::ChunkBatch::try_new(
             schema,
             into_arrow_ref(row_ids.clone()),
             index_arrays,
             data_arrays,
-        )?)
+        )?
+        .with_extra_field_metadata(data_statistics))
     }
 
     #[tracing::instrument(level = "trace", skip_all)]
@@ -237,6 +255,15 @@ impl Chunk {
             res.heap_size_bytes = heap_size_bytes.into();
         }
 
+        // The sender computed statistics, so do we: they can't be trusted as-is.
+        if batch
+            .fields()
+            .iter()
+            .any(|field| ComponentStatistics::is_in_arrow_metadata(field.metadata()))
+        {
+            res.compute_statistics();
+        }
+
         Ok(res)
     }
 }
@@ -338,7 +365,7 @@ mod tests {
         let row_ids = vec![RowId::new(), RowId::new(), RowId::new(), RowId::new()];
 
         for timelines in [timelines1, timelines2] {
-            let chunk_before = Chunk::from_native_row_ids(
+            let mut chunk_before = Chunk::from_native_row_ids(
                 ChunkId::new(),
                 entity_path.clone(),
                 None,
@@ -374,6 +401,16 @@ mod tests {
             assert_eq!(chunk_before.num_rows(), chunk_after.num_rows());
             assert!(chunk_before.are_equal(&chunk_after));
             assert_eq!(chunk_before, chunk_after);
+
+            // Statistics are only transported once they've been computed.
+            assert!(chunk_after.statistics().is_none());
+            chunk_before.compute_statistics();
+            let chunk_after = Chunk::from_record_batch(&chunk_before.to_record_batch()?)?;
+            assert_eq!(chunk_before.statistics(), chunk_after.statistics());
+            assert_eq!(
+                chunk_before.heap_size_bytes(),
+                chunk_after.heap_size_bytes(),
+            );
         }
 
         Ok(())
@@ -0,0 +1,266 @@
+use std::{sync::Arc, time::Duration};
+
+use web_time::Instant;
+
+use re_chunk::{Chunk, ChunkId, RowId};
+
+use crate::{ChunkStore, ChunkStoreConfig, ChunkStoreEvent, ChunkStoreGeneration};
+
+// ---
+
+/// Keeps track of the progress of [`ChunkStore::compact`] across calls.
+#[derive(Debug, Clone)]
+pub(crate) struct CompactionProgress {
+    /// Where the previous call left off, in [`RowId`] order.
+    cursor: RowId,
+
+    /// How many chunks in a row were visited without finding anything to do.
+    num_idle_chunks: usize,
+
+    /// Set once all chunks have been visited without finding anything to do: there is nothing
+    /// left to compact until the store gets modified.
+    idle_generation: Option<ChunkStoreGeneration>,
+}
+
+impl Default for CompactionProgress {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            cursor: RowId::ZERO,
+            num_idle_chunks: 0,
+            idle_generation: None,
+        }
+    }
+}
+
+impl ChunkStore {
+    /// Incrementally re-compacts the temporal chunks that are already in the store.
+    ///
+    /// Compaction is normally only applied at insertion time (see [`Self::insert_chunk`]), which
+    /// isn't always enough: e.g. a slow-rate sensor logged alongside lots of other data will still
+    /// end up fragmented into many tiny chunks.
+    ///
+    /// This goes through the store in [`RowId`] order, and:
+    /// * merges small chunks with their best neighbor, within the [`ChunkStoreConfig`] thresholds,
+    /// * splits chunks that are too large to remain unsorted into time-sorted runs.
+    ///
+    /// Each call picks up where the previous one left off and stops once `time_budget` is exhausted,
+    /// so this can be called repeatedly (e.g. once per frame). Calls are cheap once there's nothing
+    /// left to do, until the store gets modified again.
+    ///
+    /// From a query model standpoint, every rewritten chunk is deleted and its replacements are then
+    /// inserted: this is exactly what the returned [`ChunkStoreEvent`]s describe, and what registered
+    /// [`crate::ChunkStoreSubscriber`]s get notified of.
+    pub fn compact(&mut self, time_budget: Duration) -> Vec<ChunkStoreEvent> {
+        if self.compaction_progress.idle_generation.as_ref() == Some(&self.generation()) {
+            return Vec::new();
+        }
+
+        re_tracing::profile_function!();
+
+        let start_time = Instant::now();
+        let mut events = Vec::new();
+
+        while start_time.elapsed() < time_budget {
+            let num_chunks = self.chunk_ids_per_min_row_id.len();
+            if self.compaction_progress.num_idle_chunks >= num_chunks {
+                self.compaction_progress.num_idle_chunks = 0;
+                self.compaction_progress.idle_generation = Some(self.generation());
+                break;
+            }
+
+            // Visit the next chunk, wrapping around once the end has been reached.
+            let Some((min_row_id, chunk_id)) = self
+                .chunk_ids_per_min_row_id
+                .range(self.compaction_progress.cursor..)
+                .next()
+                .or_else(|| self.chunk_ids_per_min_row_id.first_key_value())
+                .map(|(min_row_id, chunk_id)| (*min_row_id, *chunk_id))
+            else {
+                break;
+            };
+            self.compaction_progress.cursor = min_row_id.next();
+
+            // Spilled chunks are left alone: they can't be fragmenting memory.
+            let chunk = self.chunks_per_chunk_id.get(&chunk_id).cloned();
+            let new_events = chunk.and_then(|chunk| self.compact_chunk(&chunk));
+
+            if let Some(mut new_events) = new_events {
+                self.compaction_progress.num_idle_chunks = 0;
+                events.append(&mut new_events);
+            } else {
+                self.compaction_progress.num_idle_chunks += 1;
+            }
+        }
+
+        if !events.is_empty() {
+            re_log::trace!(
+                num_events = events.len(),
+                num_chunks = self.num_chunks(),
+                "re-compacted chunks"
+            );
+        }
+
+        events
+    }
+
+    /// Rewrites the given chunk if it needs to, returning the resulting events.
+    ///
+    /// Returns `None` if there was nothing to do.
+    fn compact_chunk(&mut self, chunk: &Arc<Chunk>) -> Option<Vec<ChunkStoreEvent>> {
+        if chunk.is_static() {
+            return None; // static data is never compacted
+        }
+
+        let ChunkStoreConfig {
+            enable_changelog: _,
+            chunk_max_bytes: _,
+            chunk_max_rows: _,
+            chunk_max_rows_if_unsorted,
+            spill_directory: _,
+            compute_statistics: _,
+        } = self.config;
+
+        if !chunk.is_time_sorted() {
+            if chunk_max_rows_if_unsorted == 0
+                || chunk.num_rows() as u64 <= chunk_max_rows_if_unsorted
+            {
+                return None;
+            }
+
+            let runs = time_sorted_runs(chunk);
+            if runs.len() < 2 {
+                return None;
+            }
+
+            re_log::trace!(
+                "split {} ({} rows) into {} time-sorted chunks",
+                chunk.id(),
+                re_format::format_uint(chunk.num_rows()),
+                runs.len(),
+            );
+
+            return Some(self.replace_chunks(&[chunk.id()], runs));
+        }
+
+        let elected_chunk = self.find_and_elect_compaction_candidate(chunk)?;
+
+        let chunk_rowid_min = chunk.row_id_range().map(|(min, _)| min);
+        let elected_rowid_min = elected_chunk.row_id_range().map(|(min, _)| min);
+
+        let compacted = if elected_rowid_min < chunk_rowid_min {
+            elected_chunk.concatenated(chunk)
+        } else {
+            chunk.concatenated(&elected_chunk)
+        };
+        let mut compacted = match compacted {
+            Ok(compacted) => compacted,
+            Err(err) => {
+                re_log::warn_once!("Failed to re-compact chunks: {err}");
+                return None;
+            }
+        };
+        compacted.sort_if_unsorted();
+
+        // Don't undo the work of the splitting above.
+        if !compacted.is_time_sorted() && compacted.num_rows() as u64 > chunk_max_rows_if_unsorted {
+            return None;
+        }
+
+        re_log::trace!(
+            "re-compacted {} ({} rows) and {} ({} rows) together, resulting in {} ({} rows)",
+            chunk.id(),
+            re_format::format_uint(chunk.num_rows()),
+            elected_chunk.id(),
+            re_format::format_uint(elected_chunk.num_rows()),
+            compacted.id(),
+            re_format::format_uint(compacted.num_rows()),
+        );
+
+        Some(self.replace_chunks(&[chunk.id(), elected_chunk.id()], vec![compacted]))
+    }
+
+    /// Removes the given chunks from the store, then inserts their replacements.
+    ///
+    /// The replacements are inserted as-is, without going through insertion-time compaction:
+    /// they are the result of compaction already.
+    fn replace_chunks(
+        &mut self,
+        old_chunk_ids: &[ChunkId],
+        new_chunks: Vec<Chunk>,
+    ) -> Vec<ChunkStoreEvent> {
+        re_tracing::profile_function!();
+
+        let generation = self.generation();
+
+        let diffs = old_chunk_ids
+            .iter()
+            .flat_map(|chunk_id| self.remove_chunk(*chunk_id))
+            .collect::<Vec<_>>();
+
+        let mut events = if self.config.enable_changelog {
+            let events: Vec<_> = diffs
+                .into_iter()
+                .map(|diff| ChunkStoreEvent {
+                    store_id: self.id.clone(),
+                    store_generation: generation.clone(),
+                    event_id: self
+                        .event_id
+                        .fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+                    diff,
+                })
+                .collect();
+
+            Self::on_events(&events);
+
+            events
+        } else {
+            Vec::new()
+        };
+
+        for chunk in new_chunks {
+            match self.insert_chunk_impl(&Arc::new(chunk), false) {
+                Ok(mut new_events) => events.append(&mut new_events),
+                Err(err) => {
+                    // The chunks come from the store itself, so this should never happen.
+                    re_log::error!("Failed to re-insert compacted chunk, its data is lost: {err}");
+                }
+            }
+        }
+
+        events
+    }
+}
+
+/// Splits a chunk into the longest possible runs of rows that are sorted on every timeline.
+///
+/// Rows stay in [`RowId`] order: each run is a new chunk, with a new [`ChunkId`].
+fn time_sorted_runs(chunk: &Chunk) -> Vec<Chunk> {
+    re_tracing::profile_function!();
+
+    let times = chunk
+        .timelines()
+        .values()
+        .map(|time_column| time_column.times_raw())
+        .collect::<Vec<_>>();
+
+    let mut runs = Vec::new();
+    let mut run_start = 0;
+    for row in 1..chunk.num_rows() {
+        if times.iter().any(|times| times[row] < times[row - 1]) {
+            runs.push(
+                chunk
+                    .row_sliced(run_start, row - run_start)
+                    .with_id(ChunkId::new()),
+            );
+            run_start = row;
+        }
+    }
+    runs.push(
+        chunk
+            .row_sliced(run_start, chunk.num_rows() - run_start)
+            .with_id(ChunkId::new()),
+    );
+
+    runs
+}
@@ -34,8 +34,33 @@ pub enum SparseFillStrategy {
     /// The latest-at semantics are applied on the entire dataset as opposed to just the current
     /// view contents: it is possible to end up with values from outside the view!
     LatestAtGlobal,
-    //
-    // TODO(cmc): `LatestAtView`?
+
+    /// Fill null values using view-scope latest-at semantics.
+    ///
+    /// Contrary to [`Self::LatestAtGlobal`], only data from within the view contents is ever
+    /// considered, i.e. values logged before the start of [`QueryExpression::filtered_index_range`]
+    /// will never be used to fill nulls.
+    LatestAtView,
+
+    /// Fill null values by linearly interpolating between the closest samples before and after
+    /// the current index value, within the view contents.
+    ///
+    /// Nulls are only filled if these samples are at most `max_gap` apart on the filtered index
+    /// (e.g. nanoseconds for temporal timelines).
+    ///
+    /// Only floating-point components (and fixed-size lists thereof, e.g. `Position3D`) whose
+    /// samples have matching lengths can be interpolated. All other components, including integer
+    /// ones such as `Color` or `ClassId`, fall back to [`Self::LatestAtView`] semantics, within
+    /// the same `max_gap`.
+    Interpolate { max_gap: u64 },
+
+    /// Fill null values using the sample closest to the current index value, whether before or
+    /// after it, within the view contents.
+    ///
+    /// Nulls are only filled if that sample is at most `tolerance` away on the filtered index
+    /// (e.g. nanoseconds for temporal timelines).
+    /// In case of a tie, the sample before the current index value wins.
+    NearestWithin { tolerance: u64 },
 }
 
 impl std::fmt::Display for SparseFillStrategy {
@@ -43,6 +68,364 @@ impl std::fmt::Display for SparseFillStrategy {
         match self {
             Self::None => f.write_str("none"),
             Self::LatestAtGlobal => f.write_str("latest-at (global)"),
+            Self::LatestAtView => f.write_str("latest-at (view)"),
+            Self::Interpolate { max_gap } => write!(f, "interpolate (max gap: {max_gap})"),
+            Self::NearestWithin { tolerance } => write!(f, "nearest (tolerance: {tolerance})"),
+        }
+    }
+}
+
+/// Specifies how the values that fall into the same resampling bucket are combined.
+///
+/// See [`Resampling`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AggregationFunction {
+    /// The arithmetic mean of all values, element-wise.
+    Mean,
+
+    /// The smallest of all values, element-wise.
+    Min,
+
+    /// The largest of all values, element-wise.
+    Max,
+
+    /// The last value, i.e. the one with the highest index value (and `RowId`).
+    #[default]
+    Last,
+
+    /// The number of values.
+    ///
+    /// The resulting columns are all of type `List[u64]`, regardless of the original component type.
+    Count,
+}
+
+impl std::fmt::Display for AggregationFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mean => f.write_str("mean"),
+            Self::Min => f.write_str("min"),
+            Self::Max => f.write_str("max"),
+            Self::Last => f.write_str("last"),
+            Self::Count => f.write_str("count"),
+        }
+    }
+}
+
+/// Resamples the view contents at a fixed rate on the filtered index.
+///
+/// The filtered index is split into contiguous buckets of `interval` index units (e.g. nanoseconds
+/// for temporal timelines), aligned on multiples of `interval`.
+/// The final dataset contains exactly one row per bucket, from the first bucket containing data
+/// to the last one, and the index value of each row is the start of its bucket.
+///
+/// All the values of a column that fall into a bucket are combined using `aggregation`:
+/// * Only floating-point scalar components (e.g. `Scalar`) can be aggregated using
+///   [`AggregationFunction::Mean`], [`AggregationFunction::Min`] and [`AggregationFunction::Max`],
+///   and the results keep the original datatype. Values whose length differ from the last value in
+///   the bucket are ignored.
+///   All other components, e.g. integer `Color`s and `ClassId`s or `Position3D`s, fall back to
+///   [`AggregationFunction::Last`].
+/// * Empty buckets yield nulls (which can then be filled using the
+///   [`QueryExpression::sparse_fill_strategy`]), except when counting.
+/// * Static data is never aggregated, and counts as a single value in every bucket.
+/// * Queries that would yield more than [`Resampling::MAX_BUCKETS`] buckets are rejected: they log
+///   an error and yield no rows at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Resampling {
+    /// The size of every bucket, on the filtered index.
+    pub interval: std::num::NonZeroU64,
+
+    /// How the values within a bucket are combined.
+    pub aggregation: AggregationFunction,
+}
+
+impl Resampling {
+    /// The maximum number of buckets, i.e. rows, that a resampled query can yield.
+    pub const MAX_BUCKETS: u64 = 10_000_000;
+}
+
+impl std::fmt::Display for Resampling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            interval,
+            aggregation,
+        } = self;
+        write!(f, "{aggregation} every {interval}")
+    }
+}
+
+/// A comparison operator, see [`RowFilter::Compare`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComparisonOperator {
+    /// `==`
+    Eq,
+
+    /// `!=`
+    NotEq,
+
+    /// `<`
+    Lt,
+
+    /// `<=`
+    LtEq,
+
+    /// `>`
+    Gt,
+
+    /// `>=`
+    GtEq,
+}
+
+impl ComparisonOperator {
+    /// Does `lhs <op> rhs` hold, given `lhs.cmp(rhs)`?
+    #[inline]
+    pub fn matches(self, ordering: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering;
+
+        match self {
+            Self::Eq => ordering == Ordering::Equal,
+            Self::NotEq => ordering != Ordering::Equal,
+            Self::Lt => ordering == Ordering::Less,
+            Self::LtEq => ordering != Ordering::Greater,
+            Self::Gt => ordering == Ordering::Greater,
+            Self::GtEq => ordering != Ordering::Less,
+        }
+    }
+
+    /// Could `lhs <op> rhs` hold for any `lhs` within `[min, max]`, given `min.cmp(rhs)` and
+    /// `max.cmp(rhs)`?
+    ///
+    /// Used to prune data using min/max statistics.
+    #[inline]
+    pub fn may_match_range(self, min: std::cmp::Ordering, max: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering;
+
+        match self {
+            Self::Eq => min != Ordering::Greater && max != Ordering::Less,
+            Self::NotEq => !(min == Ordering::Equal && max == Ordering::Equal),
+            Self::Lt | Self::LtEq => self.matches(min),
+            Self::Gt | Self::GtEq => self.matches(max),
+        }
+    }
+}
+
+impl std::fmt::Display for ComparisonOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Eq => "==",
+            Self::NotEq => "!=",
+            Self::Lt => "<",
+            Self::LtEq => "<=",
+            Self::Gt => ">",
+            Self::GtEq => ">=",
+        })
+    }
+}
+
+/// A literal value that component data can be compared against, see [`RowFilter::Compare`].
+#[derive(Debug, Clone)]
+pub enum FilterValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl PartialEq for FilterValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Bool(lhs), Self::Bool(rhs)) => lhs == rhs,
+            (Self::Int(lhs), Self::Int(rhs)) => lhs == rhs,
+            (Self::Float(lhs), Self::Float(rhs)) => lhs.to_bits() == rhs.to_bits(),
+            (Self::String(lhs), Self::String(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for FilterValue {}
+
+impl std::hash::Hash for FilterValue {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::Bool(value) => value.hash(state),
+            Self::Int(value) => value.hash(state),
+            Self::Float(value) => value.to_bits().hash(state),
+            Self::String(value) => value.hash(state),
+        }
+    }
+}
+
+impl std::fmt::Display for FilterValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bool(value) => value.fmt(f),
+            Self::Int(value) => value.fmt(f),
+            Self::Float(value) => write!(f, "{value:?}"),
+            Self::String(value) => write!(f, "{value:?}"),
+        }
+    }
+}
+
+impl From<bool> for FilterValue {
+    #[inline]
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for FilterValue {
+    #[inline]
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<f64> for FilterValue {
+    #[inline]
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<&str> for FilterValue {
+    #[inline]
+    fn from(value: &str) -> Self {
+        Self::String(value.to_owned())
+    }
+}
+
+impl From<String> for FilterValue {
+    #[inline]
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+/// A predicate on the _rows_ of the view contents, see [`QueryExpression::filter`].
+///
+/// Example (pseudo-code):
+/// ```text
+/// /detections:Confidence > 0.8 AND (/camera:Image IS NOT NULL OR /lidar:Points3D:positions IS NOT NULL)
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RowFilter {
+    /// The column contains non-null data.
+    IsNotNull(ComponentColumnSelector),
+
+    /// At least one of the instances in the column satisfies the comparison.
+    ///
+    /// Numeric columns can be compared with both integer and floating point values, string
+    /// columns with strings, and boolean columns with booleans.
+    /// Any other comparison never holds, and neither do comparisons on nulls.
+    Compare {
+        column: ComponentColumnSelector,
+        op: ComparisonOperator,
+        value: FilterValue,
+    },
+
+    /// All the filters hold. Holds if empty.
+    And(Vec<Self>),
+
+    /// At least one of the filters holds. Never holds if empty.
+    Or(Vec<Self>),
+}
+
+impl RowFilter {
+    #[inline]
+    pub fn is_not_null(column: impl Into<ComponentColumnSelector>) -> Self {
+        Self::IsNotNull(column.into())
+    }
+
+    #[inline]
+    pub fn compare(
+        column: impl Into<ComponentColumnSelector>,
+        op: ComparisonOperator,
+        value: impl Into<FilterValue>,
+    ) -> Self {
+        Self::Compare {
+            column: column.into(),
+            op,
+            value: value.into(),
+        }
+    }
+
+    /// Combines two filters, flattening nested [`Self::And`]s.
+    pub fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::And(mut lhs), Self::And(rhs)) => {
+                lhs.extend(rhs);
+                Self::And(lhs)
+            }
+            (Self::And(mut lhs), rhs) => {
+                lhs.push(rhs);
+                Self::And(lhs)
+            }
+            (lhs, Self::And(mut rhs)) => {
+                rhs.insert(0, lhs);
+                Self::And(rhs)
+            }
+            (lhs, rhs) => Self::And(vec![lhs, rhs]),
+        }
+    }
+
+    /// Combines two filters, flattening nested [`Self::Or`]s.
+    pub fn or(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Or(mut lhs), Self::Or(rhs)) => {
+                lhs.extend(rhs);
+                Self::Or(lhs)
+            }
+            (Self::Or(mut lhs), rhs) => {
+                lhs.push(rhs);
+                Self::Or(lhs)
+            }
+            (lhs, Self::Or(mut rhs)) => {
+                rhs.insert(0, lhs);
+                Self::Or(rhs)
+            }
+            (lhs, rhs) => Self::Or(vec![lhs, rhs]),
+        }
+    }
+
+    /// All the columns referenced by this filter, in order of appearance, possibly duplicated.
+    pub fn columns(&self) -> Vec<&ComponentColumnSelector> {
+        match self {
+            Self::IsNotNull(column) | Self::Compare { column, .. } => vec![column],
+            Self::And(filters) | Self::Or(filters) => {
+                filters.iter().flat_map(|filter| filter.columns()).collect()
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for RowFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IsNotNull(column) => write!(f, "{column} IS NOT NULL"),
+
+            Self::Compare { column, op, value } => write!(f, "{column} {op} {value}"),
+
+            Self::And(filters) => {
+                if filters.is_empty() {
+                    return f.write_str("TRUE");
+                }
+
+                let filters = filters.iter().map(|filter| match filter {
+                    Self::Or(filters) if filters.len() > 1 => format!("({filter})"),
+                    filter => filter.to_string(),
+                });
+                f.write_str(&filters.format(" AND ").to_string())
+            }
+
+            Self::Or(filters) => {
+                if filters.is_empty() {
+                    return f.write_str("FALSE");
+                }
+
+                f.write_str(&filters.iter().format(" OR ").to_string())
+            }
         }
     }
 }
@@ -53,8 +436,7 @@ impl std::fmt::Display for SparseFillStrategy {
 ///
 /// Setting an entity's identifier to `None` means: everything.
 ///
-// TODO(cmc): we need to be able to build that easily in a command-line context, otherwise it's just
-// very annoying. E.g. `--with /world/points:[positions, radius] --with /cam:[pinhole]`.
+/// See `re_dataframe::QuerySpec` for a textual syntax, e.g. `/world/points:[positions, radius] /cam:[pinhole]`.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ViewContentsSelector(pub BTreeMap<EntityPath, Option<BTreeSet<ComponentIdentifier>>>);
 
@@ -88,18 +470,50 @@ impl FromIterator<(EntityPath, Option<BTreeSet<ComponentIdentifier>>)> for ViewC
     }
 }
 
-// TODO(cmc): Ultimately, this shouldn't be hardcoded to `Timeline`, but to a generic `I: Index`.
-//            `Index` in this case should also be implemented on tuples (`(I1, I2, ...)`).
+/// A single index.
+///
+/// Composite indices (e.g. `(episode, step)`) are made of several of these, see
+/// [`QueryExpression::secondary_indices`].
 pub type Index = TimelineName;
 
-// TODO(cmc): Ultimately, this shouldn't be hardcoded to `TimeInt`, but to a generic `I: Index`.
-//            `Index` in this case should also be implemented on tuples (`(I1, I2, ...)`).
+/// A value on a single [`Index`].
 pub type IndexValue = TimeInt;
 
-// TODO(cmc): Ultimately, this shouldn't be hardcoded to `AbsoluteTimeRange`, but to a generic `I: Index`.
-//            `Index` in this case should also be implemented on tuples (`(I1, I2, ...)`).
+/// A range of values on a single [`Index`].
 pub type IndexRange = AbsoluteTimeRange;
 
+/// One of the components of a composite index, besides the filtered index itself.
+///
+/// See [`QueryExpression::secondary_indices`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SecondaryIndex {
+    /// The index itself.
+    pub index: Index,
+
+    /// The range of index values used to filter out _rows_ from the view contents.
+    ///
+    /// Rows that aren't indexed on [`Self::index`] never fall within a range.
+    ///
+    /// Example: `AbsoluteTimeRange(10, 20)`.
+    pub range: Option<IndexRange>,
+}
+
+impl SecondaryIndex {
+    #[inline]
+    pub fn new(index: impl Into<Index>) -> Self {
+        Self {
+            index: index.into(),
+            range: None,
+        }
+    }
+
+    #[inline]
+    pub fn with_range(mut self, range: IndexRange) -> Self {
+        self.range = Some(range);
+        self
+    }
+}
+
 /// Specifies whether static columns should be included in the query.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum StaticColumnSelection {
@@ -196,6 +610,25 @@ pub struct QueryExpression {
     /// Example: `AbsoluteTimeRange(10, 20)`.
     pub filtered_index_range: Option<IndexRange>,
 
+    /// Extra indices that, together with [`QueryExpression::filtered_index`], make up a
+    /// composite index, e.g. `(episode, step)` or `(camera, frame)`.
+    ///
+    /// When set, the final dataset contains one row per unique tuple of index values rather than
+    /// per unique value of the `filtered_index`, sorted on the `filtered_index` first and then on
+    /// each secondary index in order.
+    /// Rows that aren't indexed on some secondary index get a null value for it, which sorts first.
+    ///
+    /// Each secondary index can be further filtered with its own range, see [`SecondaryIndex::range`].
+    ///
+    /// * This has no effect if `filtered_index` isn't set.
+    /// * This has no effect if [`QueryExpression::using_index_values`] is set.
+    /// * [`QueryExpression::filtered_index_range`] and [`QueryExpression::filtered_index_values`]
+    ///   apply to the `filtered_index` component of the tuple.
+    /// * [`QueryExpression::resampling`] is not supported, and is ignored.
+    ///
+    /// Example: `[SecondaryIndex { index: "step", range: None }]`.
+    pub secondary_indices: Vec<SecondaryIndex>,
+
     /// The specific index values used to filter out _rows_ from the view contents.
     ///
     /// Only rows where at least 1 column contains non-null data at these specific values will be kept
@@ -223,6 +656,22 @@ pub struct QueryExpression {
     /// Example: `[TimeInt(12), TimeInt(14)]`.
     pub using_index_values: Option<BTreeSet<IndexValue>>,
 
+    /// Resample the view contents at a fixed rate on the filtered index, aggregating the values
+    /// within each bucket.
+    ///
+    /// The final dataset will contain one row per bucket, regardless of whether data existed within
+    /// that bucket in the view contents.
+    ///
+    /// * This has no effect if `filtered_index` isn't set.
+    /// * This has no effect if [`QueryExpression::using_index_values`] is set.
+    /// * This has no effect if [`QueryExpression::secondary_indices`] is set.
+    /// * This applies after [`QueryExpression::filtered_index_range`] and
+    ///   [`QueryExpression::filtered_index_values`]: only the data that passes these filters gets
+    ///   aggregated.
+    ///
+    /// Example: `Resampling { interval: 10_000_000 /* 10ms */, aggregation: Mean }`.
+    pub resampling: Option<Resampling>,
+
     /// The component column used to filter out _rows_ from the view contents.
     ///
     /// Only rows where this column contains non-null data be kept in the final dataset.
@@ -232,6 +681,24 @@ pub struct QueryExpression {
     // TODO(cmc): multi-pov support
     pub filtered_is_not_null: Option<ComponentColumnSelector>,
 
+    /// A predicate used to filter out _rows_ from the view contents.
+    ///
+    /// Only rows for which this predicate holds will be kept in the final dataset.
+    ///
+    /// * The predicate is evaluated on the data present at each index value (static data
+    ///   included), before any sparse-filling takes place (see [`QueryExpression::sparse_fill_strategy`]).
+    ///   When resampling, it is evaluated on the aggregated data instead.
+    /// * Columns that are not part of the view contents are always null.
+    /// * This composes with [`QueryExpression::filtered_is_not_null`]: both must hold.
+    ///
+    /// Row indices, as used by `QueryHandle::num_rows` and `QueryHandle::seek_to_row`, refer to
+    /// the filtered rows. Computing them requires evaluating the predicate on every single row
+    /// once, which only happens on the first call to either of these methods: when iterating
+    /// over all the rows, prefer not calling them at all.
+    ///
+    /// Example: `/detections:Confidence > 0.8 AND /camera:Image IS NOT NULL`.
+    pub filter: Option<RowFilter>,
+
     /// Specifies how null values should be filled in the returned dataframe.
     ///
     /// Defaults to [`SparseFillStrategy::None`].
@@ -475,9 +942,12 @@ impl ChunkStore {
             include_static_columns,
             filtered_index: _,
             filtered_index_range: _,
+            secondary_indices: _,
             filtered_index_values: _,
             using_index_values: _,
+            resampling: _,
             filtered_is_not_null: _,
+            filter: _,
             sparse_fill_strategy: _,
             selection: _,
         } = query;
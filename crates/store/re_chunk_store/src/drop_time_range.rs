@@ -22,6 +22,8 @@ impl ChunkStore {
             return Default::default();
         }
 
+        self.restore_spilled_chunks_in_range(timeline, drop_range);
+
         // Prepare the changes:
 
         let mut chunk_ids_to_drop = vec![];
@@ -67,14 +67,25 @@ impl std::ops::Deref for ChunkStoreEvent {
 pub enum ChunkStoreDiffKind {
     Addition,
     Deletion,
+
+    /// The chunk was offloaded to disk in order to free up memory, see
+    /// [`crate::ChunkStoreConfig::spill_directory`].
+    ///
+    /// This is _not_ a deletion: the chunk is still part of the store, and queries keep returning
+    /// its data (reloading it from disk on demand).
+    /// Subscribers should release anything they hold onto that can be recomputed from the chunk
+    /// later on (e.g. cached query results), but must not consider its data gone.
+    Spill,
 }
 
 impl ChunkStoreDiffKind {
+    /// `+1` for additions, `-1` for deletions, `0` for spills.
     #[inline]
     pub fn delta(&self) -> i64 {
         match self {
             Self::Addition => 1,
             Self::Deletion => -1,
+            Self::Spill => 0,
         }
     }
 }
@@ -119,6 +130,9 @@ pub struct ChunkStoreDiff {
     /// That doesn't necessarily mean that the data is actually gone, i.e. don't make assumptions of e.g. the size
     /// in bytes of the store based on these events.
     /// They are in "query-model space" and are not an accurate representation of what happens in storage space.
+    ///
+    /// Spills are the one exception to that rule: they don't change anything in query-model space,
+    /// and only exist so that subscribers can let go of data that moved out of memory.
     pub kind: ChunkStoreDiffKind,
 
     /// The chunk that was added or removed.
@@ -180,12 +194,21 @@ impl ChunkStoreDiff {
         }
     }
 
+    #[inline]
+    pub fn spill(chunk: Arc<Chunk>) -> Self {
+        Self {
+            kind: ChunkStoreDiffKind::Spill,
+            chunk,
+            compacted: None,
+        }
+    }
+
     #[inline]
     pub fn is_static(&self) -> bool {
         self.chunk.is_static()
     }
 
-    /// `-1` for deletions, `+1` for additions.
+    /// `+1` for additions, `-1` for deletions, `0` for spills.
     #[inline]
     pub fn delta(&self) -> i64 {
         self.kind.delta()
@@ -28,6 +28,9 @@ pub enum GarbageCollectionTarget {
     /// Try to drop _at least_ the given fraction.
     ///
     /// The fraction must be a float in the range [0.0 : 1.0].
+    ///
+    /// A fraction of `0.0` only drops the data that has expired, see
+    /// [`GarbageCollectionOptions::retention`].
     DropAtLeastFraction(f64),
 
     /// GC Everything that isn't protected.
@@ -55,6 +58,13 @@ pub struct GarbageCollectionOptions {
 
     /// Do not remove any data within these time ranges.
     pub protected_time_ranges: IntMap<TimelineName, AbsoluteTimeRange>,
+
+    /// Time-based retention policies.
+    ///
+    /// Expired data is always dropped first, regardless of the [`Self::target`].
+    /// Protected data (see [`Self::protect_latest`] and [`Self::protected_time_ranges`]) is never
+    /// considered expired.
+    pub retention: Vec<RetentionPolicy>,
 }
 
 impl GarbageCollectionOptions {
@@ -64,6 +74,18 @@ impl GarbageCollectionOptions {
             time_budget: std::time::Duration::MAX,
             protect_latest: 0,
             protected_time_ranges: Default::default(),
+            retention: Vec::new(),
+        }
+    }
+
+    /// Only drop the data that has expired according to the given retention policies.
+    pub fn retention_only(retention: Vec<RetentionPolicy>) -> Self {
+        Self {
+            target: GarbageCollectionTarget::DropAtLeastFraction(0.0),
+            time_budget: std::time::Duration::MAX,
+            protect_latest: 0,
+            protected_time_ranges: Default::default(),
+            retention,
         }
     }
 
@@ -80,6 +102,139 @@ impl GarbageCollectionOptions {
     }
 }
 
+/// A time-based retention policy: only keep the most recent data on a given timeline.
+///
+/// E.g. "keep the last 10 minutes of `log_time`" or "keep the last 5000 frames of `/camera/**`".
+///
+/// Retention works on a chunk-level basis, like the rest of the garbage collector: a chunk is only
+/// dropped once _all_ of its data has expired.
+/// Data that isn't indexed on the policy's timeline (including static data) never expires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// The timeline the policy applies to.
+    pub timeline: TimelineName,
+
+    /// How much data to keep, relative to the most recent time on [`Self::timeline`] across the
+    /// whole store.
+    ///
+    /// This is expressed in the units of the timeline: nanoseconds for temporal timelines,
+    /// sequence numbers otherwise.
+    pub keep_last: u64,
+
+    /// Only apply the policy to this entity and its descendants, if specified.
+    pub entity_path: Option<EntityPath>,
+}
+
+impl RetentionPolicy {
+    pub fn new(timeline: impl Into<TimelineName>, keep_last: u64) -> Self {
+        Self {
+            timeline: timeline.into(),
+            keep_last,
+            entity_path: None,
+        }
+    }
+
+    /// Only apply the policy to the given entity and its descendants.
+    #[inline]
+    pub fn with_entity_path(mut self, entity_path: impl Into<EntityPath>) -> Self {
+        self.entity_path = Some(entity_path.into());
+        self
+    }
+
+    /// Everything strictly before this time has expired.
+    ///
+    /// The cutoff itself is kept, so that exactly [`Self::keep_last`] units of time (the most
+    /// recent one included) are retained: keeping the last 5 frames out of `1..=10` keeps `6..=10`.
+    ///
+    /// Returns `None` if there is no data on the timeline.
+    pub fn cutoff(&self, store: &ChunkStore) -> Option<TimeInt> {
+        let time_range = store.time_range(&self.timeline)?;
+        Some(TimeInt::new_temporal(
+            time_range
+                .max()
+                .as_i64()
+                .saturating_sub_unsigned(self.keep_last)
+                .saturating_add(1),
+        ))
+    }
+
+    /// Has all the data in this chunk expired, given the cutoff returned by [`Self::cutoff`]?
+    pub fn is_chunk_expired(&self, chunk: &Chunk, cutoff: TimeInt) -> bool {
+        if chunk.is_static() {
+            return false;
+        }
+
+        if let Some(scope) = &self.entity_path
+            && !chunk.entity_path().starts_with(scope)
+        {
+            return false;
+        }
+
+        chunk
+            .timelines()
+            .get(&self.timeline)
+            .is_some_and(|time_column| time_column.time_range().max() < cutoff)
+    }
+}
+
+impl std::fmt::Display for RetentionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            timeline,
+            keep_last,
+            entity_path,
+        } = self;
+
+        if let Some(entity_path) = entity_path {
+            write!(f, "{entity_path}:")?;
+        }
+        write!(f, "{timeline}={keep_last}")
+    }
+}
+
+impl std::str::FromStr for RetentionPolicy {
+    type Err = String;
+
+    /// Parses `[<entity path>:]<timeline>=<amount>`, e.g. `log_time=10min` or `/camera:frame=5000`.
+    ///
+    /// The amount is either a plain integer in the units of the timeline, or a duration
+    /// (e.g. `10min`, `1.5s`) for temporal timelines.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((scope, amount)) = s.split_once('=') else {
+            return Err(format!(
+                "expected `[<entity path>:]<timeline>=<amount>`, got {s:?}"
+            ));
+        };
+
+        let (entity_path, timeline) = match scope.rsplit_once(':') {
+            Some((entity_path, timeline)) => (Some(EntityPath::from(entity_path.trim())), timeline),
+            None => (None, scope),
+        };
+
+        let timeline = timeline.trim();
+        if timeline.is_empty() {
+            return Err(format!("missing timeline in {s:?}"));
+        }
+
+        let amount = amount.trim();
+        let keep_last = re_format::parse_i64(amount)
+            .or_else(|| {
+                amount
+                    .parse::<re_log_types::Duration>()
+                    .ok()
+                    .map(|duration| duration.as_nanos())
+            })
+            .and_then(|keep_last| u64::try_from(keep_last).ok())
+            .ok_or_else(|| format!("{amount:?} is not a valid amount of time"))?;
+
+        Ok(Self {
+            timeline: TimelineName::new(timeline),
+            keep_last,
+            entity_path,
+        })
+    }
+}
+
 impl std::fmt::Display for GarbageCollectionTarget {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -96,6 +251,39 @@ pub type RemovableChunkIdPerTimePerComponentPerTimelinePerEntity = IntMap<
     IntMap<TimelineName, IntMap<ComponentDescriptor, HashMap<TimeInt, Vec<ChunkId>>>>,
 >;
 
+/// Marks all the index entries of a _temporal_ chunk for removal.
+fn mark_chunk_for_removal(
+    chunk_ids_to_be_removed: &mut RemovableChunkIdPerTimePerComponentPerTimelinePerEntity,
+    chunk: &Chunk,
+) {
+    // NOTE: We cannot blindly `retain` across all temporal tables, it's way too costly
+    // and slow. Rather we need to surgically remove the superfluous chunks.
+    let entity_path = chunk.entity_path();
+    let per_timeline = chunk_ids_to_be_removed
+        .entry(entity_path.clone())
+        .or_default();
+    for (&timeline, time_column) in chunk.timelines() {
+        let per_component = per_timeline.entry(timeline).or_default();
+        for component_descr in chunk.component_descriptors() {
+            let per_time = per_component.entry(component_descr).or_default();
+
+            // NOTE: As usual, these are vectors of `ChunkId`s, as it is legal to
+            // have perfectly overlapping chunks.
+            let time_range = time_column.time_range();
+            per_time
+                .entry(time_range.min())
+                .or_default()
+                .push(chunk.id());
+            if time_range.min() != time_range.max() {
+                per_time
+                    .entry(time_range.max())
+                    .or_default()
+                    .push(chunk.id());
+            }
+        }
+    }
+}
+
 impl ChunkStore {
     /// Triggers a garbage collection according to the desired `target`.
     ///
@@ -136,11 +324,16 @@ impl ChunkStore {
 
         let protected_chunk_ids = self.find_all_protected_chunk_ids(options.protect_latest);
 
-        let diffs = match options.target {
+        let mut diffs = self.gc_expired(options, &protected_chunk_ids);
+
+        diffs.extend(match options.target {
+            // Nothing to do beyond dropping expired data.
+            GarbageCollectionTarget::DropAtLeastFraction(p) if p <= 0.0 => Vec::new(),
+
             GarbageCollectionTarget::DropAtLeastFraction(p) => {
                 assert!((0.0..=1.0).contains(&p));
 
-                let num_bytes_to_drop = total_size_bytes_before * p;
+                let mut num_bytes_to_drop = total_size_bytes_before * p;
                 let target_size_bytes = total_size_bytes_before - num_bytes_to_drop;
 
                 re_log::trace!(
@@ -155,7 +348,31 @@ impl ChunkStore {
                     "starting GC"
                 );
 
-                self.gc_drop_at_least_num_bytes(options, num_bytes_to_drop, &protected_chunk_ids)
+                let mut diffs = Vec::new();
+
+                if let Some(spill_directory) = self.config.spill_directory.clone() {
+                    let (spill_diffs, num_bytes_left) = self.spill_at_least_num_bytes(
+                        &spill_directory,
+                        options,
+                        num_bytes_to_drop,
+                        &protected_chunk_ids,
+                    );
+                    diffs.extend(spill_diffs);
+
+                    // Whatever couldn't be spilled (e.g. the disk is full, or the time budget ran
+                    // out) gets dropped: running out of memory is never an option.
+                    num_bytes_to_drop = num_bytes_left;
+                }
+
+                if num_bytes_to_drop > 0.0 {
+                    diffs.extend(self.gc_drop_at_least_num_bytes(
+                        options,
+                        num_bytes_to_drop,
+                        &protected_chunk_ids,
+                    ));
+                }
+
+                diffs
             }
             GarbageCollectionTarget::Everything => {
                 re_log::trace!(
@@ -167,9 +384,11 @@ impl ChunkStore {
                     "starting GC"
                 );
 
+                self.restore_spilled_chunks(|_| true);
+
                 self.gc_drop_at_least_num_bytes(options, f64::INFINITY, &protected_chunk_ids)
             }
-        };
+        });
 
         let stats_after = self.stats();
         let total_size_bytes_after = stats_after.total().total_size_bytes as f64;
@@ -203,10 +422,13 @@ impl ChunkStore {
                 .collect();
             {
                 if cfg!(debug_assertions) {
-                    let any_event_other_than_deletion = events
-                        .iter()
-                        .any(|e| e.kind != ChunkStoreDiffKind::Deletion);
-                    assert!(!any_event_other_than_deletion);
+                    let any_event_other_than_deletion_or_spill = events.iter().any(|e| {
+                        !matches!(
+                            e.kind,
+                            ChunkStoreDiffKind::Deletion | ChunkStoreDiffKind::Spill
+                        )
+                    });
+                    assert!(!any_event_other_than_deletion_or_spill);
                 }
 
                 Self::on_events(&events);
@@ -264,6 +486,81 @@ impl ChunkStore {
             .collect()
     }
 
+    /// Drops all the chunks that have expired according to [`GarbageCollectionOptions::retention`].
+    fn gc_expired(
+        &mut self,
+        options: &GarbageCollectionOptions,
+        protected_chunk_ids: &BTreeSet<ChunkId>,
+    ) -> Vec<ChunkStoreDiff> {
+        re_tracing::profile_function!();
+
+        let cutoffs = options
+            .retention
+            .iter()
+            .filter_map(|policy| policy.cutoff(self).map(|cutoff| (policy, cutoff)))
+            .collect::<Vec<_>>();
+
+        if cutoffs.is_empty() {
+            return Vec::new();
+        }
+
+        // Rather than scanning every chunk in the store, which would be way too costly to do on
+        // every frame, look up the chunks that end before the cutoff in the time index.
+        let mut expired_chunk_ids = BTreeSet::new();
+        for (policy, cutoff) in &cutoffs {
+            for (entity_path, chunk_ids_per_timeline) in &self.temporal_chunk_ids_per_entity {
+                if let Some(scope) = &policy.entity_path
+                    && !entity_path.starts_with(scope)
+                {
+                    continue;
+                }
+
+                let Some(chunk_ids_per_time) = chunk_ids_per_timeline.get(&policy.timeline) else {
+                    continue;
+                };
+
+                expired_chunk_ids.extend(
+                    chunk_ids_per_time
+                        .per_end_time
+                        .range(..*cutoff)
+                        .flat_map(|(_, chunk_ids)| chunk_ids.iter().copied()),
+                );
+            }
+        }
+
+        expired_chunk_ids.retain(|chunk_id| !protected_chunk_ids.contains(chunk_id));
+        if expired_chunk_ids.is_empty() {
+            return Vec::new();
+        }
+
+        self.restore_spilled_chunk_ids(expired_chunk_ids.iter().copied());
+
+        let mut chunk_ids_to_be_removed =
+            RemovableChunkIdPerTimePerComponentPerTimelinePerEntity::default();
+
+        for chunk in expired_chunk_ids
+            .iter()
+            .filter_map(|chunk_id| self.chunks_per_chunk_id.get(chunk_id))
+            .filter(|chunk| !options.is_chunk_protected(chunk))
+        {
+            mark_chunk_for_removal(&mut chunk_ids_to_be_removed, chunk);
+        }
+
+        if chunk_ids_to_be_removed.is_empty() {
+            return Vec::new();
+        }
+
+        re_log::trace!(
+            kind = "gc",
+            id = self.gc_id,
+            num_entities = chunk_ids_to_be_removed.len(),
+            "dropping expired data"
+        );
+
+        // NOTE: Expired data must go no matter what, there is no time budget for it.
+        self.remove_chunks(chunk_ids_to_be_removed, None)
+    }
+
     fn gc_drop_at_least_num_bytes(
         &mut self,
         options: &GarbageCollectionOptions,
@@ -295,33 +592,9 @@ impl ChunkStore {
                     // and would count as amortized (i.e. 0 bytes).
                     num_bytes_to_drop -= <Chunk as SizeBytes>::total_size_bytes(chunk) as f64;
 
-                    // NOTE: We cannot blindly `retain` across all temporal tables, it's way too costly
-                    // and slow. Rather we need to surgically remove the superfluous chunks.
-                    let entity_path = chunk.entity_path();
-                    let per_timeline = chunk_ids_to_be_removed
-                        .entry(entity_path.clone())
-                        .or_default();
-                    for (&timeline, time_column) in chunk.timelines() {
-                        let per_component = per_timeline.entry(timeline).or_default();
-                        for component_descr in chunk.component_descriptors() {
-                            let per_time = per_component.entry(component_descr).or_default();
-
-                            // NOTE: As usual, these are vectors of `ChunkId`s, as it is legal to
-                            // have perfectly overlapping chunks.
-                            let time_range = time_column.time_range();
-                            per_time
-                                .entry(time_range.min())
-                                .or_default()
-                                .push(chunk.id());
-                            if time_range.min() != time_range.max() {
-                                per_time
-                                    .entry(time_range.max())
-                                    .or_default()
-                                    .push(chunk.id());
-                            }
-                        }
-                    }
-                } else {
+                    mark_chunk_for_removal(&mut chunk_ids_to_be_removed, chunk);
+                } else if !self.spilled_chunks.contains_key(chunk_id) {
+                    // NOTE: Spilled chunks don't take any memory, there's no point in dropping them.
                     chunk_ids_dangling.insert(*chunk_id);
                 }
 
@@ -344,6 +617,7 @@ impl ChunkStore {
                 type_registry: _,
                 per_column_metadata: _, // column metadata is additive only
                 chunks_per_chunk_id,
+                spilled_chunks: _, // spilled data is never dropped to free up memory
                 chunk_ids_per_min_row_id,
                 temporal_chunk_ids_per_entity_per_component,
                 temporal_chunk_ids_per_entity,
@@ -353,6 +627,7 @@ impl ChunkStore {
                 insert_id: _,
                 gc_id: _,
                 event_id: _,
+                compaction_progress: _,
             } = self;
 
             let mut diffs = Vec::new();
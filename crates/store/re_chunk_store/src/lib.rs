@@ -14,26 +14,31 @@
 #![doc = document_features::document_features!()]
 //!
 
+mod compaction;
 mod dataframe;
 mod drop_time_range;
 mod events;
 mod gc;
 mod query;
+mod spill;
 mod stats;
 mod store;
 mod subscribers;
+mod value_index;
 mod writes;
 
 pub use self::{
     dataframe::{
-        Index, IndexRange, IndexValue, QueryExpression, SparseFillStrategy, StaticColumnSelection,
-        ViewContentsSelector,
+        AggregationFunction, ComparisonOperator, FilterValue, Index, IndexRange, IndexValue,
+        QueryExpression, Resampling, RowFilter, SecondaryIndex, SparseFillStrategy,
+        StaticColumnSelection, ViewContentsSelector,
     },
     events::{ChunkCompactionReport, ChunkStoreDiff, ChunkStoreDiffKind, ChunkStoreEvent},
-    gc::{GarbageCollectionOptions, GarbageCollectionTarget},
+    gc::{GarbageCollectionOptions, GarbageCollectionTarget, RetentionPolicy},
     stats::{ChunkStoreChunkStats, ChunkStoreStats},
     store::{ChunkStore, ChunkStoreConfig, ChunkStoreGeneration, ChunkStoreHandle, ColumnMetadata},
     subscribers::{ChunkStoreSubscriber, ChunkStoreSubscriberHandle, PerStoreChunkSubscriber},
+    value_index::{ValueIndexKind, ValueIndexes, ValuePredicate, ValueQuery},
 };
 pub use re_sorbet::{ColumnDescriptor, ComponentColumnDescriptor, IndexColumnDescriptor};
 
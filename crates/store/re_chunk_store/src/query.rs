@@ -524,7 +524,7 @@ impl ChunkStore {
                     })
                     .flat_map(|chunk_id_sets| chunk_id_sets.per_start_time.values())
                     .flat_map(|chunk_id_set| chunk_id_set.iter())
-                    .any(|chunk_id| self.chunks_per_chunk_id.contains_key(chunk_id))
+                    .any(|chunk_id| self.contains_chunk(chunk_id))
             })
     }
 
@@ -548,7 +548,7 @@ impl ChunkStore {
                     .values()
                     .flat_map(|chunk_id_sets| chunk_id_sets.per_start_time.values())
                     .flat_map(|chunk_id_set| chunk_id_set.iter())
-                    .any(|chunk_id| self.chunks_per_chunk_id.contains_key(chunk_id))
+                    .any(|chunk_id| self.contains_chunk(chunk_id))
             })
     }
 
@@ -809,7 +809,7 @@ impl ChunkStore {
         Some(
             temporal_chunk_ids
                 .iter()
-                .filter_map(|chunk_id| self.chunks_per_chunk_id.get(chunk_id).cloned())
+                .filter_map(|chunk_id| self.resident_or_spilled_chunk(chunk_id))
                 .collect(),
         )
     }
@@ -1051,7 +1051,7 @@ impl ChunkStore {
             .flat_map(|temporal_chunk_ids| {
                 temporal_chunk_ids
                     .iter()
-                    .filter_map(|chunk_id| self.chunks_per_chunk_id.get(chunk_id).cloned())
+                    .filter_map(|chunk_id| self.resident_or_spilled_chunk(chunk_id))
             })
             .collect()
     }
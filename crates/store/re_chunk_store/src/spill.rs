@@ -0,0 +1,310 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use nohash_hasher::IntMap;
+use re_byte_size::SizeBytes;
+use web_time::Instant;
+
+use re_chunk::{Chunk, ChunkId, RowId, TimelineName};
+use re_log_types::{AbsoluteTimeRange, EntityPath};
+
+use crate::{ChunkStore, ChunkStoreChunkStats, ChunkStoreDiff, GarbageCollectionOptions};
+
+// ---
+
+/// A temporal [`Chunk`] that was offloaded to disk in order to free up memory.
+///
+/// See [`crate::ChunkStoreConfig::spill_directory`].
+///
+/// Spilled chunks remain indexed: only their data lives on disk, everything needed to plan
+/// queries and garbage collection stays in memory.
+#[derive(Debug, Clone)]
+pub(crate) struct SpilledChunk {
+    /// Where the chunk was written to, as an Arrow IPC file.
+    pub path: PathBuf,
+
+    pub entity_path: EntityPath,
+
+    /// The smallest [`RowId`] in the chunk, i.e. its key in `chunk_ids_per_min_row_id`.
+    pub min_row_id: RowId,
+
+    /// The time range covered by the chunk on each of its timelines.
+    pub time_ranges: IntMap<TimelineName, AbsoluteTimeRange>,
+
+    /// The stats that were removed from the store's accounting when the chunk was spilled.
+    pub stats: ChunkStoreChunkStats,
+}
+
+impl SpilledChunk {
+    fn time_range(&self, timeline: &TimelineName) -> Option<AbsoluteTimeRange> {
+        self.time_ranges.get(timeline).copied()
+    }
+}
+
+/// Writes a single chunk to the given path, as an Arrow IPC file.
+fn write_chunk(path: &Path, chunk: &Chunk) -> anyhow::Result<()> {
+    re_tracing::profile_function!();
+
+    let batch = chunk.to_record_batch()?;
+
+    let file = std::fs::File::create(path).with_context(|| format!("{path:?}"))?;
+    let mut writer =
+        arrow::ipc::writer::FileWriter::try_new(std::io::BufWriter::new(file), batch.schema_ref())?;
+    writer.write(&batch)?;
+    writer.finish()?;
+
+    Ok(())
+}
+
+/// Reads back a chunk that was written with [`write_chunk`].
+fn read_chunk(path: &Path) -> anyhow::Result<Chunk> {
+    re_tracing::profile_function!();
+
+    let file = std::fs::File::open(path).with_context(|| format!("{path:?}"))?;
+    let mut reader = arrow::ipc::reader::FileReader::try_new(std::io::BufReader::new(file), None)?;
+    let batch = reader
+        .next()
+        .with_context(|| format!("{path:?} is empty"))??;
+
+    Ok(Chunk::from_record_batch(&batch)?)
+}
+
+impl ChunkStore {
+    /// How many chunks are currently offloaded to disk.
+    ///
+    /// See [`crate::ChunkStoreConfig::spill_directory`].
+    #[inline]
+    pub fn num_spilled_chunks(&self) -> usize {
+        self.spilled_chunks.len()
+    }
+
+    /// Iterate over all chunks in the store, including the ones that were spilled to disk, which
+    /// get reloaded one at a time as the iterator advances.
+    ///
+    /// Resident chunks come first, in ascending [`ChunkId`] order, followed by spilled chunks in
+    /// the same order. Spilled chunks that fail to reload are skipped.
+    ///
+    /// This is what anything that needs the full contents of the store (e.g. saving a recording)
+    /// should use, rather than [`Self::iter_chunks`].
+    pub fn iter_chunks_including_spilled(&self) -> impl Iterator<Item = Arc<Chunk>> + '_ {
+        self.chunks_per_chunk_id.values().cloned().chain(
+            self.spilled_chunks
+                .keys()
+                .filter_map(|chunk_id| self.resident_or_spilled_chunk(chunk_id)),
+        )
+    }
+
+    /// Is this chunk indexed in the store, whether it lives in memory or on disk?
+    #[inline]
+    pub(crate) fn contains_chunk(&self, chunk_id: &ChunkId) -> bool {
+        self.chunks_per_chunk_id.contains_key(chunk_id)
+            || self.spilled_chunks.contains_key(chunk_id)
+    }
+
+    /// Returns the chunk with the given ID, transparently reloading it from disk if it was spilled.
+    ///
+    /// Reloaded chunks are _not_ kept in memory by the store: it is up to the caller (e.g. the
+    /// query caches) to hold on to them for as long as they need to, so that disk reads are
+    /// limited to cache misses.
+    pub(crate) fn resident_or_spilled_chunk(&self, chunk_id: &ChunkId) -> Option<Arc<Chunk>> {
+        if let Some(chunk) = self.chunks_per_chunk_id.get(chunk_id) {
+            return Some(Arc::clone(chunk));
+        }
+
+        let spilled = self.spilled_chunks.get(chunk_id)?;
+        match read_chunk(&spilled.path) {
+            Ok(chunk) => Some(Arc::new(chunk)),
+            Err(err) => {
+                re_log::error_once!(
+                    "Failed to reload spilled chunk {chunk_id} from {:?}: {err:#}",
+                    spilled.path
+                );
+                None
+            }
+        }
+    }
+
+    /// Offloads temporal chunks to disk, oldest first (according to [`RowId`] order), until at
+    /// least `num_bytes_to_spill` have been freed or the time budget runs out.
+    ///
+    /// Unlike actual garbage collection, spilling doesn't modify any index: from a query model
+    /// standpoint, nothing has changed. A [`ChunkStoreDiffKind::Spill`] diff is still returned
+    /// for each spilled chunk, so that subscribers (e.g. the query caches) can let go of it and the
+    /// memory actually gets freed.
+    ///
+    /// Returns how many bytes are still left to free, which the caller should drop instead (e.g.
+    /// because the disk is full).
+    ///
+    /// [`ChunkStoreDiffKind::Spill`]: crate::ChunkStoreDiffKind::Spill
+    pub(crate) fn spill_at_least_num_bytes(
+        &mut self,
+        spill_directory: &Path,
+        options: &GarbageCollectionOptions,
+        mut num_bytes_to_spill: f64,
+        protected_chunk_ids: &BTreeSet<ChunkId>,
+    ) -> (Vec<ChunkStoreDiff>, f64) {
+        re_tracing::profile_function!(re_format::format_bytes(num_bytes_to_spill));
+
+        if let Err(err) = std::fs::create_dir_all(spill_directory) {
+            re_log::error_once!("Failed to create spill directory {spill_directory:?}: {err}");
+            return (Vec::new(), num_bytes_to_spill);
+        }
+
+        let start_time = Instant::now();
+
+        let mut chunks_to_spill = Vec::new();
+        {
+            let mut num_bytes_selected = 0.0;
+            for chunk in self
+                .chunk_ids_per_min_row_id
+                .values()
+                .filter(|chunk_id| !protected_chunk_ids.contains(chunk_id))
+                .filter_map(|chunk_id| self.chunks_per_chunk_id.get(chunk_id))
+                .filter(|chunk| !chunk.is_static() && !options.is_chunk_protected(chunk))
+            {
+                if num_bytes_selected >= num_bytes_to_spill {
+                    break;
+                }
+
+                // NOTE: Do _NOT_ use `chunk.total_size_bytes` as it is sitting behind an Arc
+                // and would count as amortized (i.e. 0 bytes).
+                num_bytes_selected += <Chunk as SizeBytes>::total_size_bytes(&**chunk) as f64;
+                chunks_to_spill.push(Arc::clone(chunk));
+            }
+        }
+
+        let mut diffs = Vec::new();
+        for chunk in chunks_to_spill {
+            if start_time.elapsed() >= options.time_budget {
+                break;
+            }
+
+            let Some(min_row_id) = chunk.row_id_range().map(|(min, _)| min) else {
+                continue;
+            };
+
+            let path = spill_directory.join(format!("{}.arrow", chunk.id()));
+            if let Err(err) = write_chunk(&path, &chunk) {
+                re_log::error_once!("Failed to spill chunk to disk: {err:#}");
+                _ = std::fs::remove_file(&path);
+                break;
+            }
+
+            let stats = ChunkStoreChunkStats::from_chunk(&chunk);
+            self.temporal_chunks_stats -= stats;
+            self.chunks_per_chunk_id.remove(&chunk.id());
+
+            num_bytes_to_spill -= <Chunk as SizeBytes>::total_size_bytes(&*chunk) as f64;
+
+            self.spilled_chunks.insert(
+                chunk.id(),
+                SpilledChunk {
+                    path,
+                    entity_path: chunk.entity_path().clone(),
+                    min_row_id,
+                    time_ranges: chunk
+                        .timelines()
+                        .iter()
+                        .map(|(timeline, time_column)| (*timeline, time_column.time_range()))
+                        .collect(),
+                    stats,
+                },
+            );
+
+            diffs.push(ChunkStoreDiff::spill(chunk));
+        }
+
+        re_log::trace!(
+            kind = "gc",
+            id = self.gc_id,
+            num_spilled_chunks = self.spilled_chunks.len(),
+            "spilled chunks to disk"
+        );
+
+        (diffs, num_bytes_to_spill)
+    }
+
+    /// Reloads the matching spilled chunks back into memory.
+    ///
+    /// This must happen before any destructive operation (garbage collection, dropping data…)
+    /// can be applied to these chunks, so that subscribers get to see the data that is being
+    /// removed.
+    ///
+    /// Chunks that fail to reload are lost: they are removed from the GC order, and their
+    /// remaining index entries are ignored by queries.
+    pub(crate) fn restore_spilled_chunks(&mut self, predicate: impl Fn(&SpilledChunk) -> bool) {
+        let chunk_ids = self
+            .spilled_chunks
+            .iter()
+            .filter(|(_, spilled)| predicate(spilled))
+            .map(|(chunk_id, _)| *chunk_id)
+            .collect::<Vec<_>>();
+
+        self.restore_spilled_chunk_ids(chunk_ids);
+    }
+
+    /// Reloads the given chunks back into memory, if they are spilled.
+    ///
+    /// See [`Self::restore_spilled_chunks`].
+    pub(crate) fn restore_spilled_chunk_ids(
+        &mut self,
+        chunk_ids: impl IntoIterator<Item = ChunkId>,
+    ) {
+        if self.spilled_chunks.is_empty() {
+            return;
+        }
+
+        re_tracing::profile_function!();
+
+        for chunk_id in chunk_ids {
+            let Some(spilled) = self.spilled_chunks.remove(&chunk_id) else {
+                continue;
+            };
+
+            match read_chunk(&spilled.path) {
+                Ok(chunk) => {
+                    self.temporal_chunks_stats += spilled.stats;
+                    self.chunks_per_chunk_id.insert(chunk_id, Arc::new(chunk));
+                }
+                Err(err) => {
+                    re_log::error!(
+                        %chunk_id,
+                        path = ?spilled.path,
+                        "Failed to reload spilled chunk, its data is lost: {err:#}",
+                    );
+                    self.chunk_ids_per_min_row_id.remove(&spilled.min_row_id);
+                }
+            }
+
+            _ = std::fs::remove_file(&spilled.path);
+        }
+    }
+
+    /// Reloads the spilled chunks that overlap the given time range on the given timeline.
+    pub(crate) fn restore_spilled_chunks_in_range(
+        &mut self,
+        timeline: &TimelineName,
+        time_range: AbsoluteTimeRange,
+    ) {
+        self.restore_spilled_chunks(|spilled| {
+            spilled
+                .time_range(timeline)
+                .is_some_and(|spilled_range| spilled_range.intersects(time_range))
+        });
+    }
+
+    /// Deletes all spilled chunks from disk, without reloading them.
+    ///
+    /// The index entries of these chunks are left untouched: this is only meant to be used right
+    /// before the store itself gets dropped.
+    pub(crate) fn forget_spilled_chunks(&mut self) {
+        for (_, spilled) in std::mem::take(&mut self.spilled_chunks) {
+            self.chunk_ids_per_min_row_id.remove(&spilled.min_row_id);
+            _ = std::fs::remove_file(&spilled.path);
+        }
+    }
+}
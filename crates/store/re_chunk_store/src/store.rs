@@ -1,4 +1,5 @@
 use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
 
@@ -9,7 +10,10 @@ use re_chunk::{Chunk, ChunkId, ComponentIdentifier, RowId, TimelineName};
 use re_log_types::{EntityPath, StoreId, TimeInt, TimeType};
 use re_types_core::{ComponentDescriptor, ComponentType};
 
-use crate::{ChunkStoreChunkStats, ChunkStoreError, ChunkStoreResult};
+use crate::{
+    ChunkStoreChunkStats, ChunkStoreError, ChunkStoreResult, compaction::CompactionProgress,
+    spill::SpilledChunk,
+};
 
 // ---
 
@@ -72,6 +76,36 @@ pub struct ChunkStoreConfig {
     /// The default byte threshold is set to 8MiB, which is a reasonable unit of work when e.g.
     /// sending chunks over the network.
     pub chunk_max_rows_if_unsorted: u64,
+
+    /// If set, temporal [`Chunk`]s are offloaded to this directory when the garbage collector
+    /// needs to free up memory, rather than being dropped.
+    ///
+    /// Spilled chunks are written as Arrow IPC files, and remain indexed: they are transparently
+    /// reloaded whenever a query needs them (see [`ChunkStore::latest_at_relevant_chunks`] and
+    /// [`ChunkStore::range_relevant_chunks`]), so that the history of a recording stays available
+    /// on machines with limited RAM.
+    ///
+    /// Spilling a chunk emits a [`crate::ChunkStoreDiffKind::Spill`] event, so that subscribers
+    /// (e.g. the query caches) release it. Whatever cannot be spilled within the garbage
+    /// collector's time budget (or at all, e.g. because the disk is full) is dropped instead.
+    ///
+    /// Only memory-driven garbage collection spills data (see
+    /// [`crate::GarbageCollectionTarget::DropAtLeastFraction`]). Everything else (retention
+    /// policies, [`crate::GarbageCollectionTarget::Everything`], dropping entities or time
+    /// ranges…) drops spilled data for good, after having reloaded it so that subscribers can
+    /// be notified.
+    ///
+    /// The files are removed once they are not needed anymore, including when the store is
+    /// dropped. Disabled by default.
+    pub spill_directory: Option<PathBuf>,
+
+    /// If `true`, per-component statistics are computed for every temporal [`Chunk`] on
+    /// insertion, so that value-based filters can skip chunks without looking at their data.
+    ///
+    /// See [`Chunk::compute_statistics`].
+    ///
+    /// Statistics take up memory and time to compute, so this is disabled by default.
+    pub compute_statistics: bool,
     //
     // TODO(cmc): It could make sense to have time-range-based thresholds in here, since the time
     // range covered by a chunk has direct effects on A) the complexity of backward walks and
@@ -109,6 +143,10 @@ impl ChunkStoreConfig {
         chunk_max_rows: 4096,
 
         chunk_max_rows_if_unsorted: 1024,
+
+        spill_directory: None,
+
+        compute_statistics: false,
     };
 
     /// [`Self::DEFAULT`], but with compaction entirely disabled.
@@ -131,6 +169,8 @@ impl ChunkStoreConfig {
         chunk_max_bytes: 0,
         chunk_max_rows: 0,
         chunk_max_rows_if_unsorted: 0,
+        spill_directory: None,
+        compute_statistics: false,
     };
 
     /// Environment variable to configure [`Self::enable_changelog`].
@@ -147,6 +187,12 @@ impl ChunkStoreConfig {
     // NOTE: Shared with the same env-var on the batcher side, for consistency.
     pub const ENV_CHUNK_MAX_ROWS_IF_UNSORTED: &'static str = "RERUN_CHUNK_MAX_ROWS_IF_UNSORTED";
 
+    /// Environment variable to configure [`Self::spill_directory`].
+    pub const ENV_CHUNK_SPILL_DIR: &'static str = "RERUN_CHUNK_SPILL_DIR";
+
+    /// Environment variable to configure [`Self::compute_statistics`].
+    pub const ENV_CHUNK_STATISTICS: &'static str = "RERUN_CHUNK_STATISTICS";
+
     /// Creates a new `ChunkStoreConfig` using the default values, optionally overridden
     /// through the environment.
     ///
@@ -159,8 +205,9 @@ impl ChunkStoreConfig {
     /// Returns a copy of `self`, overriding existing fields with values from the environment if
     /// they are present.
     ///
-    /// See [`Self::ENV_STORE_ENABLE_CHANGELOG`], [`Self::ENV_CHUNK_MAX_BYTES`], [`Self::ENV_CHUNK_MAX_ROWS`]
-    /// and [`Self::ENV_CHUNK_MAX_ROWS_IF_UNSORTED`].
+    /// See [`Self::ENV_STORE_ENABLE_CHANGELOG`], [`Self::ENV_CHUNK_MAX_BYTES`], [`Self::ENV_CHUNK_MAX_ROWS`],
+    /// [`Self::ENV_CHUNK_MAX_ROWS_IF_UNSORTED`], [`Self::ENV_CHUNK_SPILL_DIR`] and
+    /// [`Self::ENV_CHUNK_STATISTICS`].
     pub fn apply_env(&self) -> ChunkStoreResult<Self> {
         let mut new = self.clone();
 
@@ -197,6 +244,18 @@ impl ChunkStoreConfig {
                 })?;
         }
 
+        if let Ok(s) = std::env::var(Self::ENV_CHUNK_SPILL_DIR) {
+            new.spill_directory = (!s.is_empty()).then(|| PathBuf::from(s));
+        }
+
+        if let Ok(s) = std::env::var(Self::ENV_CHUNK_STATISTICS) {
+            new.compute_statistics = s.parse().map_err(|err| ChunkStoreError::ParseConfig {
+                name: Self::ENV_CHUNK_STATISTICS,
+                value: s.clone(),
+                err: Box::new(err),
+            })?;
+        }
+
         Ok(new)
     }
 }
@@ -212,6 +271,8 @@ fn chunk_store_config() {
         std::env::set_var("RERUN_CHUNK_MAX_BYTES", "42");
         std::env::set_var("RERUN_CHUNK_MAX_ROWS", "666");
         std::env::set_var("RERUN_CHUNK_MAX_ROWS_IF_UNSORTED", "999");
+        std::env::set_var("RERUN_CHUNK_SPILL_DIR", "/tmp/rerun_spill");
+        std::env::set_var("RERUN_CHUNK_STATISTICS", "true");
     };
 
     let config = ChunkStoreConfig::from_env().unwrap();
@@ -221,6 +282,8 @@ fn chunk_store_config() {
         chunk_max_bytes: 42,
         chunk_max_rows: 666,
         chunk_max_rows_if_unsorted: 999,
+        spill_directory: Some(PathBuf::from("/tmp/rerun_spill")),
+        compute_statistics: true,
     };
 
     assert_eq!(expected, config);
@@ -423,6 +486,12 @@ pub struct ChunkStore {
 
     pub(crate) chunks_per_chunk_id: BTreeMap<ChunkId, Arc<Chunk>>,
 
+    /// Temporal chunks that were offloaded to disk, see [`ChunkStoreConfig::spill_directory`].
+    ///
+    /// A chunk is either in there or in [`Self::chunks_per_chunk_id`], never both.
+    /// Spilled chunks remain referenced by all the other indices.
+    pub(crate) spilled_chunks: BTreeMap<ChunkId, SpilledChunk>,
+
     /// All [`ChunkId`]s currently in the store, indexed by the smallest [`RowId`] in each of them.
     ///
     /// This is effectively all chunks in global data order. Used for garbage collection.
@@ -468,6 +537,9 @@ pub struct ChunkStore {
 
     /// Monotonically increasing ID for store events.
     pub(crate) event_id: AtomicU64,
+
+    /// Where background compaction left off, see [`Self::compact`].
+    pub(crate) compaction_progress: CompactionProgress,
 }
 
 impl Drop for ChunkStore {
@@ -476,6 +548,10 @@ impl Drop for ChunkStore {
         // and therefore they can just drop entire chunks of their own state.
         Self::drop_per_store_subscribers(&self.id());
 
+        // Reloading spilled chunks just so that they can be dropped right away would defeat the
+        // purpose of spilling them in the first place: they are silently forgotten instead.
+        self.forget_spilled_chunks();
+
         if self.config.enable_changelog {
             // Then, if the changelog is enabled, trigger a full GC: this will notify all remaining
             // subscribers of all the chunks that were dropped by dropping the store itself.
@@ -485,27 +561,44 @@ impl Drop for ChunkStore {
 }
 
 impl Clone for ChunkStore {
+    /// Spilled chunks are reloaded into memory: the clone never shares any file with the original
+    /// store.
     #[inline]
     fn clone(&self) -> Self {
         re_tracing::profile_function!();
+
+        let mut chunks_per_chunk_id = self.chunks_per_chunk_id.clone();
+        for chunk_id in self.spilled_chunks.keys() {
+            if let Some(chunk) = self.resident_or_spilled_chunk(chunk_id) {
+                chunks_per_chunk_id.insert(*chunk_id, chunk);
+            }
+        }
+        let spilled_chunks_stats = self
+            .spilled_chunks
+            .values()
+            .map(|spilled| spilled.stats)
+            .sum::<ChunkStoreChunkStats>();
+
         Self {
             id: self.id.clone(),
             config: self.config.clone(),
             time_type_registry: self.time_type_registry.clone(),
             type_registry: self.type_registry.clone(),
             per_column_metadata: self.per_column_metadata.clone(),
-            chunks_per_chunk_id: self.chunks_per_chunk_id.clone(),
+            chunks_per_chunk_id,
+            spilled_chunks: Default::default(),
             chunk_ids_per_min_row_id: self.chunk_ids_per_min_row_id.clone(),
             temporal_chunk_ids_per_entity_per_component: self
                 .temporal_chunk_ids_per_entity_per_component
                 .clone(),
             temporal_chunk_ids_per_entity: self.temporal_chunk_ids_per_entity.clone(),
-            temporal_chunks_stats: self.temporal_chunks_stats,
+            temporal_chunks_stats: self.temporal_chunks_stats + spilled_chunks_stats,
             static_chunk_ids_per_entity: self.static_chunk_ids_per_entity.clone(),
             static_chunks_stats: self.static_chunks_stats,
             insert_id: Default::default(),
             gc_id: Default::default(),
             event_id: Default::default(),
+            compaction_progress: Default::default(),
         }
     }
 }
@@ -519,6 +612,7 @@ impl std::fmt::Display for ChunkStore {
             type_registry: _,
             per_column_metadata: _,
             chunks_per_chunk_id,
+            spilled_chunks,
             chunk_ids_per_min_row_id: chunk_id_per_min_row_id,
             temporal_chunk_ids_per_entity_per_component: _,
             temporal_chunk_ids_per_entity: _,
@@ -528,6 +622,7 @@ impl std::fmt::Display for ChunkStore {
             insert_id: _,
             gc_id: _,
             event_id: _,
+            compaction_progress: _,
         } = self;
 
         f.write_str("ChunkStore {\n")?;
@@ -551,6 +646,11 @@ impl std::fmt::Display for ChunkStore {
                 } else {
                     f.write_str(&indent::indent_all_by(8, format!("{chunk}\n")))?;
                 }
+            } else if let Some(spilled) = spilled_chunks.get(chunk_id) {
+                f.write_str(&indent::indent_all_by(
+                    8,
+                    format!("<spilled: {:?}>\n", spilled.path),
+                ))?;
             } else {
                 f.write_str(&indent::indent_all_by(8, "<not_found>\n"))?;
             }
@@ -581,6 +681,7 @@ impl ChunkStore {
             per_column_metadata: Default::default(),
             chunk_ids_per_min_row_id: Default::default(),
             chunks_per_chunk_id: Default::default(),
+            spilled_chunks: Default::default(),
             temporal_chunk_ids_per_entity_per_component: Default::default(),
             temporal_chunk_ids_per_entity: Default::default(),
             temporal_chunks_stats: Default::default(),
@@ -589,6 +690,7 @@ impl ChunkStore {
             insert_id: 0,
             gc_id: 0,
             event_id: AtomicU64::new(0),
+            compaction_progress: Default::default(),
         }
     }
 
@@ -625,12 +727,17 @@ impl ChunkStore {
     }
 
     /// Iterate over all chunks in the store, in ascending [`ChunkId`] order.
+    ///
+    /// Chunks that were spilled to disk are skipped, see [`ChunkStoreConfig::spill_directory`] and
+    /// [`Self::iter_chunks_including_spilled`].
     #[inline]
     pub fn iter_chunks(&self) -> impl Iterator<Item = &Arc<Chunk>> + '_ {
         self.chunks_per_chunk_id.values()
     }
 
     /// Get a chunk based on its ID.
+    ///
+    /// Only looks at chunks that are currently in memory, see [`ChunkStoreConfig::spill_directory`].
     #[inline]
     pub fn chunk(&self, id: &ChunkId) -> Option<&Arc<Chunk>> {
         self.chunks_per_chunk_id.get(id)
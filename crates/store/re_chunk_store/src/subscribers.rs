@@ -53,6 +53,7 @@ pub trait ChunkStoreSubscriber: std::any::Any + Send + Sync {
     ///         match event.kind {
     ///             ChunkStoreDiffKind::Addition => println!("Row added: {}", event.row_id),
     ///             ChunkStoreDiffKind::Deletion => println!("Row removed: {}", event.row_id),
+    ///             ChunkStoreDiffKind::Spill => println!("Row offloaded: {}", event.row_id),
     ///         }
     ///     }
     /// }
@@ -0,0 +1,478 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use ahash::{HashMap, HashSet};
+use arrow::{
+    array::{Array as _, ArrayRef as ArrowArrayRef, AsArray as _},
+    datatypes::{DataType as ArrowDataType, Float64Type as ArrowFloat64Type, Int64Type},
+};
+use nohash_hasher::IntMap;
+
+use re_chunk::{Chunk, RowId, TimelineName};
+use re_log_types::{EntityPath, ResolvedEntityPathFilter, StoreId, TimeInt};
+use re_types_core::ComponentType;
+
+use crate::{ChunkStore, ChunkStoreDiffKind, ChunkStoreEvent, ChunkStoreSubscriber, FilterValue};
+
+// ---
+
+/// How the values of a component get indexed by [`ValueIndexes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueIndexKind {
+    /// Indexes every distinct value, for exact lookups.
+    ///
+    /// Meant for identifiers and labels: class ids, keypoint ids, strings…
+    Hash,
+
+    /// Indexes values in order, for range lookups.
+    ///
+    /// Meant for numeric data, e.g. scalars. Integers are widened to `f64`.
+    Sorted,
+}
+
+/// What the values of a component must look like for a row to be returned by [`ValueIndexes::query`].
+///
+/// A row matches if any of its instances matches.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValuePredicate {
+    /// The value is equal to the given value.
+    ///
+    /// Integer and floating point values are compared numerically.
+    Equals(FilterValue),
+
+    /// The value lies within the given inclusive numeric range.
+    InRange { min: f64, max: f64 },
+
+    /// The value is a string that contains the given substring.
+    Contains(String),
+}
+
+/// A lookup into [`ValueIndexes`].
+#[derive(Debug, Clone)]
+pub struct ValueQuery {
+    /// The timeline that the returned times are expressed in.
+    pub timeline: TimelineName,
+
+    /// The type of the component whose values are being looked for, e.g. `rerun.components.ClassId`.
+    ///
+    /// All the components of that type are looked at, regardless of their archetype.
+    pub component_type: ComponentType,
+
+    pub predicate: ValuePredicate,
+
+    /// Only consider the entities matched by this filter.
+    ///
+    /// All entities are considered if `None`.
+    pub entity_filter: Option<ResolvedEntityPathFilter>,
+}
+
+impl ValueQuery {
+    #[inline]
+    pub fn new(
+        timeline: impl Into<TimelineName>,
+        component_type: impl Into<ComponentType>,
+        predicate: ValuePredicate,
+    ) -> Self {
+        Self {
+            timeline: timeline.into(),
+            component_type: component_type.into(),
+            predicate,
+            entity_filter: None,
+        }
+    }
+
+    #[inline]
+    pub fn with_entity_filter(mut self, entity_filter: ResolvedEntityPathFilter) -> Self {
+        self.entity_filter = Some(entity_filter);
+        self
+    }
+}
+
+/// Secondary indexes on the _values_ of some components, maintained as a [`ChunkStoreSubscriber`].
+///
+/// These answer questions such as "at which times did `/tracks/**` have `ClassId == 7`?" or
+/// "where did `Text` contain `ERROR`?" without having to scan the data, see [`Self::query`].
+///
+/// Indexing is opt-in, per component type, see [`ValueIndexKind`].
+/// Only temporal data is indexed: static data has no time to jump to.
+///
+/// Register it with [`ChunkStore::register_subscriber`] so that it gets kept up to date with all
+/// stores, and access it with [`ChunkStore::with_subscriber`]. Stores that already contain data at
+/// registration time can be caught up with [`Self::index_store`].
+pub struct ValueIndexes {
+    /// Which component types are indexed, and how.
+    indexed: IntMap<ComponentType, ValueIndexKind>,
+
+    per_store: HashMap<StoreId, StoreValueIndexes>,
+}
+
+/// All the value indexes of a single store.
+#[derive(Default)]
+struct StoreValueIndexes {
+    per_timeline: IntMap<TimelineName, IntMap<ComponentType, ValueIndex>>,
+}
+
+enum ValueIndex {
+    Hash(HashMap<FilterValue, BTreeSet<ValueIndexEntry>>),
+    Sorted(BTreeMap<SortedKey, BTreeSet<ValueIndexEntry>>),
+}
+
+/// A single row, on a single timeline.
+///
+/// Ordered by time first, so that results come out in time order.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct ValueIndexEntry {
+    time: TimeInt,
+    entity_path: EntityPath,
+    row_id: RowId,
+}
+
+/// An `f64` with a total order, see [`f64::total_cmp`].
+#[derive(Debug, Clone, Copy)]
+struct SortedKey(f64);
+
+impl PartialEq for SortedKey {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for SortedKey {}
+
+impl PartialOrd for SortedKey {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SortedKey {
+    #[inline]
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl ValueIndexes {
+    /// Indexes the components of the given types, using the given kind of index.
+    pub fn new(indexed: impl IntoIterator<Item = (ComponentType, ValueIndexKind)>) -> Self {
+        Self {
+            indexed: indexed.into_iter().collect(),
+            per_store: Default::default(),
+        }
+    }
+
+    /// Indexes all the data that is currently in the given store, including spilled chunks.
+    ///
+    /// This is how stores that already contained data when the indexes were registered get caught up.
+    pub fn index_store(&mut self, store: &ChunkStore) {
+        re_tracing::profile_function!();
+
+        let indexes = self.per_store.entry(store.id().clone()).or_default();
+        for chunk in store
+            .chunk_ids_per_min_row_id
+            .values()
+            .filter_map(|chunk_id| store.resident_or_spilled_chunk(chunk_id))
+        {
+            indexes.add_chunk(&self.indexed, &chunk);
+        }
+    }
+
+    /// Returns all the `(entity, time)` pairs whose data matches the given query, in time order.
+    ///
+    /// Returns nothing if the component type of the query isn't indexed.
+    pub fn query(&self, store_id: &StoreId, query: &ValueQuery) -> Vec<(EntityPath, TimeInt)> {
+        re_tracing::profile_function!();
+
+        let Some(index) = self
+            .per_store
+            .get(store_id)
+            .and_then(|indexes| indexes.per_timeline.get(&query.timeline))
+            .and_then(|per_component| per_component.get(&query.component_type))
+        else {
+            return Vec::new();
+        };
+
+        let mut entries: BTreeSet<&ValueIndexEntry> = match index {
+            ValueIndex::Hash(index) => match &query.predicate {
+                ValuePredicate::Equals(value) => equal_keys(value)
+                    .iter()
+                    .filter_map(|key| index.get(key))
+                    .flatten()
+                    .collect(),
+
+                ValuePredicate::InRange { min, max } => index
+                    .iter()
+                    .filter(|(key, _)| as_f64(key).is_some_and(|key| *min <= key && key <= *max))
+                    .flat_map(|(_, entries)| entries)
+                    .collect(),
+
+                ValuePredicate::Contains(needle) => index
+                    .iter()
+                    .filter(|(key, _)| {
+                        matches!(key, FilterValue::String(key) if key.contains(needle.as_str()))
+                    })
+                    .flat_map(|(_, entries)| entries)
+                    .collect(),
+            },
+
+            ValueIndex::Sorted(index) => {
+                let range = match &query.predicate {
+                    ValuePredicate::Equals(value) => as_f64(value).map(|value| (value, value)),
+                    ValuePredicate::InRange { min, max } => Some((*min, *max)),
+                    ValuePredicate::Contains(_) => None,
+                };
+
+                match range {
+                    Some((min, max)) if min <= max => index
+                        .range(SortedKey(min)..=SortedKey(max))
+                        .flat_map(|(_, entries)| entries)
+                        .collect(),
+                    _ => BTreeSet::new(),
+                }
+            }
+        };
+
+        if let Some(entity_filter) = &query.entity_filter {
+            entries.retain(|entry| entity_filter.matches(&entry.entity_path));
+        }
+
+        // A row can match several times, e.g. if several of its instances match.
+        let mut results: Vec<_> = entries
+            .into_iter()
+            .map(|entry| (entry.entity_path.clone(), entry.time))
+            .collect();
+        results.dedup();
+
+        results
+    }
+}
+
+impl ChunkStoreSubscriber for ValueIndexes {
+    fn name(&self) -> String {
+        "rerun.store.ValueIndexes".into()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn on_events(&mut self, events: &[ChunkStoreEvent]) {
+        re_tracing::profile_function!();
+
+        for event in events {
+            let indexes = self.per_store.entry(event.store_id.clone()).or_default();
+
+            // NOTE: Entries are keyed by `RowId`, so it doesn't matter whether additions expose
+            // pre- or post-compaction chunks: rows that were already indexed are just no-ops.
+            match event.kind {
+                ChunkStoreDiffKind::Addition => indexes.add_chunk(&self.indexed, &event.chunk),
+                ChunkStoreDiffKind::Deletion => indexes.remove_chunk(&self.indexed, &event.chunk),
+                // Spilled data can still be queried.
+                ChunkStoreDiffKind::Spill => {}
+            }
+        }
+    }
+
+    fn on_drop(&mut self, store_id: &StoreId) {
+        self.per_store.remove(store_id);
+    }
+}
+
+impl StoreValueIndexes {
+    fn add_chunk(&mut self, indexed: &IntMap<ComponentType, ValueIndexKind>, chunk: &Chunk) {
+        for_each_entry(
+            indexed,
+            chunk,
+            |timeline, component_type, kind, key, entry| {
+                let index = self
+                    .per_timeline
+                    .entry(*timeline)
+                    .or_default()
+                    .entry(component_type)
+                    .or_insert_with(|| match kind {
+                        ValueIndexKind::Hash => ValueIndex::Hash(Default::default()),
+                        ValueIndexKind::Sorted => ValueIndex::Sorted(Default::default()),
+                    });
+
+                match index {
+                    ValueIndex::Hash(index) => {
+                        index.entry(key).or_default().insert(entry);
+                    }
+                    ValueIndex::Sorted(index) => {
+                        if let Some(key) = as_f64(&key) {
+                            index.entry(SortedKey(key)).or_default().insert(entry);
+                        }
+                    }
+                }
+            },
+        );
+    }
+
+    fn remove_chunk(&mut self, indexed: &IntMap<ComponentType, ValueIndexKind>, chunk: &Chunk) {
+        for_each_entry(
+            indexed,
+            chunk,
+            |timeline, component_type, _kind, key, entry| {
+                let Some(index) = self
+                    .per_timeline
+                    .get_mut(timeline)
+                    .and_then(|per_component| per_component.get_mut(&component_type))
+                else {
+                    return;
+                };
+
+                match index {
+                    ValueIndex::Hash(index) => {
+                        if let Some(entries) = index.get_mut(&key) {
+                            entries.remove(&entry);
+                            if entries.is_empty() {
+                                index.remove(&key);
+                            }
+                        }
+                    }
+                    ValueIndex::Sorted(index) => {
+                        let Some(key) = as_f64(&key).map(SortedKey) else {
+                            return;
+                        };
+                        if let Some(entries) = index.get_mut(&key) {
+                            entries.remove(&entry);
+                            if entries.is_empty() {
+                                index.remove(&key);
+                            }
+                        }
+                    }
+                }
+            },
+        );
+    }
+}
+
+/// Calls `f` once for every distinct indexed value of every row of the chunk, on every timeline.
+fn for_each_entry(
+    indexed: &IntMap<ComponentType, ValueIndexKind>,
+    chunk: &Chunk,
+    mut f: impl FnMut(&TimelineName, ComponentType, ValueIndexKind, FilterValue, ValueIndexEntry),
+) {
+    if chunk.is_static() {
+        return;
+    }
+
+    #[expect(clippy::iter_over_hash_type)] // order doesn't matter, the indexes are sorted anyway
+    for (descr, list_array) in chunk.components().iter() {
+        let Some(component_type) = descr.component_type else {
+            continue;
+        };
+        let Some(kind) = indexed.get(&component_type).copied() else {
+            continue;
+        };
+        let Some(values) = index_keys(list_array.values()) else {
+            re_log::debug_once!(
+                "Cannot index values of {component_type}: unsupported datatype {}",
+                list_array.values().data_type()
+            );
+            continue;
+        };
+
+        let offsets = list_array.offsets();
+        for (row, row_id) in chunk.row_ids().enumerate() {
+            if list_array.is_null(row) {
+                continue;
+            }
+
+            let start = offsets[row] as usize;
+            let end = offsets[row + 1] as usize;
+            let keys: HashSet<&FilterValue> = values[start..end].iter().flatten().collect();
+
+            #[expect(clippy::iter_over_hash_type)]
+            // order doesn't matter, the indexes are sorted anyway
+            for (timeline, time_column) in chunk.timelines() {
+                let time = TimeInt::new_temporal(time_column.times_raw()[row]);
+                for key in &keys {
+                    f(
+                        timeline,
+                        component_type,
+                        kind,
+                        (*key).clone(),
+                        ValueIndexEntry {
+                            time,
+                            entity_path: chunk.entity_path().clone(),
+                            row_id,
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Converts a flat array of values into index keys, or `None` if the datatype cannot be indexed.
+///
+/// Integers are widened to `i64` and floats to `f64`, the same way value filters do.
+fn index_keys(values: &ArrowArrayRef) -> Option<Vec<Option<FilterValue>>> {
+    let datatype = values.data_type();
+
+    if datatype.is_integer() {
+        let values = arrow::compute::cast(values, &ArrowDataType::Int64).ok()?;
+        return Some(
+            values
+                .as_primitive::<Int64Type>()
+                .iter()
+                .map(|value| value.map(FilterValue::Int))
+                .collect(),
+        );
+    }
+
+    if datatype.is_floating() {
+        let values = arrow::compute::cast(values, &ArrowDataType::Float64).ok()?;
+        return Some(
+            values
+                .as_primitive::<ArrowFloat64Type>()
+                .iter()
+                .map(|value| {
+                    value
+                        .filter(|value| !value.is_nan())
+                        .map(FilterValue::Float)
+                })
+                .collect(),
+        );
+    }
+
+    let to_string = |value: Option<&str>| value.map(|value| FilterValue::String(value.to_owned()));
+    match datatype {
+        ArrowDataType::Boolean => Some(
+            values
+                .as_boolean()
+                .iter()
+                .map(|value| value.map(FilterValue::Bool))
+                .collect(),
+        ),
+        ArrowDataType::Utf8 => Some(values.as_string::<i32>().iter().map(to_string).collect()),
+        ArrowDataType::LargeUtf8 => Some(values.as_string::<i64>().iter().map(to_string).collect()),
+        ArrowDataType::Utf8View => Some(values.as_string_view().iter().map(to_string).collect()),
+        _ => None,
+    }
+}
+
+fn as_f64(value: &FilterValue) -> Option<f64> {
+    match value {
+        FilterValue::Int(value) => Some(*value as f64),
+        FilterValue::Float(value) => Some(*value),
+        FilterValue::Bool(_) | FilterValue::String(_) => None,
+    }
+}
+
+/// All the keys that are equal to the given value: integers and floats are compared numerically.
+fn equal_keys(value: &FilterValue) -> Vec<FilterValue> {
+    match value {
+        FilterValue::Int(int) => vec![value.clone(), FilterValue::Float(*int as f64)],
+        FilterValue::Float(float) if float.fract() == 0.0 => {
+            vec![value.clone(), FilterValue::Int(*float as i64)]
+        }
+        _ => vec![value.clone()],
+    }
+}
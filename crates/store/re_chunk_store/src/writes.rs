@@ -28,6 +28,18 @@ impl ChunkStore {
     /// * Inserting a duplicated [`ChunkId`] will result in a no-op.
     /// * Inserting an empty [`Chunk`] will result in a no-op.
     pub fn insert_chunk(&mut self, chunk: &Arc<Chunk>) -> ChunkStoreResult<Vec<ChunkStoreEvent>> {
+        self.insert_chunk_impl(chunk, true)
+    }
+
+    /// See [`Self::insert_chunk`].
+    ///
+    /// If `allow_compaction` is false, the chunk is stored as-is, even if it could have been
+    /// compacted with one of its neighbors.
+    pub(crate) fn insert_chunk_impl(
+        &mut self,
+        chunk: &Arc<Chunk>,
+        allow_compaction: bool,
+    ) -> ChunkStoreResult<Vec<ChunkStoreEvent>> {
         if chunk.components().is_empty() {
             // This can happen in 2 scenarios: A) a badly manually crafted chunk or B) an Indicator
             // chunk that went through the Sorbet migration process, and ended up with zero
@@ -42,7 +54,7 @@ impl ChunkStore {
             return Ok(vec![]);
         }
 
-        if self.chunks_per_chunk_id.contains_key(&chunk.id()) {
+        if self.contains_chunk(&chunk.id()) {
             // We assume that chunk IDs are unique, and that reinserting a chunk has no effect.
             re_log::debug_once!(
                 "Chunk #{} was inserted more than once (this has no effect)",
@@ -198,7 +210,11 @@ impl ChunkStore {
             let (elected_chunk, chunk_or_compacted) = {
                 re_tracing::profile_scope!("election");
 
-                let elected_chunk = self.find_and_elect_compaction_candidate(chunk);
+                let elected_chunk = if allow_compaction {
+                    self.find_and_elect_compaction_candidate(chunk)
+                } else {
+                    None
+                };
 
                 let chunk_or_compacted = if let Some(elected_chunk) = &elected_chunk {
                     let chunk_rowid_min = chunk.row_id_range().map(|(min, _)| min);
@@ -235,6 +251,18 @@ impl ChunkStore {
                 (elected_chunk, chunk_or_compacted)
             };
 
+            // Computed before anything gets accounted for, since they make up for part of the
+            // size of the chunk. See `Chunk::compute_statistics`.
+            let chunk_or_compacted =
+                if self.config.compute_statistics && chunk_or_compacted.statistics().is_none() {
+                    re_tracing::profile_scope!("statistics");
+                    let mut chunk = (*chunk_or_compacted).clone();
+                    chunk.compute_statistics();
+                    Arc::new(chunk)
+                } else {
+                    chunk_or_compacted
+                };
+
             {
                 re_tracing::profile_scope!("insertion (w/ component)");
 
@@ -452,7 +480,12 @@ impl ChunkStore {
     /// Everytime we encounter a neighbor, it earns points.
     ///
     /// The neighbor with the most points at the end of the process is elected.
-    fn find_and_elect_compaction_candidate(&self, chunk: &Arc<Chunk>) -> Option<Arc<Chunk>> {
+    ///
+    /// The chunk itself might or might not already be in the store, see [`Self::compact`].
+    pub(crate) fn find_and_elect_compaction_candidate(
+        &self,
+        chunk: &Arc<Chunk>,
+    ) -> Option<Arc<Chunk>> {
         re_tracing::profile_function!();
 
         {
@@ -464,6 +497,8 @@ impl ChunkStore {
                 chunk_max_bytes,
                 chunk_max_rows,
                 chunk_max_rows_if_unsorted,
+                spill_directory: _,
+                compute_statistics: _,
             } = self.config;
 
             let total_bytes = <Chunk as SizeBytes>::total_size_bytes(chunk);
@@ -484,11 +519,17 @@ impl ChunkStore {
         let mut candidates_below_threshold: HashMap<ChunkId, bool> = HashMap::default();
         let mut check_if_chunk_below_threshold =
             |store: &Self, candidate_chunk_id: ChunkId| -> bool {
+                if candidate_chunk_id == chunk.id() {
+                    return false; // can't compact a chunk with itself
+                }
+
                 let ChunkStoreConfig {
                     enable_changelog: _,
                     chunk_max_bytes,
                     chunk_max_rows,
                     chunk_max_rows_if_unsorted,
+                    spill_directory: _,
+                    compute_statistics: _,
                 } = store.config;
 
                 *candidates_below_threshold
@@ -604,6 +645,8 @@ impl ChunkStore {
 
         self.gc_id += 1; // close enough
 
+        self.restore_spilled_chunks(|spilled| spilled.entity_path == *entity_path);
+
         let generation = self.generation();
 
         let Self {
@@ -613,6 +656,7 @@ impl ChunkStore {
             type_registry: _,
             per_column_metadata,
             chunks_per_chunk_id,
+            spilled_chunks: _, // restored above
             chunk_ids_per_min_row_id,
             temporal_chunk_ids_per_entity_per_component,
             temporal_chunk_ids_per_entity,
@@ -622,6 +666,7 @@ impl ChunkStore {
             insert_id: _,
             gc_id: _,
             event_id,
+            compaction_progress: _,
         } = self;
 
         per_column_metadata.remove(entity_path);
@@ -1176,6 +1221,8 @@ mod tests {
                     chunk_max_bytes: u64::MAX,
                     chunk_max_rows: u64::MAX,
                     chunk_max_rows_if_unsorted: u64::MAX,
+                    spill_directory: None,
+                    compute_statistics: false,
                 },
             );
 
@@ -1205,6 +1252,8 @@ mod tests {
                     chunk_max_bytes: u64::MAX,
                     chunk_max_rows: u64::MAX,
                     chunk_max_rows_if_unsorted: u64::MAX,
+                    spill_directory: None,
+                    compute_statistics: false,
                 },
             );
 
@@ -0,0 +1,77 @@
+use std::{sync::Arc, time::Duration};
+
+use re_chunk::{Chunk, RowId};
+use re_chunk_store::{ChunkStore, ChunkStoreConfig, ChunkStoreDiffKind};
+use re_log_types::{
+    EntityPath, build_frame_nr,
+    example_components::{MyPoint, MyPoints},
+};
+
+// ---
+
+#[test]
+fn compact_split_then_merge() -> anyhow::Result<()> {
+    re_log::setup_logging();
+
+    let mut store = ChunkStore::new(
+        re_log_types::StoreId::random(re_log_types::StoreKind::Recording, "test_app"),
+        ChunkStoreConfig {
+            chunk_max_rows_if_unsorted: 2,
+            ..ChunkStoreConfig::DEFAULT
+        },
+    );
+
+    let entity_path = EntityPath::from("points");
+    let descr = MyPoints::descriptor_points();
+
+    let chunk_for_frames = |frames: &[i64]| {
+        let mut builder = Chunk::builder(entity_path.clone());
+        for &frame in frames {
+            let points = MyPoint::from_iter(frame as u32..frame as u32 + 1);
+            builder = builder.with_component_batches(
+                RowId::new(),
+                [build_frame_nr(frame)],
+                [(descr.clone(), &points as _)],
+            );
+        }
+        builder.build().map(Arc::new)
+    };
+
+    // Too large to stay unsorted, but it's too late to do anything about it at insertion time.
+    store.insert_chunk(&chunk_for_frames(&[10, 11, 1, 2])?)?;
+    // Its only neighbor is unsorted, so this one can't be merged at insertion time either.
+    store.insert_chunk(&chunk_for_frames(&[3])?)?;
+    assert_eq!(2, store.num_chunks());
+
+    let events = store.compact(Duration::MAX);
+
+    // The unsorted chunk gets split into [10, 11] and [1, 2]…
+    // …and then [1, 2] gets merged with [3].
+    let kinds = events.iter().map(|event| event.kind).collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            ChunkStoreDiffKind::Deletion,
+            ChunkStoreDiffKind::Addition,
+            ChunkStoreDiffKind::Addition,
+            ChunkStoreDiffKind::Deletion,
+            ChunkStoreDiffKind::Deletion,
+            ChunkStoreDiffKind::Addition,
+        ],
+        kinds
+    );
+
+    let mut num_rows = store
+        .iter_chunks()
+        .inspect(|chunk| assert!(chunk.is_time_sorted()))
+        .map(|chunk| chunk.num_rows())
+        .collect::<Vec<_>>();
+    num_rows.sort_unstable();
+    assert_eq!(vec![2, 3], num_rows);
+
+    // Merging the remaining two chunks would result in an unsorted chunk that's too large: there's
+    // nothing left to do.
+    assert!(store.compact(Duration::MAX).is_empty());
+    assert_eq!(2, store.num_chunks());
+
+    Ok(())
+}
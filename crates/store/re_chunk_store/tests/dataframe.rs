@@ -118,9 +118,12 @@ fn schema_for_query() -> anyhow::Result<()> {
         include_static_columns: StaticColumnSelection::Both,
         filtered_index: Some(TimelineName::new("frame_nr")),
         filtered_index_range: None,
+        secondary_indices: Vec::new(),
         filtered_index_values: None,
         using_index_values: None,
+        resampling: None,
         filtered_is_not_null: None,
+        filter: None,
         sparse_fill_strategy: re_chunk_store::SparseFillStrategy::None,
         selection: None,
     };
@@ -183,9 +186,12 @@ fn schema_static_columns() -> anyhow::Result<()> {
         include_static_columns: StaticColumnSelection::Both,
         filtered_index: None,
         filtered_index_range: None,
+        secondary_indices: Vec::new(),
         filtered_index_values: None,
         using_index_values: None,
+        resampling: None,
         filtered_is_not_null: None,
+        filter: None,
         sparse_fill_strategy: re_chunk_store::SparseFillStrategy::None,
         selection: None,
     };
@@ -6,7 +6,7 @@ use rand::Rng as _;
 use re_chunk::{Chunk, ChunkId, LatestAtQuery, RowId, TimeInt, TimePoint, TimelineName};
 use re_chunk_store::{
     ChunkStore, ChunkStoreConfig, ChunkStoreDiffKind, GarbageCollectionOptions,
-    GarbageCollectionTarget,
+    GarbageCollectionTarget, RetentionPolicy,
 };
 use re_log_types::{
     AbsoluteTimeRange, EntityPath, Timestamp, build_frame_nr, build_log_time,
@@ -445,6 +445,108 @@ fn protected_time_ranges() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn retention() -> anyhow::Result<()> {
+    re_log::setup_logging();
+
+    let mut store = ChunkStore::new(
+        re_log_types::StoreId::random(re_log_types::StoreKind::Recording, "test_app"),
+        ChunkStoreConfig::COMPACTION_DISABLED,
+    );
+
+    let camera = EntityPath::from("camera");
+    let lidar = EntityPath::from("lidar");
+
+    for entity_path in [&camera, &lidar] {
+        for frame in 1..=10 {
+            let chunk = Chunk::builder(entity_path.clone())
+                .with_component_batches(
+                    RowId::new(),
+                    [build_frame_nr(frame)],
+                    [(
+                        MyPoints::descriptor_points(),
+                        &MyPoint::from_iter(0..1) as _,
+                    )],
+                )
+                .build()?;
+            store.insert_chunk(&Arc::new(chunk))?;
+        }
+    }
+
+    let static_chunk = Chunk::builder(camera.clone())
+        .with_component_batches(
+            RowId::new(),
+            TimePoint::default(),
+            [(
+                MyPoints::descriptor_colors(),
+                &MyColor::from_iter(0..1) as _,
+            )],
+        )
+        .build()?;
+    store.insert_chunk(&Arc::new(static_chunk))?;
+
+    eprintln!("{store}");
+
+    let frame_range = |store: &ChunkStore, entity_path: &EntityPath| {
+        store
+            .entity_time_range(&TimelineName::new("frame_nr"), entity_path)
+            .map(|time_range| (time_range.min().as_i64(), time_range.max().as_i64()))
+    };
+
+    // Keep the last 5 frames: everything strictly before frame #6 has expired.
+    let (events, _) = store.gc(&GarbageCollectionOptions::retention_only(vec![
+        "frame_nr=5".parse().map_err(anyhow::Error::msg)?,
+    ]));
+    assert_eq!(10, events.len());
+    assert!(
+        events
+            .iter()
+            .all(|event| event.kind == ChunkStoreDiffKind::Deletion)
+    );
+    assert_eq!(Some((6, 10)), frame_range(&store, &camera));
+    assert_eq!(Some((6, 10)), frame_range(&store, &lidar));
+
+    // Nothing else has expired since.
+    let (events, _) = store.gc(&GarbageCollectionOptions::retention_only(vec![
+        "frame_nr=5".parse().map_err(anyhow::Error::msg)?,
+    ]));
+    assert!(events.is_empty());
+
+    // Retention only applies to the specified subtree.
+    let (events, _) = store.gc(&GarbageCollectionOptions::retention_only(vec![
+        "/camera:frame_nr=2".parse().map_err(anyhow::Error::msg)?,
+    ]));
+    assert_eq!(3, events.len());
+    assert_eq!(Some((9, 10)), frame_range(&store, &camera));
+    assert_eq!(Some((6, 10)), frame_range(&store, &lidar));
+
+    // Static data never expires.
+    let (events, _) = store.gc(&GarbageCollectionOptions::retention_only(vec![
+        RetentionPolicy::new("frame_nr", 0),
+    ]));
+    assert_eq!(7, events.len());
+    assert!(store.entity_has_static_data(&camera));
+
+    Ok(())
+}
+
+#[test]
+fn retention_policy_syntax() {
+    let policy: RetentionPolicy = "log_time=10min".parse().unwrap();
+    assert_eq!(RetentionPolicy::new("log_time", 600_000_000_000), policy);
+
+    let policy: RetentionPolicy = "/camera:frame_nr=5000".parse().unwrap();
+    assert_eq!(
+        RetentionPolicy::new("frame_nr", 5000).with_entity_path("camera"),
+        policy
+    );
+    assert_eq!(policy, policy.to_string().parse().unwrap());
+
+    assert!("frame_nr".parse::<RetentionPolicy>().is_err());
+    assert!("frame_nr=-5".parse::<RetentionPolicy>().is_err());
+    assert!("=5".parse::<RetentionPolicy>().is_err());
+}
+
 // ---
 
 #[test]
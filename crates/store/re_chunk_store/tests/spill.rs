@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use re_chunk::{Chunk, ChunkId, LatestAtQuery, RangeQuery, RowId, TimelineName};
+use re_chunk_store::{
+    ChunkStore, ChunkStoreConfig, ChunkStoreDiffKind, GarbageCollectionOptions,
+    GarbageCollectionTarget,
+};
+use re_log_types::{
+    AbsoluteTimeRange, EntityPath, build_frame_nr,
+    example_components::{MyPoint, MyPoints},
+};
+
+// ---
+
+#[test]
+fn spill_and_reload() -> anyhow::Result<()> {
+    re_log::setup_logging();
+
+    let spill_directory = std::env::temp_dir().join(format!("rerun_spill_test_{}", ChunkId::new()));
+
+    let mut store = ChunkStore::new(
+        re_log_types::StoreId::random(re_log_types::StoreKind::Recording, "test_app"),
+        ChunkStoreConfig {
+            spill_directory: Some(spill_directory.clone()),
+            ..ChunkStoreConfig::COMPACTION_DISABLED
+        },
+    );
+
+    let entity_path = EntityPath::from("points");
+    let descr = MyPoints::descriptor_points();
+
+    let chunks = (1..=10)
+        .map(|frame| {
+            let points = MyPoint::from_iter(frame..frame + 1);
+            Chunk::builder(entity_path.clone())
+                .with_component_batches(
+                    RowId::new(),
+                    [build_frame_nr(frame as i64)],
+                    [(descr.clone(), &points as _)],
+                )
+                .build()
+                .map(Arc::new)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for chunk in &chunks {
+        store.insert_chunk(chunk)?;
+    }
+
+    // Memory pressure: everything gets spilled to disk, nothing gets dropped.
+    let (events, stats_diff) = store.gc(&GarbageCollectionOptions {
+        target: GarbageCollectionTarget::DropAtLeastFraction(1.0),
+        ..GarbageCollectionOptions::gc_everything()
+    });
+    assert_eq!(10, events.len());
+    assert!(
+        events
+            .iter()
+            .all(|event| event.kind == ChunkStoreDiffKind::Spill)
+    );
+    assert_eq!(10, stats_diff.temporal_chunks.num_chunks);
+    assert_eq!(10, store.num_spilled_chunks());
+    assert_eq!(0, store.num_chunks());
+    assert_eq!(10, std::fs::read_dir(&spill_directory)?.count());
+    assert!(store.entity_has_temporal_data(&entity_path));
+
+    // Spilled chunks are transparently reloaded at query time.
+    let query = LatestAtQuery::new(TimelineName::new("frame_nr"), 5);
+    let got = store.latest_at_relevant_chunks(&query, &entity_path, &descr);
+    assert_eq!(1, got.len());
+    assert_eq!(*chunks[4], *got[0]);
+
+    let query = RangeQuery::new(TimelineName::new("frame_nr"), AbsoluteTimeRange::new(3, 6));
+    let got = store.range_relevant_chunks(&query, &entity_path, &descr);
+    assert_eq!(
+        chunks[2..6]
+            .iter()
+            .map(|chunk| chunk.id())
+            .collect::<Vec<_>>(),
+        got.iter().map(|chunk| chunk.id()).collect::<Vec<_>>(),
+    );
+
+    // Actually dropping the data reloads it, so that subscribers can be notified.
+    let (events, _) = store.gc(&GarbageCollectionOptions::gc_everything());
+    assert_eq!(10, events.len());
+    assert!(
+        events
+            .iter()
+            .all(|event| event.kind == ChunkStoreDiffKind::Deletion)
+    );
+    assert_eq!(0, store.num_spilled_chunks());
+    assert_eq!(0, std::fs::read_dir(&spill_directory)?.count());
+    assert!(!store.entity_has_temporal_data(&entity_path));
+
+    std::fs::remove_dir_all(&spill_directory)?;
+
+    Ok(())
+}
+
+#[test]
+fn drop_what_cannot_be_spilled() -> anyhow::Result<()> {
+    re_log::setup_logging();
+
+    // A file, rather than a directory: nothing can be spilled there.
+    let spill_directory = std::env::temp_dir().join(format!("rerun_spill_test_{}", ChunkId::new()));
+    std::fs::write(&spill_directory, b"")?;
+
+    let mut store = ChunkStore::new(
+        re_log_types::StoreId::random(re_log_types::StoreKind::Recording, "test_app"),
+        ChunkStoreConfig {
+            spill_directory: Some(spill_directory.clone()),
+            ..ChunkStoreConfig::COMPACTION_DISABLED
+        },
+    );
+
+    let entity_path = EntityPath::from("points");
+    for frame in 1..=10 {
+        let points = MyPoint::from_iter(frame..frame + 1);
+        let chunk = Chunk::builder(entity_path.clone())
+            .with_component_batches(
+                RowId::new(),
+                [build_frame_nr(frame as i64)],
+                [(MyPoints::descriptor_points(), &points as _)],
+            )
+            .build()?;
+        store.insert_chunk(&Arc::new(chunk))?;
+    }
+
+    // The memory limit must be enforced no matter what.
+    let (events, _) = store.gc(&GarbageCollectionOptions {
+        target: GarbageCollectionTarget::DropAtLeastFraction(1.0),
+        ..GarbageCollectionOptions::gc_everything()
+    });
+    assert_eq!(10, events.len());
+    assert!(
+        events
+            .iter()
+            .all(|event| event.kind == ChunkStoreDiffKind::Deletion)
+    );
+    assert_eq!(0, store.num_spilled_chunks());
+    assert!(!store.entity_has_temporal_data(&entity_path));
+
+    std::fs::remove_file(&spill_directory)?;
+
+    Ok(())
+}
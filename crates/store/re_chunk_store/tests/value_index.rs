@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use re_chunk::{Chunk, RowId, TimelineName};
+use re_chunk_store::{
+    ChunkStore, ChunkStoreConfig, ChunkStoreSubscriber as _, FilterValue, GarbageCollectionOptions,
+    ValueIndexKind, ValueIndexes, ValuePredicate, ValueQuery,
+};
+use re_log_types::{
+    EntityPath, EntityPathFilter, TimeInt, build_frame_nr,
+    example_components::{MyColor, MyIndex, MyLabel, MyPoints},
+};
+use re_types_core::Loggable as _;
+
+// ---
+
+#[test]
+fn value_indexes() -> anyhow::Result<()> {
+    re_log::setup_logging();
+
+    let mut store = ChunkStore::new(
+        re_log_types::StoreId::random(re_log_types::StoreKind::Recording, "test_app"),
+        ChunkStoreConfig::DEFAULT,
+    );
+
+    let mut indexes = ValueIndexes::new([
+        (MyColor::name(), ValueIndexKind::Hash),
+        (MyLabel::name(), ValueIndexKind::Hash),
+        (MyIndex::name(), ValueIndexKind::Sorted),
+    ]);
+
+    let track_a = EntityPath::from("tracks/a");
+    let track_b = EntityPath::from("tracks/b");
+    let logs = EntityPath::from("logs");
+
+    for frame in 1..=6 {
+        let colors = [MyColor(frame as u32 % 3), MyColor(42)];
+        let first_color = [MyColor(frame as u32 % 3)];
+        let labels = [MyLabel(if frame == 4 {
+            "ERROR: out of cheese".to_owned()
+        } else {
+            "all good".to_owned()
+        })];
+        let index = [MyIndex(frame * 10)];
+
+        for chunk in [
+            Chunk::builder(track_a.clone())
+                .with_component_batches(
+                    RowId::new(),
+                    [build_frame_nr(frame as i64)],
+                    [(MyPoints::descriptor_colors(), &colors as _)],
+                )
+                .build()?,
+            Chunk::builder(track_b.clone())
+                .with_component_batches(
+                    RowId::new(),
+                    [build_frame_nr(frame as i64)],
+                    [
+                        (MyPoints::descriptor_colors(), &first_color as _),
+                        (MyIndex::partial_descriptor(), &index as _),
+                    ],
+                )
+                .build()?,
+            Chunk::builder(logs.clone())
+                .with_component_batches(
+                    RowId::new(),
+                    [build_frame_nr(frame as i64)],
+                    [(MyPoints::descriptor_labels(), &labels as _)],
+                )
+                .build()?,
+        ] {
+            indexes.on_events(&store.insert_chunk(&Arc::new(chunk))?);
+        }
+    }
+
+    let store_id = store.id().clone();
+    let frame_nr = TimelineName::new("frame_nr");
+    let at = |entity_path: &EntityPath, frames: &[i64]| {
+        frames
+            .iter()
+            .map(|frame| (entity_path.clone(), TimeInt::new_temporal(*frame)))
+            .collect::<Vec<_>>()
+    };
+
+    // Exact lookups, across entities.
+    let query = ValueQuery::new(
+        frame_nr,
+        MyColor::name(),
+        ValuePredicate::Equals(FilterValue::Int(1)),
+    );
+    let mut expected = at(&track_a, &[1, 4]);
+    expected.extend(at(&track_b, &[1, 4]));
+    expected.sort_by_key(|(entity_path, time)| (*time, entity_path.clone()));
+    assert_eq!(expected, indexes.query(&store_id, &query));
+
+    // …restricted to some entities.
+    let query = query.with_entity_filter(
+        EntityPathFilter::parse_forgiving("+ /tracks/b").resolve_without_substitutions(),
+    );
+    assert_eq!(at(&track_b, &[1, 4]), indexes.query(&store_id, &query));
+
+    // Rows only match once, even if several of their instances do.
+    let query = ValueQuery::new(
+        frame_nr,
+        MyColor::name(),
+        ValuePredicate::Equals(FilterValue::Int(42)),
+    );
+    assert_eq!(
+        at(&track_a, &[1, 2, 3, 4, 5, 6]),
+        indexes.query(&store_id, &query)
+    );
+
+    // Substring lookups.
+    let query = ValueQuery::new(
+        frame_nr,
+        MyLabel::name(),
+        ValuePredicate::Contains("ERROR".to_owned()),
+    );
+    assert_eq!(at(&logs, &[4]), indexes.query(&store_id, &query));
+
+    // Range lookups.
+    let query = ValueQuery::new(
+        frame_nr,
+        MyIndex::name(),
+        ValuePredicate::InRange {
+            min: 15.0,
+            max: 40.0,
+        },
+    );
+    assert_eq!(at(&track_b, &[2, 3, 4]), indexes.query(&store_id, &query));
+
+    // Catching up on an existing store yields the same indexes.
+    let mut late_indexes = ValueIndexes::new([(MyIndex::name(), ValueIndexKind::Sorted)]);
+    late_indexes.index_store(&store);
+    assert_eq!(
+        at(&track_b, &[2, 3, 4]),
+        late_indexes.query(&store_id, &query)
+    );
+
+    // Integers and floats are compared numerically.
+    let query = ValueQuery::new(
+        frame_nr,
+        MyIndex::name(),
+        ValuePredicate::Equals(FilterValue::Float(10.0)),
+    );
+    assert_eq!(at(&track_b, &[1]), indexes.query(&store_id, &query));
+
+    // Unindexed component types never match.
+    let query = ValueQuery::new(
+        frame_nr,
+        MyLabel::name(),
+        ValuePredicate::Contains("ERROR".to_owned()),
+    );
+    assert!(late_indexes.query(&store_id, &query).is_empty());
+
+    // Deleted data gets unindexed.
+    let (events, _) = store.gc(&GarbageCollectionOptions::gc_everything());
+    indexes.on_events(&events);
+    let query = ValueQuery::new(
+        frame_nr,
+        MyColor::name(),
+        ValuePredicate::Equals(FilterValue::Int(42)),
+    );
+    assert!(indexes.query(&store_id, &query).is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,364 @@
+use std::cmp::Ordering;
+
+use arrow::{
+    array::{Array as _, ArrayRef as ArrowArrayRef, AsArray as _},
+    datatypes::{
+        DataType as ArrowDataType, Float64Type as ArrowFloat64Type, Int64Type, UInt64Type,
+    },
+};
+use itertools::Itertools as _;
+
+use re_chunk::{Chunk, ComponentStatistics, ValueBounds};
+use re_chunk_store::{ColumnDescriptor, ComparisonOperator, FilterValue, RowFilter};
+use re_sorbet::ComponentColumnSelector;
+
+// ---
+
+/// A [`RowFilter`] whose columns have been resolved to indices in the view contents.
+///
+/// See [`re_chunk_store::QueryExpression::filter`].
+#[derive(Debug, Clone)]
+pub(crate) enum ResolvedRowFilter {
+    /// `None` if the column is not part of the view contents.
+    IsNotNull(Option<usize>),
+
+    Compare {
+        /// `None` if the column is not part of the view contents.
+        view_idx: Option<usize>,
+        op: ComparisonOperator,
+        value: FilterValue,
+    },
+
+    And(Vec<Self>),
+
+    Or(Vec<Self>),
+}
+
+impl ResolvedRowFilter {
+    pub fn new(filter: &RowFilter, view_contents: &[ColumnDescriptor]) -> Self {
+        let resolve = |selector: &ComponentColumnSelector| {
+            view_contents.iter().position(|column| {
+                matches!(column, ColumnDescriptor::Component(descr) if descr.matches(selector))
+            })
+        };
+
+        match filter {
+            RowFilter::IsNotNull(column) => Self::IsNotNull(resolve(column)),
+
+            RowFilter::Compare { column, op, value } => Self::Compare {
+                view_idx: resolve(column),
+                op: *op,
+                value: value.clone(),
+            },
+
+            RowFilter::And(filters) => Self::And(
+                filters
+                    .iter()
+                    .map(|filter| Self::new(filter, view_contents))
+                    .collect(),
+            ),
+
+            RowFilter::Or(filters) => Self::Or(
+                filters
+                    .iter()
+                    .map(|filter| Self::new(filter, view_contents))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Evaluates the filter for the current row.
+    ///
+    /// `cell` returns the data of the given view column for the current row, if any.
+    pub fn evaluate(&self, cell: &impl Fn(usize) -> Option<ArrowArrayRef>) -> bool {
+        match self {
+            Self::IsNotNull(view_idx) => view_idx.and_then(cell).is_some(),
+
+            Self::Compare {
+                view_idx,
+                op,
+                value,
+            } => view_idx.and_then(cell).is_some_and(|values| {
+                compare_values(&values, value)
+                    .into_iter()
+                    .flatten()
+                    .any(|ordering| op.matches(ordering))
+            }),
+
+            Self::And(filters) => filters.iter().all(|filter| filter.evaluate(cell)),
+
+            Self::Or(filters) => filters.iter().any(|filter| filter.evaluate(cell)),
+        }
+    }
+
+    /// The view column this leaf applies to.
+    ///
+    /// The outer layer is `None` for [`Self::And`] and [`Self::Or`].
+    pub fn view_idx(&self) -> Option<Option<usize>> {
+        match self {
+            Self::IsNotNull(view_idx) | Self::Compare { view_idx, .. } => Some(*view_idx),
+            Self::And(_) | Self::Or(_) => None,
+        }
+    }
+
+    /// The leaves that must hold for this filter to possibly hold, i.e. the leaves that are only
+    /// ever combined using [`Self::And`].
+    pub fn required_leaves(&self) -> Vec<&Self> {
+        match self {
+            Self::IsNotNull(_) | Self::Compare { .. } => vec![self],
+            Self::And(filters) => filters
+                .iter()
+                .flat_map(|filter| filter.required_leaves())
+                .collect(),
+            Self::Or(filters) => match filters.as_slice() {
+                [filter] => filter.required_leaves(),
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    /// Could this leaf possibly hold for any of the rows of this chunk?
+    ///
+    /// Decided using the min/max values of the chunk's component data.
+    /// The chunk is expected to be densified, see `QueryHandleState::view_chunks`.
+    ///
+    /// If `statistics` are provided, they are used instead of looking at the data. They must
+    /// describe a superset of the chunk's data, e.g. the stored chunk it was sliced from.
+    pub fn chunk_may_match(&self, chunk: &Chunk, statistics: Option<&ComponentStatistics>) -> bool {
+        match self {
+            Self::IsNotNull(_) => !chunk.is_empty(),
+
+            Self::Compare { op, value, .. } => {
+                if let Some(statistics) = statistics {
+                    return statistics_may_match(statistics, *op, value);
+                }
+
+                let Some((_, list_array)) = chunk.components().iter().next() else {
+                    return false;
+                };
+
+                // NOTE: The list array might be a slice of a larger one: only look at the values
+                // that are actually referenced.
+                let offsets = list_array.value_offsets();
+                let (Some(&start), Some(&end)) = (offsets.first(), offsets.last()) else {
+                    return false;
+                };
+                let values = list_array
+                    .values()
+                    .slice(start as usize, (end - start) as usize);
+
+                let Some(orderings) = compare_values(&values, value) else {
+                    return false; // can never hold
+                };
+
+                // Comparing against NaNs: no way to tell, keep the chunk.
+                if orderings.iter().any(|ordering| ordering.is_none()) {
+                    return true;
+                }
+
+                match orderings.into_iter().flatten().minmax().into_option() {
+                    Some((min, max)) => op.may_match_range(min, max),
+                    None => false, // no data
+                }
+            }
+
+            Self::And(_) | Self::Or(_) => true,
+        }
+    }
+}
+
+/// Could `values <op> value` hold for any of the values described by `statistics`?
+fn statistics_may_match(
+    statistics: &ComponentStatistics,
+    op: ComparisonOperator,
+    value: &FilterValue,
+) -> bool {
+    if let (FilterValue::String(value), Some(distinct_values)) =
+        (value, statistics.distinct_values.as_ref())
+    {
+        match op {
+            ComparisonOperator::Eq => return distinct_values.contains(value),
+            ComparisonOperator::NotEq => return distinct_values.iter().any(|v| v != value),
+            _ => {}
+        }
+    }
+
+    let Some(bounds) = statistics.bounds.as_ref() else {
+        return false; // no data, or nothing that can be compared
+    };
+
+    match compare_bounds(bounds, value) {
+        Some((Some(min), Some(max))) => op.may_match_range(min, max),
+        Some(_) => true, // comparing against NaNs: no way to tell, keep the chunk
+        None => false,   // can never hold
+    }
+}
+
+/// Compares the bounds of a column against `value`, following the same rules as [`compare_values`].
+///
+/// Returns `None` if the column and the value cannot be compared at all.
+fn compare_bounds(
+    bounds: &ValueBounds,
+    value: &FilterValue,
+) -> Option<(Option<Ordering>, Option<Ordering>)> {
+    match (bounds, value) {
+        (ValueBounds::Bool { min, max }, FilterValue::Bool(value)) => {
+            Some((Some(min.cmp(value)), Some(max.cmp(value))))
+        }
+
+        (ValueBounds::String { min, max }, FilterValue::String(value)) => {
+            Some((Some(min.cmp(value)), Some(max.cmp(value))))
+        }
+
+        (ValueBounds::Int { min, max }, FilterValue::Int(value)) => {
+            Some((Some(min.cmp(value)), Some(max.cmp(value))))
+        }
+
+        (ValueBounds::Int { min, max }, FilterValue::Float(value)) => Some((
+            (*min as f64).partial_cmp(value),
+            (*max as f64).partial_cmp(value),
+        )),
+
+        (ValueBounds::Float { min, max }, FilterValue::Int(value)) => {
+            let value = *value as f64;
+            Some((min.partial_cmp(&value), max.partial_cmp(&value)))
+        }
+
+        (ValueBounds::Float { min, max }, FilterValue::Float(value)) => {
+            Some((min.partial_cmp(value), max.partial_cmp(value)))
+        }
+
+        _ => None,
+    }
+}
+
+/// Compares every non-null value in the given array against `value`, i.e. `values[i].cmp(value)`.
+///
+/// Returns `None` if the array and the value cannot be compared at all.
+/// Individual orderings are `None` when comparing against NaNs.
+fn compare_values(values: &ArrowArrayRef, value: &FilterValue) -> Option<Vec<Option<Ordering>>> {
+    let datatype = values.data_type();
+
+    match value {
+        FilterValue::Bool(value) => {
+            let values = values.as_boolean_opt()?;
+            Some(
+                values
+                    .iter()
+                    .flatten()
+                    .map(|v| Some(v.cmp(value)))
+                    .collect(),
+            )
+        }
+
+        FilterValue::String(value) => {
+            let value = value.as_str();
+            match datatype {
+                ArrowDataType::Utf8 => Some(
+                    values
+                        .as_string::<i32>()
+                        .iter()
+                        .flatten()
+                        .map(|v| Some(v.cmp(value)))
+                        .collect(),
+                ),
+                ArrowDataType::LargeUtf8 => Some(
+                    values
+                        .as_string::<i64>()
+                        .iter()
+                        .flatten()
+                        .map(|v| Some(v.cmp(value)))
+                        .collect(),
+                ),
+                ArrowDataType::Utf8View => Some(
+                    values
+                        .as_string_view()
+                        .iter()
+                        .flatten()
+                        .map(|v| Some(v.cmp(value)))
+                        .collect(),
+                ),
+                _ => None,
+            }
+        }
+
+        // Integers are compared as integers whenever possible, to avoid any loss of precision.
+        // `u64`s don't all fit in an `i64`, so they get compared on their own.
+        FilterValue::Int(value) if datatype == &ArrowDataType::UInt64 => Some(
+            values
+                .as_primitive::<UInt64Type>()
+                .iter()
+                .flatten()
+                .map(|v| Some(i128::from(v).cmp(&i128::from(*value))))
+                .collect(),
+        ),
+
+        FilterValue::Int(value) if datatype.is_integer() => {
+            let values = arrow::compute::cast(values, &ArrowDataType::Int64).ok()?;
+            Some(
+                values
+                    .as_primitive::<Int64Type>()
+                    .iter()
+                    .flatten()
+                    .map(|v| Some(v.cmp(value)))
+                    .collect(),
+            )
+        }
+
+        FilterValue::Int(_) | FilterValue::Float(_) if datatype.is_numeric() => {
+            let value = match value {
+                FilterValue::Int(value) => *value as f64,
+                FilterValue::Float(value) => *value,
+                _ => return None,
+            };
+
+            let values = arrow::compute::cast(values, &ArrowDataType::Float64).ok()?;
+            Some(
+                values
+                    .as_primitive::<ArrowFloat64Type>()
+                    .iter()
+                    .flatten()
+                    .map(|v| v.partial_cmp(&value))
+                    .collect(),
+            )
+        }
+
+        FilterValue::Int(_) | FilterValue::Float(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::UInt64Array;
+
+    use super::*;
+
+    #[test]
+    fn compare_u64_beyond_i64() {
+        let values: ArrowArrayRef = Arc::new(UInt64Array::from(vec![
+            Some(1),
+            None,
+            Some(i64::MAX as u64 + 1),
+            Some(u64::MAX),
+        ]));
+
+        assert_eq!(
+            Some(vec![
+                Some(Ordering::Less),
+                Some(Ordering::Greater),
+                Some(Ordering::Greater),
+            ]),
+            compare_values(&values, &FilterValue::Int(i64::MAX)),
+        );
+        assert_eq!(
+            Some(vec![
+                Some(Ordering::Greater),
+                Some(Ordering::Greater),
+                Some(Ordering::Greater),
+            ]),
+            compare_values(&values, &FilterValue::Int(-1)),
+        );
+    }
+}
@@ -1,15 +1,24 @@
 //! The Rerun public data APIs. Get dataframes back from your Rerun datastore.
 
 mod engine;
+mod filter;
 mod query;
+mod query_syntax;
 
 pub use self::engine::QueryEngine;
 pub use self::query::QueryHandle;
+pub use self::query_syntax::{
+    ContentsRule, IndexRangeSpec, QuerySpec, QuerySyntaxError, ResamplingSpec, SecondaryIndexSpec,
+    format_fill_strategy, format_index_range, parse_fill_strategy, parse_index_range,
+    parse_row_filter,
+};
 
 #[doc(no_inline)]
 pub use self::external::re_chunk_store::{
-    ChunkStoreConfig, ChunkStoreHandle, Index, IndexRange, IndexValue, QueryExpression,
-    SparseFillStrategy, ViewContentsSelector,
+    AggregationFunction, ChunkStoreConfig, ChunkStoreHandle, ComparisonOperator, FilterValue,
+    Index, IndexRange, IndexValue, QueryExpression, Resampling, RowFilter, SecondaryIndex,
+    SparseFillStrategy, ValueIndexKind, ValueIndexes, ValuePredicate, ValueQuery,
+    ViewContentsSelector,
 };
 #[doc(no_inline)]
 pub use self::external::re_log_types::{
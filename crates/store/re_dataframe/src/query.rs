@@ -1,5 +1,5 @@
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeSet, HashMap},
     sync::{
         OnceLock,
         atomic::{AtomicU64, Ordering},
@@ -9,13 +9,15 @@ use std::{
 use arrow::array::RecordBatchOptions;
 use arrow::{
     array::{
-        ArrayRef as ArrowArrayRef, BooleanArray as ArrowBooleanArray,
-        PrimitiveArray as ArrowPrimitiveArray, RecordBatch as ArrowRecordBatch,
+        Array as _, ArrayRef as ArrowArrayRef, AsArray as _, BooleanArray as ArrowBooleanArray,
+        FixedSizeListArray as ArrowFixedSizeListArray, Float64Array as ArrowFloat64Array,
+        ListArray as ArrowListArray, PrimitiveArray as ArrowPrimitiveArray,
+        RecordBatch as ArrowRecordBatch, UInt64Array as ArrowUInt64Array,
     },
-    buffer::ScalarBuffer as ArrowScalarBuffer,
+    buffer::{OffsetBuffer as ArrowOffsetBuffer, ScalarBuffer as ArrowScalarBuffer},
     datatypes::{
-        DataType as ArrowDataType, Fields as ArrowFields, Schema as ArrowSchema,
-        SchemaRef as ArrowSchemaRef,
+        DataType as ArrowDataType, Field as ArrowField, Fields as ArrowFields,
+        Float64Type as ArrowFloat64Type, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef,
     },
 };
 use itertools::{Either, Itertools as _};
@@ -27,8 +29,9 @@ use re_chunk::{
     external::arrow::array::ArrayRef,
 };
 use re_chunk_store::{
-    ChunkStore, ColumnDescriptor, ComponentColumnDescriptor, Index, IndexColumnDescriptor,
-    IndexValue, QueryExpression, SparseFillStrategy,
+    AggregationFunction, ChunkStore, ColumnDescriptor, ComponentColumnDescriptor, Index,
+    IndexColumnDescriptor, IndexValue, QueryExpression, Resampling, SecondaryIndex,
+    SparseFillStrategy,
 };
 use re_log_types::AbsoluteTimeRange;
 use re_query::{QueryCache, StorageEngineLike};
@@ -37,6 +40,8 @@ use re_sorbet::{
 };
 use re_types_core::{ComponentDescriptor, Loggable as _, archetypes, arrow_helpers::as_array_ref};
 
+use crate::filter::ResolvedRowFilter;
+
 // ---
 
 // TODO(cmc): (no specific order) (should we make issues for these?)
@@ -51,6 +56,8 @@ use re_types_core::{ComponentDescriptor, Loggable as _, archetypes, arrow_helper
 // * [x] pagination (fast)
 // * [x] take kernel duplicates all memory
 // * [x] dedupe-latest without allocs/copies
+// * [x] resampling
+// * [x] composite indices
 // * [ ] allocate null arrays once
 // * [ ] overlaps (less dumb)
 // * [ ] selector-based `filtered_index`
@@ -129,6 +136,14 @@ struct QueryHandleState {
     // NOTE: Reminder: we have to query everything in the _view_, irrelevant of the current selection.
     view_chunks: Vec<Vec<(AtomicU64, Chunk)>>,
 
+    /// For each entry in [`QueryHandleState::view_chunks`], the rows of that chunk that each value
+    /// of the composite index resolves to, computed on first use.
+    ///
+    /// Empty unless a composite index is in use.
+    ///
+    /// See [`QueryExpression::secondary_indices`].
+    view_composite_index_rows: Vec<Vec<OnceLock<CompositeIndexRows>>>,
+
     /// Tracks the current row index: the position of the iterator. For [`QueryHandle::next_row`].
     ///
     /// This represents the number of rows that the caller has iterated on: it is completely
@@ -141,10 +156,31 @@ struct QueryHandleState {
 
     /// All unique index values that can possibly be returned by this query.
     ///
-    /// Guaranteed ascendingly sorted and deduped.
+    /// Guaranteed ascendingly sorted and deduped, unless a composite index is in use, in which
+    /// case it is the tuples formed with [`QueryHandleState::unique_secondary_index_values`] that
+    /// are sorted and deduped.
     ///
     /// See also [`QueryHandleState::cur_row`].
     unique_index_values: Vec<IndexValue>,
+
+    /// The values of the secondary indices for each entry in
+    /// [`QueryHandleState::unique_index_values`], if a composite index is in use.
+    ///
+    /// Empty otherwise.
+    ///
+    /// See [`QueryExpression::secondary_indices`].
+    unique_secondary_index_values: Vec<Vec<Option<IndexValue>>>,
+
+    /// The row filter, resolved against the view contents.
+    ///
+    /// See [`QueryExpression::filter`].
+    row_filter: Option<ResolvedRowFilter>,
+
+    /// The indices, in [`QueryHandleState::unique_index_values`], of the rows for which the
+    /// [`QueryHandleState::row_filter`] holds, computed on first use.
+    ///
+    /// Unused if there is no row filter.
+    filtered_rows: OnceLock<Vec<u64>>,
 }
 
 impl<E: StorageEngineLike> QueryHandle<E> {
@@ -192,6 +228,28 @@ impl<E: StorageEngineLike> QueryHandle<E> {
             view_contents.clone().into_iter().enumerate().collect()
         };
 
+        // When counting, every component column ends up as a column of counts.
+        let selected_contents = if self
+            .resampling()
+            .is_some_and(|resampling| resampling.aggregation == AggregationFunction::Count)
+        {
+            selected_contents
+                .into_iter()
+                .map(|(view_idx, descr)| match descr {
+                    ColumnDescriptor::Component(descr) => (
+                        view_idx,
+                        ColumnDescriptor::Component(ComponentColumnDescriptor {
+                            store_datatype: count_datatype(),
+                            ..descr
+                        }),
+                    ),
+                    descr => (view_idx, descr),
+                })
+                .collect()
+        } else {
+            selected_contents
+        };
+
         // 3. Compute the Arrow schema of the selected components.
         //
         // Every result returned using this `QueryHandle` will match this schema exactly.
@@ -227,6 +285,12 @@ impl<E: StorageEngineLike> QueryHandle<E> {
         let (view_pov_chunks_idx, mut view_chunks) =
             self.fetch_view_chunks(store, cache, &query, &view_contents);
 
+        let row_filter = self
+            .query
+            .filter
+            .as_ref()
+            .map(|filter| ResolvedRowFilter::new(filter, &view_contents));
+
         // 5. Collect all relevant clear chunks and update the view accordingly.
         //
         // We'll turn the clears into actual empty arrays of the expected component type.
@@ -292,45 +356,147 @@ impl<E: StorageEngineLike> QueryHandle<E> {
         // 6. Collect all unique index values.
         //
         // Used to achieve ~O(log(n)) pagination.
-        let unique_index_values = if self.query.filtered_index.is_none() {
-            vec![TimeInt::STATIC]
-        } else if let Some(using_index_values) = self.query.using_index_values.as_ref() {
-            using_index_values
-                .iter()
-                .filter(|index_value| !index_value.is_static())
-                .copied()
-                .collect_vec()
-        } else {
-            re_tracing::profile_scope!("index_values");
-
-            let mut view_chunks = view_chunks.iter();
-            let view_chunks = if let Some(view_pov_chunks_idx) = view_pov_chunks_idx {
-                Either::Left(view_chunks.nth(view_pov_chunks_idx).into_iter())
+        let (unique_index_values, unique_secondary_index_values) =
+            if self.query.filtered_index.is_none() {
+                (vec![TimeInt::STATIC], Vec::new())
+            } else if let Some(using_index_values) = self.query.using_index_values.as_ref() {
+                let unique_index_values = using_index_values
+                    .iter()
+                    .filter(|index_value| !index_value.is_static())
+                    .copied()
+                    .collect_vec();
+                (unique_index_values, Vec::new())
             } else {
-                Either::Right(view_chunks)
-            };
+                re_tracing::profile_scope!("index_values");
 
-            let mut all_unique_index_values: BTreeSet<TimeInt> = view_chunks
-                .flat_map(|chunks| {
-                    chunks.iter().filter_map(|(_cursor, chunk)| {
-                        chunk
-                            .timelines()
-                            .get(&filtered_index)
-                            .map(|time_column| time_column.times())
-                    })
-                })
-                .flatten()
-                .collect();
+                let mut view_chunks = view_chunks.iter();
+                let view_chunks = if let Some(view_pov_chunks_idx) = view_pov_chunks_idx {
+                    Either::Left(view_chunks.nth(view_pov_chunks_idx).into_iter())
+                } else {
+                    Either::Right(view_chunks)
+                };
 
-            if let Some(filtered_index_values) = self.query.filtered_index_values.as_ref() {
-                all_unique_index_values.retain(|time| filtered_index_values.contains(time));
-            }
+                let secondary_indices = &self.query.secondary_indices;
+
+                // NOTE: The secondary index values are always empty if there is no composite index.
+                let mut all_unique_index_values: BTreeSet<(TimeInt, Vec<Option<TimeInt>>)> =
+                    view_chunks
+                        .flat_map(|chunks| {
+                            chunks.iter().filter_map(|(_cursor, chunk)| {
+                                chunk
+                                    .timelines()
+                                    .get(&filtered_index)
+                                    .map(|time_column| (chunk, time_column.times()))
+                            })
+                        })
+                        .flat_map(|(chunk, times)| {
+                            times.enumerate().map(move |(row, time)| {
+                                let secondary_values = secondary_indices
+                                    .iter()
+                                    .map(|secondary_index| {
+                                        secondary_index_value(chunk, &secondary_index.index, row)
+                                    })
+                                    .collect_vec();
+                                (time, secondary_values)
+                            })
+                        })
+                        .filter(|(_time, secondary_values)| {
+                            secondary_indices.iter().zip(secondary_values).all(
+                                |(secondary_index, value)| {
+                                    secondary_index.range.is_none_or(|range| {
+                                        value.is_some_and(|value| range.contains(value))
+                                    })
+                                },
+                            )
+                        })
+                        .collect();
 
-            all_unique_index_values
-                .into_iter()
-                .filter(|index_value| !index_value.is_static())
-                .collect_vec()
-        };
+                if let Some(filtered_index_values) = self.query.filtered_index_values.as_ref() {
+                    all_unique_index_values
+                        .retain(|(time, _)| filtered_index_values.contains(time));
+                }
+
+                // Prune the index values for which the row filter cannot possibly hold: these are the
+                // ones where none of the chunks of a required column could match.
+                //
+                // When resampling, the filter applies to aggregated data, so there's nothing we can
+                // prune ahead of time.
+                if let Some(row_filter) = row_filter.as_ref()
+                    && self.resampling().is_none()
+                {
+                    re_tracing::profile_scope!("row_filter_pruning");
+
+                    for leaf in row_filter.required_leaves() {
+                        let Some(view_idx) = leaf.view_idx() else {
+                            continue;
+                        };
+
+                        let Some(view_idx) = view_idx else {
+                            // The column is not part of the view contents: it is always null.
+                            all_unique_index_values.clear();
+                            break;
+                        };
+
+                        // Static data wins over everything else, for every index value: it's either
+                        // always there or never, and there's no chunk to prune either way.
+                        if let Some(ColumnDescriptor::Component(descr)) =
+                            view_contents.get(view_idx)
+                            && descr.is_static
+                        {
+                            continue;
+                        }
+
+                        let component_descr = match view_contents.get(view_idx) {
+                            Some(ColumnDescriptor::Component(descr)) => {
+                                Some(descr.component_descriptor())
+                            }
+                            _ => None,
+                        };
+
+                        let candidates: BTreeSet<TimeInt> = view_chunks
+                            .get(view_idx)
+                            .into_iter()
+                            .flatten()
+                            .filter(|(_cursor, chunk)| {
+                                // View chunks are slices of the stored chunks and share their IDs:
+                                // the statistics of the latter bound the data of the former.
+                                let statistics = component_descr.as_ref().and_then(|descr| {
+                                    store.chunk(&chunk.id())?.statistics()?.get(descr)
+                                });
+                                leaf.chunk_may_match(chunk, statistics)
+                            })
+                            .filter_map(|(_cursor, chunk)| {
+                                chunk
+                                    .timelines()
+                                    .get(&filtered_index)
+                                    .map(|time_column| time_column.times())
+                            })
+                            .flatten()
+                            .collect();
+
+                        all_unique_index_values.retain(|(time, _)| candidates.contains(time));
+                    }
+                }
+
+                let unique_index_values = all_unique_index_values
+                    .into_iter()
+                    .filter(|(index_value, _)| !index_value.is_static());
+
+                if let Some(resampling) = self.resampling() {
+                    let unique_index_values = resampled_index_values(
+                        unique_index_values.map(|(index_value, _)| index_value),
+                        resampling,
+                    );
+                    (unique_index_values, Vec::new())
+                } else if secondary_indices.is_empty() {
+                    let unique_index_values = unique_index_values
+                        .map(|(index_value, _)| index_value)
+                        .collect_vec();
+                    (unique_index_values, Vec::new())
+                } else {
+                    unique_index_values.unzip()
+                }
+            };
 
         let selected_static_values = {
             re_tracing::profile_scope!("static_values");
@@ -367,6 +533,15 @@ impl<E: StorageEngineLike> QueryHandle<E> {
             descr.sanity_check();
         }
 
+        let view_composite_index_rows = if query.secondary_indices.is_empty() {
+            Vec::new()
+        } else {
+            view_chunks
+                .iter()
+                .map(|chunks| chunks.iter().map(|_| OnceLock::new()).collect())
+                .collect()
+        };
+
         QueryHandleState {
             view_contents: view_contents_schema,
             selected_contents,
@@ -374,9 +549,27 @@ impl<E: StorageEngineLike> QueryHandle<E> {
             filtered_index,
             arrow_schema,
             view_chunks,
+            view_composite_index_rows,
             cur_row: AtomicU64::new(0),
             unique_index_values,
+            unique_secondary_index_values,
+            row_filter,
+            filtered_rows: OnceLock::new(),
+        }
+    }
+
+    /// The resampling that is effectively applied, if any.
+    ///
+    /// See [`QueryExpression::resampling`].
+    fn resampling(&self) -> Option<Resampling> {
+        if self.query.filtered_index.is_none()
+            || self.query.using_index_values.is_some()
+            || !self.query.secondary_indices.is_empty()
+        {
+            return None;
         }
+
+        self.query.resampling
     }
 
     #[tracing::instrument(level = "info", skip_all)]
@@ -695,6 +888,9 @@ impl<E: StorageEngineLike> QueryHandle<E> {
     ///
     /// Does nothing if `row_idx` is out of bounds.
     ///
+    /// If [`QueryExpression::filter`] is set, `row_idx` refers to the filtered rows, see
+    /// [`Self::num_rows`].
+    ///
     /// ## Concurrency
     ///
     /// Cursors are implemented using atomic variables, which means calling any of the `seek_*`
@@ -712,6 +908,15 @@ impl<E: StorageEngineLike> QueryHandle<E> {
     pub fn seek_to_row(&self, row_idx: usize) {
         let state = self.init();
 
+        let row_idx = if let Some(filtered_rows) = self.filtered_rows() {
+            let Some(row_idx) = filtered_rows.get(row_idx) else {
+                return;
+            };
+            *row_idx as usize
+        } else {
+            row_idx
+        };
+
         let Some(index_value) = state.unique_index_values.get(row_idx) else {
             return;
         };
@@ -739,50 +944,67 @@ impl<E: StorageEngineLike> QueryHandle<E> {
     /// I.e.: it's pretty cheap already.
     #[tracing::instrument(level = "debug", skip_all)]
     fn seek_to_index_value(&self, index_value: IndexValue) {
-        re_tracing::profile_function!();
+        seek_cursors_to_index_value(self.init(), index_value);
+    }
 
+    /// The indices of the unfiltered rows for which [`QueryExpression::filter`] holds, if set.
+    ///
+    /// The first call evaluates the filter on every single row, which costs as much as iterating
+    /// over the whole query, then puts the cursors back where they were.
+    fn filtered_rows(&self) -> Option<&[u64]> {
         let state = self.init();
+        state.row_filter.as_ref()?;
 
-        if index_value.is_static() {
-            for chunks in &state.view_chunks {
-                for (cursor, _chunk) in chunks {
-                    cursor.store(0, Ordering::Relaxed);
-                }
-            }
-            return;
-        }
+        let filtered_rows = state.filtered_rows.get_or_init(|| {
+            self.engine
+                .with(|store, cache| self.compute_filtered_rows(store, cache))
+        });
 
-        for chunks in &state.view_chunks {
-            for (cursor, chunk) in chunks {
-                // NOTE: The chunk has been densified already: its global time range is the same as
-                // the time range for the specific component of interest.
-                let Some(time_column) = chunk.timelines().get(&state.filtered_index) else {
-                    continue;
-                };
+        Some(filtered_rows)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn compute_filtered_rows(&self, store: &ChunkStore, cache: &QueryCache) -> Vec<u64> {
+        re_tracing::profile_function!();
 
-                let time_range = time_column.time_range();
+        let state = self.state.get_or_init(move || self.init_(store, cache));
 
-                let new_cursor = if index_value < time_range.min() {
-                    0
-                } else if index_value > time_range.max() {
-                    chunk.num_rows() as u64 /* yes, one past the end -- not a mistake */
-                } else {
-                    time_column
-                        .times_raw()
-                        .partition_point(|&time| time < index_value.as_i64())
-                        as u64
-                };
+        let cur_row = state.cur_row.load(Ordering::Relaxed);
+        state.cur_row.store(0, Ordering::Relaxed);
+        if let Some(index_value) = state.unique_index_values.first() {
+            seek_cursors_to_index_value(state, *index_value);
+        }
 
-                cursor.store(new_cursor, Ordering::Relaxed);
+        let mut filtered_rows = Vec::new();
+        let mut row_idx = 0;
+        while let Some(row) = self._next_row_or_filtered_out(store, cache) {
+            if row.is_some() {
+                filtered_rows.push(row_idx);
             }
+            row_idx += 1;
+        }
+
+        state.cur_row.store(cur_row, Ordering::Relaxed);
+        if let Some(index_value) = state.unique_index_values.get(cur_row as usize) {
+            seek_cursors_to_index_value(state, *index_value);
         }
+
+        filtered_rows
     }
 
     /// How many rows of data will be returned?
     ///
     /// The number of rows depends and only depends on the _view contents_.
     /// The _selected contents_ has no influence on this value.
+    ///
+    /// If [`QueryExpression::filter`] is set, only the rows for which it holds are counted. The
+    /// first call then evaluates the filter on every single row, which costs as much as iterating
+    /// over the whole query.
     pub fn num_rows(&self) -> u64 {
+        if let Some(filtered_rows) = self.filtered_rows() {
+            return filtered_rows.len() as _;
+        }
+
         self.init().unique_index_values.len() as _
     }
 
@@ -875,6 +1097,20 @@ impl<E: StorageEngineLike> QueryHandle<E> {
     pub fn _next_row(&self, store: &ChunkStore, cache: &QueryCache) -> Option<Vec<ArrowArrayRef>> {
         re_tracing::profile_function!();
 
+        loop {
+            if let Some(row) = self._next_row_or_filtered_out(store, cache)? {
+                return Some(row);
+            }
+        }
+    }
+
+    /// Same as [`Self::_next_row`], but returns `Some(None)` for rows that are filtered out by
+    /// [`QueryExpression::filter`].
+    fn _next_row_or_filtered_out(
+        &self,
+        store: &ChunkStore,
+        cache: &QueryCache,
+    ) -> Option<Option<Vec<ArrowArrayRef>>> {
         /// Temporary state used to resolve the streaming join for the current iteration.
         #[derive(Debug)]
         struct StreamingJoinStateEntry<'a> {
@@ -900,6 +1136,14 @@ impl<E: StorageEngineLike> QueryHandle<E> {
             ///
             /// See [`QueryExpression::sparse_fill_strategy`].
             Retrofilled(UnitChunkShared),
+
+            /// Data computed from one or more rows in the view contents, e.g. interpolated or
+            /// aggregated.
+            ///
+            /// This doesn't correspond to any actual row of data.
+            ///
+            /// See [`SparseFillStrategy::Interpolate`] and [`QueryExpression::resampling`].
+            Synthesized(ArrowListArray),
         }
 
         // Although that's a synchronous lock, we probably don't need to worry about it until
@@ -909,6 +1153,7 @@ impl<E: StorageEngineLike> QueryHandle<E> {
 
         let row_idx = state.cur_row.fetch_add(1, Ordering::Relaxed);
         let cur_index_value = state.unique_index_values.get(row_idx as usize)?;
+        let cur_secondary_index_values = state.unique_secondary_index_values.get(row_idx as usize);
 
         // First, we need to find, among all the chunks available for the current view contents,
         // what is their index value for the current row?
@@ -924,7 +1169,7 @@ impl<E: StorageEngineLike> QueryHandle<E> {
         for (view_column_idx, view_chunks) in state.view_chunks.iter().enumerate() {
             let streaming_state = &mut view_streaming_state[view_column_idx];
 
-            'overlaps: for (cur_cursor, cur_chunk) in view_chunks {
+            'overlaps: for (chunk_idx, (cur_cursor, cur_chunk)) in view_chunks.iter().enumerate() {
                 // TODO(cmc): This can easily be optimized by looking ahead and breaking as soon as chunks
                 // stop overlapping.
 
@@ -950,6 +1195,44 @@ impl<E: StorageEngineLike> QueryHandle<E> {
                         continue 'overlaps;
                     };
 
+                    if index_value == *cur_index_value
+                        && let Some(cur_secondary_index_values) = cur_secondary_index_values
+                    {
+                        // With a composite index, the rows that share the same filtered index
+                        // value can still belong to different output rows: only the latest one
+                        // that matches the whole tuple is relevant.
+                        //
+                        // NOTE: The cursor must not move past these rows yet, as the next output
+                        // rows might still need them.
+                        let Some(composite_index_rows) = state
+                            .view_composite_index_rows
+                            .get(view_column_idx)
+                            .and_then(|chunks| chunks.get(chunk_idx))
+                            .map(|rows| {
+                                rows.get_or_init(|| {
+                                    composite_index_rows(
+                                        cur_chunk,
+                                        &state.filtered_index,
+                                        &self.query.secondary_indices,
+                                    )
+                                })
+                            })
+                        else {
+                            debug_assert!(false, "chunk_idx out of bounds");
+                            continue 'overlaps;
+                        };
+
+                        let Some(&(row, row_id)) = composite_index_rows
+                            .get(&cur_index_value.as_i64())
+                            .and_then(|rows| rows.get(cur_secondary_index_values.as_slice()))
+                        else {
+                            continue 'overlaps;
+                        };
+
+                        cur_cursor_value = row as u64;
+                        break 'walk (index_value, row_id);
+                    }
+
                     if index_value == *cur_index_value {
                         // TODO(cmc): Because of Arrow's `ListArray` limitations, we inline the
                         // "deduped_latest_on_index" logic here directly, which prevents a lot of
@@ -1011,11 +1294,55 @@ impl<E: StorageEngineLike> QueryHandle<E> {
             .map(|streaming_state| streaming_state.map(StreamingJoinState::StreamingJoinState))
             .collect_vec();
 
+        let resampling = self.resampling();
+
+        // When resampling, the current index value is the start of a bucket: rather than only
+        // looking at that exact index value, every column gets aggregated over the whole bucket.
+        //
+        // NOTE: The streaming join above still has to run regardless, in order to keep the
+        // chunk cursors moving forward.
+        if let Some(resampling) = resampling {
+            let bucket = AbsoluteTimeRange::new(
+                *cur_index_value,
+                cur_index_value
+                    .as_i64()
+                    .saturating_add_unsigned(resampling.interval.get() - 1),
+            );
+
+            for (view_idx, streaming_state) in view_streaming_state.iter_mut().enumerate() {
+                let (Some(ColumnDescriptor::Component(_)), Some(view_chunks)) = (
+                    state.view_contents.get_index_or_component(view_idx),
+                    state.view_chunks.get(view_idx),
+                ) else {
+                    continue;
+                };
+
+                *streaming_state = aggregate_bucket(
+                    view_chunks,
+                    &state.filtered_index,
+                    self.query.filtered_index_values.as_ref(),
+                    bucket,
+                    resampling.aggregation,
+                )
+                .map(StreamingJoinState::Synthesized);
+            }
+        }
+
         // Static always wins, no matter what.
         for (selected_idx, static_state) in state.selected_static_values.iter().enumerate() {
-            if let static_state @ Some(_) =
-                static_state.clone().map(StreamingJoinState::Retrofilled)
+            let static_state = if resampling
+                .is_some_and(|resampling| resampling.aggregation == AggregationFunction::Count)
             {
+                // Static data counts as a single value, in every bucket.
+                static_state
+                    .as_ref()
+                    .and_then(|_| count_list_array(1))
+                    .map(StreamingJoinState::Synthesized)
+            } else {
+                static_state.clone().map(StreamingJoinState::Retrofilled)
+            };
+
+            if let static_state @ Some(_) = static_state {
                 let Some(view_idx) = state
                     .selected_contents
                     .get(selected_idx)
@@ -1034,6 +1361,27 @@ impl<E: StorageEngineLike> QueryHandle<E> {
             }
         }
 
+        // The row filter applies before any sparse-filling takes place.
+        if let Some(row_filter) = state.row_filter.as_ref() {
+            let cell = |view_idx: usize| -> Option<ArrowArrayRef> {
+                match view_streaming_state.get(view_idx)?.as_ref()? {
+                    StreamingJoinState::StreamingJoinState(s) => {
+                        let (_, list_array) = s.chunk.components().iter().next()?;
+                        Some(list_array.value(s.cursor as usize))
+                    }
+                    StreamingJoinState::Retrofilled(unit) => {
+                        let (_, list_array) = unit.components().iter().next()?;
+                        Some(list_array.value(0))
+                    }
+                    StreamingJoinState::Synthesized(list_array) => Some(list_array.value(0)),
+                }
+            };
+
+            if !row_filter.evaluate(&cell) {
+                return Some(None);
+            }
+        }
+
         match self.query.sparse_fill_strategy {
             SparseFillStrategy::None => {}
 
@@ -1088,6 +1436,80 @@ impl<E: StorageEngineLike> QueryHandle<E> {
                         .map(|unit| StreamingJoinState::Retrofilled(unit.clone()));
                 }
             }
+
+            SparseFillStrategy::LatestAtView
+            | SparseFillStrategy::Interpolate { .. }
+            | SparseFillStrategy::NearestWithin { .. } => {
+                // Everything that yielded `null` for the current iteration.
+                let null_streaming_states = view_streaming_state
+                    .iter_mut()
+                    .enumerate()
+                    .filter(|(_view_idx, streaming_state)| streaming_state.is_none());
+
+                for (view_idx, streaming_state) in null_streaming_states {
+                    let Some(view_chunks) = state.view_chunks.get(view_idx) else {
+                        continue;
+                    };
+
+                    // NOTE: Contrary to `LatestAtGlobal`, there is no need for any extra query here:
+                    // the view chunks already contain everything that we're allowed to fill from.
+                    let (before, after) =
+                        find_view_samples(view_chunks, &state.filtered_index, *cur_index_value);
+
+                    let as_streaming_state = |sample: ViewSample<'_>| {
+                        StreamingJoinState::StreamingJoinState(StreamingJoinStateEntry {
+                            chunk: sample.chunk,
+                            cursor: sample.cursor,
+                            row_id: sample.row_id,
+                        })
+                    };
+                    let distance = |sample: &ViewSample<'_>| {
+                        sample.index_value.abs_diff(cur_index_value.as_i64())
+                    };
+
+                    *streaming_state = match self.query.sparse_fill_strategy {
+                        SparseFillStrategy::Interpolate { max_gap } => {
+                            let interpolated = before
+                                .as_ref()
+                                .zip(after.as_ref())
+                                .filter(|(before, after)| {
+                                    before.index_value.abs_diff(after.index_value) <= max_gap
+                                })
+                                .and_then(|(before, after)| {
+                                    interpolate_samples(before, after, *cur_index_value)
+                                });
+
+                            if let Some(interpolated) = interpolated {
+                                Some(StreamingJoinState::Synthesized(interpolated))
+                            } else {
+                                // Cannot be interpolated: fall back to latest-at semantics.
+                                before
+                                    .filter(|before| distance(before) <= max_gap)
+                                    .map(as_streaming_state)
+                            }
+                        }
+
+                        SparseFillStrategy::NearestWithin { tolerance } => {
+                            let nearest = match (before, after) {
+                                (Some(before), Some(after)) => {
+                                    if distance(&after) < distance(&before) {
+                                        Some(after)
+                                    } else {
+                                        Some(before)
+                                    }
+                                }
+                                (before, after) => before.or(after),
+                            };
+
+                            nearest
+                                .filter(|nearest| distance(nearest) <= tolerance)
+                                .map(as_streaming_state)
+                        }
+
+                        _ => before.map(as_streaming_state),
+                    };
+                }
+            }
         }
 
         // We are stitching a bunch of unrelated cells together in order to create the final row
@@ -1115,15 +1537,19 @@ impl<E: StorageEngineLike> QueryHandle<E> {
                 .flatten()
                 .flat_map(|streaming_state| {
                     match streaming_state {
-                        StreamingJoinState::StreamingJoinState(s) => s.chunk.timelines(),
-                        StreamingJoinState::Retrofilled(unit) => unit.timelines(),
+                        StreamingJoinState::StreamingJoinState(s) => Some(s.chunk.timelines()),
+                        StreamingJoinState::Retrofilled(unit) => Some(unit.timelines()),
+                        // Synthesized data doesn't correspond to any actual row.
+                        StreamingJoinState::Synthesized(_) => None,
                     }
-                    .values()
+                    .into_iter()
+                    .flat_map(|timelines| timelines.values())
                     // NOTE: Cannot fail, just want to stay away from unwraps.
                     .filter_map(move |time_column| {
                         let cursor = match streaming_state {
                             StreamingJoinState::StreamingJoinState(s) => s.cursor as usize,
-                            StreamingJoinState::Retrofilled(_) => 0,
+                            StreamingJoinState::Retrofilled(_)
+                            | StreamingJoinState::Synthesized(_) => 0,
                         };
                         time_column
                             .times_raw()
@@ -1161,6 +1587,25 @@ impl<E: StorageEngineLike> QueryHandle<E> {
                     ),
                 );
             }
+
+            // Same thing for every other component of a composite index.
+            if let Some(cur_secondary_index_values) = cur_secondary_index_values {
+                for (secondary_index, value) in self
+                    .query
+                    .secondary_indices
+                    .iter()
+                    .zip(cur_secondary_index_values)
+                {
+                    if let Some(value) = value {
+                        max_value_per_index.insert(
+                            secondary_index.index,
+                            (*value, ArrowScalarBuffer::from(vec![value.as_i64()])),
+                        );
+                    } else {
+                        max_value_per_index.remove(&secondary_index.index);
+                    }
+                }
+            }
         }
 
         // NOTE: Non-component entries have no data to slice, hence the optional layer.
@@ -1201,6 +1646,8 @@ impl<E: StorageEngineLike> QueryHandle<E> {
                         })?;
                         unit.components().get(&component_desc).cloned()
                     }
+
+                    StreamingJoinState::Synthesized(list_array) => Some(list_array.clone()),
                 };
 
 
@@ -1254,7 +1701,7 @@ impl<E: StorageEngineLike> QueryHandle<E> {
 
         debug_assert_eq!(state.arrow_schema.fields.len(), selected_arrays.len());
 
-        Some(selected_arrays)
+        Some(Some(selected_arrays))
     }
 
     /// Calls [`Self::next_row`] and wraps the result in a [`ArrowRecordBatch`].
@@ -1313,177 +1760,1325 @@ impl<E: StorageEngineLike> QueryHandle<E> {
         std::iter::from_fn(move || self.next_row())
     }
 
-    /// Returns an iterator backed by [`Self::next_row`].
-    #[allow(clippy::should_implement_trait)] // we need an anonymous closure, this won't work
-    pub fn into_iter(self) -> impl Iterator<Item = Vec<ArrowArrayRef>> {
-        std::iter::from_fn(move || self.next_row())
-    }
+    /// Returns an iterator backed by [`Self::next_row`].
+    #[allow(clippy::should_implement_trait)] // we need an anonymous closure, this won't work
+    pub fn into_iter(self) -> impl Iterator<Item = Vec<ArrowArrayRef>> {
+        std::iter::from_fn(move || self.next_row())
+    }
+
+    /// Returns an iterator backed by [`Self::next_row_batch`].
+    #[allow(clippy::should_implement_trait)] // we need an anonymous closure, this won't work
+    pub fn batch_iter(&self) -> impl Iterator<Item = ArrowRecordBatch> + '_ {
+        std::iter::from_fn(move || self.next_row_batch())
+    }
+
+    /// Returns an iterator backed by [`Self::next_row_batch`].
+    #[allow(clippy::should_implement_trait)] // we need an anonymous closure, this won't work
+    pub fn into_batch_iter(self) -> impl Iterator<Item = ArrowRecordBatch> {
+        std::iter::from_fn(move || self.next_row_batch())
+    }
+}
+
+// ---
+
+/// Advances all the cursors of the query so that the next row yielded will correspond to
+/// `index_value`, see [`QueryHandle::seek_to_index_value`].
+fn seek_cursors_to_index_value(state: &QueryHandleState, index_value: IndexValue) {
+    re_tracing::profile_function!();
+
+    if index_value.is_static() {
+        for chunks in &state.view_chunks {
+            for (cursor, _chunk) in chunks {
+                cursor.store(0, Ordering::Relaxed);
+            }
+        }
+        return;
+    }
+
+    for chunks in &state.view_chunks {
+        for (cursor, chunk) in chunks {
+            // NOTE: The chunk has been densified already: its global time range is the same as
+            // the time range for the specific component of interest.
+            let Some(time_column) = chunk.timelines().get(&state.filtered_index) else {
+                continue;
+            };
+
+            let time_range = time_column.time_range();
+
+            let new_cursor = if index_value < time_range.min() {
+                0
+            } else if index_value > time_range.max() {
+                chunk.num_rows() as u64 /* yes, one past the end -- not a mistake */
+            } else {
+                time_column
+                    .times_raw()
+                    .partition_point(|&time| time < index_value.as_i64()) as u64
+            };
+
+            cursor.store(new_cursor, Ordering::Relaxed);
+        }
+    }
+}
+
+/// A non-null sample found in the view contents, see [`find_view_samples`].
+struct ViewSample<'a> {
+    chunk: &'a Chunk,
+    cursor: u64,
+    index_value: i64,
+    row_id: RowId,
+}
+
+/// Finds the closest samples at-or-before and strictly after `index_value` in the given
+/// view chunks.
+///
+/// Among samples sharing the same index value, the one with the highest `RowId` wins, as usual.
+///
+/// The view chunks are expected to be sorted and densified, see [`QueryHandleState::view_chunks`].
+fn find_view_samples<'a>(
+    view_chunks: &'a [(AtomicU64, Chunk)],
+    filtered_index: &TimelineName,
+    index_value: TimeInt,
+) -> (Option<ViewSample<'a>>, Option<ViewSample<'a>>) {
+    let index_value = index_value.as_i64();
+
+    let mut before: Option<ViewSample<'a>> = None;
+    let mut after: Option<ViewSample<'a>> = None;
+
+    for (_cursor, chunk) in view_chunks {
+        let Some(time_column) = chunk.timelines().get(filtered_index) else {
+            continue;
+        };
+        let times = time_column.times_raw();
+        let row_ids = chunk.row_ids_slice();
+
+        let sample_at = |cursor: usize| {
+            Some(ViewSample {
+                chunk,
+                cursor: cursor as u64,
+                index_value: *times.get(cursor)?,
+                row_id: *row_ids.get(cursor)?,
+            })
+        };
+
+        // The last row at-or-before `index_value` is also the one with the highest `RowId`.
+        let first_after = times.partition_point(|&time| time <= index_value);
+        if let Some(candidate) = first_after.checked_sub(1).and_then(sample_at)
+            && before.as_ref().is_none_or(|before| {
+                (candidate.index_value, candidate.row_id) > (before.index_value, before.row_id)
+            })
+        {
+            before = Some(candidate);
+        }
+
+        // Same thing for the next index value.
+        if let Some(&next_index_value) = times.get(first_after) {
+            let last_at_next = times.partition_point(|&time| time <= next_index_value) - 1;
+            if let Some(candidate) = sample_at(last_at_next)
+                && after.as_ref().is_none_or(|after| {
+                    candidate.index_value < after.index_value
+                        || (candidate.index_value == after.index_value
+                            && candidate.row_id > after.row_id)
+                })
+            {
+                after = Some(candidate);
+            }
+        }
+    }
+
+    (before, after)
+}
+
+/// Linearly interpolates the component data of two samples at the given index value.
+///
+/// Returns `None` if the data cannot be interpolated, see [`interpolate_arrays`].
+fn interpolate_samples(
+    before: &ViewSample<'_>,
+    after: &ViewSample<'_>,
+    index_value: TimeInt,
+) -> Option<ArrowListArray> {
+    let index_value = index_value.as_i64();
+    if after.index_value <= before.index_value {
+        return None;
+    }
+
+    let (_, before_list_array) = before.chunk.components().iter().next()?;
+    let (_, after_list_array) = after.chunk.components().iter().next()?;
+
+    let before_values = before_list_array.value(before.cursor as usize);
+    let after_values = after_list_array.value(after.cursor as usize);
+
+    let t =
+        (index_value - before.index_value) as f64 / (after.index_value - before.index_value) as f64;
+    let values = interpolate_arrays(&before_values, &after_values, t)?;
+
+    single_row_list_array(before_list_array.data_type(), values)
+}
+
+/// Linearly interpolates between two arrays, element-wise, with `t` in `[0, 1]`.
+///
+/// Only floating-point arrays (and fixed-size lists thereof) of the same datatype and length can
+/// be interpolated. Integers are never interpolated: they usually encode colors, identifiers,
+/// enums or flags, for which a value in between two samples is meaningless.
+fn interpolate_arrays(
+    before: &dyn arrow::array::Array,
+    after: &dyn arrow::array::Array,
+    t: f64,
+) -> Option<ArrowArrayRef> {
+    if before.data_type() != after.data_type() || before.len() != after.len() {
+        return None;
+    }
+
+    match before.data_type() {
+        ArrowDataType::FixedSizeList(field, size) => {
+            let before = before.as_fixed_size_list_opt()?;
+            let after = after.as_fixed_size_list_opt()?;
+            let values = interpolate_arrays(before.values(), after.values(), t)?;
+
+            let array = ArrowFixedSizeListArray::try_new(
+                field.clone(),
+                *size,
+                values,
+                before.nulls().cloned(),
+            )
+            .ok()?;
+
+            Some(std::sync::Arc::new(array))
+        }
+
+        datatype if datatype.is_floating() => {
+            let before_f64 = arrow::compute::cast(before, &ArrowDataType::Float64).ok()?;
+            let after_f64 = arrow::compute::cast(after, &ArrowDataType::Float64).ok()?;
+
+            let values: ArrowFloat64Array = before_f64
+                .as_primitive::<ArrowFloat64Type>()
+                .iter()
+                .zip(after_f64.as_primitive::<ArrowFloat64Type>().iter())
+                .map(|(before, after)| {
+                    let (before, after) = (before?, after?);
+                    Some(before + (after - before) * t)
+                })
+                .collect();
+
+            arrow::compute::cast(&values, datatype).ok()
+        }
+
+        _ => None,
+    }
+}
+
+/// Computes the start of every resampling bucket between the first and last given index values.
+///
+/// Yields no buckets at all if there would be more than [`Resampling::MAX_BUCKETS`] of them.
+///
+/// See [`QueryExpression::resampling`].
+fn resampled_index_values(
+    mut index_values: impl DoubleEndedIterator<Item = IndexValue>,
+    resampling: Resampling,
+) -> Vec<IndexValue> {
+    let interval = i64::try_from(resampling.interval.get()).unwrap_or(i64::MAX);
+    let bucket_start = |index_value: IndexValue| {
+        index_value
+            .as_i64()
+            .div_euclid(interval)
+            .saturating_mul(interval)
+    };
+
+    let Some(first) = index_values.next() else {
+        return Vec::new();
+    };
+    let last = index_values.next_back().unwrap_or(first);
+    let (first_bucket, last_bucket) = (bucket_start(first), bucket_start(last));
+
+    let num_buckets =
+        (i128::from(last_bucket) - i128::from(first_bucket)) / i128::from(interval) + 1;
+    if num_buckets > i128::from(Resampling::MAX_BUCKETS) {
+        re_log::error_once!(
+            "Resampling every {} index units would yield {num_buckets} rows, more than the maximum of {}: use a larger interval",
+            resampling.interval,
+            Resampling::MAX_BUCKETS,
+        );
+        return Vec::new();
+    }
+
+    std::iter::successors(Some(first_bucket), |start| start.checked_add(interval))
+        .take_while(|start| *start <= last_bucket)
+        .map(TimeInt::new_temporal)
+        .collect()
+}
+
+/// Aggregates all the values of a view column that fall within the given bucket.
+///
+/// Returns `None` if there is nothing to aggregate, except when counting.
+///
+/// The view chunks are expected to be sorted and densified, see [`QueryHandleState::view_chunks`].
+fn aggregate_bucket(
+    view_chunks: &[(AtomicU64, Chunk)],
+    filtered_index: &TimelineName,
+    filtered_index_values: Option<&BTreeSet<IndexValue>>,
+    bucket: AbsoluteTimeRange,
+    aggregation: AggregationFunction,
+) -> Option<ArrowListArray> {
+    let mut samples: Vec<(i64, RowId, &ArrowListArray, usize)> = Vec::new();
+
+    for (_cursor, chunk) in view_chunks {
+        let Some(time_column) = chunk.timelines().get(filtered_index) else {
+            continue;
+        };
+        let Some((_, list_array)) = chunk.components().iter().next() else {
+            continue;
+        };
+
+        let times = time_column.times_raw();
+        let start = times.partition_point(|&time| time < bucket.min().as_i64());
+        let end = times.partition_point(|&time| time <= bucket.max().as_i64());
+
+        samples.extend(
+            itertools::izip!(times, chunk.row_ids_slice())
+                .enumerate()
+                .take(end)
+                .skip(start)
+                .filter(|(_, (time, _))| {
+                    filtered_index_values.is_none_or(|index_values| {
+                        index_values.contains(&TimeInt::new_temporal(**time))
+                    })
+                })
+                .map(|(cursor, (time, row_id))| (*time, *row_id, list_array, cursor)),
+        );
+    }
+
+    // Only the latest row is kept for any given index value, as usual.
+    samples.sort_by(|(time1, row_id1, ..), (time2, row_id2, ..)| {
+        (time2, row_id2).cmp(&(time1, row_id1))
+    });
+    samples.dedup_by_key(|(time, ..)| *time);
+
+    if aggregation == AggregationFunction::Count {
+        return count_list_array(samples.len() as u64);
+    }
+
+    // Samples are sorted in descending order: the first one is the last one.
+    let (_, _, last_list_array, last_cursor) = samples.first()?;
+    let last = || Some(last_list_array.slice(*last_cursor, 1));
+
+    if aggregation == AggregationFunction::Last {
+        return last();
+    }
+
+    let values = samples
+        .iter()
+        .map(|(_, _, list_array, cursor)| list_array.value(*cursor))
+        .collect_vec();
+
+    aggregate_arrays(&values, aggregation)
+        .and_then(|values| single_row_list_array(last_list_array.data_type(), values))
+        // Cannot be aggregated: fall back to the last value.
+        .or_else(last)
+}
+
+/// Aggregates arrays element-wise.
+///
+/// The first array is the reference: arrays whose datatype or length differ from it are ignored.
+/// Only floating-point arrays can be aggregated, and the results keep the datatype of the
+/// reference. Everything else (integers, fixed-size lists such as positions, etc.) yields `None`:
+/// the mean of two colors or class IDs is meaningless, and so is the element-wise minimum of two
+/// positions.
+fn aggregate_arrays(
+    arrays: &[ArrowArrayRef],
+    aggregation: AggregationFunction,
+) -> Option<ArrowArrayRef> {
+    let reference = arrays.first()?;
+    let arrays = arrays
+        .iter()
+        .filter(|array| {
+            array.data_type() == reference.data_type() && array.len() == reference.len()
+        })
+        .collect_vec();
+
+    let datatype = reference.data_type();
+    if !datatype.is_floating() {
+        return None;
+    }
+
+    let arrays_f64 = arrays
+        .iter()
+        .map(|array| arrow::compute::cast(array, &ArrowDataType::Float64).ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    let values: ArrowFloat64Array = (0..reference.len())
+        .map(|i| {
+            let mut values = arrays_f64
+                .iter()
+                .map(|array| array.as_primitive::<ArrowFloat64Type>())
+                .filter(|array| array.is_valid(i))
+                .map(|array| array.value(i));
+
+            match aggregation {
+                AggregationFunction::Mean => {
+                    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), value| {
+                        (sum + value, count + 1)
+                    });
+                    (count > 0).then(|| sum / count as f64)
+                }
+                AggregationFunction::Min => values.reduce(f64::min),
+                AggregationFunction::Max => values.reduce(f64::max),
+                AggregationFunction::Last => values.next(),
+                AggregationFunction::Count => Some(values.count() as f64),
+            }
+        })
+        .collect();
+
+    arrow::compute::cast(&values, datatype).ok()
+}
+
+/// The latest row of a chunk, and its [`RowId`], for each value of a composite index: the value
+/// of the filtered index, then the values of the secondary indices.
+type CompositeIndexRows = HashMap<i64, HashMap<Vec<Option<IndexValue>>, (usize, RowId)>>;
+
+/// Walks all the rows of the chunk once to compute its [`CompositeIndexRows`].
+fn composite_index_rows(
+    chunk: &Chunk,
+    filtered_index: &Index,
+    secondary_indices: &[SecondaryIndex],
+) -> CompositeIndexRows {
+    re_tracing::profile_function!();
+
+    let mut rows = CompositeIndexRows::default();
+
+    let Some(index_times) = chunk
+        .timelines()
+        .get(filtered_index)
+        .map(|time_column| time_column.times_raw())
+    else {
+        return rows;
+    };
+
+    for (row, (&index_time, &row_id)) in index_times.iter().zip(chunk.row_ids_slice()).enumerate() {
+        let secondary_index_values = secondary_indices
+            .iter()
+            .map(|secondary_index| secondary_index_value(chunk, &secondary_index.index, row))
+            .collect_vec();
+
+        rows.entry(index_time)
+            .or_default()
+            .entry(secondary_index_values)
+            .and_modify(|latest| {
+                if latest.1 < row_id {
+                    *latest = (row, row_id);
+                }
+            })
+            .or_insert((row, row_id));
+    }
+
+    rows
+}
+
+/// The value of the given row of the chunk on a secondary index, if it is indexed on it.
+///
+/// See [`QueryExpression::secondary_indices`].
+fn secondary_index_value(chunk: &Chunk, index: &Index, row: usize) -> Option<IndexValue> {
+    chunk
+        .timelines()
+        .get(index)
+        .and_then(|time_column| time_column.times_raw().get(row).copied())
+        .map(TimeInt::new_temporal)
+}
+
+/// The datatype of component columns when counting, see [`AggregationFunction::Count`].
+fn count_datatype() -> ArrowDataType {
+    ArrowDataType::List(std::sync::Arc::new(ArrowField::new_list_field(
+        ArrowDataType::UInt64,
+        true,
+    )))
+}
+
+/// A single-row list array containing a single count, see [`AggregationFunction::Count`].
+fn count_list_array(count: u64) -> Option<ArrowListArray> {
+    single_row_list_array(
+        &count_datatype(),
+        std::sync::Arc::new(ArrowUInt64Array::from(vec![count])),
+    )
+}
+
+/// Wraps the given values in a single-row list array of the given datatype.
+fn single_row_list_array(
+    list_datatype: &ArrowDataType,
+    values: ArrowArrayRef,
+) -> Option<ArrowListArray> {
+    let ArrowDataType::List(field) = list_datatype else {
+        return None;
+    };
+
+    ArrowListArray::try_new(
+        field.clone(),
+        ArrowOffsetBuffer::from_lengths([values.len()]),
+        values,
+        None,
+    )
+    .ok()
+}
+
+// ---
+
+#[cfg(test)]
+#[allow(clippy::iter_on_single_items)]
+mod tests {
+    use std::sync::Arc;
+
+    use anyhow::Context as _;
+    use arrow::array::{Array as _, AsArray as _, StringArray, UInt32Array};
+    use arrow::compute::concat_batches;
+    use insta::assert_snapshot;
+
+    use re_chunk::{Chunk, ChunkId, ComponentIdentifier, RowId, TimePoint};
+    use re_chunk_store::{
+        AbsoluteTimeRange, ChunkStore, ChunkStoreConfig, ChunkStoreHandle, QueryExpression, TimeInt,
+    };
+    use re_format_arrow::format_record_batch;
+    use re_log_types::{
+        EntityPath, Timeline, build_frame_nr, build_log_time,
+        example_components::{MyColor, MyLabel, MyPoint, MyPoints},
+    };
+    use re_query::StorageEngine;
+    use re_sorbet::ComponentColumnSelector;
+    use re_types::{AnyValues, AsComponents as _, ComponentDescriptor};
+    use re_types_core::components;
+
+    use crate::{QueryCache, QueryEngine};
+
+    use super::*;
+
+    /// Implement `Display` for `ArrowRecordBatch`
+    struct DisplayRB(ArrowRecordBatch);
+
+    impl std::fmt::Display for DisplayRB {
+        #[inline]
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let width = 200;
+            re_format_arrow::format_record_batch_with_width(&self.0, Some(width)).fmt(f)
+        }
+    }
+
+    /// The first value of every row of the component column of the given entity.
+    fn column_values<T: arrow::datatypes::ArrowPrimitiveType>(
+        dataframe: &ArrowRecordBatch,
+        entity_path: &str,
+    ) -> anyhow::Result<Vec<Option<T::Native>>> {
+        let (column_idx, _) = dataframe
+            .schema()
+            .fields()
+            .iter()
+            .find_position(|field| field.name().starts_with(&format!("{entity_path}:")))
+            .with_context(|| format!("missing {entity_path} column"))?;
+
+        let column = dataframe.column(column_idx).as_list::<i32>();
+        Ok((0..column.len())
+            .map(|row| {
+                column
+                    .is_valid(row)
+                    .then(|| column.value(row).as_primitive::<T>().value(0))
+            })
+            .collect())
+    }
+
+    // NOTE: The best way to understand what these tests are doing is to run them in verbose mode,
+    // e.g. `cargo t -p re_dataframe -- --show-output barebones`.
+    // Each test will print the state of the store, the query being run, and the results that were
+    // returned in the usual human-friendly format.
+    // From there it is generally straightforward to infer what's going on.
+
+    // TODO(cmc): at least one basic test for every feature in `QueryExpression`.
+    // In no particular order:
+    // * [x] filtered_index
+    // * [x] filtered_index_range
+    // * [x] filtered_index_values
+    // * [x] view_contents
+    // * [x] selection
+    // * [x] filtered_is_not_null
+    // * [x] filter
+    // * [x] sparse_fill_strategy
+    // * [x] using_index_values
+    // * [x] resampling
+    // * [x] secondary_indices
+    //
+    // In addition to those, some much needed extras:
+    // * [x] num_rows
+    // * [x] clears
+    // * [ ] timelines returned with selection=none
+    // * [x] pagination
+
+    // TODO(cmc): At some point I'd like to stress multi-entity queries too, but that feels less
+    // urgent considering how things are implemented (each entity lives in its own index, so it's
+    // really just more of the same).
+
+    /// All features disabled.
+    #[test]
+    fn barebones() -> anyhow::Result<()> {
+        re_log::setup_logging();
+
+        let store = ChunkStoreHandle::new(create_nasty_store()?);
+        eprintln!("{store}");
+        let query_cache = QueryCache::new_handle(store.clone());
+        let query_engine = QueryEngine::new(store.clone(), query_cache.clone());
+
+        let filtered_index = Some(TimelineName::new("frame_nr"));
+
+        // static
+        {
+            let query = QueryExpression::default();
+            eprintln!("{query:#?}:");
+
+            let query_handle = query_engine.query(query.clone());
+            assert_eq!(
+                query_engine.query(query.clone()).into_iter().count() as u64,
+                query_handle.num_rows()
+            );
+            let dataframe = concat_batches(
+                query_handle.schema(),
+                &query_handle.batch_iter().collect_vec(),
+            )?;
+            eprintln!("{}", format_record_batch(&dataframe.clone()));
+
+            assert_snapshot!(DisplayRB(dataframe));
+        }
+
+        // temporal
+        {
+            let query = QueryExpression {
+                filtered_index,
+                ..Default::default()
+            };
+            eprintln!("{query:#?}:");
+
+            let query_handle = query_engine.query(query.clone());
+            assert_eq!(
+                query_engine.query(query.clone()).into_iter().count() as u64,
+                query_handle.num_rows()
+            );
+            let dataframe = concat_batches(
+                query_handle.schema(),
+                &query_handle.batch_iter().collect_vec(),
+            )?;
+            eprintln!("{}", format_record_batch(&dataframe.clone()));
+
+            assert_snapshot!(DisplayRB(dataframe));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_fill_strategy_latestatglobal() -> anyhow::Result<()> {
+        re_log::setup_logging();
+
+        let store = ChunkStoreHandle::new(create_nasty_store()?);
+        eprintln!("{store}");
+        let query_cache = QueryCache::new_handle(store.clone());
+        let query_engine = QueryEngine::new(store.clone(), query_cache.clone());
+
+        let filtered_index = Some(TimelineName::new("frame_nr"));
+        let query = QueryExpression {
+            filtered_index,
+            sparse_fill_strategy: SparseFillStrategy::LatestAtGlobal,
+            ..Default::default()
+        };
+        eprintln!("{query:#?}:");
+
+        let query_handle = query_engine.query(query.clone());
+        assert_eq!(
+            query_engine.query(query.clone()).into_iter().count() as u64,
+            query_handle.num_rows()
+        );
+        let dataframe = concat_batches(
+            query_handle.schema(),
+            &query_handle.batch_iter().collect_vec(),
+        )?;
+        eprintln!("{}", format_record_batch(&dataframe.clone()));
+
+        assert_snapshot!(DisplayRB(dataframe));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_fill_strategy_view_scoped() -> anyhow::Result<()> {
+        re_log::setup_logging();
+
+        let store = ChunkStoreHandle::new(create_scalars_store()?);
+        eprintln!("{store}");
+        let query_cache = QueryCache::new_handle(store.clone());
+        let query_engine = QueryEngine::new(store.clone(), query_cache.clone());
+
+        // Samples for `/scalars` are at frames #10 (1.0) and #30 (3.0), while `/other` adds
+        // extra rows at frames #20 and #25.
+        let scalars_at = |sparse_fill_strategy| -> anyhow::Result<Vec<Option<f64>>> {
+            let query = QueryExpression {
+                filtered_index: Some(TimelineName::new("frame_nr")),
+                sparse_fill_strategy,
+                ..Default::default()
+            };
+            eprintln!("{query:#?}:");
+
+            let query_handle = query_engine.query(query);
+            let dataframe = concat_batches(
+                query_handle.schema(),
+                &query_handle.batch_iter().collect_vec(),
+            )?;
+            eprintln!("{}", format_record_batch(&dataframe.clone()));
+
+            let (column_idx, _) = dataframe
+                .schema()
+                .fields()
+                .iter()
+                .find_position(|field| field.name().starts_with("/scalars:"))
+                .context("missing /scalars column")?;
+
+            let column = dataframe.column(column_idx).as_list::<i32>();
+            Ok((0..column.len())
+                .map(|row| {
+                    column.is_valid(row).then(|| {
+                        column
+                            .value(row)
+                            .as_primitive::<ArrowFloat64Type>()
+                            .value(0)
+                    })
+                })
+                .collect())
+        };
+
+        assert_eq!(
+            vec![Some(1.0), None, None, Some(3.0)],
+            scalars_at(SparseFillStrategy::None)?,
+        );
+        assert_eq!(
+            vec![Some(1.0), Some(1.0), Some(1.0), Some(3.0)],
+            scalars_at(SparseFillStrategy::LatestAtView)?,
+        );
+        assert_eq!(
+            vec![Some(1.0), Some(2.0), Some(2.5), Some(3.0)],
+            scalars_at(SparseFillStrategy::Interpolate { max_gap: 20 })?,
+        );
+        // Gap is too large to interpolate: falls back to latest-at within the max gap.
+        assert_eq!(
+            vec![Some(1.0), Some(1.0), None, Some(3.0)],
+            scalars_at(SparseFillStrategy::Interpolate { max_gap: 10 })?,
+        );
+        assert_eq!(
+            vec![Some(1.0), None, Some(3.0), Some(3.0)],
+            scalars_at(SparseFillStrategy::NearestWithin { tolerance: 5 })?,
+        );
+        // Ties go to the sample before.
+        assert_eq!(
+            vec![Some(1.0), Some(1.0), Some(3.0), Some(3.0)],
+            scalars_at(SparseFillStrategy::NearestWithin { tolerance: 10 })?,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_fill_strategy_interpolate_datatypes() -> anyhow::Result<()> {
+        use re_types::archetypes::Points2D;
+
+        re_log::setup_logging();
+
+        let mut store = ChunkStore::new(
+            re_log_types::StoreId::random(re_log_types::StoreKind::Recording, "test_app"),
+            ChunkStoreConfig::COMPACTION_DISABLED,
+        );
+
+        let points = |x: f32, color: u32, class_id: u16| {
+            Points2D::new([(x, 0.0)])
+                .with_colors([color])
+                .with_class_ids([class_id])
+        };
+        let chunk1 = Chunk::builder(EntityPath::from("/points"))
+            .with_archetype(
+                RowId::new(),
+                [build_frame_nr(10)],
+                &points(1.0, 0xFF00_00FF, 1),
+            )
+            .with_archetype(
+                RowId::new(),
+                [build_frame_nr(30)],
+                &points(3.0, 0x0000_FFFF, 3),
+            )
+            .build()?;
+        let chunk2 = Chunk::builder(EntityPath::from("/other"))
+            .with_archetype(RowId::new(), [build_frame_nr(20)], &points(0.0, 0, 0))
+            .build()?;
+        store.insert_chunk(&Arc::new(chunk1))?;
+        store.insert_chunk(&Arc::new(chunk2))?;
+
+        let store = ChunkStoreHandle::new(store);
+        let query_cache = QueryCache::new_handle(store.clone());
+        let query_engine = QueryEngine::new(store.clone(), query_cache.clone());
+
+        let query = QueryExpression {
+            filtered_index: Some(TimelineName::new("frame_nr")),
+            sparse_fill_strategy: SparseFillStrategy::Interpolate { max_gap: 20 },
+            ..Default::default()
+        };
+        eprintln!("{query:#?}:");
+
+        let query_handle = query_engine.query(query);
+        let dataframe = concat_batches(
+            query_handle.schema(),
+            &query_handle.batch_iter().collect_vec(),
+        )?;
+        eprintln!("{}", format_record_batch(&dataframe.clone()));
+
+        let column = |name: &str| -> anyhow::Result<ArrowArrayRef> {
+            let (column_idx, _) = dataframe
+                .schema()
+                .fields()
+                .iter()
+                .find_position(|field| field.name() == name)
+                .with_context(|| format!("missing {name} column"))?;
+            Ok(dataframe.column(column_idx).as_list::<i32>().value(1))
+        };
+
+        // Floating-point positions are interpolated…
+        let positions = column("/points:Points2D:positions")?;
+        assert_eq!(
+            &[2.0, 0.0],
+            positions
+                .as_fixed_size_list()
+                .values()
+                .as_primitive::<arrow::datatypes::Float32Type>()
+                .values()
+                .as_ref(),
+        );
+
+        // …while integer colors and class IDs fall back to latest-at.
+        let colors = column("/points:Points2D:colors")?;
+        assert_eq!(
+            &[0xFF00_00FF],
+            colors
+                .as_primitive::<arrow::datatypes::UInt32Type>()
+                .values()
+                .as_ref(),
+        );
+        let class_ids = column("/points:Points2D:class_ids")?;
+        assert_eq!(
+            &[1],
+            class_ids
+                .as_primitive::<arrow::datatypes::UInt16Type>()
+                .values()
+                .as_ref(),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn resampling() -> anyhow::Result<()> {
+        re_log::setup_logging();
+
+        let store = ChunkStoreHandle::new(create_scalars_store()?);
+        eprintln!("{store}");
+        let query_cache = QueryCache::new_handle(store.clone());
+        let query_engine = QueryEngine::new(store.clone(), query_cache.clone());
+
+        // Samples for `/scalars` are at frames #10 (1.0) and #30 (3.0), while `/other` has
+        // samples at frames #20 (20.0) and #25 (25.0).
+        let resample = |aggregation| -> anyhow::Result<ArrowRecordBatch> {
+            let query = QueryExpression {
+                filtered_index: Some(TimelineName::new("frame_nr")),
+                resampling: Some(Resampling {
+                    interval: std::num::NonZeroU64::new(20).context("non-zero")?,
+                    aggregation,
+                }),
+                ..Default::default()
+            };
+            eprintln!("{query:#?}:");
+
+            let query_handle = query_engine.query(query);
+            assert_eq!(2, query_handle.num_rows());
+
+            let dataframe = concat_batches(
+                query_handle.schema(),
+                &query_handle.batch_iter().collect_vec(),
+            )?;
+            eprintln!("{}", format_record_batch(&dataframe.clone()));
+
+            Ok(dataframe)
+        };
+
+        {
+            let dataframe = resample(AggregationFunction::Mean)?;
+            assert_eq!(
+                vec![0, 20],
+                dataframe
+                    .column(0)
+                    .as_primitive::<arrow::datatypes::Int64Type>()
+                    .values()
+                    .to_vec(),
+            );
+            assert_eq!(
+                vec![Some(1.0), Some(3.0)],
+                column_values::<ArrowFloat64Type>(&dataframe, "/scalars")?,
+            );
+            assert_eq!(
+                vec![None, Some(22.5)],
+                column_values::<ArrowFloat64Type>(&dataframe, "/other")?,
+            );
+        }
+
+        {
+            let dataframe = resample(AggregationFunction::Min)?;
+            assert_eq!(
+                vec![None, Some(20.0)],
+                column_values::<ArrowFloat64Type>(&dataframe, "/other")?,
+            );
+        }
+
+        {
+            let dataframe = resample(AggregationFunction::Max)?;
+            assert_eq!(
+                vec![None, Some(25.0)],
+                column_values::<ArrowFloat64Type>(&dataframe, "/other")?,
+            );
+        }
+
+        {
+            let dataframe = resample(AggregationFunction::Last)?;
+            assert_eq!(
+                vec![None, Some(25.0)],
+                column_values::<ArrowFloat64Type>(&dataframe, "/other")?,
+            );
+        }
+
+        {
+            let dataframe = resample(AggregationFunction::Count)?;
+            assert_eq!(
+                vec![Some(1), Some(1)],
+                column_values::<arrow::datatypes::UInt64Type>(&dataframe, "/scalars")?,
+            );
+            assert_eq!(
+                vec![Some(0), Some(2)],
+                column_values::<arrow::datatypes::UInt64Type>(&dataframe, "/other")?,
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn resampling_non_floats() -> anyhow::Result<()> {
+        use re_types::archetypes::Points2D;
+
+        re_log::setup_logging();
+
+        let mut store = ChunkStore::new(
+            re_log_types::StoreId::random(re_log_types::StoreKind::Recording, "test_app"),
+            ChunkStoreConfig::COMPACTION_DISABLED,
+        );
+
+        let points = |x: f32, color: u32, class_id: u16| {
+            Points2D::new([(x, 0.0)])
+                .with_colors([color])
+                .with_class_ids([class_id])
+        };
+        let chunk = Chunk::builder(EntityPath::from("/points"))
+            .with_archetype(
+                RowId::new(),
+                [build_frame_nr(10)],
+                &points(1.0, 0xFF00_00FF, 1),
+            )
+            .with_archetype(
+                RowId::new(),
+                [build_frame_nr(15)],
+                &points(3.0, 0x0000_FFFF, 3),
+            )
+            .build()?;
+        store.insert_chunk(&Arc::new(chunk))?;
+
+        let store = ChunkStoreHandle::new(store);
+        let query_cache = QueryCache::new_handle(store.clone());
+        let query_engine = QueryEngine::new(store.clone(), query_cache.clone());
+
+        for aggregation in [
+            AggregationFunction::Mean,
+            AggregationFunction::Min,
+            AggregationFunction::Max,
+        ] {
+            let query = QueryExpression {
+                filtered_index: Some(TimelineName::new("frame_nr")),
+                resampling: Some(Resampling {
+                    interval: std::num::NonZeroU64::new(20).context("non-zero")?,
+                    aggregation,
+                }),
+                ..Default::default()
+            };
+            eprintln!("{query:#?}:");
+
+            let query_handle = query_engine.query(query);
+            let dataframe = concat_batches(
+                query_handle.schema(),
+                &query_handle.batch_iter().collect_vec(),
+            )?;
+            eprintln!("{}", format_record_batch(&dataframe.clone()));
+            assert_eq!(1, dataframe.num_rows());
 
-    /// Returns an iterator backed by [`Self::next_row_batch`].
-    #[allow(clippy::should_implement_trait)] // we need an anonymous closure, this won't work
-    pub fn batch_iter(&self) -> impl Iterator<Item = ArrowRecordBatch> + '_ {
-        std::iter::from_fn(move || self.next_row_batch())
-    }
+            let column = |name: &str| -> anyhow::Result<ArrowArrayRef> {
+                let (column_idx, _) = dataframe
+                    .schema()
+                    .fields()
+                    .iter()
+                    .find_position(|field| field.name() == name)
+                    .with_context(|| format!("missing {name} column"))?;
+                Ok(dataframe.column(column_idx).as_list::<i32>().value(0))
+            };
 
-    /// Returns an iterator backed by [`Self::next_row_batch`].
-    #[allow(clippy::should_implement_trait)] // we need an anonymous closure, this won't work
-    pub fn into_batch_iter(self) -> impl Iterator<Item = ArrowRecordBatch> {
-        std::iter::from_fn(move || self.next_row_batch())
+            // Neither positions, colors nor class IDs are aggregated: the last value wins.
+            let positions = column("/points:Points2D:positions")?;
+            assert_eq!(
+                &[3.0, 0.0],
+                positions
+                    .as_fixed_size_list()
+                    .values()
+                    .as_primitive::<arrow::datatypes::Float32Type>()
+                    .values()
+                    .as_ref(),
+            );
+            let colors = column("/points:Points2D:colors")?;
+            assert_eq!(
+                &[0x0000_FFFF],
+                colors
+                    .as_primitive::<arrow::datatypes::UInt32Type>()
+                    .values()
+                    .as_ref(),
+            );
+            let class_ids = column("/points:Points2D:class_ids")?;
+            assert_eq!(
+                &[3],
+                class_ids
+                    .as_primitive::<arrow::datatypes::UInt16Type>()
+                    .values()
+                    .as_ref(),
+            );
+        }
+
+        Ok(())
     }
-}
 
-// ---
+    #[test]
+    fn resampling_too_many_buckets() -> anyhow::Result<()> {
+        use re_types::archetypes::Scalars;
 
-#[cfg(test)]
-#[allow(clippy::iter_on_single_items)]
-mod tests {
-    use std::sync::Arc;
+        re_log::setup_logging();
 
-    use arrow::array::{StringArray, UInt32Array};
-    use arrow::compute::concat_batches;
-    use insta::assert_snapshot;
+        let mut store = ChunkStore::new(
+            re_log_types::StoreId::random(re_log_types::StoreKind::Recording, "test_app"),
+            ChunkStoreConfig::COMPACTION_DISABLED,
+        );
 
-    use re_chunk::{Chunk, ChunkId, ComponentIdentifier, RowId, TimePoint};
-    use re_chunk_store::{
-        AbsoluteTimeRange, ChunkStore, ChunkStoreConfig, ChunkStoreHandle, QueryExpression, TimeInt,
-    };
-    use re_format_arrow::format_record_batch;
-    use re_log_types::{
-        EntityPath, Timeline, build_frame_nr, build_log_time,
-        example_components::{MyColor, MyLabel, MyPoint, MyPoints},
-    };
-    use re_query::StorageEngine;
-    use re_sorbet::ComponentColumnSelector;
-    use re_types::{AnyValues, AsComponents as _, ComponentDescriptor};
-    use re_types_core::components;
+        let chunk = Chunk::builder(EntityPath::from("/scalars"))
+            .with_archetype(RowId::new(), [build_frame_nr(0)], &Scalars::new([1.0]))
+            .with_archetype(
+                RowId::new(),
+                [build_frame_nr(1_000_000_000_000_i64)],
+                &Scalars::new([2.0]),
+            )
+            .build()?;
+        store.insert_chunk(&Arc::new(chunk))?;
 
-    use crate::{QueryCache, QueryEngine};
+        let store = ChunkStoreHandle::new(store);
+        let query_cache = QueryCache::new_handle(store.clone());
+        let query_engine = QueryEngine::new(store.clone(), query_cache.clone());
 
-    use super::*;
+        let num_rows = |interval| -> anyhow::Result<u64> {
+            let query = QueryExpression {
+                filtered_index: Some(TimelineName::new("frame_nr")),
+                resampling: Some(Resampling {
+                    interval: std::num::NonZeroU64::new(interval).context("non-zero")?,
+                    aggregation: AggregationFunction::Mean,
+                }),
+                ..Default::default()
+            };
+            eprintln!("{query:#?}:");
 
-    /// Implement `Display` for `ArrowRecordBatch`
-    struct DisplayRB(ArrowRecordBatch);
+            let query_handle = query_engine.query(query);
+            assert_eq!(
+                query_handle.num_rows(),
+                query_handle
+                    .batch_iter()
+                    .map(|batch| batch.num_rows() as u64)
+                    .sum::<u64>()
+            );
 
-    impl std::fmt::Display for DisplayRB {
-        #[inline]
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            let width = 200;
-            re_format_arrow::format_record_batch_with_width(&self.0, Some(width)).fmt(f)
-        }
-    }
+            Ok(query_handle.num_rows())
+        };
 
-    // NOTE: The best way to understand what these tests are doing is to run them in verbose mode,
-    // e.g. `cargo t -p re_dataframe -- --show-output barebones`.
-    // Each test will print the state of the store, the query being run, and the results that were
-    // returned in the usual human-friendly format.
-    // From there it is generally straightforward to infer what's going on.
+        // A trillion buckets: rejected.
+        assert_eq!(0, num_rows(1)?);
 
-    // TODO(cmc): at least one basic test for every feature in `QueryExpression`.
-    // In no particular order:
-    // * [x] filtered_index
-    // * [x] filtered_index_range
-    // * [x] filtered_index_values
-    // * [x] view_contents
-    // * [x] selection
-    // * [x] filtered_is_not_null
-    // * [x] sparse_fill_strategy
-    // * [x] using_index_values
-    //
-    // In addition to those, some much needed extras:
-    // * [x] num_rows
-    // * [x] clears
-    // * [ ] timelines returned with selection=none
-    // * [x] pagination
+        // A reasonable interval: every bucket is there, empty or not.
+        assert_eq!(11, num_rows(100_000_000_000)?);
 
-    // TODO(cmc): At some point I'd like to stress multi-entity queries too, but that feels less
-    // urgent considering how things are implemented (each entity lives in its own index, so it's
-    // really just more of the same).
+        Ok(())
+    }
 
-    /// All features disabled.
     #[test]
-    fn barebones() -> anyhow::Result<()> {
+    fn row_filter() -> anyhow::Result<()> {
+        use re_chunk_store::{ComparisonOperator, RowFilter};
+        use re_types::archetypes::Scalars;
+
         re_log::setup_logging();
 
-        let store = ChunkStoreHandle::new(create_nasty_store()?);
+        let store = ChunkStoreHandle::new(create_scalars_store()?);
         eprintln!("{store}");
         let query_cache = QueryCache::new_handle(store.clone());
         let query_engine = QueryEngine::new(store.clone(), query_cache.clone());
 
-        let filtered_index = Some(TimelineName::new("frame_nr"));
+        let scalars = ComponentColumnSelector::from_descriptor(
+            EntityPath::from("/scalars"),
+            &Scalars::descriptor_scalars(),
+        );
+        let other = ComponentColumnSelector::from_descriptor(
+            EntityPath::from("/other"),
+            &Scalars::descriptor_scalars(),
+        );
 
-        // static
-        {
-            let query = QueryExpression::default();
+        // Samples for `/scalars` are at frames #10 (1.0) and #30 (3.0), while `/other` has
+        // samples at frames #20 (20.0) and #25 (25.0).
+        let filtered_rows = |filter: RowFilter, sparse_fill_strategy| -> anyhow::Result<_> {
+            let query = QueryExpression {
+                filtered_index: Some(TimelineName::new("frame_nr")),
+                filter: Some(filter),
+                sparse_fill_strategy,
+                ..Default::default()
+            };
             eprintln!("{query:#?}:");
 
-            let query_handle = query_engine.query(query.clone());
-            assert_eq!(
-                query_engine.query(query.clone()).into_iter().count() as u64,
-                query_handle.num_rows()
-            );
+            let query_handle = query_engine.query(query);
+            let num_rows = query_handle.num_rows();
             let dataframe = concat_batches(
                 query_handle.schema(),
                 &query_handle.batch_iter().collect_vec(),
             )?;
             eprintln!("{}", format_record_batch(&dataframe.clone()));
 
-            assert_snapshot!(DisplayRB(dataframe));
+            assert_eq!(num_rows, dataframe.num_rows() as u64);
+
+            let frames = dataframe
+                .column(0)
+                .as_primitive::<arrow::datatypes::Int64Type>()
+                .values()
+                .to_vec();
+
+            Ok((frames, dataframe))
+        };
+
+        {
+            let filter = RowFilter::compare(scalars.clone(), ComparisonOperator::Gt, 2.0);
+            let (frames, _) = filtered_rows(filter, SparseFillStrategy::None)?;
+            assert_eq!(vec![30], frames);
         }
 
-        // temporal
+        {
+            let filter = RowFilter::compare(scalars.clone(), ComparisonOperator::Gt, 2.0)
+                .or(RowFilter::is_not_null(other.clone()));
+            let (frames, _) = filtered_rows(filter, SparseFillStrategy::None)?;
+            assert_eq!(vec![20, 25, 30], frames);
+        }
+
+        {
+            let filter = RowFilter::compare(other.clone(), ComparisonOperator::GtEq, 25_i64).and(
+                RowFilter::compare(other.clone(), ComparisonOperator::Lt, 30.0),
+            );
+            let (frames, _) = filtered_rows(filter, SparseFillStrategy::None)?;
+            assert_eq!(vec![25], frames);
+        }
+
+        {
+            let filter = RowFilter::is_not_null(ComponentColumnSelector {
+                entity_path: EntityPath::from("/does/not/exist"),
+                component: "Scalars:scalars".to_owned(),
+            });
+            let (frames, _) = filtered_rows(filter, SparseFillStrategy::None)?;
+            assert!(frames.is_empty());
+        }
+
+        // The filter applies before sparse-filling.
+        {
+            let filter = RowFilter::is_not_null(other.clone());
+            let (frames, dataframe) = filtered_rows(filter, SparseFillStrategy::LatestAtGlobal)?;
+            assert_eq!(vec![20, 25], frames);
+
+            let (column_idx, _) = dataframe
+                .schema()
+                .fields()
+                .iter()
+                .find_position(|field| field.name().starts_with("/scalars:"))
+                .context("missing /scalars column")?;
+            let column = dataframe.column(column_idx).as_list::<i32>();
+            assert!(column.is_valid(0) && column.is_valid(1));
+        }
+
+        // Row indices refer to the filtered rows.
         {
             let query = QueryExpression {
-                filtered_index,
+                filtered_index: Some(TimelineName::new("frame_nr")),
+                filter: Some(
+                    RowFilter::compare(scalars.clone(), ComparisonOperator::Gt, 2.0)
+                        .or(RowFilter::is_not_null(other.clone())),
+                ),
                 ..Default::default()
             };
             eprintln!("{query:#?}:");
 
-            let query_handle = query_engine.query(query.clone());
-            assert_eq!(
-                query_engine.query(query.clone()).into_iter().count() as u64,
-                query_handle.num_rows()
-            );
-            let dataframe = concat_batches(
-                query_handle.schema(),
-                &query_handle.batch_iter().collect_vec(),
-            )?;
-            eprintln!("{}", format_record_batch(&dataframe.clone()));
+            let next_frame = |query_handle: &QueryHandle<_>| {
+                query_handle.next_row_batch().map(|batch| {
+                    batch
+                        .column(0)
+                        .as_primitive::<arrow::datatypes::Int64Type>()
+                        .value(0)
+                })
+            };
 
-            assert_snapshot!(DisplayRB(dataframe));
+            // Counting the rows halfway through doesn't move the cursors.
+            let query_handle = query_engine.query(query.clone());
+            assert_eq!(Some(20), next_frame(&query_handle));
+            assert_eq!(3, query_handle.num_rows());
+            assert_eq!(Some(25), next_frame(&query_handle));
+
+            let query_handle = query_engine.query(query);
+            query_handle.seek_to_row(1);
+            assert_eq!(Some(25), next_frame(&query_handle));
+            query_handle.seek_to_row(2);
+            assert_eq!(Some(30), next_frame(&query_handle));
+            assert_eq!(None, next_frame(&query_handle));
+            query_handle.seek_to_row(0);
+            assert_eq!(Some(20), next_frame(&query_handle));
+
+            // Out of bounds: does nothing.
+            query_handle.seek_to_row(3);
+            assert_eq!(Some(25), next_frame(&query_handle));
         }
 
         Ok(())
     }
 
     #[test]
-    fn sparse_fill_strategy_latestatglobal() -> anyhow::Result<()> {
+    fn secondary_indices() -> anyhow::Result<()> {
+        use re_types::archetypes::Scalars;
+
         re_log::setup_logging();
 
-        let store = ChunkStoreHandle::new(create_nasty_store()?);
+        let episode = |episode: i64| {
+            (
+                Timeline::new_sequence("episode"),
+                TimeInt::new_temporal(episode),
+            )
+        };
+        let step = |step: i64| (Timeline::new_sequence("step"), TimeInt::new_temporal(step));
+
+        let mut store = ChunkStore::new(
+            re_log_types::StoreId::random(re_log_types::StoreKind::Recording, "test_app"),
+            ChunkStoreConfig::COMPACTION_DISABLED,
+        );
+
+        let chunk_obs = Chunk::builder(EntityPath::from("/obs"))
+            .with_archetype(RowId::new(), [episode(1), step(0)], &Scalars::new([1.0]))
+            .with_archetype(RowId::new(), [episode(1), step(1)], &Scalars::new([2.0]))
+            .with_archetype(RowId::new(), [episode(2), step(0)], &Scalars::new([3.0]))
+            .build()?;
+        let chunk_reward = Chunk::builder(EntityPath::from("/reward"))
+            .with_archetype(RowId::new(), [episode(1), step(1)], &Scalars::new([10.0]))
+            .build()?;
+        let chunk_meta = Chunk::builder(EntityPath::from("/meta"))
+            .with_archetype(RowId::new(), [episode(1)], &Scalars::new([100.0]))
+            .build()?;
+
+        store.insert_chunk(&Arc::new(chunk_obs))?;
+        store.insert_chunk(&Arc::new(chunk_reward))?;
+        store.insert_chunk(&Arc::new(chunk_meta))?;
+
+        let store = ChunkStoreHandle::new(store);
         eprintln!("{store}");
         let query_cache = QueryCache::new_handle(store.clone());
         let query_engine = QueryEngine::new(store.clone(), query_cache.clone());
 
-        let filtered_index = Some(TimelineName::new("frame_nr"));
-        let query = QueryExpression {
-            filtered_index,
-            sparse_fill_strategy: SparseFillStrategy::LatestAtGlobal,
-            ..Default::default()
+        let run = |secondary_indices: Vec<SecondaryIndex>| -> anyhow::Result<ArrowRecordBatch> {
+            let query = QueryExpression {
+                filtered_index: Some(TimelineName::new("episode")),
+                secondary_indices,
+                ..Default::default()
+            };
+            eprintln!("{query:#?}:");
+
+            let query_handle = query_engine.query(query.clone());
+            assert_eq!(
+                query_engine.query(query.clone()).into_iter().count() as u64,
+                query_handle.num_rows()
+            );
+
+            let dataframe = concat_batches(
+                query_handle.schema(),
+                &query_handle.batch_iter().collect_vec(),
+            )?;
+            eprintln!("{}", format_record_batch(&dataframe.clone()));
+
+            Ok(dataframe)
         };
-        eprintln!("{query:#?}:");
 
-        let query_handle = query_engine.query(query.clone());
-        assert_eq!(
-            query_engine.query(query.clone()).into_iter().count() as u64,
-            query_handle.num_rows()
-        );
-        let dataframe = concat_batches(
-            query_handle.schema(),
-            &query_handle.batch_iter().collect_vec(),
-        )?;
-        eprintln!("{}", format_record_batch(&dataframe.clone()));
+        let index_values =
+            |dataframe: &ArrowRecordBatch, index: &str| -> anyhow::Result<Vec<Option<i64>>> {
+                let column = dataframe
+                    .column_by_name(index)
+                    .with_context(|| format!("missing {index} column"))?
+                    .as_primitive::<arrow::datatypes::Int64Type>();
+                Ok(column.iter().collect())
+            };
 
-        assert_snapshot!(DisplayRB(dataframe));
+        // Single index: one row per episode.
+        {
+            let dataframe = run(Vec::new())?;
+            assert_eq!(vec![Some(1), Some(2)], index_values(&dataframe, "episode")?);
+        }
+
+        // Composite index: one row per `(episode, step)`, data without a step comes first.
+        {
+            let dataframe = run(vec![SecondaryIndex::new("step")])?;
+            assert_eq!(
+                vec![Some(1), Some(1), Some(1), Some(2)],
+                index_values(&dataframe, "episode")?
+            );
+            assert_eq!(
+                vec![None, Some(0), Some(1), Some(0)],
+                index_values(&dataframe, "step")?
+            );
+            assert_eq!(
+                vec![None, Some(1.0), Some(2.0), Some(3.0)],
+                column_values::<ArrowFloat64Type>(&dataframe, "/obs")?,
+            );
+            assert_eq!(
+                vec![None, None, Some(10.0), None],
+                column_values::<ArrowFloat64Type>(&dataframe, "/reward")?,
+            );
+            assert_eq!(
+                vec![Some(100.0), None, None, None],
+                column_values::<ArrowFloat64Type>(&dataframe, "/meta")?,
+            );
+        }
+
+        // Composite index with a range on the secondary index.
+        {
+            let dataframe = run(vec![
+                SecondaryIndex::new("step").with_range(AbsoluteTimeRange::new(1, 1)),
+            ])?;
+            assert_eq!(vec![Some(1)], index_values(&dataframe, "episode")?);
+            assert_eq!(vec![Some(1)], index_values(&dataframe, "step")?);
+            assert_eq!(
+                vec![Some(2.0)],
+                column_values::<ArrowFloat64Type>(&dataframe, "/obs")?,
+            );
+            assert_eq!(
+                vec![Some(10.0)],
+                column_values::<ArrowFloat64Type>(&dataframe, "/reward")?,
+            );
+        }
 
         Ok(())
     }
@@ -2347,9 +3942,12 @@ mod tests {
             include_static_columns: re_chunk_store::StaticColumnSelection::Both,
             filtered_index: None,
             filtered_index_range: None,
+            secondary_indices: Vec::new(),
             filtered_index_values: None,
             using_index_values: None,
+            resampling: None,
             filtered_is_not_null: None,
+            filter: None,
             sparse_fill_strategy: re_chunk_store::SparseFillStrategy::None,
             selection: None,
         };
@@ -2716,6 +4314,30 @@ mod tests {
         Ok(store)
     }
 
+    fn create_scalars_store() -> anyhow::Result<ChunkStore> {
+        use re_types::archetypes::Scalars;
+
+        let mut store = ChunkStore::new(
+            re_log_types::StoreId::random(re_log_types::StoreKind::Recording, "test_app"),
+            ChunkStoreConfig::COMPACTION_DISABLED,
+        );
+
+        let chunk1 = Chunk::builder(EntityPath::from("/scalars"))
+            .with_archetype(RowId::new(), [build_frame_nr(10)], &Scalars::new([1.0]))
+            .with_archetype(RowId::new(), [build_frame_nr(30)], &Scalars::new([3.0]))
+            .build()?;
+
+        let chunk2 = Chunk::builder(EntityPath::from("/other"))
+            .with_archetype(RowId::new(), [build_frame_nr(20)], &Scalars::new([20.0]))
+            .with_archetype(RowId::new(), [build_frame_nr(25)], &Scalars::new([25.0]))
+            .build()?;
+
+        store.insert_chunk(&Arc::new(chunk1))?;
+        store.insert_chunk(&Arc::new(chunk2))?;
+
+        Ok(store)
+    }
+
     fn extend_nasty_store_with_clears(store: &mut ChunkStore) -> anyhow::Result<()> {
         let entity_path = EntityPath::from("/this/that");
         let entity_path_parent = EntityPath::from("/this");
@@ -0,0 +1,1454 @@
+//! A compact textual syntax for dataframe queries.
+//!
+//! This is shared by the CLI, the dataframe view and the SDKs, so that queries can be copied
+//! from one to the other as-is.
+//!
+//! ```text
+//! /world/** -/world/secret/** /world/points:[positions, radius] index=frame_nr range=10..20 fill=latest-at
+//! ```
+//!
+//! A query is a whitespace-separated list of clauses, in any order:
+//! * `[+|-]<entity expression>[:<component> | :[<component>, …]]`: the view contents, using the
+//!   same expressions as [`EntityPathFilter`]. Components can be referred to using either their
+//!   full identifier (`Points3D:positions`) or their short name (`positions`).
+//!   Leaving out the contents altogether means: everything.
+//! * `index=<timeline>[,<timeline>…]`: the filtered index, see [`QueryExpression::filtered_index`],
+//!   followed by the secondary indices of a composite index, if any,
+//!   see [`QueryExpression::secondary_indices`].
+//! * `range=<min>..<max>`: the filtered index range, either side being optional,
+//!   see [`QueryExpression::filtered_index_range`] and [`IndexRangeSpec`].
+//! * `range.<timeline>=<min>..<max>`: the range of one of the secondary indices,
+//!   see [`SecondaryIndex::range`].
+//! * `not-null=<entity path>:<component>`: see [`QueryExpression::filtered_is_not_null`].
+//! * `filter=[<predicate>]`: e.g. `filter=[/points:Points3D:radii > 0.5 AND /cam:Image IS NOT NULL]`,
+//!   see [`QueryExpression::filter`] and [`parse_row_filter`].
+//! * `fill=none|latest-at|latest-at-view|interpolate:<max gap>|nearest:<tolerance>`:
+//!   see [`QueryExpression::sparse_fill_strategy`].
+//! * `resample=<interval>[:mean|min|max|last|count]`: e.g. `resample=10ms:mean`,
+//!   see [`QueryExpression::resampling`] and [`ResamplingSpec`].
+//!
+//! Index values (range bounds, resampling intervals) can be written using the natural syntax of
+//! the filtered index, e.g. `2024-01-01T12:00:00Z` for timestamps or `1.5s` for durations, and are
+//! only resolved once its type is known, see [`QuerySpec::to_query_expression`].
+//!
+//! Whitespace can be escaped using a backslash (`/my\ entity`), like in entity paths.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use itertools::Itertools as _;
+
+use re_chunk::{ComponentIdentifier, TimeInt, TimelineName};
+use re_chunk_store::{
+    AggregationFunction, ComparisonOperator, FilterValue, IndexRange, QueryExpression, Resampling,
+    RowFilter, SecondaryIndex, SparseFillStrategy, ViewContentsSelector,
+};
+use re_log_types::{AbsoluteTimeRange, EntityPathFilter, TimeType, Timestamp, TimestampFormat};
+use re_query::StorageEngineLike;
+use re_sorbet::ComponentColumnSelector;
+
+use crate::QueryEngine;
+
+// ---
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum QuerySyntaxError {
+    #[error("unbalanced brackets in {0:?}")]
+    UnbalancedBrackets(String),
+
+    #[error("missing entity path in {0:?}")]
+    MissingEntityPath(String),
+
+    #[error("empty component list in {0:?}")]
+    EmptyComponentList(String),
+
+    #[error("components cannot be specified on exclusions: {0:?}")]
+    ComponentsOnExclusion(String),
+
+    #[error(
+        "invalid index clause {0:?}, expected `index=<timeline>[,<timeline>…]` without duplicates"
+    )]
+    InvalidIndex(String),
+
+    #[error("`range.{0}=` doesn't refer to any of the secondary indices listed in `index=`")]
+    UnknownSecondaryIndex(String),
+
+    #[error("`range.{0}=` was specified more than once")]
+    DuplicateSecondaryIndexRange(String),
+
+    #[error("invalid range {0:?}, expected `<min>..<max>` (either side is optional)")]
+    InvalidRange(String),
+
+    #[error("invalid value {0:?} for a {1} index")]
+    InvalidIndexValue(String, TimeType),
+
+    #[error(
+        "invalid resampling {0:?}, expected `<interval>[:<aggregation>]` with a positive interval and one of: `mean`, `min`, `max`, `last`, `count`"
+    )]
+    InvalidResampling(String),
+
+    #[error("invalid filter {0:?}: {1}")]
+    InvalidFilter(String, String),
+
+    #[error(
+        "invalid fill strategy {0:?}, expected one of: `none`, `latest-at`, `latest-at-view`, `interpolate:<max gap>`, `nearest:<tolerance>`"
+    )]
+    InvalidFillStrategy(String),
+
+    #[error("invalid column {0:?}, expected `<entity path>:<component>`")]
+    InvalidColumn(String),
+
+    #[error("`{0}=` was specified more than once")]
+    DuplicateClause(&'static str),
+}
+
+/// A single view contents clause, e.g. `/world/points:[positions, radius]`.
+///
+/// See the [module-level documentation](self) for the syntax.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContentsRule {
+    /// Whether matching entities are included or excluded.
+    pub include: bool,
+
+    /// The entity expression, e.g. `/world/**`, see [`EntityPathFilter`].
+    pub entity_expression: String,
+
+    /// The components to include for the matching entities, if restricted.
+    ///
+    /// Always `None` for exclusions.
+    pub components: Option<Vec<String>>,
+}
+
+impl ContentsRule {
+    /// Converts the rules of an [`EntityPathFilter`] into contents rules.
+    pub fn from_entity_path_filter(filter: &EntityPathFilter) -> Vec<Self> {
+        filter
+            .iter_expressions()
+            .map(|expression| {
+                let (include, entity_expression) =
+                    if let Some(expression) = expression.strip_prefix('-') {
+                        (false, expression)
+                    } else {
+                        (true, expression.strip_prefix('+').unwrap_or(&expression))
+                    };
+
+                Self {
+                    include,
+                    entity_expression: entity_expression.trim().to_owned(),
+                    components: None,
+                }
+            })
+            .collect()
+    }
+
+    /// The equivalent [`EntityPathFilter`] expression, e.g. `+ /world/**`.
+    fn filter_expression(&self) -> String {
+        let sign = if self.include { '+' } else { '-' };
+        format!("{sign} {}", self.entity_expression)
+    }
+}
+
+impl std::str::FromStr for ContentsRule {
+    type Err = QuerySyntaxError;
+
+    fn from_str(clause: &str) -> Result<Self, Self::Err> {
+        let (include, rest) = if let Some(rest) = clause.strip_prefix('-') {
+            (false, rest)
+        } else {
+            (true, clause.strip_prefix('+').unwrap_or(clause))
+        };
+
+        let (entity_expression, components) = match find_unescaped(rest, ':') {
+            Some(i) => (&rest[..i], Some(&rest[i + 1..])),
+            None => (rest, None),
+        };
+
+        let entity_expression = entity_expression.trim();
+        if entity_expression.is_empty() {
+            return Err(QuerySyntaxError::MissingEntityPath(clause.to_owned()));
+        }
+
+        let components = components
+            .map(|components| {
+                let components = components.trim();
+                let components = if let Some(list) = components.strip_prefix('[') {
+                    let list = list
+                        .strip_suffix(']')
+                        .ok_or_else(|| QuerySyntaxError::UnbalancedBrackets(clause.to_owned()))?;
+                    list.split(',')
+                        .map(|component| component.trim().to_owned())
+                        .filter(|component| !component.is_empty())
+                        .collect_vec()
+                } else if components.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![components.to_owned()]
+                };
+
+                if components.is_empty() {
+                    Err(QuerySyntaxError::EmptyComponentList(clause.to_owned()))
+                } else {
+                    Ok(components)
+                }
+            })
+            .transpose()?;
+
+        if !include && components.is_some() {
+            return Err(QuerySyntaxError::ComponentsOnExclusion(clause.to_owned()));
+        }
+
+        Ok(Self {
+            include,
+            entity_expression: entity_expression.to_owned(),
+            components,
+        })
+    }
+}
+
+impl std::fmt::Display for ContentsRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            include,
+            entity_expression,
+            components,
+        } = self;
+
+        if !include {
+            f.write_str("-")?;
+        }
+        f.write_str(entity_expression)?;
+
+        match components.as_deref() {
+            None => Ok(()),
+            Some([component]) => write!(f, ":{component}"),
+            Some(components) => write!(f, ":[{}]", components.iter().format(", ")),
+        }
+    }
+}
+
+/// A dataframe query, expressed using a compact textual syntax.
+///
+/// See the [module-level documentation](self) for the syntax.
+///
+/// Parsing the output of [`std::fmt::Display`] always yields back the same query, up to the nesting
+/// of [`RowFilter`]s.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct QuerySpec {
+    /// The view contents. Empty means: everything.
+    pub contents: Vec<ContentsRule>,
+
+    /// See [`QueryExpression::filtered_index`].
+    pub filtered_index: Option<TimelineName>,
+
+    /// See [`QueryExpression::filtered_index_range`].
+    pub filtered_index_range: Option<IndexRangeSpec>,
+
+    /// See [`QueryExpression::secondary_indices`].
+    pub secondary_indices: Vec<SecondaryIndexSpec>,
+
+    /// See [`QueryExpression::filtered_is_not_null`].
+    pub filtered_is_not_null: Option<ComponentColumnSelector>,
+
+    /// See [`QueryExpression::filter`].
+    pub filter: Option<RowFilter>,
+
+    /// See [`QueryExpression::sparse_fill_strategy`].
+    pub sparse_fill_strategy: SparseFillStrategy,
+
+    /// See [`QueryExpression::resampling`].
+    pub resampling: Option<ResamplingSpec>,
+}
+
+impl QuerySpec {
+    /// The [`EntityPathFilter`] corresponding to the view contents, if any.
+    pub fn entity_path_filter(&self) -> Option<EntityPathFilter> {
+        if self.contents.is_empty() {
+            return None;
+        }
+
+        let expressions = self
+            .contents
+            .iter()
+            .map(ContentsRule::filter_expression)
+            .collect_vec();
+
+        Some(EntityPathFilter::from_query_expressions(
+            expressions.iter().map(String::as_str),
+        ))
+    }
+
+    /// Resolves the view contents against the entities and components present in the database.
+    ///
+    /// Returns `None` if the query has no contents, which means: everything.
+    pub fn resolve_view_contents<E: StorageEngineLike + Clone>(
+        &self,
+        engine: &QueryEngine<E>,
+    ) -> Option<ViewContentsSelector> {
+        let filter = self.entity_path_filter()?;
+
+        let schema = engine.schema();
+        let rules = self
+            .contents
+            .iter()
+            .filter(|rule| rule.include)
+            .map(|rule| {
+                let filter =
+                    EntityPathFilter::from_query_expressions([rule.filter_expression().as_str()])
+                        .resolve_without_substitutions();
+                (filter, rule.components.as_ref())
+            })
+            .collect_vec();
+
+        let view_contents = engine
+            .iter_entity_paths_sorted(&filter)
+            .map(|entity_path| {
+                // If any of the matching rules doesn't restrict components, nothing is restricted.
+                let mut components = Some(BTreeSet::new());
+
+                for (_, rule_components) in rules
+                    .iter()
+                    .filter(|(filter, _)| filter.matches(&entity_path))
+                {
+                    let (Some(components), Some(rule_components)) =
+                        (components.as_mut(), rule_components)
+                    else {
+                        components = None;
+                        continue;
+                    };
+
+                    for name in *rule_components {
+                        let mut matching = schema
+                            .components
+                            .iter()
+                            .filter(|descr| descr.entity_path == entity_path)
+                            .map(|descr| descr.component)
+                            .filter(|component| component_matches(component, name))
+                            .peekable();
+
+                        if matching.peek().is_some() {
+                            components.extend(matching);
+                        } else {
+                            // Unknown components are kept as-is: they will simply yield no data.
+                            components.insert(ComponentIdentifier::from(name.as_str()));
+                        }
+                    }
+                }
+
+                (entity_path, components)
+            })
+            .collect();
+
+        Some(view_contents)
+    }
+
+    /// Converts this into a [`QueryExpression`], resolving the view contents against the database.
+    ///
+    /// Index values are resolved according to the type of the index they apply to in the
+    /// database. If there is no such index, only raw integers are accepted.
+    ///
+    /// See [`Self::resolve_view_contents`].
+    pub fn to_query_expression<E: StorageEngineLike + Clone>(
+        &self,
+        engine: &QueryEngine<E>,
+    ) -> Result<QueryExpression, QuerySyntaxError> {
+        let schema = engine.schema();
+        let index_type = |index: Option<TimelineName>| {
+            index
+                .and_then(|index| {
+                    schema
+                        .indices
+                        .iter()
+                        .find(|descr| descr.timeline_name() == index)
+                        .map(|descr| descr.timeline().typ())
+                })
+                .unwrap_or(TimeType::Sequence)
+        };
+        let filtered_index_type = index_type(self.filtered_index);
+
+        Ok(QueryExpression {
+            view_contents: self.resolve_view_contents(engine),
+            filtered_index: self.filtered_index,
+            filtered_index_range: self
+                .filtered_index_range
+                .as_ref()
+                .map(|range| range.resolve(filtered_index_type))
+                .transpose()?,
+            secondary_indices: self
+                .secondary_indices
+                .iter()
+                .map(|secondary| secondary.resolve(index_type(Some(secondary.index))))
+                .collect::<Result<_, _>>()?,
+            filtered_is_not_null: self.filtered_is_not_null.clone(),
+            filter: self.filter.clone(),
+            sparse_fill_strategy: self.sparse_fill_strategy.clone(),
+            resampling: self
+                .resampling
+                .as_ref()
+                .map(|resampling| resampling.resolve(filtered_index_type))
+                .transpose()?,
+            ..Default::default()
+        })
+    }
+}
+
+impl std::str::FromStr for QuerySpec {
+    type Err = QuerySyntaxError;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        fn set_once<T>(
+            slot: &mut Option<T>,
+            key: &'static str,
+            value: T,
+        ) -> Result<(), QuerySyntaxError> {
+            if slot.replace(value).is_some() {
+                return Err(QuerySyntaxError::DuplicateClause(key));
+            }
+            Ok(())
+        }
+
+        let mut spec = Self::default();
+        let mut sparse_fill_strategy = None;
+        let mut secondary_ranges = BTreeMap::new();
+
+        for clause in split_clauses(query)? {
+            if let Some(indices) = clause.strip_prefix("index=") {
+                let indices = indices
+                    .split(',')
+                    .map(|index| TimelineName::new(index.trim()))
+                    .collect_vec();
+                if indices.iter().any(|index| index.as_str().is_empty())
+                    || !indices.iter().all_unique()
+                {
+                    return Err(QuerySyntaxError::InvalidIndex(clause.to_owned()));
+                }
+
+                let Some((filtered_index, secondary_indices)) = indices.split_first() else {
+                    return Err(QuerySyntaxError::InvalidIndex(clause.to_owned()));
+                };
+                set_once(&mut spec.filtered_index, "index", *filtered_index)?;
+                spec.secondary_indices = secondary_indices
+                    .iter()
+                    .copied()
+                    .map(SecondaryIndexSpec::new)
+                    .collect();
+            } else if let Some(range) = clause.strip_prefix("range=") {
+                set_once(&mut spec.filtered_index_range, "range", range.parse()?)?;
+            } else if let Some((index, range)) = clause
+                .strip_prefix("range.")
+                .and_then(|clause| clause.split_once('='))
+            {
+                let index = TimelineName::new(index.trim());
+                if secondary_ranges
+                    .insert(index, range.parse::<IndexRangeSpec>()?)
+                    .is_some()
+                {
+                    return Err(QuerySyntaxError::DuplicateSecondaryIndexRange(
+                        index.to_string(),
+                    ));
+                }
+            } else if let Some(column) = clause.strip_prefix("not-null=") {
+                let column = column
+                    .parse()
+                    .map_err(|_err| QuerySyntaxError::InvalidColumn(column.to_owned()))?;
+                set_once(&mut spec.filtered_is_not_null, "not-null", column)?;
+            } else if let Some(filter) = clause.strip_prefix("filter=") {
+                let predicate = filter
+                    .trim()
+                    .strip_prefix('[')
+                    .and_then(|filter| filter.strip_suffix(']'))
+                    .ok_or_else(|| {
+                        QuerySyntaxError::InvalidFilter(
+                            filter.to_owned(),
+                            "expected `filter=[<predicate>]`".to_owned(),
+                        )
+                    })?;
+                set_once(&mut spec.filter, "filter", parse_row_filter(predicate)?)?;
+            } else if let Some(resampling) = clause.strip_prefix("resample=") {
+                set_once(&mut spec.resampling, "resample", resampling.parse()?)?;
+            } else if let Some(fill) = clause.strip_prefix("fill=") {
+                set_once(
+                    &mut sparse_fill_strategy,
+                    "fill",
+                    parse_fill_strategy(fill)?,
+                )?;
+            } else {
+                spec.contents.push(clause.parse()?);
+            }
+        }
+
+        spec.sparse_fill_strategy = sparse_fill_strategy.unwrap_or_default();
+
+        for (index, range) in secondary_ranges {
+            let secondary = spec
+                .secondary_indices
+                .iter_mut()
+                .find(|secondary| secondary.index == index)
+                .ok_or_else(|| QuerySyntaxError::UnknownSecondaryIndex(index.to_string()))?;
+            secondary.range = Some(range);
+        }
+
+        Ok(spec)
+    }
+}
+
+impl std::fmt::Display for QuerySpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            contents,
+            filtered_index,
+            filtered_index_range,
+            secondary_indices,
+            filtered_is_not_null,
+            filter,
+            sparse_fill_strategy,
+            resampling,
+        } = self;
+
+        let clauses = contents
+            .iter()
+            .map(ToString::to_string)
+            .chain(filtered_index.map(|index| {
+                let indices = std::iter::once(index)
+                    .chain(secondary_indices.iter().map(|secondary| secondary.index));
+                format!("index={}", indices.format(","))
+            }))
+            .chain(
+                filtered_index_range
+                    .as_ref()
+                    .map(|range| format!("range={range}")),
+            )
+            .chain(secondary_indices.iter().filter_map(|secondary| {
+                let SecondaryIndexSpec { index, range } = secondary;
+                range.as_ref().map(|range| format!("range.{index}={range}"))
+            }))
+            .chain(
+                filtered_is_not_null
+                    .as_ref()
+                    .map(|column| format!("not-null={column}")),
+            )
+            .chain(filter.as_ref().map(|filter| format!("filter=[{filter}]")))
+            .chain(
+                (*sparse_fill_strategy != SparseFillStrategy::None)
+                    .then(|| format!("fill={}", format_fill_strategy(sparse_fill_strategy))),
+            )
+            .chain(
+                resampling
+                    .as_ref()
+                    .map(|resampling| format!("resample={resampling}")),
+            );
+
+        f.write_str(&clauses.format(" ").to_string())
+    }
+}
+
+/// One of the secondary indices of a composite index, as written in a query:
+/// `index=<filtered index>,<index>` and optionally `range.<index>=<min>..<max>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SecondaryIndexSpec {
+    /// See [`SecondaryIndex::index`].
+    pub index: TimelineName,
+
+    /// See [`SecondaryIndex::range`].
+    pub range: Option<IndexRangeSpec>,
+}
+
+impl SecondaryIndexSpec {
+    #[inline]
+    pub fn new(index: TimelineName) -> Self {
+        Self { index, range: None }
+    }
+
+    /// Resolves the range according to the type of this index.
+    pub fn resolve(&self, typ: TimeType) -> Result<SecondaryIndex, QuerySyntaxError> {
+        Ok(SecondaryIndex {
+            index: self.index,
+            range: self
+                .range
+                .as_ref()
+                .map(|range| range.resolve(typ))
+                .transpose()?,
+        })
+    }
+}
+
+/// An index range, as written in a query: `<min>..<max>`, either side being optional.
+///
+/// Bounds can be raw integers, or use the natural syntax of the index they apply to: `#42` for
+/// sequences, `1.5s` or `250ms` for durations, `2024-01-01T12:00:00Z` for timestamps.
+/// They're only resolved once the type of that index is known, see [`Self::resolve`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IndexRangeSpec {
+    /// The lower bound, if any.
+    pub min: Option<String>,
+
+    /// The upper bound, if any.
+    pub max: Option<String>,
+}
+
+impl IndexRangeSpec {
+    /// Formats the given range using the natural syntax of the given index type.
+    pub fn from_range(range: &IndexRange, typ: TimeType) -> Self {
+        let format_bound = |bound: TimeInt, unbounded: TimeInt| {
+            (bound != unbounded).then(|| format_index_value(bound, typ))
+        };
+
+        Self {
+            min: format_bound(range.min(), TimeInt::MIN),
+            max: format_bound(range.max(), TimeInt::MAX),
+        }
+    }
+
+    /// Resolves the bounds according to the type of the index the range applies to.
+    pub fn resolve(&self, typ: TimeType) -> Result<IndexRange, QuerySyntaxError> {
+        let resolve_bound = |bound: Option<&String>, unbounded: TimeInt| {
+            bound.map_or(Ok(unbounded), |bound| {
+                parse_index_value(bound, typ)
+                    .ok_or_else(|| QuerySyntaxError::InvalidIndexValue(bound.clone(), typ))
+            })
+        };
+
+        Ok(AbsoluteTimeRange::new(
+            resolve_bound(self.min.as_ref(), TimeInt::MIN)?,
+            resolve_bound(self.max.as_ref(), TimeInt::MAX)?,
+        ))
+    }
+}
+
+impl std::str::FromStr for IndexRangeSpec {
+    type Err = QuerySyntaxError;
+
+    fn from_str(range: &str) -> Result<Self, Self::Err> {
+        let err = || QuerySyntaxError::InvalidRange(range.to_owned());
+
+        let (min, max) = range.trim().split_once("..").ok_or_else(err)?;
+        let parse_bound = |bound: &str| {
+            let bound = bound.trim();
+            if bound.is_empty() {
+                Ok(None)
+            } else if is_valid_index_value(bound) {
+                Ok(Some(bound.to_owned()))
+            } else {
+                Err(err())
+            }
+        };
+
+        Ok(Self {
+            min: parse_bound(min)?,
+            max: parse_bound(max)?,
+        })
+    }
+}
+
+impl std::fmt::Display for IndexRangeSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self { min, max } = self;
+        write!(
+            f,
+            "{}..{}",
+            min.as_deref().unwrap_or_default(),
+            max.as_deref().unwrap_or_default()
+        )
+    }
+}
+
+/// Parses `<min>..<max>`, either side being optional, for an index of the given type.
+///
+/// See [`IndexRangeSpec`].
+pub fn parse_index_range(range: &str, typ: TimeType) -> Result<IndexRange, QuerySyntaxError> {
+    range.parse::<IndexRangeSpec>()?.resolve(typ)
+}
+
+/// Formats an index range using the syntax expected by [`parse_index_range`].
+pub fn format_index_range(range: &IndexRange, typ: TimeType) -> String {
+    IndexRangeSpec::from_range(range, typ).to_string()
+}
+
+/// A resampling, as written in a query: `<interval>[:<aggregation>]`, the aggregation being
+/// [`AggregationFunction::Last`] if left out.
+///
+/// The interval can be a raw integer, or a duration (`10ms`) for temporal indices. It is only
+/// resolved once the type of the filtered index is known, see [`Self::resolve`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResamplingSpec {
+    /// The size of every bucket.
+    pub interval: String,
+
+    /// How the values within a bucket are combined.
+    pub aggregation: AggregationFunction,
+}
+
+impl ResamplingSpec {
+    /// Resolves the interval according to the type of the filtered index.
+    pub fn resolve(&self, typ: TimeType) -> Result<Resampling, QuerySyntaxError> {
+        let interval = parse_interval(&self.interval, typ)
+            .ok_or_else(|| QuerySyntaxError::InvalidIndexValue(self.interval.clone(), typ))?;
+
+        Ok(Resampling {
+            interval,
+            aggregation: self.aggregation,
+        })
+    }
+}
+
+impl std::str::FromStr for ResamplingSpec {
+    type Err = QuerySyntaxError;
+
+    fn from_str(resampling: &str) -> Result<Self, Self::Err> {
+        let resampling = resampling.trim();
+
+        // Intervals can contain colons themselves, e.g. `00:00:01.5`.
+        let (interval, aggregation) = resampling
+            .rsplit_once(':')
+            .and_then(|(interval, aggregation)| {
+                Some((interval, parse_aggregation(aggregation.trim())?))
+            })
+            .unwrap_or((resampling, AggregationFunction::Last));
+
+        let interval = interval.trim();
+        let is_valid = [TimeType::Sequence, TimeType::DurationNs]
+            .into_iter()
+            .any(|typ| parse_interval(interval, typ).is_some());
+        if !is_valid {
+            return Err(QuerySyntaxError::InvalidResampling(resampling.to_owned()));
+        }
+
+        Ok(Self {
+            interval: interval.to_owned(),
+            aggregation,
+        })
+    }
+}
+
+impl std::fmt::Display for ResamplingSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            interval,
+            aggregation,
+        } = self;
+
+        if *aggregation == AggregationFunction::Last {
+            f.write_str(interval)
+        } else {
+            write!(f, "{interval}:{aggregation}")
+        }
+    }
+}
+
+/// Parses a single index value for an index of the given type, see [`IndexRangeSpec`].
+fn parse_index_value(value: &str, typ: TimeType) -> Option<TimeInt> {
+    typ.parse_time(value.trim(), TimestampFormat::utc())
+        .filter(|value| !value.is_static())
+}
+
+/// Is this a valid index value for at least one type of index?
+fn is_valid_index_value(value: &str) -> bool {
+    [
+        TimeType::Sequence,
+        TimeType::DurationNs,
+        TimeType::TimestampNs,
+    ]
+    .into_iter()
+    .any(|typ| parse_index_value(value, typ).is_some())
+}
+
+/// Formats a single index value so that [`parse_index_value`] yields it back, losslessly.
+fn format_index_value(value: TimeInt, typ: TimeType) -> String {
+    match typ {
+        TimeType::Sequence => value.as_i64().to_string(),
+
+        TimeType::DurationNs => {
+            let nanos = value.as_i64();
+            let sign = if nanos < 0 { "-" } else { "" };
+            let (secs, subsec_nanos) = (
+                nanos.unsigned_abs() / 1_000_000_000,
+                nanos.unsigned_abs() % 1_000_000_000,
+            );
+
+            if subsec_nanos == 0 {
+                format!("{sign}{secs}s")
+            } else {
+                let subsecs = format!("{subsec_nanos:09}");
+                format!("{sign}{secs}.{}s", subsecs.trim_end_matches('0'))
+            }
+        }
+
+        TimeType::TimestampNs => Timestamp::from(value).format_iso(),
+    }
+}
+
+/// Parses a strictly positive resampling interval for an index of the given type.
+fn parse_interval(interval: &str, typ: TimeType) -> Option<std::num::NonZeroU64> {
+    // Intervals on timestamps are durations.
+    let interval_type = match typ {
+        TimeType::Sequence => TimeType::Sequence,
+        TimeType::DurationNs | TimeType::TimestampNs => TimeType::DurationNs,
+    };
+    let interval = interval_type.parse_time(interval.trim(), TimestampFormat::utc())?;
+
+    u64::try_from(interval.as_i64())
+        .ok()
+        .and_then(std::num::NonZeroU64::new)
+}
+
+/// Parses `mean`, `min`, `max`, `last` or `count`.
+fn parse_aggregation(aggregation: &str) -> Option<AggregationFunction> {
+    match aggregation {
+        "mean" => Some(AggregationFunction::Mean),
+        "min" => Some(AggregationFunction::Min),
+        "max" => Some(AggregationFunction::Max),
+        "last" => Some(AggregationFunction::Last),
+        "count" => Some(AggregationFunction::Count),
+        _ => None,
+    }
+}
+
+/// Parses `none`, `latest-at`, `latest-at-view`, `interpolate:<max gap>` or `nearest:<tolerance>`.
+pub fn parse_fill_strategy(fill: &str) -> Result<SparseFillStrategy, QuerySyntaxError> {
+    let err = || QuerySyntaxError::InvalidFillStrategy(fill.to_owned());
+
+    let (name, arg) = match fill.trim().split_once(':') {
+        Some((name, arg)) => (name, Some(arg.parse::<u64>().map_err(|_err| err())?)),
+        None => (fill.trim(), None),
+    };
+
+    match (name, arg) {
+        ("none", None) => Ok(SparseFillStrategy::None),
+        ("latest-at", None) => Ok(SparseFillStrategy::LatestAtGlobal),
+        ("latest-at-view", None) => Ok(SparseFillStrategy::LatestAtView),
+        ("interpolate", Some(max_gap)) => Ok(SparseFillStrategy::Interpolate { max_gap }),
+        ("nearest", Some(tolerance)) => Ok(SparseFillStrategy::NearestWithin { tolerance }),
+        _ => Err(err()),
+    }
+}
+
+/// Formats a fill strategy using the syntax expected by [`parse_fill_strategy`].
+pub fn format_fill_strategy(strategy: &SparseFillStrategy) -> String {
+    match strategy {
+        SparseFillStrategy::None => "none".to_owned(),
+        SparseFillStrategy::LatestAtGlobal => "latest-at".to_owned(),
+        SparseFillStrategy::LatestAtView => "latest-at-view".to_owned(),
+        SparseFillStrategy::Interpolate { max_gap } => format!("interpolate:{max_gap}"),
+        SparseFillStrategy::NearestWithin { tolerance } => format!("nearest:{tolerance}"),
+    }
+}
+
+/// Parses a row filter, using the same syntax as its [`std::fmt::Display`] implementation, e.g.
+/// `/detections:Confidence > 0.8 AND (/camera:Image IS NOT NULL OR /lidar:Points3D:positions IS NOT NULL)`.
+///
+/// * Columns are written as `<entity path>:<component>`.
+/// * Values are booleans, integers, floats or double-quoted strings.
+/// * `AND` binds tighter than `OR`, and parentheses can be used for grouping.
+/// * `TRUE` and `FALSE` are the filters that always and never hold, respectively.
+pub fn parse_row_filter(filter: &str) -> Result<RowFilter, QuerySyntaxError> {
+    let err = |reason: String| QuerySyntaxError::InvalidFilter(filter.to_owned(), reason);
+
+    let mut parser = RowFilterParser {
+        tokens: tokenize_row_filter(filter)
+            .map_err(err)?
+            .into_iter()
+            .peekable(),
+    };
+
+    let row_filter = parser.parse_or().map_err(err)?;
+    if let Some(token) = parser.tokens.next() {
+        return Err(err(format!("unexpected {token}")));
+    }
+
+    Ok(row_filter)
+}
+
+/// A token of the row filter syntax, see [`parse_row_filter`].
+#[derive(Debug, Clone, PartialEq)]
+enum RowFilterToken {
+    OpenParen,
+    CloseParen,
+    Operator(ComparisonOperator),
+    String(String),
+
+    /// Keywords, columns, and any value other than strings.
+    Word(String),
+}
+
+impl std::fmt::Display for RowFilterToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OpenParen => f.write_str("`(`"),
+            Self::CloseParen => f.write_str("`)`"),
+            Self::Operator(op) => write!(f, "`{op}`"),
+            Self::String(value) => write!(f, "{value:?}"),
+            Self::Word(word) => write!(f, "`{word}`"),
+        }
+    }
+}
+
+/// Splits a row filter into tokens, see [`parse_row_filter`].
+fn tokenize_row_filter(filter: &str) -> Result<Vec<RowFilterToken>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = filter.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+
+            '(' => tokens.push(RowFilterToken::OpenParen),
+            ')' => tokens.push(RowFilterToken::CloseParen),
+
+            '=' | '!' | '<' | '>' => {
+                let op = match (c, chars.next_if_eq(&'=').is_some()) {
+                    ('=', _) => ComparisonOperator::Eq,
+                    ('!', true) => ComparisonOperator::NotEq,
+                    ('<', false) => ComparisonOperator::Lt,
+                    ('<', true) => ComparisonOperator::LtEq,
+                    ('>', false) => ComparisonOperator::Gt,
+                    ('>', true) => ComparisonOperator::GtEq,
+                    _ => return Err(format!("unknown operator `{c}`")),
+                };
+                tokens.push(RowFilterToken::Operator(op));
+            }
+
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('r') => value.push('\r'),
+                            Some('t') => value.push('\t'),
+                            Some(c) => value.push(c),
+                            None => return Err("unterminated string".to_owned()),
+                        },
+                        Some(c) => value.push(c),
+                        None => return Err("unterminated string".to_owned()),
+                    }
+                }
+                tokens.push(RowFilterToken::String(value));
+            }
+
+            c => {
+                // Escaped characters are kept as-is, for entity paths to unescape them.
+                let mut word = String::from(c);
+                let mut escaped = c == '\\';
+                while let Some(&c) = chars.peek() {
+                    if !escaped && (c.is_whitespace() || "()=!<>\"".contains(c)) {
+                        break;
+                    }
+                    escaped = !escaped && c == '\\';
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(RowFilterToken::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// A recursive descent parser for the row filter syntax, see [`parse_row_filter`].
+struct RowFilterParser {
+    tokens: std::iter::Peekable<std::vec::IntoIter<RowFilterToken>>,
+}
+
+impl RowFilterParser {
+    fn parse_or(&mut self) -> Result<RowFilter, String> {
+        let mut filter = self.parse_and()?;
+        while self.next_if_keyword("OR") {
+            filter = filter.or(self.parse_and()?);
+        }
+        Ok(filter)
+    }
+
+    fn parse_and(&mut self) -> Result<RowFilter, String> {
+        let mut filter = self.parse_primary()?;
+        while self.next_if_keyword("AND") {
+            filter = filter.and(self.parse_primary()?);
+        }
+        Ok(filter)
+    }
+
+    fn parse_primary(&mut self) -> Result<RowFilter, String> {
+        match self.tokens.next() {
+            Some(RowFilterToken::OpenParen) => {
+                let filter = self.parse_or()?;
+                match self.tokens.next() {
+                    Some(RowFilterToken::CloseParen) => Ok(filter),
+                    Some(token) => Err(format!("expected `)`, found {token}")),
+                    None => Err("unbalanced parentheses".to_owned()),
+                }
+            }
+
+            Some(RowFilterToken::Word(word)) if word.eq_ignore_ascii_case("TRUE") => {
+                Ok(RowFilter::And(Vec::new()))
+            }
+
+            Some(RowFilterToken::Word(word)) if word.eq_ignore_ascii_case("FALSE") => {
+                Ok(RowFilter::Or(Vec::new()))
+            }
+
+            Some(RowFilterToken::Word(column)) => {
+                let column: ComponentColumnSelector = column.parse().map_err(|_err| {
+                    format!("invalid column {column:?}, expected `<entity path>:<component>`")
+                })?;
+
+                if self.next_if_keyword("IS") {
+                    return if self.next_if_keyword("NOT") && self.next_if_keyword("NULL") {
+                        Ok(RowFilter::is_not_null(column))
+                    } else {
+                        Err(format!("expected `IS NOT NULL` after {column}"))
+                    };
+                }
+
+                let Some(RowFilterToken::Operator(op)) = self.tokens.next() else {
+                    return Err(format!(
+                        "expected a comparison or `IS NOT NULL` after {column}"
+                    ));
+                };
+
+                let value = match self.tokens.next() {
+                    Some(RowFilterToken::String(value)) => FilterValue::String(value),
+                    Some(RowFilterToken::Word(value)) => parse_filter_value(&value)
+                        .ok_or_else(|| format!("invalid value `{value}`"))?,
+                    _ => return Err(format!("expected a value after {column} {op}")),
+                };
+
+                Ok(RowFilter::compare(column, op, value))
+            }
+
+            Some(token) => Err(format!("unexpected {token}")),
+            None => Err("unexpected end of filter".to_owned()),
+        }
+    }
+
+    /// Consumes the next token if it is the given keyword, case-insensitively.
+    fn next_if_keyword(&mut self, keyword: &str) -> bool {
+        self.tokens
+            .next_if(|token| {
+                matches!(token, RowFilterToken::Word(word) if word.eq_ignore_ascii_case(keyword))
+            })
+            .is_some()
+    }
+}
+
+/// Parses a boolean, integer or float value.
+fn parse_filter_value(value: &str) -> Option<FilterValue> {
+    if value.eq_ignore_ascii_case("true") {
+        Some(FilterValue::Bool(true))
+    } else if value.eq_ignore_ascii_case("false") {
+        Some(FilterValue::Bool(false))
+    } else if let Ok(value) = value.parse::<i64>() {
+        Some(FilterValue::Int(value))
+    } else {
+        value.parse::<f64>().ok().map(FilterValue::Float)
+    }
+}
+
+/// Does the given name refer to this component, either by full identifier (`Points3D:positions`)
+/// or short name (`positions`)?
+fn component_matches(component: &ComponentIdentifier, name: &str) -> bool {
+    component.as_str() == name
+        || component
+            .as_str()
+            .rsplit_once(':')
+            .is_some_and(|(_, short_name)| short_name == name)
+}
+
+/// Finds the first occurrence of `needle` that is neither escaped nor within brackets.
+fn find_unescaped(s: &str, needle: char) -> Option<usize> {
+    let mut escaped = false;
+    let mut depth = 0_usize;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            _ if c == needle && depth == 0 => return Some(i),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Splits a query into clauses, on whitespace that is neither escaped nor within brackets.
+fn split_clauses(query: &str) -> Result<Vec<&str>, QuerySyntaxError> {
+    let mut clauses = Vec::new();
+
+    let mut escaped = false;
+    let mut depth = 0_usize;
+    let mut start = None;
+
+    for (i, c) in query.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '[' => depth += 1,
+            ']' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| QuerySyntaxError::UnbalancedBrackets(query.to_owned()))?;
+            }
+            c if c.is_whitespace() && depth == 0 => {
+                if let Some(start) = start.take() {
+                    clauses.push(&query[start..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        start.get_or_insert(i);
+    }
+
+    if depth > 0 {
+        return Err(QuerySyntaxError::UnbalancedBrackets(query.to_owned()));
+    }
+
+    if let Some(start) = start {
+        clauses.push(&query[start..]);
+    }
+
+    Ok(clauses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_query_spec() {
+        let spec: QuerySpec = "/world/** -/world/secret/** /world/points:[positions, radius] \
+                               /cam:Pinhole:image_from_camera index=frame_nr range=10.. fill=latest-at"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                ContentsRule {
+                    include: true,
+                    entity_expression: "/world/**".to_owned(),
+                    components: None,
+                },
+                ContentsRule {
+                    include: false,
+                    entity_expression: "/world/secret/**".to_owned(),
+                    components: None,
+                },
+                ContentsRule {
+                    include: true,
+                    entity_expression: "/world/points".to_owned(),
+                    components: Some(vec!["positions".to_owned(), "radius".to_owned()]),
+                },
+                ContentsRule {
+                    include: true,
+                    entity_expression: "/cam".to_owned(),
+                    components: Some(vec!["Pinhole:image_from_camera".to_owned()]),
+                },
+            ],
+            spec.contents
+        );
+        assert_eq!(Some(TimelineName::new("frame_nr")), spec.filtered_index);
+        assert_eq!(
+            Some(AbsoluteTimeRange::new(
+                TimeInt::new_temporal(10),
+                TimeInt::MAX
+            )),
+            spec.filtered_index_range
+                .map(|range| range.resolve(TimeType::Sequence).unwrap())
+        );
+        assert_eq!(
+            SparseFillStrategy::LatestAtGlobal,
+            spec.sparse_fill_strategy
+        );
+    }
+
+    #[test]
+    fn query_spec_roundtrip() {
+        let queries = [
+            "",
+            "/world/**",
+            "/my\\ entity/** -/world/secret/**",
+            "/world/points:[positions, radius] index=log_time",
+            "/world/points:positions index=frame_nr range=-5..20",
+            "index=frame_nr range=..20 not-null=/world/points:Points3D:positions fill=nearest:10",
+            "/world/** fill=interpolate:1000000",
+            "index=log_time range=2024-01-01T12:00:00Z..2024-01-01T12:00:01.5Z resample=10ms:mean",
+            "index=sim_time range=-1.5s.. resample=00:00:01",
+            "index=frame_nr,episode,step range=10.. range.step=..#5 fill=latest-at",
+            "index=frame_nr filter=[/world/points:Points3D:radii > 0.5 AND (/cam:Image IS NOT NULL OR /cam:Label == \"a b\")]",
+        ];
+
+        for query in queries {
+            let spec: QuerySpec = query.parse().unwrap();
+            assert_eq!(query, spec.to_string());
+            assert_eq!(spec, spec.to_string().parse().unwrap());
+        }
+    }
+
+    #[test]
+    fn query_spec_errors() {
+        assert!(matches!(
+            "/world/points:[positions".parse::<QuerySpec>(),
+            Err(QuerySyntaxError::UnbalancedBrackets(_))
+        ));
+        assert!(matches!(
+            "/world/points:[]".parse::<QuerySpec>(),
+            Err(QuerySyntaxError::EmptyComponentList(_))
+        ));
+        assert!(matches!(
+            "-/world/points:positions".parse::<QuerySpec>(),
+            Err(QuerySyntaxError::ComponentsOnExclusion(_))
+        ));
+        assert!(matches!(
+            "range=10".parse::<QuerySpec>(),
+            Err(QuerySyntaxError::InvalidRange(_))
+        ));
+        assert!(matches!(
+            "fill=interpolate".parse::<QuerySpec>(),
+            Err(QuerySyntaxError::InvalidFillStrategy(_))
+        ));
+        assert!(matches!(
+            "index=a index=b".parse::<QuerySpec>(),
+            Err(QuerySyntaxError::DuplicateClause("index"))
+        ));
+        assert!(matches!(
+            "index=a,".parse::<QuerySpec>(),
+            Err(QuerySyntaxError::InvalidIndex(_))
+        ));
+        assert!(matches!(
+            "index=a,b,a".parse::<QuerySpec>(),
+            Err(QuerySyntaxError::InvalidIndex(_))
+        ));
+        assert_eq!(
+            Err(QuerySyntaxError::UnknownSecondaryIndex("a".to_owned())),
+            "index=a,b range.a=0..".parse::<QuerySpec>()
+        );
+        assert_eq!(
+            Err(QuerySyntaxError::DuplicateSecondaryIndexRange(
+                "b".to_owned()
+            )),
+            "index=a,b range.b=0.. range.b=..1".parse::<QuerySpec>()
+        );
+        assert!(matches!(
+            "range=yesterday..".parse::<QuerySpec>(),
+            Err(QuerySyntaxError::InvalidRange(_))
+        ));
+        assert!(matches!(
+            "resample=0".parse::<QuerySpec>(),
+            Err(QuerySyntaxError::InvalidResampling(_))
+        ));
+        assert!(matches!(
+            "resample=10:median".parse::<QuerySpec>(),
+            Err(QuerySyntaxError::InvalidResampling(_))
+        ));
+        assert!(matches!(
+            "filter=/a:b>1".parse::<QuerySpec>(),
+            Err(QuerySyntaxError::InvalidFilter(..))
+        ));
+    }
+
+    #[test]
+    fn secondary_indices() {
+        let spec: QuerySpec = "range.step=2.. index=frame_nr,episode,step range=..10"
+            .parse()
+            .unwrap();
+
+        assert_eq!(Some(TimelineName::new("frame_nr")), spec.filtered_index);
+        assert_eq!(
+            vec![
+                SecondaryIndexSpec::new(TimelineName::new("episode")),
+                SecondaryIndexSpec {
+                    index: TimelineName::new("step"),
+                    range: Some(IndexRangeSpec {
+                        min: Some("2".to_owned()),
+                        max: None,
+                    }),
+                },
+            ],
+            spec.secondary_indices
+        );
+
+        // Each range is resolved using the type of its own index.
+        let secondary = SecondaryIndexSpec {
+            index: TimelineName::new("sim_time"),
+            range: Some("..1.5s".parse().unwrap()),
+        };
+        assert_eq!(
+            Ok(
+                SecondaryIndex::new("sim_time").with_range(AbsoluteTimeRange::new(
+                    TimeInt::MIN,
+                    TimeInt::new_temporal(1_500_000_000)
+                ))
+            ),
+            secondary.resolve(TimeType::DurationNs)
+        );
+        assert!(secondary.resolve(TimeType::Sequence).is_err());
+    }
+
+    #[test]
+    fn index_ranges() {
+        let resolve = |range: &str, typ| range.parse::<IndexRangeSpec>()?.resolve(typ);
+        let range = |min: i64, max: i64| {
+            AbsoluteTimeRange::new(TimeInt::new_temporal(min), TimeInt::new_temporal(max))
+        };
+
+        assert_eq!(Ok(range(-5, 20)), resolve("-5..#20", TimeType::Sequence));
+        assert_eq!(
+            Ok(range(-1_500_000_000, 250_000_000)),
+            resolve("-1.5s..250ms", TimeType::DurationNs)
+        );
+        assert_eq!(
+            Ok(range(1_704_110_400_000_000_000, 1_704_110_401_500_000_000)),
+            resolve(
+                "2024-01-01T12:00:00Z..2024-01-01T12:00:01.5Z",
+                TimeType::TimestampNs
+            )
+        );
+
+        // Raw integers are valid for any type of index.
+        for typ in [
+            TimeType::Sequence,
+            TimeType::DurationNs,
+            TimeType::TimestampNs,
+        ] {
+            assert_eq!(Ok(range(10, 20)), resolve("10..20", typ));
+        }
+
+        // Bounds must make sense for the type of the index.
+        assert_eq!(
+            Err(QuerySyntaxError::InvalidIndexValue(
+                "1.5s".to_owned(),
+                TimeType::Sequence
+            )),
+            resolve("1.5s..", TimeType::Sequence)
+        );
+        assert!(resolve("..2024-01-01T12:00:00Z", TimeType::DurationNs).is_err());
+
+        // Formatting is lossless.
+        for (range, typ) in [
+            (range(-5, 20), TimeType::Sequence),
+            (range(-1_500_000_001, 3_000_000_000), TimeType::DurationNs),
+            (
+                range(1_704_110_400_000_000_001, 1_704_110_401_500_000_000),
+                TimeType::TimestampNs,
+            ),
+            (
+                AbsoluteTimeRange::new(TimeInt::MIN, TimeInt::new_temporal(0)),
+                TimeType::DurationNs,
+            ),
+        ] {
+            let formatted = format_index_range(&range, typ);
+            assert_eq!(Ok(range), parse_index_range(&formatted, typ), "{formatted}");
+        }
+        assert_eq!(
+            "-1.500000001s..3s",
+            format_index_range(&range(-1_500_000_001, 3_000_000_000), TimeType::DurationNs)
+        );
+        assert_eq!(
+            "..2024-01-01T12:00:00Z",
+            format_index_range(
+                &AbsoluteTimeRange::new(
+                    TimeInt::MIN,
+                    TimeInt::new_temporal(1_704_110_400_000_000_000)
+                ),
+                TimeType::TimestampNs
+            )
+        );
+    }
+
+    #[test]
+    fn resampling() {
+        let resolve = |resampling: &str, typ| resampling.parse::<ResamplingSpec>()?.resolve(typ);
+        let resampling = |interval: u64, aggregation| Resampling {
+            interval: std::num::NonZeroU64::new(interval).unwrap(),
+            aggregation,
+        };
+
+        assert_eq!(
+            Ok(resampling(10, AggregationFunction::Last)),
+            resolve("10", TimeType::Sequence)
+        );
+        assert_eq!(
+            Ok(resampling(10_000_000, AggregationFunction::Mean)),
+            resolve("10ms:mean", TimeType::TimestampNs)
+        );
+        assert_eq!(
+            Ok(resampling(1_500_000_000, AggregationFunction::Count)),
+            resolve("00:00:01.5:count", TimeType::DurationNs)
+        );
+        assert_eq!(
+            Err(QuerySyntaxError::InvalidIndexValue(
+                "10ms".to_owned(),
+                TimeType::Sequence
+            )),
+            resolve("10ms:max", TimeType::Sequence)
+        );
+    }
+
+    #[test]
+    fn row_filters() {
+        let column = |column: &str| column.parse::<ComponentColumnSelector>().unwrap();
+
+        assert_eq!(
+            Ok(RowFilter::compare(
+                column("/points:Points3D:radii"),
+                ComparisonOperator::GtEq,
+                0.5
+            )
+            .and(
+                RowFilter::is_not_null(column("/cam:Image"))
+                    .or(RowFilter::compare(
+                        column("/cam:Label"),
+                        ComparisonOperator::Eq,
+                        "a \"b\")"
+                    ))
+                    .or(RowFilter::compare(
+                        column("/cam:Visible"),
+                        ComparisonOperator::NotEq,
+                        false
+                    ))
+            )),
+            parse_row_filter(
+                "/points:Points3D:radii>=0.5 and (/cam:Image is not null or /cam:Label = \"a \\\"b\\\")\" OR /cam:Visible != FALSE)"
+            )
+        );
+
+        // `AND` binds tighter than `OR`.
+        assert_eq!(
+            Ok(
+                RowFilter::is_not_null(column("/a:x")).or(RowFilter::is_not_null(column("/b:x"))
+                    .and(RowFilter::compare(
+                        column("/c:x"),
+                        ComparisonOperator::Lt,
+                        -3_i64
+                    )))
+            ),
+            parse_row_filter("/a:x IS NOT NULL OR /b:x IS NOT NULL AND /c:x < -3")
+        );
+
+        assert_eq!(Ok(RowFilter::And(Vec::new())), parse_row_filter("TRUE"));
+        assert_eq!(Ok(RowFilter::Or(Vec::new())), parse_row_filter("FALSE"));
+
+        for filter in [
+            "",
+            "/a:x",
+            "/a:x >",
+            "/a:x IS NULL",
+            "(/a:x IS NOT NULL",
+            "/a:x IS NOT NULL)",
+            "/a:x == \"unterminated",
+            "/a:x == nope",
+            "x == 1",
+            "/a:x ! 1",
+        ] {
+            assert!(
+                matches!(
+                    parse_row_filter(filter),
+                    Err(QuerySyntaxError::InvalidFilter(..))
+                ),
+                "{filter}"
+            );
+        }
+    }
+}
@@ -8,6 +8,7 @@ mod dataframe_query_provider_wasm;
 mod datafusion_connector;
 mod grpc_streaming_provider;
 mod partition_table;
+mod recording_table_provider;
 mod search_provider;
 mod table_entry_provider;
 mod wasm_compat;
@@ -19,5 +20,6 @@ pub(crate) use dataframe_query_provider::PartitionStreamExec;
 pub(crate) use dataframe_query_provider_wasm::PartitionStreamExec;
 pub use datafusion_connector::DataFusionConnector;
 pub use partition_table::PartitionTableProvider;
+pub use recording_table_provider::RecordingTableProvider;
 pub use search_provider::SearchResultsTableProvider;
 pub use table_entry_provider::TableEntryTableProvider;
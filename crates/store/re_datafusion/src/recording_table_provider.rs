@@ -0,0 +1,234 @@
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+
+use arrow::datatypes::{DataType, SchemaRef};
+use datafusion::{
+    catalog::{Session, TableProvider},
+    common::ScalarValue,
+    datasource::TableType,
+    error::Result as DataFusionResult,
+    execution::{SendableRecordBatchStream, TaskContext},
+    logical_expr::{Between, BinaryExpr, Operator, TableProviderFilterPushDown},
+    physical_plan::{
+        ExecutionPlan,
+        stream::RecordBatchStreamAdapter,
+        streaming::{PartitionStream, StreamingTableExec},
+    },
+    prelude::Expr,
+};
+
+use re_dataframe::{AbsoluteTimeRange, QueryEngine, QueryExpression, StorageEngine};
+
+/// A [`TableProvider`] that exposes a local recording as a table, by running a
+/// [`QueryExpression`] against it using [`re_dataframe`].
+///
+/// Every scan runs the query from scratch and streams the resulting batches as they are
+/// produced by the [`re_dataframe::QueryHandle`], so the full recording is never materialized
+/// as a dataframe in memory.
+///
+/// Comparisons between the index column and literals are pushed down into
+/// [`QueryExpression::filtered_index_range`], and so are limits, so that only the rows that are
+/// needed are ever queried.
+#[derive(Clone)]
+pub struct RecordingTableProvider {
+    engine: QueryEngine<StorageEngine>,
+    query_expression: QueryExpression,
+    schema: SchemaRef,
+}
+
+impl std::fmt::Debug for RecordingTableProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordingTableProvider")
+            .field("query_expression", &self.query_expression)
+            .field("schema", &self.schema)
+            .finish()
+    }
+}
+
+impl RecordingTableProvider {
+    pub fn new(engine: QueryEngine<StorageEngine>, query_expression: QueryExpression) -> Self {
+        let schema = engine.query(query_expression.clone()).schema().clone();
+
+        Self {
+            engine,
+            query_expression,
+            schema,
+        }
+    }
+
+    /// This is a convenience function
+    pub fn into_provider(self) -> Arc<dyn TableProvider> {
+        Arc::new(self)
+    }
+
+    /// The name and datatype of the index column, if any.
+    fn index_field(&self) -> Option<(&str, &DataType)> {
+        let index = self.query_expression.filtered_index?;
+        let field = self.schema.field_with_name(index.as_str()).ok()?;
+        Some((field.name().as_str(), field.data_type()))
+    }
+
+    /// The range of the index column that `filter` restricts rows to, if it's a comparison
+    /// between the index column and literals.
+    fn index_range_from_filter(&self, filter: &Expr) -> Option<AbsoluteTimeRange> {
+        let (index_name, index_datatype) = self.index_field()?;
+
+        let index_value = |expr: &Expr| -> Option<i64> {
+            let Expr::Literal(value) = expr else {
+                return None;
+            };
+            match value.cast_to(index_datatype).ok()? {
+                ScalarValue::Int64(Some(value))
+                | ScalarValue::TimestampNanosecond(Some(value), _)
+                | ScalarValue::DurationNanosecond(Some(value)) => Some(value),
+                _ => None,
+            }
+        };
+        let is_index =
+            |expr: &Expr| matches!(expr, Expr::Column(column) if column.name() == index_name);
+
+        match filter {
+            Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+                if *op == Operator::And {
+                    let left = self.index_range_from_filter(left)?;
+                    let right = self.index_range_from_filter(right)?;
+                    return Some(left.intersection(right).unwrap_or(AbsoluteTimeRange::EMPTY));
+                }
+
+                let (op, value) = if is_index(left) {
+                    (*op, index_value(right)?)
+                } else if is_index(right) {
+                    (op.swap()?, index_value(left)?)
+                } else {
+                    return None;
+                };
+
+                match op {
+                    Operator::Eq => Some(AbsoluteTimeRange::point(value)),
+                    Operator::Lt => Some(AbsoluteTimeRange::new(i64::MIN, value.saturating_sub(1))),
+                    Operator::LtEq => Some(AbsoluteTimeRange::new(i64::MIN, value)),
+                    Operator::Gt => Some(AbsoluteTimeRange::new(value.saturating_add(1), i64::MAX)),
+                    Operator::GtEq => Some(AbsoluteTimeRange::new(value, i64::MAX)),
+                    _ => None,
+                }
+            }
+
+            Expr::Between(Between {
+                expr,
+                negated: false,
+                low,
+                high,
+            }) if is_index(expr) => Some(AbsoluteTimeRange::new(
+                index_value(low)?,
+                index_value(high)?,
+            )),
+
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl TableProvider for RecordingTableProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        let mut query_expression = self.query_expression.clone();
+
+        let index_range = filters
+            .iter()
+            .filter_map(|filter| self.index_range_from_filter(filter))
+            .fold(
+                query_expression
+                    .filtered_index_range
+                    .unwrap_or(AbsoluteTimeRange::EVERYTHING),
+                |range, filter_range| {
+                    range
+                        .intersection(filter_range)
+                        .unwrap_or(AbsoluteTimeRange::EMPTY)
+                },
+            );
+        if index_range != AbsoluteTimeRange::EVERYTHING {
+            query_expression.filtered_index_range = Some(index_range);
+        }
+
+        StreamingTableExec::try_new(
+            self.schema.clone(),
+            vec![Arc::new(RecordingPartitionStream {
+                engine: self.engine.clone(),
+                query_expression,
+                schema: self.schema.clone(),
+            })],
+            projection,
+            Vec::default(),
+            false,
+            limit,
+        )
+        .map(|e| Arc::new(e) as Arc<dyn ExecutionPlan>)
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> DataFusionResult<Vec<TableProviderFilterPushDown>> {
+        // The index range is applied at the chunk level, so let DataFusion re-apply the filters
+        // on the resulting rows.
+        Ok(filters
+            .iter()
+            .map(|filter| {
+                if self.index_range_from_filter(filter).is_some() {
+                    TableProviderFilterPushDown::Inexact
+                } else {
+                    TableProviderFilterPushDown::Unsupported
+                }
+            })
+            .collect())
+    }
+}
+
+struct RecordingPartitionStream {
+    engine: QueryEngine<StorageEngine>,
+    query_expression: QueryExpression,
+    schema: SchemaRef,
+}
+
+impl std::fmt::Debug for RecordingPartitionStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordingPartitionStream")
+            .field("query_expression", &self.query_expression)
+            .finish()
+    }
+}
+
+impl PartitionStream for RecordingPartitionStream {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let query_handle = self.engine.query(self.query_expression.clone());
+        let batches = std::iter::from_fn(move || query_handle.next_row_batch().map(Ok));
+
+        Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            futures::stream::iter(batches),
+        ))
+    }
+}
@@ -10,6 +10,7 @@ use re_chunk::{
 use re_chunk_store::{
     ChunkStore, ChunkStoreChunkStats, ChunkStoreConfig, ChunkStoreDiffKind, ChunkStoreEvent,
     ChunkStoreHandle, ChunkStoreSubscriber as _, GarbageCollectionOptions, GarbageCollectionTarget,
+    RetentionPolicy,
 };
 use re_log_types::{
     AbsoluteTimeRange, AbsoluteTimeRangeF, ApplicationId, EntityPath, EntityPathHash, LogMsg,
@@ -28,6 +29,10 @@ use crate::{Error, TimesPerTimeline, ingestion_statistics::IngestionStatistics};
 /// See [`GarbageCollectionOptions::time_budget`].
 pub const DEFAULT_GC_TIME_BUDGET: std::time::Duration = std::time::Duration::from_micros(3500); // empirical
 
+/// See [`EntityDb::compact`].
+pub const DEFAULT_COMPACTION_TIME_BUDGET: std::time::Duration =
+    std::time::Duration::from_micros(1000);
+
 // ----------------------------------------------------------------------------¨
 
 /// What class of [`EntityDb`] is this?
@@ -517,7 +522,9 @@ impl EntityDb {
 
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.set_store_info.is_none() && self.num_rows() == 0
+        self.set_store_info.is_none()
+            && self.num_rows() == 0
+            && self.storage_engine.read().store().num_spilled_chunks() == 0
     }
 
     /// A sorted list of all the entity paths in this database.
@@ -653,6 +660,8 @@ impl EntityDb {
             // exactly how far back the latest-at is of each component at the current time…
             // …but maybe it doesn't have to be perfect.
             protected_time_ranges: Default::default(),
+
+            retention: Vec::new(),
         });
 
         if store_events.is_empty() {
@@ -669,6 +678,45 @@ impl EntityDb {
         store_events
     }
 
+    /// Forget all the data that has expired according to the given retention policies.
+    ///
+    /// See [`RetentionPolicy`].
+    pub fn purge_expired(&mut self, retention: &[RetentionPolicy]) -> Vec<ChunkStoreEvent> {
+        re_tracing::profile_function!();
+
+        if retention.is_empty() {
+            return Vec::new();
+        }
+
+        self.gc(&GarbageCollectionOptions::retention_only(
+            retention.to_vec(),
+        ))
+    }
+
+    /// Incrementally re-compacts the data that was already ingested, within the given time budget.
+    ///
+    /// See [`re_chunk_store::ChunkStore::compact`].
+    pub fn compact(&mut self, time_budget: std::time::Duration) -> Vec<ChunkStoreEvent> {
+        re_tracing::profile_function!();
+
+        let mut engine = self.storage_engine.write();
+        let store_events = engine.store().compact(time_budget);
+        if store_events.is_empty() {
+            return store_events;
+        }
+
+        self.tree.on_store_additions(&store_events);
+        Self::on_store_deletions(
+            &mut self.times_per_timeline,
+            &mut self.time_histogram_per_timeline,
+            &mut self.tree,
+            engine,
+            &store_events,
+        );
+
+        store_events
+    }
+
     pub fn gc(&mut self, gc_options: &GarbageCollectionOptions) -> Vec<ChunkStoreEvent> {
         re_tracing::profile_function!();
 
@@ -801,7 +849,7 @@ impl EntityDb {
 
             let mut chunks: Vec<Arc<Chunk>> = engine
                 .store()
-                .iter_chunks()
+                .iter_chunks_including_spilled()
                 .filter(move |chunk| {
                     let Some((timeline, time_range)) = time_filter else {
                         return true;
@@ -813,7 +861,6 @@ impl EntityDb {
                             || time_range.contains(time_column.time_range().max())
                     })
                 })
-                .cloned() // refcount
                 .collect();
 
             // Try to roughly preserve the order of the chunks
@@ -878,8 +925,8 @@ impl EntityDb {
         }
 
         let engine = self.storage_engine.read();
-        for chunk in engine.store().iter_chunks() {
-            new_db.add_chunk(&Arc::clone(chunk))?;
+        for chunk in engine.store().iter_chunks_including_spilled() {
+            new_db.add_chunk(&chunk)?;
         }
 
         Ok(new_db)
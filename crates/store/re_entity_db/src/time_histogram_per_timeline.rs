@@ -155,6 +155,7 @@ impl ChunkStoreSubscriber for TimeHistogramPerTimeline {
                 ChunkStoreDiffKind::Deletion => {
                     self.remove(&times, event.num_components() as _);
                 }
+                ChunkStoreDiffKind::Spill => {} // still there, just not in memory
             }
         }
     }
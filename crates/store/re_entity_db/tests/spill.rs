@@ -0,0 +1,83 @@
+// https://github.com/rust-lang/rust-clippy/issues/10011
+#![cfg(test)]
+
+use std::sync::Arc;
+
+use re_chunk::{Chunk, ChunkId, RowId};
+use re_chunk_store::{ChunkStoreConfig, GarbageCollectionOptions, GarbageCollectionTarget};
+use re_entity_db::EntityDb;
+use re_log_types::{
+    EntityPath, LogMsg, StoreId, StoreKind, build_frame_nr,
+    example_components::{MyPoint, MyPoints},
+};
+
+// ---
+
+/// Spilled chunks are still part of the recording: saving or cloning it must not lose them.
+#[test]
+fn save_after_spill() -> anyhow::Result<()> {
+    re_log::setup_logging();
+
+    let spill_directory = std::env::temp_dir().join(format!("rerun_spill_test_{}", ChunkId::new()));
+
+    let mut db = EntityDb::with_store_config(
+        StoreId::random(StoreKind::Recording, "test_app"),
+        ChunkStoreConfig {
+            spill_directory: Some(spill_directory.clone()),
+            ..ChunkStoreConfig::COMPACTION_DISABLED
+        },
+    );
+
+    let entity_path = EntityPath::from("points");
+    let chunks = (1..=10)
+        .map(|frame| {
+            let points = MyPoint::from_iter(frame..frame + 1);
+            Chunk::builder(entity_path.clone())
+                .with_component_batches(
+                    RowId::new(),
+                    [build_frame_nr(frame as i64)],
+                    [(MyPoints::descriptor_points(), &points as _)],
+                )
+                .build()
+                .map(Arc::new)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for chunk in &chunks {
+        db.add_chunk(chunk)?;
+    }
+
+    db.gc(&GarbageCollectionOptions {
+        target: GarbageCollectionTarget::DropAtLeastFraction(0.5),
+        ..GarbageCollectionOptions::gc_everything()
+    });
+
+    {
+        let engine = db.storage_engine();
+        let num_spilled_chunks = engine.store().num_spilled_chunks();
+        assert!(0 < num_spilled_chunks && num_spilled_chunks < chunks.len());
+    }
+
+    let saved_chunks = db
+        .to_messages(None)
+        .map(|msg| match msg? {
+            LogMsg::ArrowMsg(_, msg) => Ok(Some(Chunk::from_arrow_msg(&msg)?)),
+            _ => Ok(None),
+        })
+        .filter_map(Result::transpose)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    assert_eq!(chunks.len(), saved_chunks.len());
+    for (expected, saved) in chunks.iter().zip(&saved_chunks) {
+        assert_eq!(**expected, *saved);
+    }
+
+    let cloned_db = db.clone_with_new_id(StoreId::random(StoreKind::Recording, "test_app"))?;
+    assert_eq!(chunks.len() as u64, cloned_db.num_rows());
+
+    drop(cloned_db);
+    drop(db);
+    std::fs::remove_dir_all(&spill_directory)?;
+
+    Ok(())
+}
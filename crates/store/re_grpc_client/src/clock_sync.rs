@@ -0,0 +1,376 @@
+//! Aligning the clock of a client with the one of a `re_grpc_server`.
+//!
+//! Several processes logging to the same recording each timestamp their data with their own
+//! clock, and these clocks may be offset from each other and drift apart over time. Using
+//! `GetServerTime`, each client estimates how its clock relates to the one of the server, which
+//! then acts as the common reference.
+//!
+//! Each round of synchronization performs a few NTP-style exchanges with the server, and keeps
+//! the one with the shortest round-trip, since it bounds the error on the offset the tightest.
+//! The drift is then estimated by fitting a line through the offsets of the latest rounds.
+
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use tokio::{runtime, sync::oneshot};
+
+use re_chunk::external::crossbeam::atomic::AtomicCell;
+use re_protos::sdk_comms::v1alpha1::GetServerTimeRequest;
+use re_uri::ProxyUri;
+
+use crate::{
+    TonicStatusError,
+    write_table::{ViewerClient, viewer_client},
+};
+
+/// Options for [`ClockSync`].
+#[derive(Debug, Clone)]
+pub struct ClockSyncOptions {
+    /// How often the clocks are synchronized, in order to follow their drift.
+    ///
+    /// Defaults to 10 seconds.
+    pub interval: Duration,
+
+    /// How many exchanges with the server each round of synchronization performs.
+    ///
+    /// Defaults to 8.
+    pub num_exchanges: usize,
+
+    /// How many of the latest rounds the drift is estimated from.
+    ///
+    /// Defaults to 30, i.e. the last 5 minutes with the default [`Self::interval`].
+    pub num_rounds_for_drift: usize,
+
+    /// How long [`ClockSync::start`] waits for the first round of synchronization.
+    ///
+    /// Zero doesn't wait at all: synchronization then starts in the background, and
+    /// [`ClockSync::model`] returns `None` until the first round completes.
+    ///
+    /// Defaults to 5 seconds.
+    pub initial_timeout: Duration,
+}
+
+impl Default for ClockSyncOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(10),
+            num_exchanges: 8,
+            num_rounds_for_drift: 30,
+            initial_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+/// How the clock of a client relates to the one of the server, as estimated by [`ClockSync`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockModel {
+    /// When this estimate was made, in nanoseconds since the epoch according to the client's clock.
+    pub reference_nanos: i64,
+
+    /// What to add to the client's clock to get the server's, as of [`Self::reference_nanos`].
+    pub offset_nanos: i64,
+
+    /// How much faster the server's clock runs than the client's, in nanoseconds per nanosecond.
+    ///
+    /// Typically in the order of a few parts per million.
+    pub drift: f64,
+
+    /// The round-trip time of the latest exchange the offset was measured with.
+    ///
+    /// The error on the measured offset is at most half of it.
+    pub round_trip_nanos: i64,
+
+    /// How many rounds of synchronization were performed so far, including this one.
+    pub num_rounds: u64,
+}
+
+impl ClockModel {
+    /// Converts a time from the client's clock to the server's.
+    pub fn to_server_time(&self, client_nanos: i64) -> i64 {
+        let elapsed_nanos = client_nanos.saturating_sub(self.reference_nanos) as f64;
+        client_nanos
+            .saturating_add(self.offset_nanos)
+            .saturating_add((elapsed_nanos * self.drift) as i64)
+    }
+}
+
+/// Keeps estimating how the clock of this process relates to the one of a `re_grpc_server`, in
+/// the background.
+///
+/// See the [module-level documentation](self) for more information.
+///
+/// Stops when dropped.
+pub struct ClockSync {
+    model: Arc<AtomicCell<Option<ClockModel>>>,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ClockSync {
+    /// Starts synchronizing with the server at `uri`, and waits for the first round to complete.
+    ///
+    /// If it doesn't complete within [`ClockSyncOptions::initial_timeout`], e.g. because the server
+    /// can't be reached or predates clock synchronization, this keeps trying in the background,
+    /// and [`Self::model`] returns `None` until it succeeds.
+    ///
+    /// Fails if the background thread or its runtime can't be created.
+    pub fn start(uri: ProxyUri, options: ClockSyncOptions) -> std::io::Result<Self> {
+        let model = Arc::new(AtomicCell::new(None));
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let (synchronized_tx, synchronized_rx) = crossbeam::channel::bounded(1);
+
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        let initial_timeout = options.initial_timeout;
+        let thread = thread::Builder::new()
+            .name("clock_sync".to_owned())
+            .spawn({
+                let uri = uri.clone();
+                let model = model.clone();
+                move || runtime.block_on(run(uri, options, model, synchronized_tx, shutdown_rx))
+            })?;
+
+        if !initial_timeout.is_zero() && synchronized_rx.recv_timeout(initial_timeout).is_err() {
+            re_log::warn!(
+                "Couldn't synchronize clock with {uri} within {initial_timeout:?}, timestamps won't be aligned until it succeeds"
+            );
+        }
+
+        Ok(Self {
+            model,
+            shutdown_tx: Some(shutdown_tx),
+            thread: Some(thread),
+        })
+    }
+
+    /// The latest estimate, if any round of synchronization succeeded yet.
+    pub fn model(&self) -> Option<ClockModel> {
+        self.model.load()
+    }
+}
+
+impl Drop for ClockSync {
+    fn drop(&mut self) {
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            shutdown_tx.send(()).ok();
+        }
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+enum ClockSyncError {
+    #[error(transparent)]
+    Connect(#[from] tonic::transport::Error),
+
+    #[error(transparent)]
+    Status(#[from] TonicStatusError),
+}
+
+async fn run(
+    uri: ProxyUri,
+    options: ClockSyncOptions,
+    model: Arc<AtomicCell<Option<ClockModel>>>,
+    synchronized_tx: crossbeam::channel::Sender<()>,
+    mut shutdown_rx: oneshot::Receiver<()>,
+) {
+    let mut client = None;
+    let mut rounds = VecDeque::new();
+    let mut num_rounds = 0;
+
+    loop {
+        match sync_round(&uri, &mut client, options.num_exchanges).await {
+            Ok(best_exchange) => {
+                num_rounds += 1;
+                rounds.push_back(best_exchange);
+                while options.num_rounds_for_drift.max(1) < rounds.len() {
+                    rounds.pop_front();
+                }
+
+                let estimate = estimate(rounds.make_contiguous(), num_rounds);
+                re_log::debug!(?estimate, "Synchronized clock with {uri}");
+                model.store(estimate);
+                synchronized_tx.try_send(()).ok();
+            }
+
+            Err(err) => {
+                re_log::debug!("Failed to synchronize clock with {uri}: {err}");
+                client = None; // Reconnect next time.
+            }
+        }
+
+        tokio::select! {
+            _ = &mut shutdown_rx => break,
+            () = tokio::time::sleep(options.interval) => {}
+        }
+    }
+}
+
+/// Performs a round of synchronization, and returns its exchange with the shortest round-trip.
+async fn sync_round(
+    uri: &ProxyUri,
+    client: &mut Option<ViewerClient>,
+    num_exchanges: usize,
+) -> Result<Exchange, ClockSyncError> {
+    let client = match client {
+        Some(client) => client,
+        None => client.insert(viewer_client(uri.origin.clone()).await?),
+    };
+
+    let mut best_exchange = exchange(client).await?;
+    for _ in 1..num_exchanges {
+        let exchange = exchange(client).await?;
+        if exchange.round_trip_nanos() < best_exchange.round_trip_nanos() {
+            best_exchange = exchange;
+        }
+    }
+
+    Ok(best_exchange)
+}
+
+async fn exchange(client: &mut ViewerClient) -> Result<Exchange, ClockSyncError> {
+    let send_nanos = now_nanos();
+    let server_nanos = client
+        .get_server_time(GetServerTimeRequest {})
+        .await
+        .map_err(TonicStatusError::from)?
+        .into_inner()
+        .server_time_nanos;
+    let receive_nanos = now_nanos();
+
+    Ok(Exchange {
+        send_nanos,
+        server_nanos,
+        receive_nanos,
+    })
+}
+
+fn now_nanos() -> i64 {
+    re_log_types::Timestamp::now().nanos_since_epoch()
+}
+
+/// A single request for the server's time, with the client's time at both ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Exchange {
+    send_nanos: i64,
+    server_nanos: i64,
+    receive_nanos: i64,
+}
+
+impl Exchange {
+    fn round_trip_nanos(&self) -> i64 {
+        self.receive_nanos - self.send_nanos
+    }
+
+    /// The client's time at which the server read its clock, assuming symmetric latencies.
+    fn midpoint_nanos(&self) -> i64 {
+        self.send_nanos + self.round_trip_nanos() / 2
+    }
+
+    fn offset_nanos(&self) -> i64 {
+        self.server_nanos - self.midpoint_nanos()
+    }
+}
+
+/// Estimates a [`ClockModel`] from the best exchange of each of the latest rounds, oldest first.
+fn estimate(rounds: &[Exchange], num_rounds: u64) -> Option<ClockModel> {
+    let latest = rounds.last()?;
+    let reference_nanos = latest.midpoint_nanos();
+
+    // Least-squares fit of the offsets over time. Times are taken relative to the latest round,
+    // so that they fit in an `f64` without losing precision.
+    let points = rounds
+        .iter()
+        .map(|exchange| {
+            (
+                (exchange.midpoint_nanos() - reference_nanos) as f64,
+                exchange.offset_nanos() as f64,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let (covariance, variance) =
+        points
+            .iter()
+            .fold((0.0, 0.0), |(covariance, variance), (x, y)| {
+                (
+                    covariance + (x - mean_x) * (y - mean_y),
+                    variance + (x - mean_x) * (x - mean_x),
+                )
+            });
+
+    let drift = if 0.0 < variance {
+        covariance / variance
+    } else {
+        0.0
+    };
+
+    Some(ClockModel {
+        reference_nanos,
+        offset_nanos: (mean_y - drift * mean_x).round() as i64,
+        drift,
+        round_trip_nanos: latest.round_trip_nanos(),
+        num_rounds,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate_offset_and_drift() {
+        const START_NANOS: i64 = 1_700_000_000_000_000_000;
+        const OFFSET_NANOS: i64 = 1_500_000_000;
+        const DRIFT: f64 = 20e-6;
+        const HALF_ROUND_TRIP_NANOS: i64 = 250_000;
+
+        let server_nanos = |client_nanos: i64| {
+            client_nanos + OFFSET_NANOS + ((client_nanos - START_NANOS) as f64 * DRIFT) as i64
+        };
+
+        let rounds = (0..10)
+            .map(|i| {
+                let midpoint_nanos = START_NANOS + i * 10_000_000_000;
+                Exchange {
+                    send_nanos: midpoint_nanos - HALF_ROUND_TRIP_NANOS,
+                    server_nanos: server_nanos(midpoint_nanos),
+                    receive_nanos: midpoint_nanos + HALF_ROUND_TRIP_NANOS,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(None, estimate(&[], 0));
+
+        // A single round cannot tell anything about the drift.
+        let model = estimate(&rounds[..1], 1).unwrap();
+        assert_eq!(OFFSET_NANOS, model.offset_nanos);
+        assert_eq!(0.0, model.drift);
+        assert_eq!(2 * HALF_ROUND_TRIP_NANOS, model.round_trip_nanos);
+
+        let model = estimate(&rounds, 10).unwrap();
+        assert!((model.drift - DRIFT).abs() < 1e-9, "{model:?}");
+        assert_eq!(10, model.num_rounds);
+
+        // Both for the times the model was estimated from, and beyond.
+        for client_nanos in [
+            START_NANOS,
+            model.reference_nanos,
+            START_NANOS + 200_000_000_000,
+        ] {
+            let error_nanos = model.to_server_time(client_nanos) - server_nanos(client_nanos);
+            assert!(error_nanos.abs() <= 1_000, "{error_nanos}");
+        }
+    }
+}
@@ -3,6 +3,12 @@
 pub mod read;
 pub use read::stream;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod clock_sync;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod spool;
+
 #[cfg(not(target_arch = "wasm32"))]
 pub mod write;
 
@@ -0,0 +1,571 @@
+//! An on-disk queue for the messages that couldn't be sent yet, see [`SpoolOptions`].
+
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufReader, BufWriter, Read as _, Write as _},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use re_chunk::external::crossbeam::atomic::AtomicCell;
+
+/// Configures the on-disk spool of a [`crate::write::Client`].
+///
+/// When spooling is enabled, every message goes through a bounded on-disk queue, and is only
+/// removed from it once the server acknowledged it. Whenever the connection is down, messages
+/// keep being written to disk rather than piling up in memory, and the client keeps trying to
+/// reconnect. Once it succeeds, whatever the server didn't acknowledge is replayed in order,
+/// before anything new.
+///
+/// Messages that were in flight when the connection got lost may thus be sent twice.
+///
+/// The spool outlives the client: a new client spooling to the same directory picks up where the
+/// previous one left off.
+#[derive(Debug, Clone)]
+pub struct SpoolOptions {
+    /// The directory where spooled messages are stored.
+    ///
+    /// It is created if it doesn't exist. It should be dedicated to a single client at a time.
+    pub directory: PathBuf,
+
+    /// The maximum size of the spool on disk.
+    ///
+    /// Once it is reached, the oldest spooled messages that aren't in flight are dropped to make
+    /// room for new ones.
+    pub max_bytes: u64,
+}
+
+impl SpoolOptions {
+    /// 1 GiB.
+    pub const DEFAULT_MAX_BYTES: u64 = 1024 * 1024 * 1024;
+
+    #[inline]
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            max_bytes: Self::DEFAULT_MAX_BYTES,
+        }
+    }
+
+    #[inline]
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+}
+
+/// How much data is waiting in the spool of a [`crate::write::Client`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SpoolBacklog {
+    /// How many messages haven't been acknowledged by the server yet.
+    pub num_messages: u64,
+
+    /// How many bytes haven't been acknowledged by the server yet.
+    pub num_bytes: u64,
+
+    /// How many messages had to be dropped because the spool was full, since the client started.
+    pub num_dropped_messages: u64,
+}
+
+/// A file of the spool, holding length-prefixed encoded messages.
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    id: u64,
+    num_messages: u64,
+    num_bytes: u64,
+}
+
+/// A message read back from the spool, along with the segment it is stored in.
+struct SpooledMessage {
+    segment_id: u64,
+    msg: Vec<u8>,
+}
+
+/// Spooled messages are rotated into a new segment once the current one gets this large.
+///
+/// This is also the granularity at which messages get dropped once the spool is full.
+const SEGMENT_MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+const SEGMENT_EXTENSION: &str = "spool";
+
+/// An on-disk FIFO of encoded messages.
+///
+/// Messages are handed out with [`Self::next_unsent`], and are only removed once they've been
+/// [acknowledged](Self::acknowledge). Until then, they can be [sent again](Self::rewind).
+pub(crate) struct Spool {
+    options: SpoolOptions,
+
+    /// The segments on disk that haven't been read yet, oldest first.
+    ///
+    /// The last one is the one being written to, if `writer` is set.
+    segments: VecDeque<Segment>,
+    writer: Option<BufWriter<File>>,
+
+    /// The segments that were read back in memory, oldest first.
+    ///
+    /// Their files are only deleted once all of their messages have been acknowledged.
+    read_segments: VecDeque<Segment>,
+
+    /// The messages of [`Self::read_segments`] that haven't been sent yet.
+    unsent: VecDeque<SpooledMessage>,
+
+    /// The messages of [`Self::read_segments`] that were sent, but not acknowledged yet.
+    in_flight: VecDeque<SpooledMessage>,
+
+    next_segment_id: u64,
+
+    backlog: SpoolBacklog,
+
+    /// Shared with the [`crate::write::Client`], see [`crate::write::Client::backlog`].
+    shared_backlog: Arc<AtomicCell<SpoolBacklog>>,
+}
+
+impl Spool {
+    /// Opens the spool, picking up whatever was left in it.
+    pub fn open(
+        options: SpoolOptions,
+        shared_backlog: Arc<AtomicCell<SpoolBacklog>>,
+    ) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&options.directory)?;
+
+        let mut segment_ids = Vec::new();
+        for entry in std::fs::read_dir(&options.directory)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == SEGMENT_EXTENSION)
+                && let Some(id) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse::<u64>().ok())
+            {
+                segment_ids.push(id);
+            }
+        }
+        segment_ids.sort_unstable();
+
+        let mut backlog = SpoolBacklog::default();
+        let mut segments = VecDeque::new();
+        for id in segment_ids {
+            let messages = read_segment(&segment_path(&options.directory, id))?;
+            let segment = Segment {
+                id,
+                num_messages: messages.len() as u64,
+                num_bytes: messages.iter().map(|msg| msg.len() as u64).sum(),
+            };
+            backlog.num_messages += segment.num_messages;
+            backlog.num_bytes += segment.num_bytes;
+            segments.push_back(segment);
+        }
+
+        if 0 < backlog.num_messages {
+            re_log::info!(
+                "Found {} spooled messages ({} bytes) in {:?}, they will be sent once connected",
+                backlog.num_messages,
+                backlog.num_bytes,
+                options.directory,
+            );
+        }
+
+        let next_segment_id = segments.back().map_or(0, |segment| segment.id + 1);
+
+        let spool = Self {
+            options,
+            segments,
+            writer: None,
+            read_segments: VecDeque::new(),
+            unsent: VecDeque::new(),
+            in_flight: VecDeque::new(),
+            next_segment_id,
+            backlog,
+            shared_backlog,
+        };
+        spool.publish_backlog();
+
+        Ok(spool)
+    }
+
+    /// Is there nothing left that the server hasn't acknowledged?
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.backlog.num_messages == 0
+    }
+
+    /// Appends an encoded message at the end of the spool.
+    ///
+    /// Drops the oldest spooled messages that aren't in flight if there isn't enough room left.
+    pub fn push(&mut self, msg: &[u8]) {
+        let msg_bytes = msg.len() as u64;
+
+        while self.options.max_bytes < self.backlog.num_bytes + msg_bytes {
+            if !self.drop_oldest_messages() {
+                // Nothing left that can be dropped: drop the message itself.
+                self.on_dropped(1);
+                return;
+            }
+        }
+
+        if let Err(err) = self.write(msg) {
+            re_log::error_once!(
+                "Failed to spool message to {:?}: {err}",
+                self.options.directory
+            );
+            self.writer = None;
+            self.on_dropped(1);
+            return;
+        }
+
+        self.backlog.num_messages += 1;
+        self.backlog.num_bytes += msg_bytes;
+        self.publish_backlog();
+    }
+
+    /// Returns the oldest message that wasn't sent yet, which is now considered in flight.
+    ///
+    /// It stays in the spool until it is [acknowledged](Self::acknowledge).
+    pub fn next_unsent(&mut self) -> Option<&[u8]> {
+        while self.unsent.is_empty() {
+            let segment = self.segments.pop_front()?;
+            if self.segments.is_empty() {
+                // That's the segment being written to: we're done with it.
+                self.close_writer();
+            }
+
+            let path = segment_path(&self.options.directory, segment.id);
+            match read_segment(&path) {
+                Ok(messages) => {
+                    self.unsent
+                        .extend(messages.into_iter().map(|msg| SpooledMessage {
+                            segment_id: segment.id,
+                            msg,
+                        }));
+                    self.read_segments.push_back(segment);
+                }
+                Err(err) => {
+                    re_log::error!("Failed to read spooled messages from {path:?}: {err}");
+                    self.remove_segment(segment.id);
+                    self.forget(segment.num_messages, segment.num_bytes);
+                }
+            }
+        }
+
+        let msg = self.unsent.pop_front()?;
+        self.in_flight.push_back(msg);
+        self.in_flight.back().map(|msg| msg.msg.as_slice())
+    }
+
+    /// The server received all the messages in flight: they can be removed for good.
+    pub fn acknowledge(&mut self) {
+        for SpooledMessage { segment_id: _, msg } in self.in_flight.drain(..) {
+            self.backlog.num_messages = self.backlog.num_messages.saturating_sub(1);
+            self.backlog.num_bytes = self.backlog.num_bytes.saturating_sub(msg.len() as u64);
+        }
+
+        // Nothing is in flight anymore, so a segment is done with once it has nothing left to send.
+        let oldest_unsent_segment_id = self.unsent.front().map(|msg| msg.segment_id);
+        while let Some(segment) = self.read_segments.front().copied() {
+            if oldest_unsent_segment_id.is_some_and(|id| id <= segment.id) {
+                break;
+            }
+            self.read_segments.pop_front();
+            self.remove_segment(segment.id);
+        }
+
+        self.publish_backlog();
+    }
+
+    /// The messages in flight may not have made it to the server: they'll be the next ones sent.
+    pub fn rewind(&mut self) {
+        while let Some(msg) = self.in_flight.pop_back() {
+            self.unsent.push_front(msg);
+        }
+    }
+
+    /// Makes sure all spooled messages made it to disk.
+    pub fn flush(&mut self) {
+        if let Some(writer) = &mut self.writer
+            && let Err(err) = writer.flush()
+        {
+            re_log::error_once!(
+                "Failed to flush spool to {:?}: {err}",
+                self.options.directory
+            );
+        }
+    }
+
+    fn write(&mut self, msg: &[u8]) -> std::io::Result<()> {
+        let needs_new_segment = self.writer.is_none()
+            || self
+                .segments
+                .back()
+                .is_none_or(|segment| SEGMENT_MAX_BYTES <= segment.num_bytes);
+
+        if needs_new_segment {
+            self.close_writer();
+
+            let id = self.next_segment_id;
+            self.next_segment_id += 1;
+
+            let file = File::create(segment_path(&self.options.directory, id))?;
+            self.writer = Some(BufWriter::new(file));
+            self.segments.push_back(Segment {
+                id,
+                num_messages: 0,
+                num_bytes: 0,
+            });
+        }
+
+        let (Some(writer), Some(segment)) = (&mut self.writer, self.segments.back_mut()) else {
+            return Err(std::io::Error::other("no segment to write to"));
+        };
+
+        writer.write_all(&(msg.len() as u64).to_le_bytes())?;
+        writer.write_all(msg)?;
+
+        segment.num_messages += 1;
+        segment.num_bytes += msg.len() as u64;
+
+        Ok(())
+    }
+
+    fn close_writer(&mut self) {
+        self.flush();
+        self.writer = None;
+    }
+
+    /// Drops the oldest messages that aren't in flight, returning `false` if there were none.
+    fn drop_oldest_messages(&mut self) -> bool {
+        if let Some(segment_id) = self.unsent.front().map(|msg| msg.segment_id) {
+            // What's left of the oldest segment that was read back.
+            let mut num_messages = 0;
+            let mut num_bytes = 0;
+            while self
+                .unsent
+                .front()
+                .is_some_and(|msg| msg.segment_id == segment_id)
+            {
+                if let Some(msg) = self.unsent.pop_front() {
+                    num_messages += 1;
+                    num_bytes += msg.msg.len() as u64;
+                }
+            }
+
+            if !self
+                .in_flight
+                .iter()
+                .any(|msg| msg.segment_id == segment_id)
+            {
+                self.read_segments
+                    .retain(|segment| segment.id != segment_id);
+                self.remove_segment(segment_id);
+            }
+
+            self.on_full();
+            self.forget(num_messages, num_bytes);
+        } else {
+            let Some(segment) = self.segments.pop_front() else {
+                return false;
+            };
+            if self.segments.is_empty() {
+                self.writer = None;
+            }
+
+            self.on_full();
+            self.remove_segment(segment.id);
+            self.forget(segment.num_messages, segment.num_bytes);
+        }
+
+        true
+    }
+
+    fn on_full(&self) {
+        re_log::warn_once!(
+            "Spool {:?} is full ({} bytes): dropping the oldest messages",
+            self.options.directory,
+            self.options.max_bytes,
+        );
+    }
+
+    fn remove_segment(&self, segment_id: u64) {
+        let path = segment_path(&self.options.directory, segment_id);
+        if let Err(err) = std::fs::remove_file(&path) {
+            re_log::warn_once!("Failed to remove spool segment {path:?}: {err}");
+        }
+    }
+
+    /// Accounts for messages that were dropped.
+    fn forget(&mut self, num_messages: u64, num_bytes: u64) {
+        self.backlog.num_messages = self.backlog.num_messages.saturating_sub(num_messages);
+        self.backlog.num_bytes = self.backlog.num_bytes.saturating_sub(num_bytes);
+        self.on_dropped(num_messages);
+    }
+
+    fn on_dropped(&mut self, num_messages: u64) {
+        self.backlog.num_dropped_messages += num_messages;
+        self.publish_backlog();
+    }
+
+    fn publish_backlog(&self) {
+        self.shared_backlog.store(self.backlog);
+    }
+}
+
+impl Drop for Spool {
+    fn drop(&mut self) {
+        self.flush();
+
+        // Don't replay the messages that were already acknowledged next time.
+        for segment in std::mem::take(&mut self.read_segments) {
+            let messages = self
+                .in_flight
+                .iter()
+                .chain(&self.unsent)
+                .filter(|msg| msg.segment_id == segment.id)
+                .map(|msg| &msg.msg)
+                .collect::<Vec<_>>();
+            if messages.is_empty() {
+                self.remove_segment(segment.id);
+                continue;
+            }
+
+            let path = segment_path(&self.options.directory, segment.id);
+            if let Err(err) = write_segment(&path, messages) {
+                re_log::error!("Failed to update spool segment {path:?}: {err}");
+            }
+        }
+    }
+}
+
+fn segment_path(directory: &Path, id: u64) -> PathBuf {
+    directory.join(format!("{id:020}.{SEGMENT_EXTENSION}"))
+}
+
+/// Overwrites a segment with the given messages.
+fn write_segment<'a>(
+    path: &Path,
+    messages: impl IntoIterator<Item = &'a Vec<u8>>,
+) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for msg in messages {
+        writer.write_all(&(msg.len() as u64).to_le_bytes())?;
+        writer.write_all(msg)?;
+    }
+    writer.flush()
+}
+
+/// Reads all the messages of a segment.
+///
+/// A truncated message at the end of the file (e.g. because of a crash) is ignored.
+fn read_segment(path: &Path) -> std::io::Result<Vec<Vec<u8>>> {
+    let file = File::open(path)?;
+    let mut num_bytes_left = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+
+    let mut messages = Vec::new();
+    loop {
+        let mut len = [0u8; 8];
+        if reader.read_exact(&mut len).is_err() {
+            break;
+        }
+        num_bytes_left = num_bytes_left.saturating_sub(len.len() as u64);
+
+        // Never trust the length prefix further than the file goes, e.g. if it got corrupted.
+        let len = u64::from_le_bytes(len);
+        if num_bytes_left < len {
+            re_log::warn!("Ignoring truncated spooled message in {path:?}");
+            break;
+        }
+        num_bytes_left -= len;
+
+        let mut msg = vec![0u8; len as usize];
+        if reader.read_exact(&mut msg).is_err() {
+            re_log::warn!("Ignoring truncated spooled message in {path:?}");
+            break;
+        }
+
+        messages.push(msg);
+    }
+
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spool_roundtrip() -> std::io::Result<()> {
+        let directory =
+            std::env::temp_dir().join(format!("rerun_spool_test_{}", re_chunk::ChunkId::new()));
+        let backlog = Arc::new(AtomicCell::new(SpoolBacklog::default()));
+
+        let messages = (0..10_u8).map(|i| vec![i; 100]).collect::<Vec<_>>();
+
+        {
+            let mut spool = Spool::open(
+                SpoolOptions::new(&directory).with_max_bytes(500),
+                backlog.clone(),
+            )?;
+            for msg in &messages {
+                spool.push(msg);
+            }
+
+            // Only the most recent messages fit.
+            assert_eq!(5, backlog.load().num_messages);
+            assert_eq!(5, backlog.load().num_dropped_messages);
+
+            assert_eq!(Some(messages[5].as_slice()), spool.next_unsent());
+            spool.acknowledge();
+            assert_eq!(4, backlog.load().num_messages);
+
+            // Sent, but never acknowledged.
+            assert_eq!(Some(messages[6].as_slice()), spool.next_unsent());
+        }
+
+        // Whatever wasn't acknowledged is picked up by the next spool, in order.
+        let mut spool = Spool::open(SpoolOptions::new(&directory), backlog.clone())?;
+        assert_eq!(4, backlog.load().num_messages);
+
+        assert_eq!(Some(messages[6].as_slice()), spool.next_unsent());
+        assert_eq!(Some(messages[7].as_slice()), spool.next_unsent());
+        spool.rewind();
+
+        let mut sent = Vec::new();
+        while let Some(msg) = spool.next_unsent() {
+            sent.push(msg.to_vec());
+        }
+        assert_eq!(messages[6..].to_vec(), sent);
+        assert!(!spool.is_empty());
+
+        spool.acknowledge();
+        assert!(spool.is_empty());
+        assert_eq!(SpoolBacklog::default(), backlog.load());
+
+        drop(spool);
+        assert_eq!(0, std::fs::read_dir(&directory)?.count());
+        std::fs::remove_dir_all(&directory)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn corrupted_segment() -> std::io::Result<()> {
+        let directory =
+            std::env::temp_dir().join(format!("rerun_spool_test_{}", re_chunk::ChunkId::new()));
+        std::fs::create_dir_all(&directory)?;
+
+        let path = segment_path(&directory, 0);
+        write_segment(&path, &[vec![42; 10]])?;
+
+        // A bogus length must not lead to a huge allocation.
+        let mut bytes = std::fs::read(&path)?;
+        bytes.extend(u64::MAX.to_le_bytes());
+        bytes.extend([0; 10]);
+        std::fs::write(&path, bytes)?;
+
+        assert_eq!(vec![vec![42; 10]], read_segment(&path)?);
+
+        std::fs::remove_dir_all(&directory)?;
+
+        Ok(())
+    }
+}
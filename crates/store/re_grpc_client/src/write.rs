@@ -1,4 +1,5 @@
 use std::{
+    collections::VecDeque,
     sync::Arc,
     thread::{self, JoinHandle},
     time::Duration,
@@ -14,12 +15,18 @@ use web_time::Instant;
 use re_chunk::external::crossbeam::atomic::AtomicCell;
 use re_log_encoding::Compression;
 use re_log_types::LogMsg;
-use re_protos::sdk_comms::v1alpha1::{
-    WriteMessagesRequest, message_proxy_service_client::MessageProxyServiceClient,
+use re_protos::{
+    external::prost::Message as _,
+    log_msg::v1alpha1::LogMsg as ProtoLogMsg,
+    sdk_comms::v1alpha1::{
+        WriteMessagesRequest, message_proxy_service_client::MessageProxyServiceClient,
+    },
 };
 use re_uri::ProxyUri;
 
-use crate::TonicStatusError;
+use crate::{TonicStatusError, spool::Spool};
+
+pub use crate::spool::{SpoolBacklog, SpoolOptions};
 
 /// An error that can occur when flushing.
 #[derive(Debug, thiserror::Error)]
@@ -78,6 +85,15 @@ pub struct Options {
     /// But blocking [`Client::flush_blocking`] forever when the
     /// server just isn't there is not a good idea.
     pub connect_timeout_on_flush: Duration,
+
+    /// If set, messages are spooled to disk until the server acknowledges them, and the client
+    /// keeps reconnecting for as long as it lives.
+    ///
+    /// Otherwise, messages are buffered in memory until the first connection is established, and
+    /// the client gives up once that connection is lost.
+    ///
+    /// See [`SpoolOptions`].
+    pub spool: Option<SpoolOptions>,
 }
 
 impl Default for Options {
@@ -85,6 +101,7 @@ impl Default for Options {
         Self {
             compression: Compression::LZ4,
             connect_timeout_on_flush: Duration::from_secs(5),
+            spool: None,
         }
     }
 }
@@ -121,24 +138,22 @@ pub enum ClientConnectionState {
 pub struct Client {
     uri: ProxyUri,
     options: Options,
+
+    /// The thread running our own runtime, if the client isn't running on the caller's runtime.
     thread: Option<JoinHandle<()>>,
+    is_on_caller_runtime: bool,
+
     cmd_tx: UnboundedSender<Cmd>,
     shutdown_tx: Sender<()>,
     status: Arc<AtomicCell<ClientConnectionState>>,
+    backlog: Arc<AtomicCell<SpoolBacklog>>,
 }
 
 impl Client {
+    /// Creates a client that runs on its own thread and runtime.
     pub fn new(uri: ProxyUri, options: Options) -> Self {
-        let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
-        let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
-
-        let status = Arc::new(AtomicCell::new(ClientConnectionState::Connecting {
-            started: Instant::now(),
-        }));
-        let thread = {
-            let uri = uri.clone();
-            let status = status.clone();
-            thread::Builder::new()
+        Self::new_impl(uri, options, |task| {
+            let thread = thread::Builder::new()
                 .name("message_proxy_client".to_owned())
                 .spawn(move || {
                     let mut runtime = runtime::Builder::new_current_thread();
@@ -146,24 +161,76 @@ impl Client {
                     runtime
                         .build()
                         .expect("Failed to build tokio runtime")
-                        .block_on(message_proxy_client(
-                            uri.clone(),
-                            cmd_rx,
-                            shutdown_rx,
-                            options.compression,
-                            status,
-                        ));
+                        .block_on(task);
                 })
-                .expect("Failed to spawn message proxy client thread")
-        };
+                .expect("Failed to spawn message proxy client thread");
+            Some(thread)
+        })
+    }
+
+    /// Creates a client that runs as a task on the given runtime, instead of spawning its own.
+    ///
+    /// Dropping such a client does not wait for its messages to be sent: they keep being sent in
+    /// the background for as long as the runtime lives, unless the client is still connecting, in
+    /// which case it gives up. Await [`Self::wait_for_connection`] and flush before dropping it if
+    /// that matters.
+    pub fn new_in_runtime(uri: ProxyUri, options: Options, runtime: &runtime::Handle) -> Self {
+        Self::new_impl(uri, options, |task| {
+            runtime.spawn(task);
+            None
+        })
+    }
+
+    fn new_impl(
+        uri: ProxyUri,
+        options: Options,
+        spawn: impl FnOnce(
+            std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>,
+        ) -> Option<JoinHandle<()>>,
+    ) -> Self {
+        let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+        let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+
+        let status = Arc::new(AtomicCell::new(ClientConnectionState::Connecting {
+            started: Instant::now(),
+        }));
+
+        let backlog = Arc::new(AtomicCell::new(SpoolBacklog::default()));
+        let spool = options.spool.clone().and_then(|spool_options| {
+            let directory = spool_options.directory.clone();
+            Spool::open(spool_options, backlog.clone())
+                .map_err(|err| {
+                    re_log::error!(
+                        "Failed to open spool at {directory:?}, unsent messages will be kept in memory instead: {err}"
+                    );
+                })
+                .ok()
+        });
+
+        let task = message_proxy_client(
+            uri.clone(),
+            ClientState {
+                cmd_rx,
+                shutdown_rx,
+                spool,
+                pending_cmds: VecDeque::new(),
+                pending_flushes: Vec::new(),
+                is_done: false,
+            },
+            options.compression,
+            status.clone(),
+        );
+        let thread = spawn(Box::pin(task));
 
         Self {
             uri,
             options,
-            thread: Some(thread),
+            is_on_caller_runtime: thread.is_none(),
+            thread,
             cmd_tx,
             shutdown_tx,
             status,
+            backlog,
         }
     }
 
@@ -171,11 +238,51 @@ impl Client {
         self.cmd_tx.send(Cmd::LogMsg(msg)).ok();
     }
 
+    /// Whether the client runs as a task on the caller's runtime, see [`Self::new_in_runtime`].
+    pub fn is_on_caller_runtime(&self) -> bool {
+        self.is_on_caller_runtime
+    }
+
     /// Whether the client is connected to a remote server.
     pub fn status(&self) -> ClientConnectionState {
         self.status.load()
     }
 
+    /// How much data is waiting to be sent in the on-disk spool, see [`Options::spool`].
+    ///
+    /// Always empty if spooling is disabled.
+    pub fn backlog(&self) -> SpoolBacklog {
+        self.backlog.load()
+    }
+
+    /// Waits until the client is connected to the remote server.
+    ///
+    /// Fails if the connection could not be established within
+    /// [`Options::connect_timeout_on_flush`], or if the client was disconnected.
+    pub async fn wait_for_connection(&self) -> Result<(), GrpcFlushError> {
+        loop {
+            match self.status() {
+                ClientConnectionState::Connected => return Ok(()),
+
+                ClientConnectionState::Connecting { started } => {
+                    if self.options.connect_timeout_on_flush < started.elapsed() {
+                        return Err(GrpcFlushError::FailedToConnect {
+                            uri: self.uri.clone(),
+                            duration_sec: started.elapsed().as_secs_f32(),
+                        });
+                    }
+                }
+
+                status @ ClientConnectionState::Disconnected(_) => {
+                    return Err(GrpcFlushError::from_status(self.uri.clone(), status));
+                }
+            }
+
+            // The connection state is not observable, so we poll it.
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
     /// Block until all messages are sent, or there is a failure.
     ///
     /// If the gRPC connection has not yet been established,
@@ -285,6 +392,13 @@ impl Client {
 
 impl Drop for Client {
     fn drop(&mut self) {
+        if self.is_on_caller_runtime {
+            // Blocking here could deadlock the caller's runtime. Closing the command channel lets
+            // the task send its remaining messages, and then stop.
+            re_log::debug!("Detaching message proxy client task");
+            return;
+        }
+
         re_log::debug!("Shutting down message proxy client");
 
         // Wait for flush, blocking forever if needed.
@@ -307,10 +421,25 @@ impl Drop for Client {
     }
 }
 
+/// Everything that outlives a single connection.
+struct ClientState {
+    cmd_rx: UnboundedReceiver<Cmd>,
+    shutdown_rx: Receiver<()>,
+    spool: Option<Spool>,
+
+    /// Commands received while connecting, if there is no spool to put them in.
+    pending_cmds: VecDeque<Cmd>,
+
+    /// Flushes waiting for the server to acknowledge the spooled messages that preceded them.
+    pending_flushes: Vec<crossbeam::channel::Sender<()>>,
+
+    /// Set once there is no point in reconnecting anymore.
+    is_done: bool,
+}
+
 async fn message_proxy_client(
     uri: ProxyUri,
-    mut cmd_rx: UnboundedReceiver<Cmd>,
-    mut shutdown_rx: Receiver<()>,
+    state: ClientState,
     compression: Compression,
     status: Arc<AtomicCell<ClientConnectionState>>,
 ) {
@@ -325,114 +454,442 @@ async fn message_proxy_client(
         }
     };
 
-    let mut last_connect_failure_log_time: Option<Instant> = None;
-    let channel = loop {
-        match endpoint.connect().await {
-            Ok(channel) => break channel,
-            Err(err) => {
-                let log_interval = Duration::from_secs(5);
-                if last_connect_failure_log_time
-                    .is_none_or(|last_log_time| log_interval < last_log_time.elapsed())
-                {
-                    re_log::debug!("Failed to connect to {uri}: {err}, retrying…");
-                    last_connect_failure_log_time = Some(Instant::now());
+    let has_spool = state.spool.is_some();
+
+    // NOTE: The state is shared with the message streams of each connection.
+    let state = Arc::new(tokio::sync::Mutex::new(state));
+
+    loop {
+        let mut last_connect_failure_log_time: Option<Instant> = None;
+        let channel = loop {
+            match endpoint.connect().await {
+                Ok(channel) => break channel,
+                Err(err) => {
+                    let log_interval = Duration::from_secs(5);
+                    if last_connect_failure_log_time
+                        .is_none_or(|last_log_time| log_interval < last_log_time.elapsed())
+                    {
+                        re_log::debug!("Failed to connect to {uri}: {err}, retrying…");
+                        last_connect_failure_log_time = Some(Instant::now());
+                    }
+
+                    let mut state = state.lock().await;
+                    let state = &mut *state;
+
+                    let retry = tokio::time::sleep(Duration::from_millis(100));
+                    tokio::pin!(retry);
+
+                    loop {
+                        tokio::select! {
+                            // Commands come before the shutdown, so that nothing that was sent
+                            // before the client got dropped is lost, but they can't hold up the
+                            // next connection attempt.
+                            biased;
+
+                            _ = &mut retry => break,
+
+                            cmd = state.cmd_rx.recv() => {
+                                let Some(cmd) = cmd else {
+                                    // The client was dropped: there is nobody left to connect for.
+                                    status.store(ClientConnectionState::Disconnected(Ok(())));
+                                    re_log::debug!("Shutdown channel closed");
+                                    return;
+                                };
+
+                                if let Some(spool) = &mut state.spool {
+                                    // While disconnected, messages go to the spool rather than piling up in memory.
+                                    spool_cmd(spool, cmd, compression);
+                                } else {
+                                    state.pending_cmds.push_back(cmd);
+                                }
+                            }
+
+                            // `None` means that the client was dropped.
+                            _ = state.shutdown_rx.recv() => {
+                                status.store(ClientConnectionState::Disconnected(Ok(())));
+                                re_log::debug!("Shutting down client without flush");
+                                return;
+                            }
+                        }
+                    }
                 }
+            }
+        };
+
+        re_log::debug!("Connected to {uri}");
+        status.store(ClientConnectionState::Connected);
+
+        let mut client = MessageProxyServiceClient::new(channel)
+            .max_decoding_message_size(crate::MAX_DECODING_MESSAGE_SIZE);
+
+        let write_result = if has_spool {
+            write_spooled_messages(&mut client, &state, compression).await
+        } else {
+            write_messages(&mut client, &state, compression, &status).await
+        };
+
+        let disconnect_result = if let Err(status) = write_result {
+            re_log::error!(
+                "Write messages call failed: {}",
+                TonicStatusError::from(status.clone())
+            );
+
+            // Ignore status code "Unknown" since this was observed to happen on regular Viewer shutdowns.
+            if status.code() != tonic::Code::Ok && status.code() != tonic::Code::Unknown {
+                Err(ClientConnectionFailure::FailedToSendMessages(status.code()))
+            } else {
+                Ok(())
+            }
+        } else {
+            Ok(())
+        };
+
+        let reconnect = {
+            let state = state.lock().await;
+            state.spool.is_some() && !state.is_done
+        };
+        if reconnect {
+            // NOTE: Whatever the server didn't acknowledge is still in the spool, and will be
+            // sent again.
+            re_log::warn!(
+                "Lost connection to {uri}, spooling messages to disk until it comes back"
+            );
+            status.store(ClientConnectionState::Connecting {
+                started: Instant::now(),
+            });
+
+            // Don't hammer a server that accepts connections but keeps failing the calls.
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            continue;
+        }
+
+        // Don't set error status if we already did so in the stream.
+        if !matches!(status.load(), ClientConnectionState::Disconnected(_)) {
+            status.store(ClientConnectionState::Disconnected(disconnect_result));
+        }
+
+        return;
+    }
+}
 
+/// Sends messages as they come, for as long as the connection lasts.
+async fn write_messages(
+    client: &mut MessageProxyServiceClient<tonic::transport::Channel>,
+    state: &Arc<tokio::sync::Mutex<ClientState>>,
+    compression: Compression,
+    status: &Arc<AtomicCell<ClientConnectionState>>,
+) -> Result<(), tonic::Status> {
+    let mut stream_state = state.clone().lock_owned().await;
+    let stream_status = status.clone();
+    let stream = async_stream::stream! {
+        loop {
+            // What was received while connecting goes first.
+            let cmd = if let Some(cmd) = stream_state.pending_cmds.pop_front() {
+                Some(cmd)
+            } else {
                 tokio::select! {
-                    _ = shutdown_rx.recv() => {
-                        status.store(ClientConnectionState::Disconnected(Ok(())));
+                    // Commands come before the shutdown, so that nothing that was sent before the
+                    // client got dropped is lost.
+                    biased;
+
+                    cmd = stream_state.cmd_rx.recv() => cmd,
+
+                    // `None` means that the client was dropped.
+                    _ = stream_state.shutdown_rx.recv() => {
                         re_log::debug!("Shutting down client without flush");
-                        return;
+                        stream_state.is_done = true;
+                        break;
                     }
-                    _ = tokio::time::sleep(Duration::from_millis(100)) => {
+                }
+            };
+
+            match cmd {
+                Some(Cmd::LogMsg(log_msg)) => {
+                    let msg = match encode_log_msg(log_msg, compression) {
+                        Ok(msg) => msg,
+                        Err(err) => {
+                            stream_status.store(ClientConnectionState::Disconnected(
+                                Err(ClientConnectionFailure::FailedToEncodeMessage),
+                            ));
+                            re_log::error!("Failed to encode message: {err}");
+                            stream_state.is_done = true;
+                            break;
+                        }
+                    };
+
+                    let msg = WriteMessagesRequest {
+                        log_msg: Some(msg),
+                    };
+
+                    yield msg;
+                }
+
+                Some(Cmd::Flush { on_done }) => {
+                    // Messages are received in order, so once we receive a `flush`
+                    // we know we've sent all messages before that flush through already.
+                    re_log::debug!("Flush requested");
+                    if on_done.send(()).is_err() {
+                        // Flush channel may already be closed for non-blocking flush, so this isn't an error.
+                        re_log::debug!("Failed to respond to flush: flush report channel was closed");
+                        stream_state.is_done = true;
+                        break;
                     }
                 }
+
+                None => {
+                    // Assume channel closing is intentional, so don't report as error.
+                    re_log::debug!("Shutdown channel closed");
+                    stream_state.is_done = true;
+                    break;
+                }
             }
         }
     };
 
-    re_log::debug!("Connected to {uri}");
-    status.store(ClientConnectionState::Connected);
+    client.write_messages(stream).await.map(|_| ())
+}
 
-    let mut client = MessageProxyServiceClient::new(channel)
-        .max_decoding_message_size(crate::MAX_DECODING_MESSAGE_SIZE);
+/// Sends messages through the spool, for as long as the connection lasts.
+///
+/// Messages are sent in batches, each of which is a `WriteMessages` call of its own: the server
+/// only responds to it once it received all of its messages, which acknowledges them. A batch ends
+/// once it gets large or old enough, or when a flush is requested, so that the flush only
+/// completes once the server acknowledged everything that preceded it.
+async fn write_spooled_messages(
+    client: &mut MessageProxyServiceClient<tonic::transport::Channel>,
+    state: &Arc<tokio::sync::Mutex<ClientState>>,
+    compression: Compression,
+) -> Result<(), tonic::Status> {
+    /// A batch ends once it has sent at least this many bytes…
+    const BATCH_MAX_BYTES: u64 = 16 * 1024 * 1024;
 
-    let stream_status = status.clone();
-    let stream = async_stream::stream! {
-        loop {
-            tokio::select! {
-                cmd = cmd_rx.recv() => {
-                    match cmd {
-                        Some(Cmd::LogMsg(mut log_msg)) => {
-                            // Insert the timestamp metadata into the Arrow message for accurate e2e latency measurements:
-                             log_msg.insert_arrow_record_batch_metadata(
-                                re_sorbet::timestamp_metadata::KEY_TIMESTAMP_SDK_IPC_ENCODE.to_owned(),
-                                re_sorbet::timestamp_metadata::now_timestamp(),
-                            );
+    /// …or once it has been sending for this long.
+    const BATCH_MAX_DURATION: Duration = Duration::from_secs(1);
 
-                            let msg = match re_log_encoding::protobuf_conversions::log_msg_to_proto(log_msg, compression) {
-                                Ok(msg) => msg,
-                                Err(err) => {
-                                    stream_status.store(ClientConnectionState::Disconnected(
-                                        Err(ClientConnectionFailure::FailedToEncodeMessage),
-                                    ));
-                                    re_log::error!("Failed to encode message: {err}");
-                                    break;
-                                }
-                            };
+    loop {
+        let batch_state = state.clone();
+        let batch = async_stream::stream! {
+            let started = Instant::now();
+            let mut num_bytes = 0;
+
+            loop {
+                if BATCH_MAX_BYTES <= num_bytes || BATCH_MAX_DURATION <= started.elapsed() {
+                    break;
+                }
+
+                // NOTE: The state is never locked across a `yield`, since the batch may outlive
+                // its `WriteMessages` call.
+                let mut batch_state = batch_state.lock().await;
+                let ClientState {
+                    cmd_rx,
+                    shutdown_rx,
+                    spool: Some(spool),
+                    pending_cmds: _,
+                    pending_flushes,
+                    is_done,
+                } = &mut *batch_state
+                else {
+                    break;
+                };
+
+                // Whatever is in the spool goes first, so that everything is sent in order.
+                if let Some(msg) = spool.next_unsent() {
+                    num_bytes += msg.len() as u64;
+                    let msg = ProtoLogMsg::decode(msg);
+                    drop(batch_state);
 
-                            let msg = WriteMessagesRequest {
+                    match msg {
+                        Ok(msg) => {
+                            yield WriteMessagesRequest {
                                 log_msg: Some(msg),
                             };
-
-                            yield msg;
                         }
+                        Err(err) => {
+                            re_log::error!("Failed to decode spooled message, dropping it: {err}");
+                        }
+                    }
+                    continue;
+                }
 
-                        Some(Cmd::Flush { on_done }) => {
-                            // Messages are received in order, so once we receive a `flush`
-                            // we know we've sent all messages before that flush through already.
-                            re_log::debug!("Flush requested");
-                            if on_done.send(()).is_err() {
-                                // Flush channel may already be closed for non-blocking flush, so this isn't an error.
-                                re_log::debug!("Failed to respond to flush: flush report channel was closed");
+                let batch_time_left = BATCH_MAX_DURATION.saturating_sub(started.elapsed());
+                tokio::select! {
+                    // Commands come before the shutdown, so that nothing that was sent before the
+                    // client got dropped is lost.
+                    biased;
+
+                    cmd = cmd_rx.recv() => {
+                        match cmd {
+                            // Messages only get sent once they are safely on disk.
+                            Some(Cmd::LogMsg(log_msg)) => match encode_log_msg(log_msg, compression) {
+                                Ok(msg) => spool.push(&msg.encode_to_vec()),
+                                Err(err) => re_log::error!("Failed to encode message, dropping it: {err}"),
+                            },
+
+                            Some(Cmd::Flush { on_done }) => {
+                                re_log::debug!("Flush requested");
+                                pending_flushes.push(on_done);
                                 break;
                             }
-                        }
 
-                        None => {
-                            // Assume channel closing is intentional, so don't report as error.
-                            re_log::debug!("Shutdown channel closed");
-                            break;
+                            None => {
+                                // Assume channel closing is intentional, so don't report as error.
+                                re_log::debug!("Shutdown channel closed");
+                                *is_done = true;
+                                break;
+                            }
                         }
                     }
+
+                    // `None` means that the client was dropped.
+                    _ = shutdown_rx.recv() => {
+                        re_log::debug!("Shutting down client without flush");
+                        *is_done = true;
+                        break;
+                    }
+
+                    // Don't keep what was sent unacknowledged for too long.
+                    () = tokio::time::sleep(batch_time_left), if 0 < num_bytes => break,
                 }
+            }
+        };
 
-                _ = shutdown_rx.recv() => {
-                    re_log::debug!("Shutting down client without flush");
-                    break;
+        let result = client.write_messages(batch).await;
+
+        let mut state = state.lock().await;
+        let state = &mut *state;
+        let Some(spool) = state.spool.as_mut() else {
+            return Ok(());
+        };
+
+        match result {
+            Ok(_) => spool.acknowledge(),
+            Err(status) => {
+                // Everything that wasn't acknowledged is still on disk, which is all flushes need.
+                spool.rewind();
+                spool.flush();
+                for on_done in state.pending_flushes.drain(..) {
+                    on_done.send(()).ok();
                 }
+                return Err(status);
             }
         }
-    };
 
-    let disconnect_result = if let Err(status) = client.write_messages(stream).await {
-        re_log::error!(
-            "Write messages call failed: {}",
-            TonicStatusError::from(status.clone())
-        );
+        for on_done in state.pending_flushes.drain(..) {
+            on_done.send(()).ok();
+        }
 
-        // Ignore status code "Unknown" since this was observed to happen on regular Viewer shutdowns.
-        if status.code() != tonic::Code::Ok && status.code() != tonic::Code::Unknown {
-            Err(ClientConnectionFailure::FailedToSendMessages(status.code()))
-        } else {
-            Ok(())
+        if state.is_done {
+            return Ok(());
         }
-    } else {
-        Ok(())
-    };
+    }
+}
+
+fn encode_log_msg(
+    mut log_msg: LogMsg,
+    compression: Compression,
+) -> Result<ProtoLogMsg, re_log_encoding::encoder::EncodeError> {
+    // Insert the timestamp metadata into the Arrow message for accurate e2e latency measurements:
+    log_msg.insert_arrow_record_batch_metadata(
+        re_sorbet::timestamp_metadata::KEY_TIMESTAMP_SDK_IPC_ENCODE.to_owned(),
+        re_sorbet::timestamp_metadata::now_timestamp(),
+    );
+
+    re_log_encoding::protobuf_conversions::log_msg_to_proto(log_msg, compression)
+}
+
+/// Handles a command while disconnected: messages are spooled, and flushes complete as soon as
+/// everything made it to disk.
+fn spool_cmd(spool: &mut Spool, cmd: Cmd, compression: Compression) {
+    match cmd {
+        Cmd::LogMsg(log_msg) => match encode_log_msg(log_msg, compression) {
+            Ok(msg) => spool.push(&msg.encode_to_vec()),
+            Err(err) => re_log::error!("Failed to encode message, dropping it: {err}"),
+        },
+
+        Cmd::Flush { on_done } => {
+            spool.flush();
+            on_done.send(()).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Nothing is listening on that port, so the client never manages to connect.
+    const UNREACHABLE_URI: &str = "rerun+http://127.0.0.1:1/proxy";
+
+    fn wait_for_no_tasks(runtime: &runtime::Runtime) {
+        runtime.block_on(async {
+            let metrics = runtime::Handle::current().metrics();
+            let waited = tokio::time::timeout(Duration::from_secs(10), async {
+                while 0 < metrics.num_alive_tasks() {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await;
+            assert!(
+                waited.is_ok(),
+                "The client task kept retrying after the client was dropped"
+            );
+        });
+    }
+
+    #[test]
+    fn drop_while_connecting() {
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let client = Client::new_in_runtime(
+            UNREACHABLE_URI.parse().unwrap(),
+            Options::default(),
+            runtime.handle(),
+        );
+        client.send(LogMsg::BlueprintActivationCommand(
+            re_log_types::BlueprintActivationCommand {
+                blueprint_id: re_log_types::StoreId::random(
+                    re_log_types::StoreKind::Blueprint,
+                    "test",
+                ),
+                make_active: true,
+                make_default: true,
+            },
+        ));
+        assert!(matches!(
+            client.status(),
+            ClientConnectionState::Connecting { .. }
+        ));
+
+        drop(client);
+        wait_for_no_tasks(&runtime);
+    }
+
+    #[test]
+    fn drop_while_connecting_with_spool() {
+        let directory = std::env::temp_dir().join(format!(
+            "rerun_spool_drop_test_{}",
+            re_chunk::ChunkId::new()
+        ));
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let client = Client::new_in_runtime(
+            UNREACHABLE_URI.parse().unwrap(),
+            Options {
+                spool: Some(SpoolOptions::new(&directory)),
+                ..Default::default()
+            },
+            runtime.handle(),
+        );
+
+        drop(client);
+        wait_for_no_tasks(&runtime);
 
-    // Don't set error status if we already did so in the stream.
-    if !matches!(status.load(), ClientConnectionState::Disconnected(_)) {
-        status.store(ClientConnectionState::Disconnected(disconnect_result));
+        std::fs::remove_dir_all(&directory).ok();
     }
 }
@@ -1,5 +1,6 @@
 //! Server for the legacy `StoreHub` API.
 
+pub mod shared_memory;
 pub mod shutdown;
 
 use std::{collections::VecDeque, net::SocketAddr, pin::Pin};
@@ -16,8 +17,8 @@ use re_byte_size::SizeBytes;
 use re_log_encoding::codec::wire::decoder::Decode as _;
 use re_log_types::TableMsg;
 use re_protos::sdk_comms::v1alpha1::{
-    ReadTablesRequest, ReadTablesResponse, WriteMessagesRequest, WriteTableRequest,
-    WriteTableResponse,
+    GetServerTimeRequest, GetServerTimeResponse, ReadTablesRequest, ReadTablesResponse,
+    WriteMessagesRequest, WriteTableRequest, WriteTableResponse,
 };
 
 use re_protos::{
@@ -158,6 +159,18 @@ async fn serve_impl(
     shutdown: shutdown::Shutdown,
 ) -> anyhow::Result<()> {
     let tcp_listener = TcpListener::bind(addr).await?;
+
+    // SDKs running on the same machine may send us their messages through shared memory instead.
+    // The listener stops when dropped, i.e. along with the server.
+    let _shared_memory_listener = if addr.ip().is_loopback() || addr.ip().is_unspecified() {
+        let port = tcp_listener.local_addr()?.port();
+        shared_memory::SharedMemoryListener::spawn(port, message_proxy.event_tx.clone())
+            .map_err(|err| re_log::warn!("Failed to set up the shared-memory transport: {err}"))
+            .ok()
+    } else {
+        None
+    };
+
     let incoming = TcpIncoming::from(tcp_listener).with_nodelay(Some(true));
 
     let connect_addr = if addr.ip().is_loopback() || addr.ip().is_unspecified() {
@@ -1008,6 +1021,15 @@ impl message_proxy_service_server::MessageProxyService for MessageProxy {
     ) -> tonic::Result<tonic::Response<Self::ReadTablesStream>> {
         Ok(tonic::Response::new(self.new_client_table_stream().await))
     }
+
+    async fn get_server_time(
+        &self,
+        _: tonic::Request<GetServerTimeRequest>,
+    ) -> tonic::Result<tonic::Response<GetServerTimeResponse>> {
+        Ok(tonic::Response::new(GetServerTimeResponse {
+            server_time_nanos: re_log_types::Timestamp::now().nanos_since_epoch(),
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -1290,6 +1312,26 @@ mod tests {
         completion.finish();
     }
 
+    #[tokio::test]
+    async fn server_time() {
+        let (completion, addr) = setup().await;
+        let mut client = make_client(addr).await;
+
+        let before = re_log_types::Timestamp::now().nanos_since_epoch();
+        let server_time_nanos = client
+            .get_server_time(GetServerTimeRequest {})
+            .await
+            .unwrap()
+            .into_inner()
+            .server_time_nanos;
+        let after = re_log_types::Timestamp::now().nanos_since_epoch();
+
+        // Same machine, same clock.
+        assert!(before <= server_time_nanos && server_time_nanos <= after);
+
+        completion.finish();
+    }
+
     #[tokio::test]
     async fn one_producer_many_consumers() {
         let (completion, addr) = setup().await;
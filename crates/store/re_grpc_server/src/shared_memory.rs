@@ -0,0 +1,1150 @@
+//! Shared-memory transport between an SDK and a server running on the same machine.
+//!
+//! Instead of sending its messages through a localhost gRPC connection, an SDK can write them
+//! into a ring buffer backed by a memory-mapped file, which the server reads them out of.
+//!
+//! This only skips the socket, the HTTP/2 framing and the compression. Messages are still
+//! protobuf-encoded by the SDK, copied into the ring, copied out of it and decoded by the server,
+//! just like with gRPC: no Arrow buffer is ever shared between the two processes.
+//!
+//! ## Rendezvous
+//!
+//! A server listening on port `P` creates the directory returned by [`rendezvous_dir`], which
+//! only the current user can access. Each writer creates its own ring file in there, and waits
+//! for the server to attach to it. If it doesn't in time, e.g. because the directory is a leftover
+//! of a server that crashed, the writer gives up and the SDK falls back to gRPC.
+//!
+//! Both ends refuse to use a directory that belongs to another user, who could otherwise read or
+//! tamper with everything sent through it.
+//!
+//! ## Liveness
+//!
+//! The header of a ring file holds the process ids of the writer and of the reader, which each of
+//! them checks while waiting on the other. A writer whose reader died gives up, and the SDK falls
+//! back to gRPC. A reader whose writer died removes its ring file.
+//!
+//! ## Hand-off
+//!
+//! A writer that gives up on a live reader, e.g. because it stopped reading, can abandon the ring
+//! with [`SharedMemoryWriter::abandon`]: the reader stops at the position it reached at that
+//! point, and the writer gets back every message past it, to send through another transport in
+//! order.
+//!
+//! ## Layout
+//!
+//! A ring file starts with a header holding the positions and process ids of the writer and of
+//! the reader, followed by the ring itself. Messages are split into fragments of at most a quarter
+//! of the ring, each prefixed by its length and flags, and padded to 8 bytes.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use tokio::sync::mpsc;
+
+use re_log_encoding::Compression;
+use re_protos::{external::prost::Message as _, log_msg::v1alpha1::LogMsg as LogMsgProto};
+
+use crate::Event;
+
+/// Default size of the ring of a [`SharedMemoryWriter`].
+pub const DEFAULT_CAPACITY: u64 = 64 * 1024 * 1024;
+
+/// Default for how long a [`SharedMemoryWriter`] waits for the server to make room in the ring.
+pub const DEFAULT_SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Identifies ring files, and the version of their layout.
+const MAGIC: u64 = u64::from_le_bytes(*b"RRSHMRB1");
+
+// Offsets of the fields of the header. The positions of the writer and of the reader live on
+// separate cache lines, since each of them is updated by a different process.
+const OFFSET_MAGIC: usize = 0;
+const OFFSET_CAPACITY: usize = 8;
+const OFFSET_WRITE_POS: usize = 64;
+const OFFSET_WRITER_CLOSED: usize = 72;
+const OFFSET_WRITER_PID: usize = 76;
+const OFFSET_READ_POS: usize = 128;
+const OFFSET_READER_ATTACHED: usize = 136;
+const OFFSET_READER_PID: usize = 140;
+
+/// Size of the header of a ring file, before the ring itself.
+const HEADER_SIZE: usize = 192;
+
+/// Size of the prefix of each fragment: its length, then its flags.
+const FRAGMENT_HEADER_SIZE: u64 = 8;
+
+/// Set on the last fragment of a message.
+const FLAG_LAST_FRAGMENT: u32 = 1;
+
+const RING_FILE_EXTENSION: &str = "ring";
+
+/// Ring files are created with this extension, and renamed once initialized.
+const TMP_FILE_EXTENSION: &str = "tmp";
+
+/// How often the server looks for new ring files.
+const LISTENER_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Stored as the read position of an abandoned ring, see [`SharedMemoryWriter::abandon`].
+const READ_POS_ABANDONED: u64 = u64::MAX;
+
+/// How often each end of a ring checks whether the process at the other end is still alive,
+/// while waiting on it.
+const LIVENESS_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Errors that can occur when using the shared-memory transport.
+#[derive(thiserror::Error, Debug)]
+pub enum SharedMemoryError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("no server attached to the shared-memory ring within {0:?}")]
+    NotAttached(Duration),
+
+    #[error("the server detached from the shared-memory ring, or died")]
+    Disconnected,
+
+    #[error("timed out waiting for the server to read the shared-memory ring")]
+    Timeout,
+
+    #[error("the writer abandoned the shared-memory ring")]
+    Abandoned,
+
+    #[error(transparent)]
+    Encode(#[from] re_log_encoding::encoder::EncodeError),
+
+    #[error("invalid shared-memory ring: {0}")]
+    InvalidRing(&'static str),
+}
+
+/// The directory in which writers create their ring files for the server listening on `port`.
+///
+/// Uses `/dev/shm` when available, so that ring files never hit the disk.
+pub fn rendezvous_dir(port: u16) -> PathBuf {
+    let dev_shm = Path::new("/dev/shm");
+    let root = if cfg!(target_os = "linux") && dev_shm.is_dir() {
+        dev_shm.to_path_buf()
+    } else {
+        std::env::temp_dir()
+    };
+    root.join(format!("rerun-shm-{port}"))
+}
+
+/// Creates the [`rendezvous_dir`] of a server, only accessible to the current user.
+///
+/// A directory left over by a previous server is reused, unless it belongs to another user.
+fn create_rendezvous_dir(dir: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt as _, PermissionsExt as _};
+
+        match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                owned_dir_metadata(dir)?;
+                std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+            }
+            result => result?,
+        }
+
+        check_private_dir(dir)
+    }
+
+    #[cfg(not(unix))]
+    {
+        std::fs::create_dir_all(dir)
+    }
+}
+
+/// Fails unless `dir` is a directory owned by the current user, and not a symbolic link.
+#[cfg(unix)]
+fn owned_dir_metadata(dir: &Path) -> std::io::Result<std::fs::Metadata> {
+    use std::os::unix::fs::MetadataExt as _;
+
+    let metadata = std::fs::symlink_metadata(dir)?;
+
+    // SAFETY: `geteuid` has no preconditions and cannot fail.
+    #[allow(unsafe_code)]
+    let uid = unsafe { libc::geteuid() };
+
+    if metadata.is_dir() && metadata.uid() == uid {
+        Ok(metadata)
+    } else {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("{dir:?} is not a directory owned by the current user"),
+        ))
+    }
+}
+
+/// Fails unless `dir` is a directory that only the current user has access to.
+///
+/// Always succeeds on other platforms than unix, where the temporary directory is per-user.
+fn check_private_dir(dir: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt as _;
+
+        if owned_dir_metadata(dir)?.mode() & 0o077 != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!("{dir:?} is accessible to other users"),
+            ));
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = dir;
+
+    Ok(())
+}
+
+/// Whether the process with the given id is still running.
+///
+/// `0` stands for an unknown process, which is assumed to be running. The ids of processes that
+/// exited may get reused, in which case this wrongly returns `true`.
+fn is_process_alive(pid: u32) -> bool {
+    pid == 0
+        || sysinfo::System::new().refresh_process_specifics(
+            sysinfo::Pid::from_u32(pid),
+            sysinfo::ProcessRefreshKind::new(),
+        )
+}
+
+// ---
+
+/// A ring buffer in a memory-mapped file, shared between one writer and one reader.
+struct Ring {
+    mmap: memmap2::MmapRaw,
+    capacity: u64,
+}
+
+impl Ring {
+    fn create(path: &Path, capacity: u64) -> Result<Self, SharedMemoryError> {
+        let mut options = std::fs::OpenOptions::new();
+        options.read(true).write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let file = options.open(path)?;
+        file.set_len(HEADER_SIZE as u64 + capacity)?;
+
+        let ring = Self {
+            mmap: memmap2::MmapRaw::map_raw(&file)?,
+            capacity,
+        };
+        ring.header_u64(OFFSET_CAPACITY)
+            .store(capacity, Ordering::Relaxed);
+        ring.writer_pid()
+            .store(std::process::id(), Ordering::Relaxed);
+        ring.header_u64(OFFSET_MAGIC)
+            .store(MAGIC, Ordering::Release);
+
+        Ok(ring)
+    }
+
+    fn open(path: &Path) -> Result<Self, SharedMemoryError> {
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)?;
+
+        let mmap = memmap2::MmapRaw::map_raw(&file)?;
+        if mmap.len() < HEADER_SIZE {
+            return Err(SharedMemoryError::InvalidRing("file too small"));
+        }
+
+        let mut ring = Self { mmap, capacity: 0 };
+        if ring.header_u64(OFFSET_MAGIC).load(Ordering::Acquire) != MAGIC {
+            return Err(SharedMemoryError::InvalidRing("bad magic"));
+        }
+
+        let capacity = ring.header_u64(OFFSET_CAPACITY).load(Ordering::Relaxed);
+        if !capacity.is_multiple_of(8) || HEADER_SIZE as u64 + capacity != ring.mmap.len() as u64 {
+            return Err(SharedMemoryError::InvalidRing("bad capacity"));
+        }
+        ring.capacity = capacity;
+
+        Ok(ring)
+    }
+
+    fn header_u64(&self, offset: usize) -> &AtomicU64 {
+        debug_assert!(offset.is_multiple_of(8) && offset + 8 <= HEADER_SIZE);
+
+        // SAFETY: the map is page-aligned and at least `HEADER_SIZE` long, so the field is in
+        // bounds and aligned. Both processes only ever access the header through atomics.
+        #[allow(unsafe_code)]
+        unsafe {
+            &*self.mmap.as_mut_ptr().add(offset).cast::<AtomicU64>()
+        }
+    }
+
+    fn header_u32(&self, offset: usize) -> &AtomicU32 {
+        debug_assert!(offset.is_multiple_of(4) && offset + 4 <= HEADER_SIZE);
+
+        // SAFETY: see `header_u64`.
+        #[allow(unsafe_code)]
+        unsafe {
+            &*self.mmap.as_mut_ptr().add(offset).cast::<AtomicU32>()
+        }
+    }
+
+    /// How many bytes were ever written to the ring.
+    fn write_pos(&self) -> &AtomicU64 {
+        self.header_u64(OFFSET_WRITE_POS)
+    }
+
+    /// How many bytes were ever read from the ring.
+    fn read_pos(&self) -> &AtomicU64 {
+        self.header_u64(OFFSET_READ_POS)
+    }
+
+    fn writer_closed(&self) -> &AtomicU32 {
+        self.header_u32(OFFSET_WRITER_CLOSED)
+    }
+
+    fn writer_pid(&self) -> &AtomicU32 {
+        self.header_u32(OFFSET_WRITER_PID)
+    }
+
+    fn reader_attached(&self) -> &AtomicU32 {
+        self.header_u32(OFFSET_READER_ATTACHED)
+    }
+
+    fn reader_pid(&self) -> &AtomicU32 {
+        self.header_u32(OFFSET_READER_PID)
+    }
+
+    fn is_reader_attached(&self) -> bool {
+        self.reader_attached().load(Ordering::Acquire) != 0
+    }
+
+    fn is_writer_alive(&self) -> bool {
+        is_process_alive(self.writer_pid().load(Ordering::Acquire))
+    }
+
+    fn is_reader_alive(&self) -> bool {
+        is_process_alive(self.reader_pid().load(Ordering::Acquire))
+    }
+
+    /// Copies `bytes` into the ring at the absolute position `pos`, wrapping around its end.
+    fn write_at(&self, pos: u64, bytes: &[u8]) {
+        debug_assert!(bytes.len() as u64 <= self.capacity);
+
+        let offset = (pos % self.capacity) as usize;
+        let first = bytes.len().min(self.capacity as usize - offset);
+
+        // SAFETY: both copies are in bounds of the ring. The writer only ever writes to the free
+        // part of the ring, which the reader doesn't look at until the write position is published.
+        #[allow(unsafe_code)]
+        unsafe {
+            let data = self.mmap.as_mut_ptr().add(HEADER_SIZE);
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), data.add(offset), first);
+            std::ptr::copy_nonoverlapping(bytes.as_ptr().add(first), data, bytes.len() - first);
+        }
+    }
+
+    /// Copies bytes out of the ring at the absolute position `pos`, wrapping around its end.
+    fn read_at(&self, pos: u64, out: &mut [u8]) {
+        debug_assert!(out.len() as u64 <= self.capacity);
+
+        let offset = (pos % self.capacity) as usize;
+        let first = out.len().min(self.capacity as usize - offset);
+
+        // SAFETY: both copies are in bounds of the ring. The reader only ever reads the part of
+        // the ring published by the writer, which it doesn't touch until the read position moves
+        // past it.
+        #[allow(unsafe_code)]
+        unsafe {
+            let data = self.mmap.as_ptr().add(HEADER_SIZE);
+            std::ptr::copy_nonoverlapping(data.add(offset), out.as_mut_ptr(), first);
+            std::ptr::copy_nonoverlapping(data, out.as_mut_ptr().add(first), out.len() - first);
+        }
+    }
+}
+
+/// Waits for the other end of a ring: yields for a little while, then sleeps for increasingly
+/// long, up to a couple of milliseconds.
+#[derive(Default)]
+struct Backoff {
+    iterations: u32,
+}
+
+impl Backoff {
+    const NUM_YIELDS: u32 = 64;
+
+    fn wait(&mut self) {
+        if self.iterations < Self::NUM_YIELDS {
+            std::thread::yield_now();
+        } else {
+            let exponent = (self.iterations - Self::NUM_YIELDS).min(5);
+            std::thread::sleep(Duration::from_micros(50 << exponent));
+        }
+        self.iterations = self.iterations.saturating_add(1);
+    }
+
+    fn reset(&mut self) {
+        self.iterations = 0;
+    }
+}
+
+// ---
+
+/// The writing end of a shared-memory ring, used by the SDK.
+///
+/// See the [module-level documentation](self) for more information.
+pub struct SharedMemoryWriter {
+    ring: Ring,
+    path: PathBuf,
+
+    /// Our own copy of the write position, which we're the only ones to update.
+    write_pos: u64,
+
+    /// The messages written to the ring that the server may not have read yet, along with the
+    /// position right after their last fragment, in order.
+    ///
+    /// Kept around in case the ring gets abandoned, see [`Self::abandon`]. This is cheap, since
+    /// their payloads are reference-counted.
+    unread: VecDeque<(u64, re_log_types::LogMsg)>,
+
+    /// How long to wait for the server to make room in the ring.
+    send_timeout: Duration,
+}
+
+impl SharedMemoryWriter {
+    /// Creates a ring of `capacity` bytes for the server listening on `port`, and waits for it to
+    /// attach.
+    ///
+    /// Fails if no server attaches within `timeout`, e.g. because there is no server listening on
+    /// that port, or because it runs on another machine, or if the [`rendezvous_dir`] isn't
+    /// private to the current user.
+    pub fn connect(port: u16, capacity: u64, timeout: Duration) -> Result<Self, SharedMemoryError> {
+        static NEXT_RING_ID: AtomicU64 = AtomicU64::new(0);
+
+        re_tracing::profile_function!();
+
+        let deadline = Instant::now() + timeout;
+        let capacity = capacity.max(4096).next_multiple_of(8);
+
+        // The server may still be starting up, e.g. right after spawning a viewer.
+        let dir = rendezvous_dir(port);
+        while !dir.is_dir() {
+            if deadline <= Instant::now() {
+                return Err(SharedMemoryError::NotAttached(timeout));
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        check_private_dir(&dir)?;
+
+        // Created under a temporary name, so that the server never sees a half-initialized ring.
+        let name = format!(
+            "{}-{}",
+            std::process::id(),
+            NEXT_RING_ID.fetch_add(1, Ordering::Relaxed)
+        );
+        let tmp_path = dir.join(format!("{name}.{TMP_FILE_EXTENSION}"));
+        let path = dir.join(format!("{name}.{RING_FILE_EXTENSION}"));
+        let ring = Ring::create(&tmp_path, capacity)?;
+        std::fs::rename(&tmp_path, &path)?;
+
+        let writer = Self {
+            ring,
+            path,
+            write_pos: 0,
+            unread: VecDeque::new(),
+            send_timeout: DEFAULT_SEND_TIMEOUT,
+        };
+
+        while !writer.ring.is_reader_attached() {
+            if deadline <= Instant::now() {
+                // Dropping the writer removes the ring file.
+                return Err(SharedMemoryError::NotAttached(timeout));
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        re_log::debug!(path = ?writer.path, "Connected through shared memory");
+
+        Ok(writer)
+    }
+
+    /// How long [`Self::send`] waits for the server to make room in the ring, before failing
+    /// with [`SharedMemoryError::Timeout`].
+    ///
+    /// Defaults to [`DEFAULT_SEND_TIMEOUT`].
+    #[must_use]
+    pub fn with_send_timeout(mut self, send_timeout: Duration) -> Self {
+        self.send_timeout = send_timeout;
+        self
+    }
+
+    /// Encodes a message and writes it to the ring, waiting for the server to make room if needed.
+    ///
+    /// Fails if the server goes away, or if it doesn't make room in time, see
+    /// [`Self::with_send_timeout`]. The ring is unusable from then on, and the message never
+    /// reaches the server: see [`Self::abandon`] for what to do with the previous ones.
+    pub fn send(&mut self, msg: re_log_types::LogMsg) -> Result<(), SharedMemoryError> {
+        re_tracing::profile_function!();
+
+        // Compressing is pointless when nothing goes over the wire.
+        let proto =
+            re_log_encoding::protobuf_conversions::log_msg_to_proto(msg.clone(), Compression::Off)?;
+        self.write_message(&proto.encode_to_vec())?;
+
+        self.unread.push_back((self.write_pos, msg));
+        let read_pos = self.ring.read_pos().load(Ordering::Acquire);
+        while self
+            .unread
+            .front()
+            .is_some_and(|(end_pos, _)| *end_pos <= read_pos)
+        {
+            self.unread.pop_front();
+        }
+
+        Ok(())
+    }
+
+    /// Stops the server from reading any further, and returns the messages it hasn't fully read
+    /// by then, in order.
+    ///
+    /// Everything sent before those has been, or is being, handed over to the server, so sending
+    /// them through another transport next preserves the order of messages.
+    pub fn abandon(mut self) -> Vec<re_log_types::LogMsg> {
+        // The reader only ever moves its position forward by compare-and-swapping it, so it
+        // either consumed a fragment before this, or it never will.
+        let read_pos = self
+            .ring
+            .read_pos()
+            .swap(READ_POS_ABANDONED, Ordering::AcqRel);
+
+        std::mem::take(&mut self.unread)
+            .into_iter()
+            .filter(|(end_pos, _)| read_pos < *end_pos)
+            .map(|(_, msg)| msg)
+            .collect()
+    }
+
+    /// Waits until the server has read everything written so far.
+    pub fn flush_blocking(&self, timeout: Duration) -> Result<(), SharedMemoryError> {
+        self.wait_for_reader(timeout, || {
+            self.write_pos <= self.ring.read_pos().load(Ordering::Acquire)
+        })
+    }
+
+    /// Waits until `is_done` returns `true`, failing if the reader goes away or if it takes
+    /// longer than `timeout`.
+    fn wait_for_reader(
+        &self,
+        timeout: Duration,
+        is_done: impl Fn() -> bool,
+    ) -> Result<(), SharedMemoryError> {
+        let start = Instant::now();
+        let mut last_liveness_check = start;
+
+        let mut backoff = Backoff::default();
+        while !is_done() {
+            if !self.ring.is_reader_attached() {
+                return Err(SharedMemoryError::Disconnected);
+            }
+
+            let elapsed = start.elapsed();
+            if timeout <= elapsed {
+                return Err(SharedMemoryError::Timeout);
+            }
+            if LIVENESS_CHECK_INTERVAL <= last_liveness_check.elapsed() {
+                if !self.ring.is_reader_alive() {
+                    return Err(SharedMemoryError::Disconnected);
+                }
+                last_liveness_check = Instant::now();
+            }
+
+            backoff.wait();
+        }
+
+        Ok(())
+    }
+
+    fn write_message(&mut self, bytes: &[u8]) -> Result<(), SharedMemoryError> {
+        if bytes.is_empty() {
+            return self.write_fragment(bytes, true);
+        }
+
+        let max_fragment_len = (self.ring.capacity / 4).min(u32::MAX as u64) as usize;
+        let mut fragments = bytes.chunks(max_fragment_len).peekable();
+        while let Some(fragment) = fragments.next() {
+            self.write_fragment(fragment, fragments.peek().is_none())?;
+        }
+
+        Ok(())
+    }
+
+    fn write_fragment(&mut self, fragment: &[u8], is_last: bool) -> Result<(), SharedMemoryError> {
+        let len = fragment.len() as u64;
+        let size = FRAGMENT_HEADER_SIZE + len.next_multiple_of(8);
+
+        self.wait_for_reader(self.send_timeout, || {
+            size <= self.ring.capacity
+                - (self.write_pos - self.ring.read_pos().load(Ordering::Acquire))
+        })?;
+
+        let flags = if is_last { FLAG_LAST_FRAGMENT } else { 0 };
+        let mut header = [0; FRAGMENT_HEADER_SIZE as usize];
+        header[..4].copy_from_slice(&(len as u32).to_le_bytes());
+        header[4..].copy_from_slice(&flags.to_le_bytes());
+
+        self.ring.write_at(self.write_pos, &header);
+        self.ring
+            .write_at(self.write_pos + FRAGMENT_HEADER_SIZE, fragment);
+
+        self.write_pos += size;
+        self.ring
+            .write_pos()
+            .store(self.write_pos, Ordering::Release);
+
+        Ok(())
+    }
+}
+
+impl Drop for SharedMemoryWriter {
+    fn drop(&mut self) {
+        // The server removes the ring file once it has read everything that's left, unless it
+        // isn't there anymore.
+        self.ring.writer_closed().store(1, Ordering::Release);
+        if !self.ring.is_reader_attached() || !self.ring.is_reader_alive() {
+            std::fs::remove_file(&self.path).ok();
+        }
+    }
+}
+
+// ---
+
+/// The reading end of a shared-memory ring, used by the server.
+struct SharedMemoryReader {
+    ring: Ring,
+    path: PathBuf,
+
+    /// Our own copy of the read position, which only the writer may update besides us, to
+    /// abandon the ring.
+    read_pos: u64,
+
+    /// The fragments of the message being read so far.
+    pending: Vec<u8>,
+}
+
+impl SharedMemoryReader {
+    /// Opens a ring file and attaches to it, unless another reader that is still alive already
+    /// did, or the writer abandoned it.
+    fn attach(path: PathBuf) -> Result<Option<Self>, SharedMemoryError> {
+        let ring = Ring::open(&path)?;
+        if ring.read_pos().load(Ordering::Acquire) == READ_POS_ABANDONED {
+            return Err(SharedMemoryError::Abandoned);
+        }
+
+        // Takes over from readers that died, e.g. when a server gets restarted on the same port.
+        let is_attached = ring
+            .reader_attached()
+            .compare_exchange(0, 1, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+            || !ring.is_reader_alive();
+        if !is_attached {
+            return Ok(None);
+        }
+        ring.reader_pid()
+            .store(std::process::id(), Ordering::Release);
+
+        let read_pos = ring.read_pos().load(Ordering::Acquire);
+
+        Ok(Some(Self {
+            ring,
+            path,
+            read_pos,
+            pending: Vec::new(),
+        }))
+    }
+
+    fn is_writer_closed(&self) -> bool {
+        self.ring.writer_closed().load(Ordering::Acquire) != 0
+    }
+
+    fn is_writer_alive(&self) -> bool {
+        self.ring.is_writer_alive()
+    }
+
+    /// Returns the next message, if it has been fully written yet.
+    ///
+    /// Fails with [`SharedMemoryError::Abandoned`] once the writer abandoned the ring, dropping
+    /// whatever was read of the current message.
+    fn try_read_message(&mut self) -> Result<Option<Vec<u8>>, SharedMemoryError> {
+        loop {
+            if self.read_pos == self.ring.write_pos().load(Ordering::Acquire) {
+                return Ok(None);
+            }
+
+            let mut header = [0; FRAGMENT_HEADER_SIZE as usize];
+            self.ring.read_at(self.read_pos, &mut header);
+            let [l0, l1, l2, l3, f0, f1, f2, f3] = header;
+            let len = u32::from_le_bytes([l0, l1, l2, l3]) as usize;
+            let flags = u32::from_le_bytes([f0, f1, f2, f3]);
+
+            if self.ring.capacity < FRAGMENT_HEADER_SIZE + len as u64 {
+                return Err(SharedMemoryError::InvalidRing(
+                    "fragment larger than the ring",
+                ));
+            }
+
+            let start = self.pending.len();
+            self.pending.resize(start + len, 0);
+            self.ring.read_at(
+                self.read_pos + FRAGMENT_HEADER_SIZE,
+                &mut self.pending[start..],
+            );
+
+            let next_read_pos =
+                self.read_pos + FRAGMENT_HEADER_SIZE + (len as u64).next_multiple_of(8);
+            if self
+                .ring
+                .read_pos()
+                .compare_exchange(
+                    self.read_pos,
+                    next_read_pos,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                )
+                .is_err()
+            {
+                self.pending.clear();
+                return Err(SharedMemoryError::Abandoned);
+            }
+            self.read_pos = next_read_pos;
+
+            if flags & FLAG_LAST_FRAGMENT != 0 {
+                return Ok(Some(std::mem::take(&mut self.pending)));
+            }
+        }
+    }
+}
+
+impl Drop for SharedMemoryReader {
+    fn drop(&mut self) {
+        self.ring.reader_attached().store(0, Ordering::Release);
+        std::fs::remove_file(&self.path).ok();
+    }
+}
+
+// ---
+
+/// Attaches to the rings created in the [`rendezvous_dir`] of a server, and forwards their
+/// messages to it as if they had been sent through `WriteMessages`.
+///
+/// Stops, and removes the rendezvous directory, when dropped.
+pub(crate) struct SharedMemoryListener {
+    dir: PathBuf,
+    stop: Arc<AtomicBool>,
+}
+
+impl SharedMemoryListener {
+    pub(crate) fn spawn(port: u16, event_tx: mpsc::Sender<Event>) -> std::io::Result<Self> {
+        let dir = rendezvous_dir(port);
+        create_rendezvous_dir(&dir)?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        std::thread::Builder::new()
+            .name("shm_listener".to_owned())
+            .spawn({
+                let dir = dir.clone();
+                let stop = stop.clone();
+                move || listen(&dir, &event_tx, &stop)
+            })?;
+
+        re_log::debug!(?dir, "Listening for shared-memory connections");
+
+        Ok(Self { dir, stop })
+    }
+}
+
+impl Drop for SharedMemoryListener {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        std::fs::remove_dir_all(&self.dir).ok();
+    }
+}
+
+fn listen(dir: &Path, event_tx: &mpsc::Sender<Event>, stop: &Arc<AtomicBool>) {
+    let mut known_paths = HashSet::new();
+
+    while !stop.load(Ordering::Relaxed) {
+        known_paths.retain(|path: &PathBuf| path.exists());
+
+        for path in std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+        {
+            if path
+                .extension()
+                .is_some_and(|extension| extension == TMP_FILE_EXTENSION)
+            {
+                remove_if_writer_died(&path);
+                continue;
+            }
+
+            if path
+                .extension()
+                .is_none_or(|extension| extension != RING_FILE_EXTENSION)
+                || !known_paths.insert(path.clone())
+            {
+                continue;
+            }
+
+            match SharedMemoryReader::attach(path.clone()) {
+                Ok(Some(reader)) => {
+                    re_log::debug!(?path, "New shared-memory connection");
+
+                    let event_tx = event_tx.clone();
+                    let stop = stop.clone();
+                    if let Err(err) = std::thread::Builder::new()
+                        .name("shm_reader".to_owned())
+                        .spawn(move || read_ring(reader, &event_tx, &stop))
+                    {
+                        re_log::error!("Failed to spawn shared-memory reader: {err}");
+                    }
+                }
+                Ok(None) | Err(SharedMemoryError::Abandoned) => {}
+                Err(err) => {
+                    re_log::warn!(?path, "Failed to attach to shared-memory ring: {err}");
+                }
+            }
+        }
+
+        std::thread::sleep(LISTENER_POLL_INTERVAL);
+    }
+}
+
+/// Removes a ring file that is still being created, if the process creating it died.
+fn remove_if_writer_died(path: &Path) {
+    // Named after the process id of the writer, see `SharedMemoryWriter::connect`.
+    let pid = path
+        .file_stem()
+        .and_then(|stem| stem.to_str()?.split('-').next()?.parse().ok());
+    if pid.is_some_and(|pid| !is_process_alive(pid)) {
+        re_log::debug!(?path, "Removing the ring of a dead writer");
+        std::fs::remove_file(path).ok();
+    }
+}
+
+fn read_ring(mut reader: SharedMemoryReader, event_tx: &mpsc::Sender<Event>, stop: &AtomicBool) {
+    let mut backoff = Backoff::default();
+    let mut last_liveness_check = Instant::now();
+
+    while !stop.load(Ordering::Relaxed) {
+        // Checked before reading, so that nothing written before closing is missed.
+        let mut is_writer_closed = reader.is_writer_closed();
+        if LIVENESS_CHECK_INTERVAL <= last_liveness_check.elapsed() {
+            if !reader.is_writer_alive() {
+                re_log::debug!(path = ?reader.path, "Shared-memory writer died");
+                is_writer_closed = true;
+            }
+            last_liveness_check = Instant::now();
+        }
+
+        match reader.try_read_message() {
+            Ok(Some(bytes)) => {
+                backoff.reset();
+
+                match LogMsgProto::decode(bytes.as_slice()) {
+                    Ok(msg) => {
+                        if event_tx.blocking_send(Event::Message(msg)).is_err() {
+                            re_log::debug!("shut down, closing shared-memory reader");
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        re_log::error!(
+                            "dropping shared-memory message due to failed decode: {err}"
+                        );
+                    }
+                }
+            }
+
+            Ok(None) if is_writer_closed => break,
+
+            Ok(None) => backoff.wait(),
+
+            Err(SharedMemoryError::Abandoned) => {
+                re_log::debug!(path = ?reader.path, "Shared-memory writer handed off");
+                break;
+            }
+
+            Err(err) => {
+                re_log::error!(path = ?reader.path, "closing shared-memory reader: {err}");
+                break;
+            }
+        }
+    }
+
+    re_log::debug!(path = ?reader.path, "Shared-memory connection closed");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_writer(path: &Path) -> SharedMemoryWriter {
+        SharedMemoryWriter {
+            ring: Ring::create(path, 4096).unwrap(),
+            path: path.to_owned(),
+            write_pos: 0,
+            unread: VecDeque::new(),
+            send_timeout: DEFAULT_SEND_TIMEOUT,
+        }
+    }
+
+    fn test_msg() -> re_log_types::LogMsg {
+        re_log_types::LogMsg::BlueprintActivationCommand(
+            re_log_types::BlueprintActivationCommand::make_active(re_log_types::StoreId::random(
+                re_log_types::StoreKind::Blueprint,
+                "test_app",
+            )),
+        )
+    }
+
+    /// The id of a process that exited.
+    fn dead_pid() -> u32 {
+        // Runs no test at all.
+        let mut child = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", "no_such_test"])
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        pid
+    }
+
+    #[test]
+    fn roundtrip_through_ring() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("test.{RING_FILE_EXTENSION}"));
+
+        let mut writer = create_writer(&path);
+        let mut reader = SharedMemoryReader::attach(path.clone()).unwrap().unwrap();
+        assert!(SharedMemoryReader::attach(path).unwrap().is_none());
+
+        // Messages up to about as large as the ring itself, so that they're fragmented and wrap
+        // around its end.
+        let messages = (0..100)
+            .map(|i| (0..i * 10).flat_map(u32::to_le_bytes).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+
+        let num_messages = messages.len();
+        let reader_thread = std::thread::spawn(move || {
+            let mut received = Vec::new();
+            while received.len() < num_messages {
+                match reader.try_read_message().unwrap() {
+                    Some(msg) => received.push(msg),
+                    None => std::thread::yield_now(),
+                }
+            }
+            received
+        });
+
+        for msg in &messages {
+            writer.write_message(msg).unwrap();
+        }
+        writer.flush_blocking(Duration::from_secs(10)).unwrap();
+
+        similar_asserts::assert_eq!(messages, reader_thread.join().unwrap());
+    }
+
+    #[test]
+    fn dead_reader() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("test.{RING_FILE_EXTENSION}"));
+
+        let mut writer = create_writer(&path);
+        let reader = SharedMemoryReader::attach(path.clone()).unwrap().unwrap();
+        writer
+            .ring
+            .reader_pid()
+            .store(dead_pid(), Ordering::Release);
+
+        // The reader never reads anything, so the ring fills up.
+        let result = (0..100).try_for_each(|_| writer.write_message(&[0; 1000]));
+        assert!(
+            matches!(result, Err(SharedMemoryError::Disconnected)),
+            "{result:?}"
+        );
+        assert!(matches!(
+            writer.flush_blocking(Duration::from_secs(10)),
+            Err(SharedMemoryError::Disconnected)
+        ));
+
+        // The reader dies without detaching, and a new one takes over.
+        let _dead_reader = std::mem::ManuallyDrop::new(reader);
+        let reader = SharedMemoryReader::attach(path.clone()).unwrap().unwrap();
+        let _dead_reader = std::mem::ManuallyDrop::new(reader);
+
+        writer
+            .ring
+            .reader_pid()
+            .store(dead_pid(), Ordering::Release);
+        drop(writer);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn abandon() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("test.{RING_FILE_EXTENSION}"));
+
+        let mut writer = create_writer(&path);
+        let mut reader = SharedMemoryReader::attach(path.clone()).unwrap().unwrap();
+
+        let messages = (0..4).map(|_| test_msg()).collect::<Vec<_>>();
+        for msg in &messages {
+            writer.send(msg.clone()).unwrap();
+        }
+        assert!(reader.try_read_message().unwrap().is_some());
+        assert!(reader.try_read_message().unwrap().is_some());
+
+        // Whatever the reader didn't get to is handed back, in order.
+        let unread = writer.abandon();
+        assert_eq!(
+            messages[2..]
+                .iter()
+                .map(|msg| msg.store_id())
+                .collect::<Vec<_>>(),
+            unread.iter().map(|msg| msg.store_id()).collect::<Vec<_>>()
+        );
+
+        // And the reader doesn't get it anymore.
+        assert!(matches!(
+            reader.try_read_message(),
+            Err(SharedMemoryError::Abandoned)
+        ));
+        assert!(matches!(
+            SharedMemoryReader::attach(path),
+            Err(SharedMemoryError::Abandoned)
+        ));
+    }
+
+    #[test]
+    fn abandon_mid_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("test.{RING_FILE_EXTENSION}"));
+
+        let mut writer = create_writer(&path);
+        let mut reader = SharedMemoryReader::attach(path).unwrap().unwrap();
+
+        // The reader gets the first fragment of a message, but not the last one.
+        writer.write_fragment(&[1; 1000], false).unwrap();
+        assert!(reader.try_read_message().unwrap().is_none());
+        writer.write_fragment(&[2; 1000], true).unwrap();
+        assert!(writer.abandon().is_empty());
+
+        // What was read of the message is dropped.
+        assert!(matches!(
+            reader.try_read_message(),
+            Err(SharedMemoryError::Abandoned)
+        ));
+        assert!(reader.pending.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rendezvous_dir_permissions() {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("rerun-shm-0");
+
+        create_rendezvous_dir(&dir).unwrap();
+        assert_eq!(0o700, mode(&dir));
+        check_private_dir(&dir).unwrap();
+
+        // Leftovers are reused, but made private again.
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(check_private_dir(&dir).is_err());
+        create_rendezvous_dir(&dir).unwrap();
+        assert_eq!(0o700, mode(&dir));
+
+        // Links could point anywhere.
+        let link = root.path().join("rerun-shm-1");
+        std::os::unix::fs::symlink(&dir, &link).unwrap();
+        assert!(check_private_dir(&link).is_err());
+        assert!(create_rendezvous_dir(&link).is_err());
+
+        // Ring files are private too.
+        let path = dir.join(format!("test.{RING_FILE_EXTENSION}"));
+        let _writer = create_writer(&path);
+        assert_eq!(0o600, mode(&path));
+    }
+
+    #[test]
+    fn send_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("test.{RING_FILE_EXTENSION}"));
+
+        let mut writer = create_writer(&path).with_send_timeout(Duration::from_millis(100));
+        let _reader = SharedMemoryReader::attach(path).unwrap().unwrap();
+
+        // The reader is alive, but never reads anything.
+        let result = (0..100).try_for_each(|_| writer.write_message(&[0; 1000]));
+        assert!(
+            matches!(result, Err(SharedMemoryError::Timeout)),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn dead_writer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("test.{RING_FILE_EXTENSION}"));
+
+        let mut writer = create_writer(&path);
+        writer
+            .write_message(&LogMsgProto::default().encode_to_vec())
+            .unwrap();
+        writer
+            .ring
+            .writer_pid()
+            .store(dead_pid(), Ordering::Release);
+        // The writer dies without closing the ring.
+        let _dead_writer = std::mem::ManuallyDrop::new(writer);
+
+        let reader = SharedMemoryReader::attach(path.clone()).unwrap().unwrap();
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let stop = AtomicBool::new(false);
+        read_ring(reader, &event_tx, &stop);
+
+        // What was written before dying still makes it through.
+        assert!(matches!(event_rx.try_recv(), Ok(Event::Message(_))));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn dead_writer_tmp_file() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let dead = dir
+            .path()
+            .join(format!("{}-0.{TMP_FILE_EXTENSION}", dead_pid()));
+        std::fs::write(&dead, []).unwrap();
+        remove_if_writer_died(&dead);
+        assert!(!dead.exists());
+
+        let alive = dir
+            .path()
+            .join(format!("{}-0.{TMP_FILE_EXTENSION}", std::process::id()));
+        std::fs::write(&alive, []).unwrap();
+        remove_if_writer_died(&alive);
+        assert!(alive.exists());
+    }
+}
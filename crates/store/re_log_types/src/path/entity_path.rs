@@ -119,6 +119,16 @@ impl EntityPath {
         Self::from(vec![EntityPathPart::properties()])
     }
 
+    /// The recording properties describing the environment the recording was made in
+    /// (git state, host, versions, …), if the SDK was asked to collect them.
+    #[inline]
+    pub fn environment_properties() -> Self {
+        Self::from(vec![
+            EntityPathPart::properties(),
+            EntityPathPart::new("environment"),
+        ])
+    }
+
     /// Returns `true` if the [`EntityPath`] belongs to a reserved namespace.
     ///
     /// Returns `true` iff the root entity starts with `__`.
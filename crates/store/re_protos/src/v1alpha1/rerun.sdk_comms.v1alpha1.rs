@@ -111,6 +111,34 @@ impl ::prost::Name for ReadTablesResponse {
         "/rerun.sdk_comms.v1alpha1.ReadTablesResponse".into()
     }
 }
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetServerTimeRequest {}
+impl ::prost::Name for GetServerTimeRequest {
+    const NAME: &'static str = "GetServerTimeRequest";
+    const PACKAGE: &'static str = "rerun.sdk_comms.v1alpha1";
+    fn full_name() -> ::prost::alloc::string::String {
+        "rerun.sdk_comms.v1alpha1.GetServerTimeRequest".into()
+    }
+    fn type_url() -> ::prost::alloc::string::String {
+        "/rerun.sdk_comms.v1alpha1.GetServerTimeRequest".into()
+    }
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetServerTimeResponse {
+    /// Nanoseconds since the Unix epoch, according to the server's clock.
+    #[prost(int64, tag = "1")]
+    pub server_time_nanos: i64,
+}
+impl ::prost::Name for GetServerTimeResponse {
+    const NAME: &'static str = "GetServerTimeResponse";
+    const PACKAGE: &'static str = "rerun.sdk_comms.v1alpha1";
+    fn full_name() -> ::prost::alloc::string::String {
+        "rerun.sdk_comms.v1alpha1.GetServerTimeResponse".into()
+    }
+    fn type_url() -> ::prost::alloc::string::String {
+        "/rerun.sdk_comms.v1alpha1.GetServerTimeResponse".into()
+    }
+}
 /// Generated client implementations.
 pub mod message_proxy_service_client {
     #![allow(
@@ -281,6 +309,28 @@ pub mod message_proxy_service_client {
             ));
             self.inner.server_streaming(req, path, codec).await
         }
+        /// Returns the current time according to the server's clock.
+        ///
+        /// Used by SDKs running on other machines or processes to align their clocks with the server's.
+        pub async fn get_server_time(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetServerTimeRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetServerTimeResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rerun.sdk_comms.v1alpha1.MessageProxyService/GetServerTime",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rerun.sdk_comms.v1alpha1.MessageProxyService",
+                "GetServerTime",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -324,6 +374,13 @@ pub mod message_proxy_service_server {
             &self,
             request: tonic::Request<super::ReadTablesRequest>,
         ) -> std::result::Result<tonic::Response<Self::ReadTablesStream>, tonic::Status>;
+        /// Returns the current time according to the server's clock.
+        ///
+        /// Used by SDKs running on other machines or processes to align their clocks with the server's.
+        async fn get_server_time(
+            &self,
+            request: tonic::Request<super::GetServerTimeRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetServerTimeResponse>, tonic::Status>;
     }
     /// Simple buffer for messages between SDKs and viewers.
     ///
@@ -578,6 +635,48 @@ pub mod message_proxy_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/rerun.sdk_comms.v1alpha1.MessageProxyService/GetServerTime" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetServerTimeSvc<T: MessageProxyService>(pub Arc<T>);
+                    impl<T: MessageProxyService>
+                        tonic::server::UnaryService<super::GetServerTimeRequest>
+                        for GetServerTimeSvc<T>
+                    {
+                        type Response = super::GetServerTimeResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetServerTimeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as MessageProxyService>::get_server_time(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetServerTimeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => Box::pin(async move {
                     let mut response = http::Response::new(tonic::body::Body::default());
                     let headers = response.headers_mut();
@@ -9,7 +9,10 @@ use arrow::{
 use re_log_types::EntityPath;
 use re_types_core::ChunkId;
 
-use crate::{ChunkSchema, RowIdColumnDescriptor, SorbetBatch, SorbetError, WrongDatatypeError};
+use crate::{
+    ArrowFieldMetadata, ChunkSchema, RowIdColumnDescriptor, SorbetBatch, SorbetError,
+    WrongDatatypeError,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum MismatchedChunkSchemaError {
@@ -109,6 +112,20 @@ impl ChunkBatch {
             sorbet_batch: self.sorbet_batch.drop_all_rows(),
         }
     }
+
+    /// Returns self but with extra metadata added to the fields at the given column indices.
+    ///
+    /// See [`SorbetBatch::with_extra_field_metadata`].
+    #[must_use]
+    pub fn with_extra_field_metadata(
+        self,
+        extra_metadata: impl IntoIterator<Item = (usize, ArrowFieldMetadata)>,
+    ) -> Self {
+        Self {
+            schema: self.schema,
+            sorbet_batch: self.sorbet_batch.with_extra_field_metadata(extra_metadata),
+        }
+    }
 }
 
 impl std::fmt::Display for ChunkBatch {
@@ -12,8 +12,8 @@ use itertools::Itertools as _;
 use re_log::ResultExt as _;
 
 use crate::{
-    ArrowBatchMetadata, ColumnDescriptor, ColumnDescriptorRef, ComponentColumnDescriptor,
-    IndexColumnDescriptor, SorbetError, SorbetSchema,
+    ArrowBatchMetadata, ArrowFieldMetadata, ColumnDescriptor, ColumnDescriptorRef,
+    ComponentColumnDescriptor, IndexColumnDescriptor, SorbetError, SorbetSchema,
 };
 
 /// Any rerun-compatible [`ArrowRecordBatch`].
@@ -57,6 +57,54 @@ impl SorbetBatch {
             batch: self.batch.slice(0, 0),
         }
     }
+
+    /// Returns self but with extra metadata added to the fields at the given column indices.
+    ///
+    /// This is meant for information that is not part of the Rerun schema (e.g. column statistics):
+    /// the parsed [`SorbetSchema`] is left untouched.
+    #[must_use]
+    pub fn with_extra_field_metadata(
+        self,
+        extra_metadata: impl IntoIterator<Item = (usize, ArrowFieldMetadata)>,
+    ) -> Self {
+        let mut fields = self
+            .batch
+            .schema_ref()
+            .fields()
+            .iter()
+            .map(|field| field.as_ref().clone())
+            .collect_vec();
+
+        let mut modified = false;
+        for (column_index, metadata) in extra_metadata {
+            if let Some(field) = fields.get_mut(column_index) {
+                field.metadata_mut().extend(metadata);
+                modified = true;
+            }
+        }
+
+        if !modified {
+            return self;
+        }
+
+        let arrow_schema = Arc::new(ArrowSchema::new_with_metadata(
+            fields,
+            self.batch.schema_ref().metadata.clone(),
+        ));
+
+        match self
+            .batch
+            .clone()
+            .with_schema(arrow_schema)
+            .ok_or_log_error()
+        {
+            Some(batch) => Self {
+                schema: self.schema,
+                batch,
+            },
+            None => self,
+        }
+    }
 }
 
 impl SorbetBatch {
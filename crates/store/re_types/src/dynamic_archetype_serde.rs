@@ -0,0 +1,1610 @@
+//! Converts arbitrary [`serde::Serialize`] values into a [`DynamicArchetype`].
+//!
+//! The Arrow datatypes are derived from the [`serde::Deserialize`] implementation of the type
+//! rather than from the value being logged, so that they don't change from one value to the next.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use arrow::{
+    array::{
+        ArrayRef, BinaryArray, BooleanArray, FixedSizeListArray, Float64Array, Int64Array,
+        ListArray, NullArray, StringArray, StructArray, UInt64Array, new_empty_array,
+    },
+    buffer::{NullBuffer, OffsetBuffer},
+    datatypes::{DataType, Field},
+    error::ArrowError,
+};
+use serde::{
+    de::{self, DeserializeOwned, IntoDeserializer as _},
+    ser::{self, Serialize},
+};
+
+use crate::DynamicArchetype;
+
+/// Errors that can occur when converting a [`serde::Serialize`] value, see
+/// [`DynamicArchetype::from_serde`].
+#[derive(thiserror::Error, Debug)]
+pub enum SerdeConversionError {
+    /// The value failed to serialize itself.
+    #[error("{0}")]
+    Custom(String),
+
+    /// No Arrow datatype can be derived from the type, e.g. because it is recursive.
+    #[error("Cannot derive an Arrow datatype from the type: {0}")]
+    Schema(String),
+
+    /// The value doesn't match the datatype derived from its type, e.g. because its
+    /// [`serde::Serialize`] and [`serde::Deserialize`] implementations disagree.
+    #[error("Cannot convert {value} to {datatype}")]
+    Mismatch {
+        /// The datatype derived from the type.
+        datatype: DataType,
+
+        /// A description of the offending value.
+        value: String,
+    },
+
+    /// Arrow rejected the resulting arrays.
+    #[error(transparent)]
+    Arrow(#[from] ArrowError),
+}
+
+impl ser::Error for SerdeConversionError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+impl de::Error for SerdeConversionError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Schema(msg.to_string())
+    }
+}
+
+impl DynamicArchetype {
+    /// Converts any [`serde::Serialize`] value into a dynamic archetype.
+    ///
+    /// Each field of a struct becomes a component of the same name, tagged with the name of the
+    /// struct as its archetype. Any other value becomes a single `value` component.
+    ///
+    /// Types are mapped to Arrow as follows:
+    /// * booleans, strings and bytes map to their Arrow equivalents,
+    /// * signed and unsigned integers map to `Int64` and `UInt64`, floats to `Float64`,
+    /// * nested structs map to Arrow structs,
+    /// * tuples map to fixed-size lists if all their elements have the same type, and to structs
+    ///   with fields named `0`, `1`, … otherwise,
+    /// * sequences map to Arrow lists, except at the top-level where each element becomes an
+    ///   instance of the component,
+    /// * maps map to lists of `key`/`value` structs,
+    /// * enums whose variants are all unit variants map to the name of the variant, other enums to
+    ///   a struct with a `variant` field holding that name, and a field per non-unit variant
+    ///   holding its data,
+    /// * unit values map to nulls, and so do `None`s, except at the top-level where they map to
+    ///   empty batches, clearing the components.
+    ///
+    /// The datatypes are derived from the [`serde::Deserialize`] implementation of the type,
+    /// which therefore has to be the counterpart of its [`serde::Serialize`] implementation, and
+    /// must accept placeholder values such as zeros and empty strings. Recursive types, and types
+    /// that need a self-describing format (e.g. untagged enums) are not supported.
+    pub fn from_serde<T: Serialize + DeserializeOwned>(
+        value: &T,
+    ) -> Result<Self, SerdeConversionError> {
+        let schema = Schema::of::<T>()?;
+        let value = value.serialize(ValueSerializer)?;
+
+        match (schema.struct_name, &schema.datatype, value) {
+            (Some(name), DataType::Struct(fields), value @ (Value::Struct(_) | Value::Null)) => {
+                // A `None` clears all the components.
+                let mut values = match value {
+                    Value::Struct(values) => values,
+                    _ => Vec::new(),
+                };
+                if let Some((name, _)) = values.iter().find(|(name, _)| fields.find(name).is_none())
+                {
+                    return Err(SerdeConversionError::Mismatch {
+                        datatype: schema.datatype.clone(),
+                        value: format!("a struct with a field named {name:?}"),
+                    });
+                }
+
+                fields.iter().try_fold(Self::new(name), |archetype, field| {
+                    let value = values
+                        .iter()
+                        .position(|(name, _)| *name == field.name().as_str())
+                        .map_or(Value::Null, |index| values.swap_remove(index).1);
+                    Ok(archetype.with_component_from_data(
+                        field.name(),
+                        component_array(&value, field.data_type())?,
+                    ))
+                })
+            }
+
+            (_, datatype, value) => Ok(Self::new_without_archetype()
+                .with_component_from_data("value", component_array(&value, datatype)?)),
+        }
+    }
+}
+
+/// Sequences are logged as batches of instances, `None`s as empty batches, and anything else as
+/// a single instance.
+fn component_array(value: &Value, datatype: &DataType) -> Result<ArrayRef, SerdeConversionError> {
+    match (value, datatype) {
+        (Value::Null, DataType::List(item)) => Ok(new_empty_array(item.data_type())),
+        (Value::Null, datatype) => Ok(new_empty_array(datatype)),
+        (Value::List(items), DataType::List(item)) => {
+            to_array(&items.iter().collect::<Vec<_>>(), item.data_type())
+        }
+        (value, datatype) => to_array(&[value], datatype),
+    }
+}
+
+// ---
+
+/// The Arrow datatype of a type, derived from its [`serde::Deserialize`] implementation.
+struct Schema {
+    /// The name of the type, if it is a struct.
+    struct_name: Option<&'static str>,
+
+    datatype: DataType,
+}
+
+impl Schema {
+    fn of<T: DeserializeOwned>() -> Result<Self, SerdeConversionError> {
+        let mut state = TracerState::default();
+
+        // Each pass explores at least one more variant of the enums it runs into, until there are
+        // none left to explore.
+        let mut num_explored = 0;
+        loop {
+            let mut format = Format::Null;
+            T::deserialize(TypeTracer {
+                state: &mut state,
+                out: &mut format,
+                is_root: true,
+            })?;
+
+            if state.enums.values().all(EnumTrace::is_complete) {
+                return Ok(Self {
+                    struct_name: state.root_struct_name,
+                    datatype: format.datatype(&state.enums),
+                });
+            }
+
+            // Only deserializers that ignore what they are given can end up here.
+            let previously_explored = std::mem::replace(
+                &mut num_explored,
+                state.enums.values().map(EnumTrace::num_explored).sum(),
+            );
+            if num_explored == previously_explored {
+                return Err(SerdeConversionError::Schema(
+                    "some of its enum variants are unreachable".to_owned(),
+                ));
+            }
+        }
+    }
+}
+
+/// Enums are identified by their name and the names of their variants.
+type EnumKey = (&'static str, &'static [&'static str]);
+
+/// What is known of an enum variant so far.
+#[derive(Debug, Clone)]
+enum VariantTrace {
+    Unexplored,
+    Unit,
+    Data(Format),
+}
+
+/// What is known of an enum so far.
+#[derive(Debug)]
+struct EnumTrace {
+    variants: Vec<VariantTrace>,
+
+    /// The enums found within each variant, at any depth.
+    nested: Vec<BTreeSet<EnumKey>>,
+}
+
+impl EnumTrace {
+    fn new(len: usize) -> Self {
+        Self {
+            variants: vec![VariantTrace::Unexplored; len],
+            nested: vec![BTreeSet::new(); len],
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.num_explored() == self.variants.len()
+    }
+
+    fn num_explored(&self) -> usize {
+        self.variants
+            .iter()
+            .filter(|variant| !matches!(variant, VariantTrace::Unexplored))
+            .count()
+    }
+}
+
+type EnumTraces = BTreeMap<EnumKey, EnumTrace>;
+
+/// What a type looks like, as recorded by the [`TypeTracer`].
+///
+/// Enums are referred to by key, since they are only fully known once all their variants have
+/// been explored.
+#[derive(Debug, Clone)]
+enum Format {
+    Null,
+    Bool,
+    Int,
+    UInt,
+    Float,
+    Utf8,
+    Binary,
+    List(Box<Format>),
+    Tuple(Vec<Format>),
+    Struct(Vec<(&'static str, Format)>),
+    Enum(EnumKey),
+}
+
+impl Format {
+    fn datatype(&self, enums: &EnumTraces) -> DataType {
+        match self {
+            Self::Null => DataType::Null,
+            Self::Bool => DataType::Boolean,
+            Self::Int => DataType::Int64,
+            Self::UInt => DataType::UInt64,
+            Self::Float => DataType::Float64,
+            Self::Utf8 => DataType::Utf8,
+            Self::Binary => DataType::Binary,
+
+            Self::List(item) => {
+                DataType::List(Arc::new(Field::new_list_field(item.datatype(enums), true)))
+            }
+
+            Self::Tuple(items) => {
+                let datatypes = items
+                    .iter()
+                    .map(|item| item.datatype(enums))
+                    .collect::<Vec<_>>();
+                match datatypes.as_slice() {
+                    [] => DataType::Null,
+                    [first, rest @ ..] if rest.iter().all(|datatype| datatype == first) => {
+                        DataType::FixedSizeList(
+                            Arc::new(Field::new_list_field(first.clone(), true)),
+                            datatypes.len() as i32,
+                        )
+                    }
+                    _ => DataType::Struct(
+                        datatypes
+                            .into_iter()
+                            .enumerate()
+                            .map(|(index, datatype)| Field::new(index.to_string(), datatype, true))
+                            .collect(),
+                    ),
+                }
+            }
+
+            Self::Struct(fields) => {
+                if fields.is_empty() {
+                    // Arrow structs must have at least one field.
+                    DataType::Null
+                } else {
+                    DataType::Struct(
+                        fields
+                            .iter()
+                            .map(|(name, format)| Field::new(*name, format.datatype(enums), true))
+                            .collect(),
+                    )
+                }
+            }
+
+            Self::Enum(key @ (_, names)) => {
+                let variants = enums
+                    .get(key)
+                    .into_iter()
+                    .flat_map(|trace| &trace.variants)
+                    .zip(names.iter())
+                    .filter_map(|(variant, name)| match variant {
+                        VariantTrace::Data(format) => Some((name, format)),
+                        VariantTrace::Unexplored | VariantTrace::Unit => None,
+                    })
+                    .collect::<Vec<_>>();
+
+                if variants.is_empty() {
+                    DataType::Utf8
+                } else {
+                    DataType::Struct(
+                        std::iter::once(Field::new(VARIANT_FIELD, DataType::Utf8, true))
+                            .chain(variants.into_iter().map(|(name, format)| {
+                                Field::new(*name, format.datatype(enums), true)
+                            }))
+                            .collect(),
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// The field holding the name of the variant of enums that aren't only made of unit variants.
+const VARIANT_FIELD: &str = "variant";
+
+#[derive(Default)]
+struct TracerState {
+    root_struct_name: Option<&'static str>,
+    enums: EnumTraces,
+
+    /// The named types currently being traced, to detect recursive types.
+    in_progress: Vec<&'static str>,
+
+    /// The enum variants currently being traced.
+    variant_path: Vec<(EnumKey, usize)>,
+}
+
+impl TracerState {
+    fn enter(&mut self, name: &'static str) -> Result<(), SerdeConversionError> {
+        if self.in_progress.contains(&name) {
+            return Err(SerdeConversionError::Schema(format!("{name} is recursive")));
+        }
+        self.in_progress.push(name);
+        Ok(())
+    }
+
+    fn exit(&mut self) {
+        self.in_progress.pop();
+    }
+}
+
+/// A [`serde::Deserializer`] that records the format of whatever is deserialized from it, feeding
+/// it placeholder values.
+struct TypeTracer<'a> {
+    state: &'a mut TracerState,
+    out: &'a mut Format,
+
+    /// Whether this is the top-level value, as opposed to something nested in it.
+    is_root: bool,
+}
+
+/// Traces the elements of a sequence, tuple or struct, one per format.
+fn trace_elements<'de, V: de::Visitor<'de>>(
+    state: &mut TracerState,
+    outs: &mut [Format],
+    visitor: V,
+) -> Result<V::Value, SerdeConversionError> {
+    visitor.visit_seq(ElementsTracer {
+        state,
+        outs: outs.iter_mut(),
+    })
+}
+
+fn trace_tuple<'de, V: de::Visitor<'de>>(
+    state: &mut TracerState,
+    len: usize,
+    visitor: V,
+) -> Result<(V::Value, Format), SerdeConversionError> {
+    let mut items = vec![Format::Null; len];
+    let value = trace_elements(state, &mut items, visitor)?;
+    Ok((value, Format::Tuple(items)))
+}
+
+/// Structs are traced as sequences of fields, which derived implementations support.
+fn trace_struct<'de, V: de::Visitor<'de>>(
+    state: &mut TracerState,
+    fields: &'static [&'static str],
+    visitor: V,
+) -> Result<(V::Value, Format), SerdeConversionError> {
+    let mut formats = vec![Format::Null; fields.len()];
+    let value = trace_elements(state, &mut formats, visitor)?;
+    Ok((
+        value,
+        Format::Struct(fields.iter().copied().zip(formats).collect()),
+    ))
+}
+
+macro_rules! trace_primitive {
+    ($($method:ident => $format:ident, $visit:ident($placeholder:expr);)*) => {
+        $(
+            fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                *self.out = Format::$format;
+                visitor.$visit($placeholder)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for TypeTracer<'_> {
+    type Error = SerdeConversionError;
+
+    trace_primitive! {
+        deserialize_bool => Bool, visit_bool(false);
+        deserialize_i8 => Int, visit_i8(0);
+        deserialize_i16 => Int, visit_i16(0);
+        deserialize_i32 => Int, visit_i32(0);
+        deserialize_i64 => Int, visit_i64(0);
+        deserialize_u8 => UInt, visit_u8(0);
+        deserialize_u16 => UInt, visit_u16(0);
+        deserialize_u32 => UInt, visit_u32(0);
+        deserialize_u64 => UInt, visit_u64(0);
+        deserialize_f32 => Float, visit_f32(0.0);
+        deserialize_f64 => Float, visit_f64(0.0);
+        deserialize_char => Utf8, visit_char(' ');
+        deserialize_str => Utf8, visit_borrowed_str("");
+        deserialize_string => Utf8, visit_string(String::new());
+        deserialize_bytes => Binary, visit_borrowed_bytes(&[]);
+        deserialize_byte_buf => Binary, visit_byte_buf(Vec::new());
+    }
+
+    fn deserialize_unit<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        *self.out = Format::Null;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(SerdeConversionError::Schema(
+            "it needs a self-describing format".to_owned(),
+        ))
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        // Everything is nullable anyway.
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.state.enter(name)?;
+        let value = visitor.visit_newtype_struct(TypeTracer {
+            state: &mut *self.state,
+            out: &mut *self.out,
+            is_root: self.is_root,
+        })?;
+        self.state.exit();
+        Ok(value)
+    }
+
+    fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let mut item = [Format::Null];
+        let value = trace_elements(self.state, &mut item, visitor)?;
+
+        let [item] = item;
+        *self.out = Format::List(Box::new(item));
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V: de::Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (value, format) = trace_tuple(self.state, len, visitor)?;
+        *self.out = format;
+        Ok(value)
+    }
+
+    fn deserialize_tuple_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.state.enter(name)?;
+        let (value, format) = trace_tuple(&mut *self.state, len, visitor)?;
+        self.state.exit();
+
+        *self.out = format;
+        Ok(value)
+    }
+
+    fn deserialize_map<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let mut key = Format::Null;
+        let mut value = Format::Null;
+        let result = visitor.visit_map(EntryTracer {
+            state: &mut *self.state,
+            key: Some(&mut key),
+            value: Some(&mut value),
+        })?;
+
+        *self.out = Format::List(Box::new(Format::Struct(vec![
+            ("key", key),
+            ("value", value),
+        ])));
+        Ok(result)
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.state.enter(name)?;
+        if self.is_root {
+            self.state.root_struct_name = Some(name);
+        }
+
+        let (value, format) = trace_struct(&mut *self.state, fields, visitor)?;
+        self.state.exit();
+
+        *self.out = format;
+        Ok(value)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        if variants.is_empty() {
+            return Err(SerdeConversionError::Schema(format!(
+                "{name} has no variants"
+            )));
+        }
+        self.state.enter(name)?;
+
+        let key = (name, variants);
+        for (outer, index) in &self.state.variant_path {
+            if let Some(outer) = self.state.enums.get_mut(outer) {
+                outer.nested[*index].insert(key);
+            }
+        }
+
+        // Explore the first variant that wasn't yet, or else one that leads to enums that
+        // weren't fully explored yet.
+        let enums = &mut self.state.enums;
+        let unexplored = enums
+            .entry(key)
+            .or_insert_with(|| EnumTrace::new(variants.len()))
+            .variants
+            .iter()
+            .position(|variant| matches!(variant, VariantTrace::Unexplored));
+        let index = unexplored
+            .or_else(|| {
+                enums[&key].nested.iter().position(|nested| {
+                    nested
+                        .iter()
+                        .any(|key| enums.get(key).is_some_and(|trace| !trace.is_complete()))
+                })
+            })
+            .unwrap_or(0);
+
+        self.state.variant_path.push((key, index));
+        let value = visitor.visit_enum(VariantTracer {
+            state: &mut *self.state,
+            key,
+            index,
+        })?;
+        self.state.variant_path.pop();
+        self.state.exit();
+
+        *self.out = Format::Enum(key);
+        Ok(value)
+    }
+
+    fn deserialize_identifier<V: de::Visitor<'de>>(
+        self,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(SerdeConversionError::Schema(
+            "it deserializes identifiers on its own".to_owned(),
+        ))
+    }
+
+    fn deserialize_ignored_any<V: de::Visitor<'de>>(
+        self,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(SerdeConversionError::Schema(
+            "it ignores part of its input".to_owned(),
+        ))
+    }
+}
+
+/// Traces one element per format, which is all it takes for sequences to know their items.
+struct ElementsTracer<'a> {
+    state: &'a mut TracerState,
+    outs: std::slice::IterMut<'a, Format>,
+}
+
+impl<'de> de::SeqAccess<'de> for ElementsTracer<'_> {
+    type Error = SerdeConversionError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        let Some(out) = self.outs.next() else {
+            return Ok(None);
+        };
+
+        seed.deserialize(TypeTracer {
+            state: &mut *self.state,
+            out,
+            is_root: false,
+        })
+        .map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.outs.len())
+    }
+}
+
+/// Traces a single entry of a map.
+struct EntryTracer<'a> {
+    state: &'a mut TracerState,
+    key: Option<&'a mut Format>,
+    value: Option<&'a mut Format>,
+}
+
+impl<'de> de::MapAccess<'de> for EntryTracer<'_> {
+    type Error = SerdeConversionError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some(out) = self.key.take() else {
+            return Ok(None);
+        };
+
+        seed.deserialize(TypeTracer {
+            state: &mut *self.state,
+            out,
+            is_root: false,
+        })
+        .map(Some)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let out = self.value.take().ok_or_else(|| {
+            SerdeConversionError::Schema("it reads map values without keys".to_owned())
+        })?;
+
+        seed.deserialize(TypeTracer {
+            state: &mut *self.state,
+            out,
+            is_root: false,
+        })
+    }
+}
+
+/// Traces the variant at `index` of an enum, and records its format.
+struct VariantTracer<'a> {
+    state: &'a mut TracerState,
+    key: EnumKey,
+    index: usize,
+}
+
+impl VariantTracer<'_> {
+    fn record(self, trace: VariantTrace) {
+        if let Some(variant) = self
+            .state
+            .enums
+            .get_mut(&self.key)
+            .and_then(|trace| trace.variants.get_mut(self.index))
+        {
+            *variant = trace;
+        }
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for VariantTracer<'_> {
+    type Error = SerdeConversionError;
+    type Variant = Self;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), Self::Error> {
+        let index: de::value::U32Deserializer<SerdeConversionError> =
+            (self.index as u32).into_deserializer();
+        Ok((seed.deserialize(index)?, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for VariantTracer<'_> {
+    type Error = SerdeConversionError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.record(VariantTrace::Unit);
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        let mut format = Format::Null;
+        let value = seed.deserialize(TypeTracer {
+            state: &mut *self.state,
+            out: &mut format,
+            is_root: false,
+        })?;
+
+        self.record(VariantTrace::Data(format));
+        Ok(value)
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (value, format) = trace_tuple(&mut *self.state, len, visitor)?;
+        self.record(VariantTrace::Data(format));
+        Ok(value)
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (value, format) = trace_struct(&mut *self.state, fields, visitor)?;
+        self.record(VariantTrace::Data(format));
+        Ok(value)
+    }
+}
+
+// ---
+
+/// An intermediate representation of a serialized value, to be checked against its [`Schema`].
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    List(Vec<Value>),
+    Tuple(Vec<Value>),
+    Struct(Vec<(&'static str, Value)>),
+
+    /// The name of the variant, and its data unless it is a unit variant.
+    Variant(&'static str, Option<Box<Value>>),
+}
+
+impl Value {
+    /// Describes the kind of value, for error messages.
+    fn kind(&self) -> String {
+        match self {
+            Self::Null => "null".to_owned(),
+            Self::Bool(_) => "a boolean".to_owned(),
+            Self::Int(value) => format!("the integer {value}"),
+            Self::UInt(value) => format!("the unsigned integer {value}"),
+            Self::Float(_) => "a float".to_owned(),
+            Self::String(_) => "a string".to_owned(),
+            Self::Bytes(_) => "bytes".to_owned(),
+            Self::List(_) => "a sequence".to_owned(),
+            Self::Tuple(items) => format!("a tuple of {} elements", items.len()),
+            Self::Struct(_) => "a struct".to_owned(),
+            Self::Variant(name, _) => format!("the enum variant {name:?}"),
+        }
+    }
+
+    fn mismatch(&self, datatype: &DataType) -> SerdeConversionError {
+        SerdeConversionError::Mismatch {
+            datatype: datatype.clone(),
+            value: self.kind(),
+        }
+    }
+}
+
+/// What missing values are filled with.
+static NULL: Value = Value::Null;
+
+/// Converts the values into an array of the given datatype, failing if any of them doesn't fit.
+fn to_array(values: &[&Value], datatype: &DataType) -> Result<ArrayRef, SerdeConversionError> {
+    /// Maps the values that aren't null, failing on those that `f` rejects.
+    fn map<'a, T>(
+        values: &[&'a Value],
+        datatype: &DataType,
+        f: impl Fn(&'a Value) -> Option<T>,
+    ) -> Result<Vec<Option<T>>, SerdeConversionError> {
+        values
+            .iter()
+            .map(|value| match value {
+                Value::Null => Ok(None),
+                value => f(value).map(Some).ok_or_else(|| value.mismatch(datatype)),
+            })
+            .collect()
+    }
+
+    Ok(match datatype {
+        DataType::Null => {
+            map(values, datatype, |_| None::<()>)?;
+            Arc::new(NullArray::new(values.len()))
+        }
+
+        DataType::Boolean => Arc::new(BooleanArray::from(map(
+            values,
+            datatype,
+            |value| match value {
+                Value::Bool(value) => Some(*value),
+                _ => None,
+            },
+        )?)),
+
+        DataType::Int64 => Arc::new(Int64Array::from(map(
+            values,
+            datatype,
+            |value| match value {
+                Value::Int(value) => Some(*value),
+                Value::UInt(value) => i64::try_from(*value).ok(),
+                _ => None,
+            },
+        )?)),
+
+        DataType::UInt64 => Arc::new(UInt64Array::from(map(
+            values,
+            datatype,
+            |value| match value {
+                Value::UInt(value) => Some(*value),
+                Value::Int(value) => u64::try_from(*value).ok(),
+                _ => None,
+            },
+        )?)),
+
+        DataType::Float64 => Arc::new(Float64Array::from(map(
+            values,
+            datatype,
+            |value| match value {
+                Value::Float(value) => Some(*value),
+                _ => None,
+            },
+        )?)),
+
+        DataType::Utf8 => Arc::new(StringArray::from(map(
+            values,
+            datatype,
+            |value| match value {
+                Value::String(value) => Some(value.as_str()),
+                Value::Variant(name, None) => Some(*name),
+                _ => None,
+            },
+        )?)),
+
+        DataType::Binary => Arc::new(BinaryArray::from(map(
+            values,
+            datatype,
+            |value| match value {
+                Value::Bytes(value) => Some(value.as_slice()),
+                _ => None,
+            },
+        )?)),
+
+        DataType::List(field) => {
+            let lists = map(values, datatype, |value| match value {
+                Value::List(items) => Some(items),
+                _ => None,
+            })?;
+
+            let mut items = Vec::new();
+            let mut offsets = Vec::with_capacity(values.len() + 1);
+            offsets.push(0);
+            for list in &lists {
+                items.extend(list.iter().copied().flatten());
+                offsets.push(items.len() as i32);
+            }
+
+            Arc::new(ListArray::try_new(
+                field.clone(),
+                OffsetBuffer::new(offsets.into()),
+                to_array(&items, field.data_type())?,
+                Some(NullBuffer::from(
+                    lists.iter().map(Option::is_some).collect::<Vec<_>>(),
+                )),
+            )?)
+        }
+
+        DataType::FixedSizeList(field, len) => {
+            let lists = map(values, datatype, |value| match value {
+                Value::Tuple(items) | Value::List(items) if items.len() == *len as usize => {
+                    Some(items)
+                }
+                _ => None,
+            })?;
+
+            let items = lists
+                .iter()
+                .flat_map(|list| match list {
+                    Some(items) => items.iter().collect::<Vec<_>>(),
+                    None => vec![&NULL; *len as usize],
+                })
+                .collect::<Vec<_>>();
+
+            Arc::new(FixedSizeListArray::try_new(
+                field.clone(),
+                *len,
+                to_array(&items, field.data_type())?,
+                Some(NullBuffer::from(
+                    lists.iter().map(Option::is_some).collect::<Vec<_>>(),
+                )),
+            )?)
+        }
+
+        DataType::Struct(fields) => {
+            for value in values {
+                let is_valid = match value {
+                    Value::Null => true,
+                    Value::Variant(name, data) => {
+                        fields.find(VARIANT_FIELD).is_some()
+                            && (data.is_none() || fields.find(name).is_some())
+                    }
+                    Value::Tuple(items) => items.len() == fields.len(),
+                    Value::Struct(struct_fields) => struct_fields
+                        .iter()
+                        .all(|(name, _)| fields.find(name).is_some()),
+                    _ => false,
+                };
+                if !is_valid {
+                    return Err(value.mismatch(datatype));
+                }
+            }
+
+            let variant_names = values
+                .iter()
+                .map(|value| match value {
+                    Value::Variant(name, _) => Value::String((*name).to_owned()),
+                    _ => Value::Null,
+                })
+                .collect::<Vec<_>>();
+
+            let columns = fields
+                .iter()
+                .enumerate()
+                .map(|(index, field)| {
+                    let column = values
+                        .iter()
+                        .zip(&variant_names)
+                        .map(|(value, variant_name)| match value {
+                            Value::Struct(struct_fields) => struct_fields
+                                .iter()
+                                .find(|(name, _)| *name == field.name().as_str())
+                                .map_or(&NULL, |(_, value)| value),
+                            Value::Tuple(items) => &items[index],
+                            Value::Variant(_, _) if field.name() == VARIANT_FIELD => variant_name,
+                            Value::Variant(name, Some(data)) if *name == field.name().as_str() => {
+                                data
+                            }
+                            _ => &NULL,
+                        })
+                        .collect::<Vec<_>>();
+                    to_array(&column, field.data_type())
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Arc::new(StructArray::try_new(
+                fields.clone(),
+                columns,
+                Some(NullBuffer::from(
+                    values
+                        .iter()
+                        .map(|value| !matches!(value, Value::Null))
+                        .collect::<Vec<_>>(),
+                )),
+            )?)
+        }
+
+        datatype => {
+            return Err(SerdeConversionError::Custom(format!(
+                "Unexpected datatype {datatype}"
+            )));
+        }
+    })
+}
+
+// ---
+
+struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = SerdeConversionError;
+
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = SeqSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = StructSerializer;
+    type SerializeStructVariant = StructSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Self::Error> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Self::Error> {
+        Ok(Value::Int(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, Self::Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, Self::Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, Self::Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Self::Error> {
+        Ok(Value::UInt(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Self::Error> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Self::Error> {
+        Ok(Value::Float(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Self::Error> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Self::Error> {
+        Ok(Value::String(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Self::Error> {
+        Ok(Value::Bytes(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<Value, Self::Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Value, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, Self::Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Self::Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Self::Error> {
+        Ok(Value::Variant(variant, None))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Self::Error> {
+        Ok(Value::Variant(
+            variant,
+            Some(Box::new(value.serialize(self)?)),
+        ))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, Self::Error> {
+        Ok(SeqSerializer::new(len.unwrap_or_default(), false, None))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer, Self::Error> {
+        Ok(SeqSerializer::new(len, true, None))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, Self::Error> {
+        Ok(SeqSerializer::new(len, true, None))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, Self::Error> {
+        Ok(SeqSerializer::new(len, true, Some(variant)))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer, Self::Error> {
+        Ok(MapSerializer {
+            entries: Vec::with_capacity(len.unwrap_or_default()),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<StructSerializer, Self::Error> {
+        Ok(StructSerializer::new(len, None))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<StructSerializer, Self::Error> {
+        Ok(StructSerializer::new(len, Some(variant)))
+    }
+}
+
+/// Wraps the data of an enum variant, if any.
+fn with_variant(variant: Option<&'static str>, value: Value) -> Value {
+    match variant {
+        Some(variant) => Value::Variant(variant, Some(Box::new(value))),
+        None => value,
+    }
+}
+
+struct SeqSerializer {
+    items: Vec<Value>,
+    is_tuple: bool,
+    variant: Option<&'static str>,
+}
+
+impl SeqSerializer {
+    fn new(len: usize, is_tuple: bool, variant: Option<&'static str>) -> Self {
+        Self {
+            items: Vec::with_capacity(len),
+            is_tuple,
+            variant,
+        }
+    }
+
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SerdeConversionError> {
+        self.items.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Value {
+        let value = if !self.is_tuple {
+            Value::List(self.items)
+        } else if self.items.is_empty() {
+            // Just like empty structs.
+            Value::Null
+        } else {
+            Value::Tuple(self.items)
+        };
+        with_variant(self.variant, value)
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Value;
+    type Error = SerdeConversionError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Value;
+    type Error = SerdeConversionError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Value;
+    type Error = SerdeConversionError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleVariant for SeqSerializer {
+    type Ok = Value;
+    type Error = SerdeConversionError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(self.finish())
+    }
+}
+
+/// Maps are serialized as lists of `key`/`value` structs, so that their datatype doesn't depend
+/// on their keys.
+struct MapSerializer {
+    entries: Vec<Value>,
+    next_key: Option<Value>,
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = Value;
+    type Error = SerdeConversionError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.next_key = Some(key.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| SerdeConversionError::Custom("Map value without a key".to_owned()))?;
+        self.entries.push(Value::Struct(vec![
+            ("key", key),
+            ("value", value.serialize(ValueSerializer)?),
+        ]));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(Value::List(self.entries))
+    }
+}
+
+struct StructSerializer {
+    fields: Vec<(&'static str, Value)>,
+    variant: Option<&'static str>,
+}
+
+impl StructSerializer {
+    fn new(len: usize, variant: Option<&'static str>) -> Self {
+        Self {
+            fields: Vec::with_capacity(len),
+            variant,
+        }
+    }
+
+    fn push<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeConversionError> {
+        self.fields.push((key, value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+
+    fn finish(self) -> Value {
+        // Arrow structs must have at least one field.
+        let value = if self.fields.is_empty() {
+            Value::Null
+        } else {
+            Value::Struct(self.fields)
+        };
+        with_variant(self.variant, value)
+    }
+}
+
+impl ser::SerializeStruct for StructSerializer {
+    type Ok = Value;
+    type Error = SerdeConversionError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStructVariant for StructSerializer {
+    type Ok = Value;
+    type Error = SerdeConversionError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(self.finish())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use arrow::array::{Array as _, AsArray as _};
+    use serde::{Deserialize, Serialize};
+
+    use re_types_core::AsComponents as _;
+
+    use crate::reflection::ComponentDescriptorExt as _;
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    enum Mode {
+        Idle,
+        Moving { speed: f32 },
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Pose {
+        x: f64,
+        y: f64,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct RobotState {
+        name: String,
+        battery: Option<u8>,
+        error: Option<String>,
+        pose: Option<Pose>,
+        waypoints: Vec<Pose>,
+        mode: Mode,
+        modes: Vec<Mode>,
+        params: BTreeMap<String, i32>,
+    }
+
+    fn batches<T: Serialize + DeserializeOwned>(value: &T) -> BTreeMap<String, ArrayRef> {
+        DynamicArchetype::from_serde(value)
+            .unwrap()
+            .as_serialized_batches()
+            .into_iter()
+            .map(|batch| {
+                (
+                    batch.descriptor.archetype_field_name().to_owned(),
+                    batch.array,
+                )
+            })
+            .collect()
+    }
+
+    fn datatypes<T: Serialize + DeserializeOwned>(value: &T) -> BTreeMap<String, DataType> {
+        batches(value)
+            .into_iter()
+            .map(|(name, array)| (name, array.data_type().clone()))
+            .collect()
+    }
+
+    #[test]
+    fn from_serde() {
+        let state = RobotState {
+            name: "r2".to_owned(),
+            battery: Some(42),
+            error: None,
+            pose: Some(Pose { x: 1.0, y: 2.0 }),
+            waypoints: vec![Pose { x: 3.0, y: 4.0 }, Pose { x: 5.0, y: 6.0 }],
+            mode: Mode::Idle,
+            modes: vec![Mode::Moving { speed: 0.5 }, Mode::Idle],
+            params: [("gain".to_owned(), 3)].into_iter().collect(),
+        };
+
+        let archetype = DynamicArchetype::from_serde(&state).unwrap();
+        for batch in archetype.as_serialized_batches() {
+            assert_eq!(
+                Some("RobotState".into()),
+                batch.descriptor.archetype,
+                "{batch:?}"
+            );
+        }
+
+        let batches = batches(&state);
+        assert_eq!(
+            vec![
+                "battery",
+                "error",
+                "mode",
+                "modes",
+                "name",
+                "params",
+                "pose",
+                "waypoints"
+            ],
+            batches.keys().collect::<Vec<_>>()
+        );
+
+        assert_eq!(&DataType::UInt64, batches["battery"].data_type());
+        assert_eq!("r2", batches["name"].as_string::<i32>().value(0));
+
+        // `None`s clear their component.
+        assert_eq!(&DataType::Utf8, batches["error"].data_type());
+        assert_eq!(0, batches["error"].len());
+
+        // Top-level sequences are batches of instances.
+        let pose = DataType::Struct(
+            vec![
+                Field::new("x", DataType::Float64, true),
+                Field::new("y", DataType::Float64, true),
+            ]
+            .into(),
+        );
+        assert_eq!(2, batches["waypoints"].len());
+        assert_eq!(&pose, batches["waypoints"].data_type());
+        assert_eq!(&pose, batches["pose"].data_type());
+
+        // Enums with data are structs, whichever variant is logged.
+        let mode = DataType::Struct(
+            vec![
+                Field::new(VARIANT_FIELD, DataType::Utf8, true),
+                Field::new(
+                    "Moving",
+                    DataType::Struct(vec![Field::new("speed", DataType::Float64, true)].into()),
+                    true,
+                ),
+            ]
+            .into(),
+        );
+        assert_eq!(&mode, batches["mode"].data_type());
+        assert_eq!(&mode, batches["modes"].data_type());
+
+        let variants = batches["modes"].as_struct().column(0).as_string::<i32>();
+        assert_eq!("Moving", variants.value(0));
+        assert_eq!("Idle", variants.value(1));
+
+        assert_eq!(1, batches["params"].len());
+    }
+
+    #[test]
+    fn stable_datatypes() {
+        let mut state = RobotState {
+            name: String::new(),
+            battery: None,
+            error: None,
+            pose: None,
+            waypoints: Vec::new(),
+            mode: Mode::Idle,
+            modes: Vec::new(),
+            params: BTreeMap::new(),
+        };
+        let empty = datatypes(&state);
+
+        state.battery = Some(1);
+        state.error = Some("oops".to_owned());
+        state.pose = Some(Pose { x: 1.0, y: 2.0 });
+        state.mode = Mode::Moving { speed: 1.0 };
+        state.modes = vec![Mode::Idle, Mode::Moving { speed: 2.0 }];
+        state.params.insert("gain".to_owned(), 3);
+        let full = datatypes(&state);
+
+        assert_eq!(empty, full);
+    }
+
+    #[test]
+    fn nested_enums() {
+        #[derive(Serialize, Deserialize)]
+        enum Inner {
+            A,
+            B(u8),
+        }
+
+        #[derive(Serialize, Deserialize)]
+        enum Outer {
+            None,
+            Some(Inner),
+        }
+
+        let datatype = batches(&Outer::Some(Inner::B(1)))["value"]
+            .data_type()
+            .clone();
+        let DataType::Struct(fields) = &datatype else {
+            panic!("{datatype}");
+        };
+
+        // The variants of the inner enum are only reachable through the last variant of the
+        // outer one.
+        let Some((_, inner)) = fields.find("Some") else {
+            panic!("{datatype}");
+        };
+        let DataType::Struct(inner) = inner.data_type() else {
+            panic!("{datatype}");
+        };
+        assert!(inner.find("B").is_some(), "{datatype}");
+
+        assert_eq!(
+            &datatype,
+            batches(&Outer::None)["value"].data_type(),
+            "{datatype}"
+        );
+    }
+
+    #[test]
+    fn large_integers() {
+        let batches = batches(&vec![u64::MAX, 0]);
+        let values = batches["value"]
+            .as_primitive::<arrow::datatypes::UInt64Type>()
+            .values();
+        assert_eq!(&[u64::MAX, 0], &values[..]);
+
+        assert!(matches!(
+            to_array(&[&Value::UInt(u64::MAX)], &DataType::Int64),
+            Err(SerdeConversionError::Mismatch { .. })
+        ));
+        assert!(matches!(
+            to_array(&[&Value::Int(-1)], &DataType::UInt64),
+            Err(SerdeConversionError::Mismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn recursive_types() {
+        #[derive(Serialize, Deserialize)]
+        struct Node {
+            children: Vec<Node>,
+        }
+
+        assert!(matches!(
+            DynamicArchetype::from_serde(&Node {
+                children: Vec::new()
+            }),
+            Err(SerdeConversionError::Schema(..))
+        ));
+    }
+}
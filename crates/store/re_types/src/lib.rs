@@ -303,6 +303,11 @@ pub use any_values::AnyValues;
 pub mod dynamic_archetype;
 pub use dynamic_archetype::DynamicArchetype;
 
+#[cfg(feature = "serde")]
+mod dynamic_archetype_serde;
+#[cfg(feature = "serde")]
+pub use dynamic_archetype_serde::SerdeConversionError;
+
 mod rotation3d;
 pub use rotation3d::Rotation3D;
 
@@ -0,0 +1,223 @@
+//! Aligning the timestamps of several processes logging to the same recording through a proxy.
+
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use re_chunk::{Chunk, ChunkResult, RowId, TimeColumn};
+use re_grpc_client::clock_sync::{ClockModel, ClockSync};
+use re_log_types::{EntityPath, TimeCell, TimePoint, TimeType, TimelineName};
+use re_types::{
+    DynamicArchetype, components,
+    external::arrow::array::{BooleanArray, Float64Array, Int64Array, UInt64Array},
+};
+
+/// Where a [`crate::RecordingStream`] logs its clock synchronization estimates, see
+/// [`crate::RecordingStreamBuilder::with_clock_sync`].
+///
+/// The estimates of each source are logged at `__clock_sync/<source>`, so that the corrections
+/// applied to its timestamps can be audited. Until the first estimate is available, the timestamps
+/// of a source are sent uncorrected, which is marked by a `synchronized: false` row logged at the
+/// same path.
+pub const CLOCK_SYNC_PATH: &str = "__clock_sync";
+
+/// The [`ClockAlignment`] of a [`crate::RecordingStream`], if any, shared with its forwarding thread.
+pub(crate) type SharedClockAlignment = Arc<Mutex<Option<ClockAlignment>>>;
+
+/// Corrects the timestamps of the chunks sent by a [`crate::RecordingStream`] so that they match
+/// the clock of the proxy it logs to.
+pub(crate) struct ClockAlignment {
+    source: String,
+    sync: ClockSync,
+
+    /// The estimate applied by [`Self::align_chunk`], as of the last [`Self::new_status_chunk`].
+    model: Option<ClockModel>,
+
+    /// How many rounds of synchronization were performed as of the last logged estimate.
+    num_logged_rounds: u64,
+
+    /// Whether the marker signaling that timestamps are sent uncorrected was logged.
+    logged_unsynchronized: bool,
+}
+
+impl ClockAlignment {
+    const ARCHETYPE_NAME: &'static str = "ClockSync";
+
+    pub fn new(source: String, sync: ClockSync) -> Self {
+        Self {
+            source,
+            sync,
+            model: None,
+            num_logged_rounds: 0,
+            logged_unsynchronized: false,
+        }
+    }
+
+    /// Converts all the timestamp timelines of `chunk` to the clock of the proxy.
+    ///
+    /// Applies the estimate as of the last call to [`Self::new_status_chunk`], which reports it.
+    /// Sequence and duration timelines are left untouched, and so is everything while there is no
+    /// estimate yet.
+    pub fn align_chunk(&self, chunk: &Chunk) -> ChunkResult<Chunk> {
+        let mut chunk = chunk.clone();
+        let Some(model) = self.model else {
+            // Not synchronized yet: sent as is, which `new_status_chunk` marks as such.
+            return Ok(chunk);
+        };
+
+        let timestamp_columns = chunk
+            .timelines()
+            .values()
+            .filter(|column| column.timeline().typ() == TimeType::TimestampNs)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for column in timestamp_columns {
+            let times = column
+                .times_raw()
+                .iter()
+                .map(|&time| model.to_server_time(time))
+                .collect::<Vec<_>>();
+
+            // The correction is monotonic, so sorted times stay sorted.
+            chunk.add_timeline(TimeColumn::new(
+                Some(column.is_sorted()),
+                *column.timeline(),
+                times.into(),
+            ))?;
+        }
+
+        Ok(chunk)
+    }
+
+    /// Returns a chunk logging the latest estimate, if there is one that wasn't logged yet.
+    ///
+    /// If there is no estimate yet, returns a chunk marking this source as unsynchronized instead,
+    /// once.
+    ///
+    /// Must be called, and the chunk sent, before [`Self::align_chunk`], so that aligned chunks
+    /// are always preceded by the status of the correction applied to them.
+    pub fn new_status_chunk(&mut self) -> Option<ChunkResult<Chunk>> {
+        self.model = self.sync.model();
+        let Some(model) = self.model else {
+            if self.logged_unsynchronized {
+                return None;
+            }
+            self.logged_unsynchronized = true;
+
+            return Some(self.unsynchronized_chunk());
+        };
+
+        if model.num_rounds <= self.num_logged_rounds {
+            return None;
+        }
+        self.num_logged_rounds = model.num_rounds;
+
+        Some(self.estimate_chunk(&model))
+    }
+
+    fn unsynchronized_chunk(&self) -> ChunkResult<Chunk> {
+        let mut timepoint = TimePoint::default();
+        timepoint.insert_cell(TimelineName::log_time(), TimeCell::timestamp_now());
+
+        let archetype = DynamicArchetype::new(Self::ARCHETYPE_NAME)
+            .with_component::<components::Text>("source", [self.source.as_str()])
+            .with_component_from_data("synchronized", Arc::new(BooleanArray::from(vec![false])));
+
+        Chunk::builder(self.entity_path())
+            .with_archetype(RowId::new(), timepoint, &archetype)
+            .build()
+    }
+
+    fn estimate_chunk(&self, model: &ClockModel) -> ChunkResult<Chunk> {
+        let ClockModel {
+            reference_nanos: _,
+            offset_nanos,
+            drift,
+            round_trip_nanos,
+            num_rounds,
+        } = *model;
+
+        let now_nanos = re_log_types::Timestamp::now().nanos_since_epoch();
+        let mut timepoint = TimePoint::default();
+        timepoint.insert_cell(
+            TimelineName::log_time(),
+            TimeCell::from_timestamp_nanos_since_epoch(model.to_server_time(now_nanos)),
+        );
+
+        let archetype = DynamicArchetype::new(Self::ARCHETYPE_NAME)
+            .with_component::<components::Text>("source", [self.source.as_str()])
+            .with_component_from_data("synchronized", Arc::new(BooleanArray::from(vec![true])))
+            .with_component_from_data("offset_ns", Arc::new(Int64Array::from(vec![offset_nanos])))
+            .with_component_from_data("drift_ppm", Arc::new(Float64Array::from(vec![drift * 1e6])))
+            .with_component_from_data(
+                "round_trip_ns",
+                Arc::new(Int64Array::from(vec![round_trip_nanos])),
+            )
+            .with_component_from_data("num_rounds", Arc::new(UInt64Array::from(vec![num_rounds])));
+
+        Chunk::builder(self.entity_path())
+            .with_archetype(RowId::new(), timepoint, &archetype)
+            .build()
+    }
+
+    fn entity_path(&self) -> EntityPath {
+        EntityPath::from(CLOCK_SYNC_PATH).join(&EntityPath::from_single_string(&*self.source))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use re_log_types::Timeline;
+    use re_types::reflection::ComponentDescriptorExt as _;
+
+    use super::*;
+
+    #[test]
+    fn unsynchronized() {
+        let sync = ClockSync::start(
+            "rerun+http://127.0.0.1:1/proxy".parse().unwrap(),
+            re_grpc_client::clock_sync::ClockSyncOptions {
+                initial_timeout: Duration::ZERO,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let mut alignment = ClockAlignment::new("camera".to_owned(), sync);
+
+        let status = alignment.new_status_chunk().unwrap().unwrap();
+        assert_eq!(
+            &EntityPath::from("__clock_sync/camera"),
+            status.entity_path()
+        );
+        let synchronized = status
+            .component_batch_raw(
+                &re_types::ComponentDescriptor::partial("synchronized")
+                    .with_builtin_archetype(ClockAlignment::ARCHETYPE_NAME),
+                0,
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            Some(&BooleanArray::from(vec![false])),
+            synchronized.as_any().downcast_ref::<BooleanArray>()
+        );
+
+        // The marker is only logged once.
+        assert!(alignment.new_status_chunk().is_none());
+
+        let timeline = Timeline::new_timestamp("capture_time");
+        let chunk = Chunk::builder("points")
+            .with_archetype(
+                RowId::new(),
+                [(timeline, 42)],
+                &re_types::archetypes::Scalars::new([1.0]),
+            )
+            .build()
+            .unwrap();
+        let aligned = alignment.align_chunk(&chunk).unwrap();
+        assert_eq!(chunk.timelines(), aligned.timelines());
+    }
+}
@@ -0,0 +1,416 @@
+//! Recording properties describing the environment a recording was made in.
+
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+
+use re_types::{
+    AsComponents, DynamicArchetype, SerializedComponentBatch, components,
+    external::arrow::array::BooleanArray,
+};
+
+/// Known CI providers: the environment variable that identifies them, their name, and the
+/// environment variable holding the id of the current job.
+const CI_PROVIDERS: &[(&str, &str, &str)] = &[
+    ("GITHUB_ACTIONS", "github", "GITHUB_RUN_ID"),
+    ("GITLAB_CI", "gitlab", "CI_JOB_ID"),
+    ("BUILDKITE", "buildkite", "BUILDKITE_JOB_ID"),
+    ("CIRCLECI", "circleci", "CIRCLE_WORKFLOW_JOB_ID"),
+    ("JENKINS_URL", "jenkins", "BUILD_TAG"),
+    ("TF_BUILD", "azure", "BUILD_BUILDID"),
+];
+
+/// Command line arguments whose name contains any of these, ignoring case, `-` and `_`, have
+/// their value redacted, see [`redact_command_line`].
+const SECRET_ARGUMENT_NAMES: &[&str] = &[
+    "token",
+    "password",
+    "passwd",
+    "secret",
+    "apikey",
+    "accesskey",
+    "privatekey",
+    "credential",
+    "auth",
+];
+
+/// What redacted command line arguments are replaced with.
+const REDACTED: &str = "<redacted>";
+
+/// Standard recording properties describing the environment a recording was made in: the state
+/// of the git repository, the host, the command line, versions and the CI job, if any.
+///
+/// Sent at [`re_log_types::EntityPath::environment_properties`] when using
+/// [`crate::RecordingStreamBuilder::with_environment_properties`].
+///
+/// All of these are collected on a best-effort basis, and left empty if unavailable.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EnvironmentProperties {
+    /// Commit hash of the git repository in the current working directory.
+    pub git_commit: Option<String>,
+
+    /// Current branch of the git repository in the current working directory.
+    pub git_branch: Option<String>,
+
+    /// Whether the git repository in the current working directory has uncommitted changes.
+    pub git_dirty: Option<bool>,
+
+    /// Name of the host.
+    pub hostname: Option<String>,
+
+    /// Command line of the current process.
+    ///
+    /// The values of secret-looking arguments (`--token=…`, `--password …`, `API_KEY=…`) are
+    /// redacted.
+    pub command_line: Option<String>,
+
+    /// Current working directory of the process.
+    pub working_directory: Option<String>,
+
+    /// Name of the crate being run, if run through `cargo run`.
+    pub crate_name: Option<String>,
+
+    /// Version of the crate being run, if run through `cargo run`.
+    pub crate_version: Option<String>,
+
+    /// Version of the Rerun SDK.
+    pub rerun_version: Option<String>,
+
+    /// Version of the Rust compiler the Rerun SDK was built with.
+    pub rustc_version: Option<String>,
+
+    /// Target the Rerun SDK was built for, e.g. `x86_64-unknown-linux-gnu`.
+    pub target_triple: Option<String>,
+
+    /// Name of the CI provider, e.g. `github`.
+    pub ci_provider: Option<String>,
+
+    /// Id of the CI job.
+    pub ci_job_id: Option<String>,
+}
+
+impl EnvironmentProperties {
+    /// Name of the archetype these properties are logged as.
+    pub const ARCHETYPE_NAME: &'static str = "EnvironmentProperties";
+
+    /// Collects the properties of the current process.
+    ///
+    /// This runs `git` and `hostname`, so it may take a few milliseconds.
+    pub fn collect() -> Self {
+        re_tracing::profile_function!();
+
+        let build_info = re_build_info::build_info!();
+
+        let (ci_provider, ci_job_id) = detect_ci(env_var);
+
+        let working_directory = std::env::current_dir().ok();
+        let (git_commit, git_branch, git_dirty) = working_directory
+            .as_deref()
+            .map(git_state)
+            .unwrap_or_default();
+
+        Self {
+            git_commit,
+            git_branch,
+            git_dirty,
+            hostname: env_var("HOSTNAME")
+                .or_else(|| env_var("COMPUTERNAME"))
+                .or_else(|| run_command(&mut Command::new("hostname"))),
+            command_line: Some(redact_command_line(std::env::args())),
+            working_directory: working_directory.map(|dir| dir.display().to_string()),
+            crate_name: env_var("CARGO_PKG_NAME"),
+            crate_version: env_var("CARGO_PKG_VERSION"),
+            rerun_version: Some(build_info.version.to_string()),
+            rustc_version: non_empty(&build_info.rustc_version),
+            target_triple: non_empty(&build_info.target_triple),
+            ci_provider,
+            ci_job_id,
+        }
+    }
+}
+
+impl AsComponents for EnvironmentProperties {
+    fn as_serialized_batches(&self) -> Vec<SerializedComponentBatch> {
+        let Self {
+            git_commit,
+            git_branch,
+            git_dirty,
+            hostname,
+            command_line,
+            working_directory,
+            crate_name,
+            crate_version,
+            rerun_version,
+            rustc_version,
+            target_triple,
+            ci_provider,
+            ci_job_id,
+        } = self;
+
+        let texts = [
+            ("git_commit", git_commit),
+            ("git_branch", git_branch),
+            ("hostname", hostname),
+            ("command_line", command_line),
+            ("working_directory", working_directory),
+            ("crate_name", crate_name),
+            ("crate_version", crate_version),
+            ("rerun_version", rerun_version),
+            ("rustc_version", rustc_version),
+            ("target_triple", target_triple),
+            ("ci_provider", ci_provider),
+            ("ci_job_id", ci_job_id),
+        ];
+
+        let mut archetype = DynamicArchetype::new(Self::ARCHETYPE_NAME);
+        for (field, value) in texts {
+            if let Some(value) = value {
+                archetype = archetype.with_component::<components::Text>(field, [value.as_str()]);
+            }
+        }
+        if let Some(git_dirty) = git_dirty {
+            archetype = archetype.with_component_from_data(
+                "git_dirty",
+                Arc::new(BooleanArray::from(vec![*git_dirty])),
+            );
+        }
+
+        archetype.as_serialized_batches()
+    }
+}
+
+/// The CI provider and job id, if running on CI, given a way to read environment variables.
+///
+/// Unknown providers that set `CI` are reported as `ci`, without a job id.
+fn detect_ci(env_var: impl Fn(&str) -> Option<String>) -> (Option<String>, Option<String>) {
+    CI_PROVIDERS
+        .iter()
+        .find(|(detect_var, _, _)| env_var(detect_var).is_some())
+        .map_or_else(
+            || (env_var("CI").map(|_| "ci".to_owned()), None),
+            |(_, provider, job_id_var)| (Some((*provider).to_owned()), env_var(job_id_var)),
+        )
+}
+
+/// The commit, branch, and dirtiness of the git repository containing `dir`, if any.
+fn git_state(dir: &Path) -> (Option<String>, Option<String>, Option<bool>) {
+    let git = |args: &[&str]| run_command(Command::new("git").arg("-C").arg(dir).args(args));
+
+    let commit = git(&["rev-parse", "HEAD"]);
+
+    // Only uncommitted changes produce any output.
+    let dirty = commit
+        .is_some()
+        .then(|| git(&["status", "--porcelain", "--untracked-files=no"]).is_some());
+
+    (commit, git(&["rev-parse", "--abbrev-ref", "HEAD"]), dirty)
+}
+
+/// Joins command line arguments, redacting the values of secret-looking ones, whether passed as
+/// `--name=value`, `--name value` or `NAME=value`.
+fn redact_command_line(args: impl IntoIterator<Item = String>) -> String {
+    let is_secret = |name: &str| {
+        let name = name.to_lowercase().replace(['-', '_'], "");
+        SECRET_ARGUMENT_NAMES
+            .iter()
+            .any(|secret| name.contains(secret))
+    };
+
+    let mut redact_next = false;
+    let mut redacted = Vec::new();
+    for arg in args {
+        if std::mem::take(&mut redact_next) {
+            redacted.push(REDACTED.to_owned());
+        } else if let Some((name, _)) = arg.split_once('=')
+            && is_secret(name)
+        {
+            redacted.push(format!("{name}={REDACTED}"));
+        } else {
+            redact_next = arg.starts_with('-') && is_secret(&arg);
+            redacted.push(arg);
+        }
+    }
+
+    redacted.join(" ")
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().and_then(|value| non_empty(&value))
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_owned())
+}
+
+/// Runs a command to completion, returning its trimmed output if it succeeded and wasn't empty.
+#[cfg(not(target_arch = "wasm32"))]
+fn run_command(command: &mut Command) -> Option<String> {
+    let output = command
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    non_empty(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn run_command(_command: &mut Command) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use re_types::Component as _;
+
+    fn args(command_line: &str) -> impl Iterator<Item = String> {
+        command_line.split(' ').map(ToOwned::to_owned)
+    }
+
+    #[test]
+    fn serialized_batches() {
+        let properties = EnvironmentProperties {
+            git_commit: Some("0123abcd".to_owned()),
+            git_branch: Some("main".to_owned()),
+            git_dirty: Some(true),
+            hostname: Some("host".to_owned()),
+            command_line: Some("my_app --verbose".to_owned()),
+            working_directory: Some("/home/me".to_owned()),
+            crate_name: Some("my_app".to_owned()),
+            crate_version: Some("0.1.0".to_owned()),
+            rerun_version: Some("0.25.0".to_owned()),
+            rustc_version: Some("1.88.0".to_owned()),
+            target_triple: Some("x86_64-unknown-linux-gnu".to_owned()),
+            ci_provider: Some("github".to_owned()),
+            ci_job_id: Some("42".to_owned()),
+        };
+
+        let batches = properties.as_serialized_batches();
+
+        let mut fields = batches
+            .iter()
+            .map(|batch| {
+                assert_eq!(
+                    Some(EnvironmentProperties::ARCHETYPE_NAME.into()),
+                    batch.descriptor.archetype
+                );
+                assert_eq!(1, batch.array.len());
+                batch.descriptor.component.as_str()
+            })
+            .collect::<Vec<_>>();
+        fields.sort_unstable();
+        assert_eq!(
+            vec![
+                "EnvironmentProperties:ci_job_id",
+                "EnvironmentProperties:ci_provider",
+                "EnvironmentProperties:command_line",
+                "EnvironmentProperties:crate_name",
+                "EnvironmentProperties:crate_version",
+                "EnvironmentProperties:git_branch",
+                "EnvironmentProperties:git_commit",
+                "EnvironmentProperties:git_dirty",
+                "EnvironmentProperties:hostname",
+                "EnvironmentProperties:rerun_version",
+                "EnvironmentProperties:rustc_version",
+                "EnvironmentProperties:target_triple",
+                "EnvironmentProperties:working_directory",
+            ],
+            fields
+        );
+
+        // Everything is text, except for `git_dirty`.
+        for batch in &batches {
+            let expected_type = (batch.descriptor.component.as_str()
+                != "EnvironmentProperties:git_dirty")
+                .then(components::Text::name);
+            assert_eq!(expected_type, batch.descriptor.component_type);
+        }
+
+        // Missing properties are left out altogether.
+        assert!(
+            EnvironmentProperties::default()
+                .as_serialized_batches()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn collect() {
+        let properties = EnvironmentProperties::collect();
+
+        assert!(properties.command_line.is_some());
+        assert!(properties.working_directory.is_some());
+        assert_eq!(
+            Some(re_build_info::build_info!().version.to_string()),
+            properties.rerun_version
+        );
+        assert_eq!(
+            properties.git_commit.is_some(),
+            properties.git_dirty.is_some()
+        );
+    }
+
+    #[test]
+    fn ci_detection() {
+        let detect = |vars: &[(&str, &str)]| {
+            detect_ci(|name| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| (*value).to_owned())
+            })
+        };
+
+        assert_eq!((None, None), detect(&[]));
+        assert_eq!((Some("ci".to_owned()), None), detect(&[("CI", "true")]));
+        assert_eq!(
+            (Some("github".to_owned()), Some("1234".to_owned())),
+            detect(&[
+                ("CI", "true"),
+                ("GITHUB_ACTIONS", "true"),
+                ("GITHUB_RUN_ID", "1234")
+            ])
+        );
+        assert_eq!(
+            (Some("gitlab".to_owned()), None),
+            detect(&[("GITLAB_CI", "true")])
+        );
+    }
+
+    #[test]
+    fn git_state_without_repository() {
+        // Assumes the temporary directory isn't within a git repository itself.
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!((None, None, None), git_state(dir.path()));
+    }
+
+    #[test]
+    fn redacted_command_line() {
+        assert_eq!(
+            "my_app --verbose --frames=10 input.rrd",
+            redact_command_line(args("my_app --verbose --frames=10 input.rrd"))
+        );
+        assert_eq!(
+            "my_app --token=<redacted> --api-key <redacted> --verbose",
+            redact_command_line(args("my_app --token=abc --api-key xyz --verbose"))
+        );
+        assert_eq!(
+            "my_app --Password=<redacted> --DB_PASSWORD <redacted> AWS_SECRET_ACCESS_KEY=<redacted>",
+            redact_command_line(args(
+                "my_app --Password=hunter2 --DB_PASSWORD hunter2 AWS_SECRET_ACCESS_KEY=abc"
+            ))
+        );
+
+        // Only flags redact the next argument.
+        assert_eq!(
+            "my_app tokens.txt out.rrd",
+            redact_command_line(args("my_app tokens.txt out.rrd"))
+        );
+    }
+}
@@ -16,8 +16,8 @@ pub struct GrpcServerSink {
     /// Sender to send messages to the gRPC server.
     sender: re_smart_channel::Sender<LogMsg>,
 
-    /// The gRPC server thread.
-    _server_handle: std::thread::JoinHandle<()>,
+    /// The gRPC server thread, if the server isn't running on the caller's runtime.
+    server_handle: Option<std::thread::JoinHandle<()>>,
 
     /// Rerun websocket server.
     server_shutdown_signal: re_grpc_server::shutdown::Signal,
@@ -29,6 +29,45 @@ impl GrpcServerSink {
         bind_ip: &str,
         grpc_port: u16,
         server_options: re_grpc_server::ServerOptions,
+    ) -> Result<Self, std::net::AddrParseError> {
+        Self::new_impl(bind_ip, grpc_port, server_options, |server| {
+            let server_handle = std::thread::Builder::new()
+                .name("message_proxy_server".to_owned())
+                .spawn(move || {
+                    let mut builder = tokio::runtime::Builder::new_current_thread();
+                    builder.enable_all();
+                    let rt = builder.build().expect("failed to build tokio runtime");
+
+                    rt.block_on(server);
+                })
+                .expect("failed to spawn thread for message proxy server");
+            Some(server_handle)
+        })
+    }
+
+    /// Hosts the gRPC server as a task on the given runtime, instead of on a dedicated thread.
+    ///
+    /// A `bind_ip` of `"0.0.0.0"` is a good default.
+    #[cfg(feature = "async")]
+    pub fn new_in_runtime(
+        bind_ip: &str,
+        grpc_port: u16,
+        server_options: re_grpc_server::ServerOptions,
+        runtime: &tokio::runtime::Handle,
+    ) -> Result<Self, std::net::AddrParseError> {
+        Self::new_impl(bind_ip, grpc_port, server_options, |server| {
+            runtime.spawn(server);
+            None
+        })
+    }
+
+    fn new_impl(
+        bind_ip: &str,
+        grpc_port: u16,
+        server_options: re_grpc_server::ServerOptions,
+        spawn: impl FnOnce(
+            std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>,
+        ) -> Option<std::thread::JoinHandle<()>>,
     ) -> Result<Self, std::net::AddrParseError> {
         let (server_shutdown_signal, shutdown) = re_grpc_server::shutdown::shutdown();
 
@@ -42,26 +81,17 @@ impl GrpcServerSink {
             re_smart_channel::SmartMessageSource::MessageProxy(uri.clone()),
             re_smart_channel::SmartChannelSource::Sdk,
         );
-        let server_handle = std::thread::Builder::new()
-            .name("message_proxy_server".to_owned())
-            .spawn(move || {
-                let mut builder = tokio::runtime::Builder::new_current_thread();
-                builder.enable_all();
-                let rt = builder.build().expect("failed to build tokio runtime");
-
-                rt.block_on(re_grpc_server::serve_from_channel(
-                    grpc_server_addr,
-                    server_options,
-                    shutdown,
-                    channel_rx,
-                ));
-            })
-            .expect("failed to spawn thread for message proxy server");
+        let server_handle = spawn(Box::pin(re_grpc_server::serve_from_channel(
+            grpc_server_addr,
+            server_options,
+            shutdown,
+            channel_rx,
+        )));
 
         Ok(Self {
             uri,
             sender: channel_tx,
-            _server_handle: server_handle,
+            server_handle,
             server_shutdown_signal,
         })
     }
@@ -96,6 +126,10 @@ impl crate::sink::LogSink for GrpcServerSink {
         ChunkBatcherConfig::LOW_LATENCY
     }
 
+    fn runs_on_caller_runtime(&self) -> bool {
+        self.server_handle.is_none()
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -16,9 +16,16 @@
 // Private modules:
 
 mod binary_stream_sink;
+#[cfg(not(target_arch = "wasm32"))]
+mod clock_sync;
+mod environment_properties;
 mod global;
 mod log_sink;
 mod recording_stream;
+#[cfg(feature = "async")]
+mod recording_stream_async;
+#[cfg(feature = "data_loaders")]
+mod replay;
 mod spawn;
 
 // -------------
@@ -26,9 +33,18 @@ mod spawn;
 
 pub use spawn::{SpawnError, SpawnOptions, spawn};
 
+pub use self::environment_properties::EnvironmentProperties;
+#[cfg(feature = "data_loaders")]
+pub use self::replay::ReplayOptions;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::clock_sync::CLOCK_SYNC_PATH;
+#[cfg(not(target_arch = "wasm32"))]
+pub use re_grpc_client::clock_sync::{ClockModel, ClockSyncOptions};
+
 pub use self::recording_stream::{
-    RecordingStream, RecordingStreamBuilder, RecordingStreamError, RecordingStreamResult,
-    forced_sink_path,
+    DROPPED_ROWS_PATH, RecordingStream, RecordingStreamBuilder, RecordingStreamError,
+    RecordingStreamResult, forced_sink_path,
 };
 
 /// The default port of a Rerun gRPC /proxy server.
@@ -88,17 +104,27 @@ pub mod sink {
         MultiSink, SinkFlushError,
     };
 
-    pub use crate::log_sink::{GrpcSink, GrpcSinkConnectionFailure, GrpcSinkConnectionState};
+    pub use crate::log_sink::{
+        GrpcSink, GrpcSinkBacklog, GrpcSinkConnectError, GrpcSinkConnectionFailure,
+        GrpcSinkConnectionState, GrpcSinkSpoolOptions, GrpcSinkStatus,
+    };
 
     #[cfg(not(target_arch = "wasm32"))]
     pub use re_log_encoding::{FileSink, FileSinkError};
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub use crate::log_sink::{SharedMemorySink, SharedMemorySinkError};
+
+    #[cfg(all(feature = "mcap", not(target_arch = "wasm32")))]
+    pub use crate::log_sink::{McapSink, McapSinkError};
 }
 
 /// Things directly related to logging.
 pub mod log {
     pub use re_chunk::{
-        Chunk, ChunkBatcher, ChunkBatcherConfig, ChunkBatcherError, ChunkBatcherResult,
-        ChunkComponents, ChunkError, ChunkId, ChunkResult, PendingRow, RowId, TimeColumn,
+        BackpressurePolicy, Chunk, ChunkBatcher, ChunkBatcherConfig, ChunkBatcherError,
+        ChunkBatcherResult, ChunkComponents, ChunkError, ChunkId, ChunkResult, DroppedRows,
+        PendingRow, RowId, TimeColumn,
     };
     pub use re_log_types::LogMsg;
 }
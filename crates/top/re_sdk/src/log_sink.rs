@@ -96,6 +96,15 @@ pub trait LogSink: Send + Sync + 'static {
         ChunkBatcherConfig::DEFAULT
     }
 
+    /// Whether this sink runs on the tokio runtime of the caller that created it, rather than on
+    /// threads of its own.
+    ///
+    /// Such a sink can only make progress for as long as that runtime isn't blocked, so nothing
+    /// running on it may wait for the sink.
+    fn runs_on_caller_runtime(&self) -> bool {
+        false
+    }
+
     /// As [`std::any::Any`] for dynamic downcasting.
     fn as_any(&self) -> &dyn std::any::Any;
 }
@@ -160,6 +169,7 @@ impl LogSink for MultiSink {
             mut chunk_max_rows_if_unsorted,
             mut max_commands_in_flight,
             mut max_chunks_in_flight,
+            backpressure,
         } = ChunkBatcherConfig::DEFAULT;
 
         // Use a mix of the existing sinks thus that we flush *less* often.
@@ -183,9 +193,17 @@ impl LogSink for MultiSink {
             chunk_max_rows_if_unsorted,
             max_commands_in_flight,
             max_chunks_in_flight,
+            backpressure,
         }
     }
 
+    fn runs_on_caller_runtime(&self) -> bool {
+        self.0
+            .lock()
+            .iter()
+            .any(|sink| sink.runs_on_caller_runtime())
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -242,6 +260,12 @@ impl private::Sealed for crate::sink::GrpcSink {}
 
 impl MultiSinkCompatible for crate::sink::GrpcSink {}
 
+#[cfg(all(feature = "mcap", not(target_arch = "wasm32")))]
+impl private::Sealed for crate::sink::McapSink {}
+
+#[cfg(all(feature = "mcap", not(target_arch = "wasm32")))]
+impl MultiSinkCompatible for crate::sink::McapSink {}
+
 // ----------------------------------------------------------------------------
 
 /// Store log messages in memory until you call [`LogSink::drain_backlog`].
@@ -532,6 +556,27 @@ pub type GrpcSinkConnectionState = re_grpc_client::write::ClientConnectionState;
 /// The reason why a [`GrpcSink`] was disconnected.
 pub type GrpcSinkConnectionFailure = re_grpc_client::write::ClientConnectionFailure;
 
+/// How a [`GrpcSink`] spools unsent messages to disk, see [`GrpcSink::new_with_spool`].
+pub type GrpcSinkSpoolOptions = re_grpc_client::write::SpoolOptions;
+
+/// How much data a [`GrpcSink`] has spooled to disk, waiting to be sent.
+pub type GrpcSinkBacklog = re_grpc_client::write::SpoolBacklog;
+
+/// Why a [`GrpcSink`] failed to connect, see [`GrpcSink::wait_for_connection`].
+pub type GrpcSinkConnectError = re_grpc_client::write::GrpcFlushError;
+
+/// The status of a [`GrpcSink`], see [`GrpcSink::status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrpcSinkStatus {
+    /// The state of the underlying gRPC connection.
+    pub connection: GrpcSinkConnectionState,
+
+    /// The messages spooled to disk, waiting to be acknowledged by the server.
+    ///
+    /// Always empty if the sink has no spool.
+    pub backlog: GrpcSinkBacklog,
+}
+
 impl GrpcSink {
     /// Connect to the in-memory storage node over HTTP.
     ///
@@ -551,13 +596,66 @@ impl GrpcSink {
         }
     }
 
-    /// The connection state of underlying Grpc connection of this sink.
+    /// Connect to the in-memory storage node over HTTP, spooling unsent messages to disk.
+    ///
+    /// Messages go through a bounded on-disk queue, and are only removed from it once the server
+    /// acknowledged them. Whenever the connection is down, they keep piling up on disk rather than
+    /// in memory, and the sink keeps trying to reconnect. Once it is back, whatever wasn't
+    /// acknowledged is replayed in order before any new messages.
+    ///
+    /// Messages left over in the spool directory by a previous run are replayed too.
+    ///
+    /// ### Example
+    ///
+    /// ```ignore
+    /// GrpcSink::new_with_spool(
+    ///     "rerun+http://127.0.0.1:9434/proxy",
+    ///     GrpcSinkSpoolOptions::new("/var/spool/rerun").with_max_bytes(10 * 1024 * 1024 * 1024),
+    /// );
+    /// ```
+    #[inline]
+    pub fn new_with_spool(uri: re_uri::ProxyUri, spool: GrpcSinkSpoolOptions) -> Self {
+        Self {
+            client: MessageProxyClient::new(
+                uri,
+                Options {
+                    spool: Some(spool),
+                    ..Default::default()
+                },
+            ),
+        }
+    }
+
+    /// Connect to the in-memory storage node over HTTP, running on the given tokio runtime rather
+    /// than on a dedicated thread.
+    ///
+    /// Dropping the sink does not wait for its pending messages to be sent: they keep being sent
+    /// in the background for as long as the runtime lives.
+    #[cfg(feature = "async")]
+    pub fn new_in_runtime(uri: re_uri::ProxyUri, runtime: &tokio::runtime::Handle) -> Self {
+        Self {
+            client: MessageProxyClient::new_in_runtime(uri, Options::default(), runtime),
+        }
+    }
+
+    /// Waits until the underlying gRPC connection is established.
+    ///
+    /// Fails if it cannot be established within a few seconds, or if it was severed.
+    #[cfg(feature = "async")]
+    pub async fn wait_for_connection(&self) -> Result<(), GrpcSinkConnectError> {
+        self.client.wait_for_connection().await
+    }
+
+    /// The state of the underlying gRPC connection of this sink, and the size of its backlog.
     ///
     /// # Experimental
     ///
     /// This API is experimental and may change in future releases.
-    pub fn status(&self) -> GrpcSinkConnectionState {
-        self.client.status()
+    pub fn status(&self) -> GrpcSinkStatus {
+        GrpcSinkStatus {
+            connection: self.client.status(),
+            backlog: self.client.backlog(),
+        }
     }
 }
 
@@ -589,7 +687,268 @@ impl LogSink for GrpcSink {
         ChunkBatcherConfig::LOW_LATENCY
     }
 
+    fn runs_on_caller_runtime(&self) -> bool {
+        self.client.is_on_caller_runtime()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Why a [`SharedMemorySink`] failed to connect, see [`SharedMemorySink::connect`].
+#[cfg(not(target_arch = "wasm32"))]
+pub type SharedMemorySinkError = re_grpc_server::shared_memory::SharedMemoryError;
+
+/// Stream log messages to a Rerun server running on the same machine, through shared memory.
+///
+/// Messages are written into a ring buffer shared with the server, instead of being sent through
+/// a localhost gRPC connection. This skips the socket and the HTTP/2 framing, but messages are
+/// still encoded, copied and decoded just like with gRPC.
+///
+/// If the server goes away, or stops reading for longer than
+/// [`re_grpc_server::shared_memory::DEFAULT_SEND_TIMEOUT`], the sink falls back to gRPC for good.
+/// Whatever was left unread in the ring by then is sent through gRPC first, so that messages
+/// still arrive in order.
+///
+/// See [`crate::RecordingStream::connect_grpc_opts`] and [`crate::SpawnOptions::shared_memory`]
+/// for how to opt into it.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct SharedMemorySink {
+    /// The `/proxy` endpoint of the server, to fall back to.
+    uri: re_uri::ProxyUri,
+
+    state: Mutex<SharedMemoryState>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+enum SharedMemoryState {
+    Connected(re_grpc_server::shared_memory::SharedMemoryWriter),
+
+    /// The shared-memory connection was lost, so messages go through gRPC instead.
+    Fallback(GrpcSink),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SharedMemorySink {
+    /// How long [`Self::connect`] waits for the server to attach.
+    pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// Connects to the server behind the given `/proxy` endpoint, which must be on this machine.
+    ///
+    /// Fails if it doesn't attach within [`Self::CONNECT_TIMEOUT`], e.g. because there is no
+    /// such server.
+    pub fn connect(uri: re_uri::ProxyUri) -> Result<Self, SharedMemorySinkError> {
+        let writer = re_grpc_server::shared_memory::SharedMemoryWriter::connect(
+            uri.origin.port,
+            re_grpc_server::shared_memory::DEFAULT_CAPACITY,
+            Self::CONNECT_TIMEOUT,
+        )?;
+
+        Ok(Self {
+            uri,
+            state: Mutex::new(SharedMemoryState::Connected(writer)),
+        })
+    }
+
+    /// Whether messages still go through shared memory, as opposed to having fallen back to gRPC.
+    pub fn is_using_shared_memory(&self) -> bool {
+        matches!(*self.state.lock(), SharedMemoryState::Connected(_))
+    }
+
+    /// Switches over to gRPC, starting with whatever the server didn't read from the ring.
+    fn fall_back(&self, state: &mut SharedMemoryState, err: &SharedMemorySinkError) {
+        re_log::warn!(
+            uri = %self.uri,
+            "Lost the shared-memory connection, falling back to gRPC: {err}"
+        );
+
+        let sink = GrpcSink::new(self.uri.clone());
+        let previous_state = std::mem::replace(state, SharedMemoryState::Fallback(sink));
+
+        // Abandoning the ring stops the server from reading any further, if it is still around.
+        if let (SharedMemoryState::Connected(writer), SharedMemoryState::Fallback(sink)) =
+            (previous_state, &*state)
+        {
+            let unread = writer.abandon();
+            if !unread.is_empty() {
+                re_log::debug!(
+                    uri = %self.uri,
+                    "Sending {} messages left unread in shared memory through gRPC",
+                    unread.len()
+                );
+            }
+            for msg in unread {
+                sink.send(msg);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl LogSink for SharedMemorySink {
+    fn send(&self, msg: LogMsg) {
+        let mut state = self.state.lock();
+
+        if let SharedMemoryState::Connected(writer) = &mut *state {
+            // Kept around in case it has to go through gRPC instead, which is cheap since the
+            // payloads are reference-counted.
+            match writer.send(msg.clone()) {
+                Ok(()) => return,
+                Err(err) => self.fall_back(&mut state, &err),
+            }
+        }
+
+        if let SharedMemoryState::Fallback(sink) = &*state {
+            sink.send(msg);
+        }
+    }
+
+    fn flush_blocking(&self, timeout: Duration) -> Result<(), SinkFlushError> {
+        let mut state = self.state.lock();
+
+        if let SharedMemoryState::Connected(writer) = &*state {
+            match writer.flush_blocking(timeout) {
+                Ok(()) => return Ok(()),
+                Err(SharedMemorySinkError::Timeout) => return Err(SinkFlushError::Timeout),
+
+                // Whatever the server didn't read gets flushed through gRPC instead.
+                Err(err) => self.fall_back(&mut state, &err),
+            }
+        }
+
+        match &*state {
+            SharedMemoryState::Connected(_) => Ok(()),
+            SharedMemoryState::Fallback(sink) => sink.flush_blocking(timeout),
+        }
+    }
+
+    fn default_batcher_config(&self) -> ChunkBatcherConfig {
+        // Just like the gRPC sink, this is typically used for live streams.
+        ChunkBatcherConfig::LOW_LATENCY
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl fmt::Debug for SharedMemorySink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SharedMemorySink")
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Errors that can occur when creating a [`McapSink`].
+#[cfg(all(feature = "mcap", not(target_arch = "wasm32")))]
+#[derive(thiserror::Error, Debug)]
+pub enum McapSinkError {
+    /// Error creating the file.
+    #[error("Failed to create file {0}: {1}")]
+    CreateFile(std::path::PathBuf, std::io::Error),
+
+    /// Error writing the MCAP header.
+    #[error(transparent)]
+    Mcap(#[from] re_mcap::Error),
+}
+
+/// Write log messages to an `.mcap` file.
+///
+/// Only recording data is written: blueprints and other non-data messages are ignored.
+/// See [`re_mcap::McapWriter`] for how chunks are mapped to MCAP messages.
+///
+/// The file is only complete once the sink has been dropped.
+#[cfg(all(feature = "mcap", not(target_arch = "wasm32")))]
+pub struct McapSink {
+    // None = finished
+    writer: Mutex<Option<re_mcap::McapWriter<std::io::BufWriter<std::fs::File>>>>,
+
+    /// Only used for diagnostics.
+    path: std::path::PathBuf,
+}
+
+#[cfg(all(feature = "mcap", not(target_arch = "wasm32")))]
+impl McapSink {
+    /// Start writing log messages to an `.mcap` file at the given path.
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Result<Self, McapSinkError> {
+        let path = path.into();
+
+        re_log::debug!("Saving MCAP file to {path:?}…");
+
+        let file = std::fs::File::create(&path)
+            .map_err(|err| McapSinkError::CreateFile(path.clone(), err))?;
+        let writer = re_mcap::McapWriter::new(std::io::BufWriter::new(file))?;
+
+        Ok(Self {
+            writer: Mutex::new(Some(writer)),
+            path,
+        })
+    }
+}
+
+#[cfg(all(feature = "mcap", not(target_arch = "wasm32")))]
+impl Drop for McapSink {
+    fn drop(&mut self) {
+        if let Some(writer) = self.writer.get_mut().take()
+            && let Err(err) = writer.finish()
+        {
+            re_log::error!("Failed to finish MCAP file {:?}: {err}", self.path);
+        }
+    }
+}
+
+#[cfg(all(feature = "mcap", not(target_arch = "wasm32")))]
+impl LogSink for McapSink {
+    fn send(&self, msg: LogMsg) {
+        let LogMsg::ArrowMsg(store_id, arrow_msg) = msg else {
+            return;
+        };
+        if store_id.is_blueprint() {
+            return;
+        }
+
+        let chunk = match re_chunk::Chunk::from_arrow_msg(&arrow_msg) {
+            Ok(chunk) => chunk,
+            Err(err) => {
+                re_log::error_once!(
+                    "Failed to decode chunk for MCAP file {:?}: {err}",
+                    self.path
+                );
+                return;
+            }
+        };
+
+        if let Some(writer) = self.writer.lock().as_mut()
+            && let Err(err) = writer.add_chunk(&chunk)
+        {
+            re_log::error_once!("Failed to write to MCAP file {:?}: {err}", self.path);
+        }
+    }
+
+    fn flush_blocking(&self, _timeout: Duration) -> Result<(), SinkFlushError> {
+        if let Some(writer) = self.writer.lock().as_mut() {
+            writer
+                .flush()
+                .map_err(|err| SinkFlushError::failed(err.to_string()))?;
+        }
+        Ok(())
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
 }
+
+#[cfg(all(feature = "mcap", not(target_arch = "wasm32")))]
+impl fmt::Debug for McapSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("McapSink")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
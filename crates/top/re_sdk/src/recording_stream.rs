@@ -1,7 +1,10 @@
 use std::fmt;
 use std::io::IsTerminal as _;
 use std::sync::Weak;
-use std::sync::{Arc, atomic::AtomicI64};
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, AtomicI64},
+};
 use std::time::Duration;
 
 use ahash::HashMap;
@@ -11,8 +14,9 @@ use nohash_hasher::IntMap;
 use parking_lot::Mutex;
 
 use re_chunk::{
-    BatcherFlushError, BatcherHooks, Chunk, ChunkBatcher, ChunkBatcherConfig, ChunkBatcherError,
-    ChunkComponents, ChunkError, ChunkId, PendingRow, RowId, TimeColumn,
+    BackpressurePolicy, BatcherFlushError, BatcherHooks, Chunk, ChunkBatcher, ChunkBatcherConfig,
+    ChunkBatcherError, ChunkComponents, ChunkError, ChunkId, DroppedRows, PendingRow, RowId,
+    TimeColumn,
 };
 use re_log_types::{
     ApplicationId, ArrowRecordBatchReleaseCallback, BlueprintActivationCommand, EntityPath, LogMsg,
@@ -26,6 +30,7 @@ use re_types::{AsComponents, SerializationError, SerializedComponentColumn};
 #[cfg(feature = "web_viewer")]
 use re_web_viewer_server::WebViewerServerPort;
 
+use crate::EnvironmentProperties;
 use crate::sink::{LogSink, MemorySinkStorage};
 use crate::{binary_stream_sink::BinaryStreamStorage, sink::SinkFlushError};
 
@@ -47,6 +52,50 @@ pub fn forced_sink_path() -> Option<String> {
     std::env::var(ENV_FORCE_SAVE).ok()
 }
 
+const ENV_SHARED_MEMORY: &str = "RERUN_SHARED_MEMORY";
+
+/// Whether the `RERUN_SHARED_MEMORY` environment variable opts into the shared-memory transport,
+/// see [`crate::sink::SharedMemorySink`].
+fn shared_memory_from_env() -> bool {
+    std::env::var(ENV_SHARED_MEMORY)
+        .is_ok_and(|value| matches!(value.to_lowercase().as_str(), "1" | "true" | "on"))
+}
+
+/// Creates a sink sending data to the given `/proxy` endpoint over gRPC.
+///
+/// If `shared_memory` is set and the endpoint is on this machine, the data is sent through a
+/// [`crate::sink::SharedMemorySink`] instead, falling back to gRPC if that's not possible.
+fn proxy_sink(uri: re_uri::ProxyUri, shared_memory: bool) -> Box<dyn LogSink> {
+    #[cfg(not(target_arch = "wasm32"))]
+    if shared_memory && is_local_host(&uri.origin.host) {
+        match crate::sink::SharedMemorySink::connect(uri.clone()) {
+            Ok(sink) => {
+                re_log::debug!(%uri, "Sending data through shared memory");
+                return Box::new(sink);
+            }
+            Err(err) => {
+                re_log::debug!(%uri, "Falling back to gRPC: {err}");
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    let _ = shared_memory;
+
+    Box::new(crate::log_sink::GrpcSink::new(uri))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn is_local_host(host: &re_uri::external::url::Host<String>) -> bool {
+    use re_uri::external::url::Host;
+
+    match host {
+        Host::Domain(domain) => domain.eq_ignore_ascii_case("localhost"),
+        Host::Ipv4(ip) => ip.is_loopback(),
+        Host::Ipv6(ip) => ip.is_loopback(),
+    }
+}
+
 /// Errors that can occur when creating/manipulating a [`RecordingStream`].
 #[derive(thiserror::Error, Debug)]
 pub enum RecordingStreamError {
@@ -90,6 +139,16 @@ pub enum RecordingStreamError {
     #[error(transparent)]
     DataLoaderError(#[from] re_data_loader::DataLoaderError),
 
+    /// Error reading a recording to replay, see [`RecordingStream::replay_rrd`].
+    #[cfg(feature = "data_loaders")]
+    #[error("Failed to read recording: {0}")]
+    Decode(#[from] re_log_encoding::decoder::DecodeError),
+
+    /// Invalid replay speed, see [`crate::ReplayOptions::speed`].
+    #[cfg(feature = "data_loaders")]
+    #[error("Invalid replay speed {0}: must be a positive number")]
+    InvalidReplaySpeed(f64),
+
     /// Invalid gRPC server address.
     #[error(transparent)]
     UriError(#[from] re_uri::Error),
@@ -101,6 +160,26 @@ pub enum RecordingStreamError {
     /// Invalid bind IP.
     #[error(transparent)]
     InvalidAddress(#[from] std::net::AddrParseError),
+
+    /// Failure to connect to a remote Rerun instance.
+    #[cfg(feature = "async")]
+    #[error(transparent)]
+    GrpcConnect(#[from] crate::sink::GrpcSinkConnectError),
+
+    /// Failure to flush the pipeline.
+    #[cfg(feature = "async")]
+    #[error(transparent)]
+    Flush(#[from] SinkFlushError),
+
+    /// A background task panicked or was cancelled.
+    #[cfg(feature = "async")]
+    #[error(transparent)]
+    Join(#[from] tokio::task::JoinError),
+
+    /// Failure to convert a `serde::Serialize` value into components.
+    #[cfg(feature = "serde")]
+    #[error("Failed to convert serde value: {0}")]
+    SerdeConversion(#[from] re_types::SerdeConversionError),
 }
 
 /// Results that can occur when creating/manipulating a [`RecordingStream`].
@@ -134,8 +213,22 @@ pub struct RecordingStreamBuilder {
     // Optional user-defined recording properties.
     should_send_properties: bool,
     recording_info: RecordingInfo,
+    environment_properties: Option<EnvironmentProperties>,
+
+    /// The source name and options to synchronize clocks with, see [`Self::with_clock_sync`].
+    #[cfg(not(target_arch = "wasm32"))]
+    clock_sync: ClockSyncArgs,
 }
 
+/// The source name and options to synchronize clocks with, see
+/// [`RecordingStreamBuilder::with_clock_sync`].
+#[cfg(not(target_arch = "wasm32"))]
+type ClockSyncArgs = Option<(String, crate::ClockSyncOptions)>;
+
+/// Clocks are never synchronized on the web.
+#[cfg(target_arch = "wasm32")]
+type ClockSyncArgs = ();
+
 impl RecordingStreamBuilder {
     /// Create a new [`RecordingStreamBuilder`] with the given [`ApplicationId`].
     ///
@@ -167,6 +260,10 @@ impl RecordingStreamBuilder {
             should_send_properties: true,
             recording_info: RecordingInfo::new()
                 .with_start_time(re_types::components::Timestamp::now()),
+            environment_properties: None,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            clock_sync: None,
         }
     }
 
@@ -190,6 +287,10 @@ impl RecordingStreamBuilder {
             should_send_properties: true,
             recording_info: RecordingInfo::new()
                 .with_start_time(re_types::components::Timestamp::now()),
+            environment_properties: None,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            clock_sync: None,
         }
     }
 
@@ -262,6 +363,69 @@ impl RecordingStreamBuilder {
         self
     }
 
+    /// Collects standard [`EnvironmentProperties`] about the current process (git commit and dirty
+    /// state, hostname, command line, versions, CI job, …) and sends them along with the
+    /// recording, at [`EntityPath::environment_properties`].
+    ///
+    /// The properties are collected right away, on a best-effort basis.
+    /// Secret-looking command line arguments (`--token=…`) are redacted.
+    ///
+    /// ```no_run
+    /// # use re_sdk::RecordingStreamBuilder;
+    /// let rec = RecordingStreamBuilder::new("rerun_example_app")
+    ///     .with_environment_properties()
+    ///     .save("my_recording.rrd")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn with_environment_properties(mut self) -> Self {
+        self.environment_properties = Some(EnvironmentProperties::collect());
+        self
+    }
+
+    /// Synchronizes the clock of this process with the one of the proxy the recording is sent to,
+    /// and corrects all timestamps accordingly, including `log_time`.
+    ///
+    /// This makes the timestamps of several processes logging to the same recording comparable,
+    /// even if their clocks are offset or drift apart. `source` identifies this process: the
+    /// estimated offset and drift are logged under [`crate::CLOCK_SYNC_PATH`]`/<source>`, so that
+    /// the corrections can be audited.
+    ///
+    /// Until the first estimate is available, e.g. if the proxy doesn't support clock
+    /// synchronization, timestamps are sent uncorrected. This is marked by a `synchronized: false`
+    /// row at the same path, while every estimate is logged with `synchronized: true`.
+    ///
+    /// Only applies to [`Self::connect_grpc`], [`Self::spawn`] and their variants: other sinks warn
+    /// that it is ignored.
+    ///
+    /// See [`Self::with_clock_sync_opts`] to configure how the clocks are synchronized.
+    ///
+    /// ```no_run
+    /// # use re_sdk::RecordingStreamBuilder;
+    /// let rec = RecordingStreamBuilder::new("rerun_example_app")
+    ///     .recording_id("shared_recording")
+    ///     .with_clock_sync("camera_node")
+    ///     .connect_grpc()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    #[inline]
+    pub fn with_clock_sync(self, source: impl Into<String>) -> Self {
+        self.with_clock_sync_opts(source, Default::default())
+    }
+
+    /// Same as [`Self::with_clock_sync`], with custom [`crate::ClockSyncOptions`].
+    #[cfg(not(target_arch = "wasm32"))]
+    #[inline]
+    pub fn with_clock_sync_opts(
+        mut self,
+        source: impl Into<String>,
+        options: crate::ClockSyncOptions,
+    ) -> Self {
+        self.clock_sync = Some((source.into(), options));
+        self
+    }
+
     /// Specifies the configuration of the internal data batching mechanism.
     ///
     /// If not set, the default configuration for the currently active sink will be used.
@@ -307,11 +471,19 @@ impl RecordingStreamBuilder {
     /// ```
     pub fn buffered(self) -> RecordingStreamResult<RecordingStream> {
         let sink = crate::log_sink::BufferedSink::new();
-        let (enabled, store_info, properties, batcher_config, batcher_hooks) = self.into_args();
+        let (
+            enabled,
+            store_info,
+            properties,
+            environment_properties,
+            batcher_config,
+            batcher_hooks,
+        ) = self.into_args();
         if enabled {
             RecordingStream::new(
                 store_info,
                 properties,
+                environment_properties,
                 batcher_config,
                 batcher_hooks,
                 Box::new(sink),
@@ -341,11 +513,19 @@ impl RecordingStreamBuilder {
     pub fn memory(
         self,
     ) -> RecordingStreamResult<(RecordingStream, crate::log_sink::MemorySinkStorage)> {
-        let (enabled, store_info, properties, batcher_config, batcher_hooks) = self.into_args();
+        let (
+            enabled,
+            store_info,
+            properties,
+            environment_properties,
+            batcher_config,
+            batcher_hooks,
+        ) = self.into_args();
         let rec = if enabled {
             RecordingStream::new(
                 store_info,
                 properties,
+                environment_properties,
                 batcher_config,
                 batcher_hooks,
                 Box::new(crate::log_sink::BufferedSink::new()),
@@ -380,11 +560,19 @@ impl RecordingStreamBuilder {
         self,
         sinks: impl crate::sink::IntoMultiSink,
     ) -> RecordingStreamResult<RecordingStream> {
-        let (enabled, store_info, properties, batcher_config, batcher_hooks) = self.into_args();
+        let (
+            enabled,
+            store_info,
+            properties,
+            environment_properties,
+            batcher_config,
+            batcher_hooks,
+        ) = self.into_args();
         if enabled {
             RecordingStream::new(
                 store_info,
                 properties,
+                environment_properties,
                 batcher_config,
                 batcher_hooks,
                 Box::new(sinks.into_multi_sink()),
@@ -416,6 +604,10 @@ impl RecordingStreamBuilder {
     /// Creates a new [`RecordingStream`] that is pre-configured to stream the data through to a
     /// remote Rerun instance.
     ///
+    /// If the `RERUN_SHARED_MEMORY` environment variable is set to `1` and `url` points to this
+    /// machine, the data is sent through shared memory instead whenever possible, see
+    /// [`crate::sink::SharedMemorySink`].
+    ///
     /// ## Example
     ///
     /// ```no_run
@@ -427,20 +619,76 @@ impl RecordingStreamBuilder {
         self,
         url: impl Into<String>,
     ) -> RecordingStreamResult<RecordingStream> {
-        let (enabled, store_info, properties, batcher_config, batcher_hooks) = self.into_args();
+        self.connect_proxy(url, shared_memory_from_env())
+    }
+
+    fn connect_proxy(
+        self,
+        url: impl Into<String>,
+        shared_memory: bool,
+    ) -> RecordingStreamResult<RecordingStream> {
+        let (
+            (
+                enabled,
+                store_info,
+                properties,
+                environment_properties,
+                batcher_config,
+                batcher_hooks,
+            ),
+            clock_sync,
+        ) = self.into_args_and_clock_sync();
+
+        #[cfg(target_arch = "wasm32")]
+        let _ = clock_sync;
+
         if enabled {
             let url: String = url.into();
             let re_uri::RedapUri::Proxy(uri) = url.as_str().parse()? else {
                 return Err(RecordingStreamError::NotAProxyEndpoint);
             };
 
-            RecordingStream::new(
-                store_info,
-                properties,
-                batcher_config,
-                batcher_hooks,
-                Box::new(crate::log_sink::GrpcSink::new(uri)),
-            )
+            // Synchronize before anything gets logged, so that all timestamps are corrected.
+            #[cfg(not(target_arch = "wasm32"))]
+            let clock_alignment = clock_sync
+                .map(|(source, options)| {
+                    let sync = re_grpc_client::clock_sync::ClockSync::start(uri.clone(), options)
+                        .map_err(|err| RecordingStreamError::SpawnThread {
+                        name: "clock_sync".into(),
+                        err,
+                    })?;
+                    Ok::<_, RecordingStreamError>(crate::clock_sync::ClockAlignment::new(
+                        source, sync,
+                    ))
+                })
+                .transpose()?;
+
+            let sink = proxy_sink(uri, shared_memory);
+
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                RecordingStream::new_with_clock_alignment(
+                    store_info,
+                    properties,
+                    environment_properties,
+                    batcher_config,
+                    batcher_hooks,
+                    sink,
+                    clock_alignment,
+                )
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            {
+                RecordingStream::new(
+                    store_info,
+                    properties,
+                    environment_properties,
+                    batcher_config,
+                    batcher_hooks,
+                    sink,
+                )
+            }
         } else {
             re_log::debug!("Rerun disabled - call to connect() ignored");
             Ok(RecordingStream::disabled())
@@ -498,11 +746,19 @@ impl RecordingStreamBuilder {
         port: u16,
         server_options: re_grpc_server::ServerOptions,
     ) -> RecordingStreamResult<RecordingStream> {
-        let (enabled, store_info, properties, batcher_config, batcher_hooks) = self.into_args();
+        let (
+            enabled,
+            store_info,
+            properties,
+            environment_properties,
+            batcher_config,
+            batcher_hooks,
+        ) = self.into_args();
         if enabled {
             RecordingStream::new(
                 store_info,
                 properties,
+                environment_properties,
                 batcher_config,
                 batcher_hooks,
                 Box::new(crate::grpc_server::GrpcServerSink::new(
@@ -535,12 +791,20 @@ impl RecordingStreamBuilder {
         self,
         path: impl Into<std::path::PathBuf>,
     ) -> RecordingStreamResult<RecordingStream> {
-        let (enabled, store_info, properties, batcher_config, batcher_hooks) = self.into_args();
+        let (
+            enabled,
+            store_info,
+            properties,
+            environment_properties,
+            batcher_config,
+            batcher_hooks,
+        ) = self.into_args();
 
         if enabled {
             RecordingStream::new(
                 store_info,
                 properties,
+                environment_properties,
                 batcher_config,
                 batcher_hooks,
                 Box::new(crate::sink::FileSink::new(path)?),
@@ -569,12 +833,20 @@ impl RecordingStreamBuilder {
             return self.buffered();
         }
 
-        let (enabled, store_info, properties, batcher_config, batcher_hooks) = self.into_args();
+        let (
+            enabled,
+            store_info,
+            properties,
+            environment_properties,
+            batcher_config,
+            batcher_hooks,
+        ) = self.into_args();
 
         if enabled {
             RecordingStream::new(
                 store_info,
                 properties,
+                environment_properties,
                 batcher_config,
                 batcher_hooks,
                 Box::new(crate::sink::FileSink::stdout()?),
@@ -640,7 +912,7 @@ impl RecordingStreamBuilder {
 
         crate::spawn(opts)?;
 
-        self.connect_grpc_opts(url)
+        self.connect_proxy(url, opts.shared_memory || shared_memory_from_env())
     }
 
     /// Creates a new [`RecordingStream`] that is pre-configured to stream the data through to a
@@ -688,7 +960,14 @@ impl RecordingStreamBuilder {
         server_options: re_grpc_server::ServerOptions,
         open_browser: bool,
     ) -> RecordingStreamResult<RecordingStream> {
-        let (enabled, store_info, recording_info, batcher_config, batcher_hooks) = self.into_args();
+        let (
+            enabled,
+            store_info,
+            recording_info,
+            environment_properties,
+            batcher_config,
+            batcher_hooks,
+        ) = self.into_args();
         if enabled {
             let sink = crate::web_viewer::new_sink(
                 open_browser,
@@ -700,6 +979,7 @@ impl RecordingStreamBuilder {
             RecordingStream::new(
                 store_info,
                 recording_info,
+                environment_properties,
                 batcher_config,
                 batcher_hooks,
                 sink,
@@ -710,19 +990,51 @@ impl RecordingStreamBuilder {
         }
     }
 
-    /// Returns whether or not logging is enabled, a [`StoreInfo`] and the associated batcher
-    /// configuration.
+    /// Returns whether or not logging is enabled, a [`StoreInfo`], the recording properties and the
+    /// associated batcher configuration.
     ///
     /// This can be used to then construct a [`RecordingStream`] manually using
     /// [`RecordingStream::new`].
+    ///
+    /// [`Self::with_clock_sync`] doesn't carry over, which is warned about.
     pub fn into_args(
         self,
     ) -> (
         bool,
         StoreInfo,
         Option<RecordingInfo>,
+        Option<EnvironmentProperties>,
         Option<ChunkBatcherConfig>,
         BatcherHooks,
+    ) {
+        let (args, clock_sync) = self.into_args_and_clock_sync();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some((source, _options)) = clock_sync {
+            re_log::warn!(
+                "Clock synchronization requested for {source:?} is ignored: it only applies to `connect_grpc` and `spawn`"
+            );
+        }
+        #[cfg(target_arch = "wasm32")]
+        let () = clock_sync;
+
+        args
+    }
+
+    /// Same as [`Self::into_args`], along with the settings of [`Self::with_clock_sync`].
+    #[allow(clippy::type_complexity)]
+    fn into_args_and_clock_sync(
+        self,
+    ) -> (
+        (
+            bool,
+            StoreInfo,
+            Option<RecordingInfo>,
+            Option<EnvironmentProperties>,
+            Option<ChunkBatcherConfig>,
+            BatcherHooks,
+        ),
+        ClockSyncArgs,
     ) {
         let enabled = self.is_enabled();
 
@@ -737,8 +1049,14 @@ impl RecordingStreamBuilder {
             batcher_hooks,
             should_send_properties,
             recording_info,
+            environment_properties,
+            #[cfg(not(target_arch = "wasm32"))]
+            clock_sync,
         } = self;
 
+        #[cfg(target_arch = "wasm32")]
+        let clock_sync = ();
+
         let store_id = StoreId::new(
             store_kind,
             application_id,
@@ -757,16 +1075,20 @@ impl RecordingStreamBuilder {
         };
 
         (
-            enabled,
-            store_info,
-            should_send_properties.then_some(recording_info),
-            batcher_config,
-            batcher_hooks,
+            (
+                enabled,
+                store_info,
+                should_send_properties.then_some(recording_info),
+                environment_properties,
+                batcher_config,
+                batcher_hooks,
+            ),
+            clock_sync,
         )
     }
 
     /// Internal check for whether or not logging is enabled using explicit/default settings & env var.
-    fn is_enabled(&self) -> bool {
+    pub(crate) fn is_enabled(&self) -> bool {
         self.enabled
             .unwrap_or_else(|| crate::decide_logging_enabled(self.default_enabled))
     }
@@ -873,6 +1195,12 @@ impl Drop for RecordingStream {
     }
 }
 
+/// Where a [`RecordingStream`] logs how many rows it had to drop, see [`RecordingStream::dropped_rows`].
+///
+/// The counters of each entity are logged as [`re_types::archetypes::Scalars`] at
+/// `__dropped_rows/<entity path>`.
+pub const DROPPED_ROWS_PATH: &str = "__dropped_rows";
+
 struct RecordingStreamInner {
     store_info: StoreInfo,
     recording_info: Option<RecordingInfo>,
@@ -886,6 +1214,10 @@ struct RecordingStreamInner {
     batcher: ChunkBatcher,
     batcher_to_sink_handle: Option<std::thread::JoinHandle<()>>,
 
+    /// Whether the current sink runs on the tokio runtime of whoever created it, see
+    /// [`LogSink::runs_on_caller_runtime`].
+    sink_runs_on_caller_runtime: AtomicBool,
+
     /// It true, any new sink will update the batcher's configuration (as far as possible).
     sink_dependent_batcher_config: bool,
 
@@ -895,6 +1227,10 @@ struct RecordingStreamInner {
     /// See [`RecordingStream::log_file_from_path`] and [`RecordingStream::log_file_from_contents`].
     dataloader_handles: Mutex<Vec<std::thread::JoinHandle<()>>>,
 
+    /// Corrects the timestamps of outgoing chunks, see [`RecordingStreamBuilder::with_clock_sync`].
+    #[cfg(not(target_arch = "wasm32"))]
+    clock_alignment: crate::clock_sync::SharedClockAlignment,
+
     pid_at_creation: u32,
 }
 
@@ -927,7 +1263,13 @@ impl Drop for RecordingStreamInner {
         self.cmds_tx.send(Command::PopPendingChunks).ok();
         self.cmds_tx.send(Command::Shutdown).ok();
         if let Some(handle) = self.batcher_to_sink_handle.take() {
-            handle.join().ok();
+            if self.is_on_sink_runtime() {
+                // The sink can only finish once this runtime gets going again: waiting for it
+                // would deadlock. It keeps flushing in the background instead.
+                re_log::debug!("Detaching the forwarding thread of a sink running on this runtime");
+            } else {
+                handle.join().ok();
+            }
         }
     }
 }
@@ -951,14 +1293,20 @@ impl RecordingStreamInner {
     fn new(
         store_info: StoreInfo,
         recording_info: Option<RecordingInfo>,
+        environment_properties: Option<EnvironmentProperties>,
         batcher_config: Option<ChunkBatcherConfig>,
         batcher_hooks: BatcherHooks,
         sink: Box<dyn LogSink>,
+        #[cfg(not(target_arch = "wasm32"))] clock_alignment: Option<
+            crate::clock_sync::ClockAlignment,
+        >,
     ) -> RecordingStreamResult<Self> {
         let sink_dependent_batcher_config = batcher_config.is_none();
         let batcher_config = resolve_batcher_config(batcher_config, &*sink);
+        let sink_runs_on_caller_runtime = AtomicBool::new(sink.runs_on_caller_runtime());
 
         let on_release = batcher_hooks.on_release.clone();
+        let may_drop_rows = batcher_config.backpressure != BackpressurePolicy::Block;
         let batcher = ChunkBatcher::new(batcher_config, batcher_hooks)?;
 
         {
@@ -977,14 +1325,32 @@ impl RecordingStreamInner {
 
         let (cmds_tx, cmds_rx) = crossbeam::channel::unbounded();
 
+        #[cfg(not(target_arch = "wasm32"))]
+        let clock_alignment: crate::clock_sync::SharedClockAlignment =
+            Arc::new(Mutex::new(clock_alignment));
+
         let batcher_to_sink_handle = {
             const NAME: &str = "RecordingStream::batcher_to_sink";
             std::thread::Builder::new()
                 .name(NAME.into())
                 .spawn({
                     let info = store_info.clone();
-                    let batcher = batcher.clone();
-                    move || forwarding_thread(info, sink, cmds_rx, batcher.chunks(), on_release)
+                    let chunks = batcher.chunks();
+                    let dropped_rows = may_drop_rows.then(|| batcher.dropped_rows());
+                    #[cfg(not(target_arch = "wasm32"))]
+                    let clock_alignment = clock_alignment.clone();
+                    move || {
+                        forwarding_thread(
+                            info,
+                            sink,
+                            cmds_rx,
+                            chunks,
+                            dropped_rows,
+                            on_release,
+                            #[cfg(not(target_arch = "wasm32"))]
+                            clock_alignment,
+                        );
+                    }
                 })
                 .map_err(|err| RecordingStreamError::SpawnThread {
                     name: NAME.into(),
@@ -1005,6 +1371,19 @@ impl RecordingStreamInner {
             batcher.push_chunk(chunk);
         }
 
+        if let Some(environment_properties) = environment_properties.as_ref() {
+            re_log::trace!(
+                ?environment_properties,
+                "Adding EnvironmentProperties to batcher"
+            );
+
+            let chunk = Chunk::builder(EntityPath::environment_properties())
+                .with_archetype(RowId::new(), TimePoint::default(), environment_properties)
+                .build()?;
+
+            batcher.push_chunk(chunk);
+        }
+
         Ok(Self {
             store_info,
             recording_info,
@@ -1012,8 +1391,11 @@ impl RecordingStreamInner {
             cmds_tx,
             batcher,
             batcher_to_sink_handle: Some(batcher_to_sink_handle),
+            sink_runs_on_caller_runtime,
             sink_dependent_batcher_config,
             dataloader_handles: Mutex::new(Vec::new()),
+            #[cfg(not(target_arch = "wasm32"))]
+            clock_alignment,
             pid_at_creation: std::process::id(),
         })
     }
@@ -1023,6 +1405,22 @@ impl RecordingStreamInner {
         self.pid_at_creation != std::process::id()
     }
 
+    /// Whether we're running on a tokio runtime that the current sink relies on, in which case
+    /// nothing may block on the sink.
+    fn is_on_sink_runtime(&self) -> bool {
+        #[cfg(feature = "async")]
+        {
+            self.sink_runs_on_caller_runtime
+                .load(std::sync::atomic::Ordering::Relaxed)
+                && tokio::runtime::Handle::try_current().is_ok()
+        }
+
+        #[cfg(not(feature = "async"))]
+        {
+            false
+        }
+    }
+
     /// Make sure all pending top-level `DataLoader` threads that were started from the SDK run to completion.
     //
     // TODO(cmc): At some point we might want to make it configurable, though I cannot really
@@ -1049,7 +1447,7 @@ enum Command {
     // TODO(#10444): This should go away with more explicit sinks.
     InspectSink(InspectSinkFn),
     Flush {
-        on_done: Sender<FlushResult>,
+        on_done: FlushReply,
         timeout: Duration,
     },
     PopPendingChunks,
@@ -1059,7 +1457,35 @@ enum Command {
 impl Command {
     fn flush(timeout: Duration) -> (Self, Receiver<FlushResult>) {
         let (on_done, rx) = crossbeam::channel::bounded(1); // oneshot
-        (Self::Flush { on_done, timeout }, rx)
+        (
+            Self::Flush {
+                on_done: FlushReply::Blocking(on_done),
+                timeout,
+            },
+            rx,
+        )
+    }
+}
+
+/// Where the result of a [`Command::Flush`] goes.
+pub(crate) enum FlushReply {
+    Blocking(Sender<FlushResult>),
+
+    #[cfg(feature = "async")]
+    Async(tokio::sync::oneshot::Sender<FlushResult>),
+}
+
+impl FlushReply {
+    /// Gives the result back if nobody is waiting for it anymore.
+    fn send(self, result: FlushResult) -> Result<(), FlushResult> {
+        match self {
+            Self::Blocking(on_done) => on_done
+                .send(result)
+                .map_err(|crossbeam::channel::SendError(result)| result),
+
+            #[cfg(feature = "async")]
+            Self::Async(on_done) => on_done.send(result),
+        }
     }
 }
 
@@ -1081,9 +1507,56 @@ impl RecordingStream {
     pub fn new(
         store_info: StoreInfo,
         recording_info: Option<RecordingInfo>,
+        environment_properties: Option<EnvironmentProperties>,
+        batcher_config: Option<ChunkBatcherConfig>,
+        batcher_hooks: BatcherHooks,
+        sink: Box<dyn LogSink>,
+    ) -> RecordingStreamResult<Self> {
+        Self::new_impl(
+            store_info,
+            recording_info,
+            environment_properties,
+            batcher_config,
+            batcher_hooks,
+            sink,
+            #[cfg(not(target_arch = "wasm32"))]
+            None,
+        )
+    }
+
+    /// Same as [`Self::new`], correcting the timestamps of all outgoing chunks from the start, see
+    /// [`RecordingStreamBuilder::with_clock_sync`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn new_with_clock_alignment(
+        store_info: StoreInfo,
+        recording_info: Option<RecordingInfo>,
+        environment_properties: Option<EnvironmentProperties>,
+        batcher_config: Option<ChunkBatcherConfig>,
+        batcher_hooks: BatcherHooks,
+        sink: Box<dyn LogSink>,
+        clock_alignment: Option<crate::clock_sync::ClockAlignment>,
+    ) -> RecordingStreamResult<Self> {
+        Self::new_impl(
+            store_info,
+            recording_info,
+            environment_properties,
+            batcher_config,
+            batcher_hooks,
+            sink,
+            clock_alignment,
+        )
+    }
+
+    fn new_impl(
+        store_info: StoreInfo,
+        recording_info: Option<RecordingInfo>,
+        environment_properties: Option<EnvironmentProperties>,
         batcher_config: Option<ChunkBatcherConfig>,
         batcher_hooks: BatcherHooks,
         sink: Box<dyn LogSink>,
+        #[cfg(not(target_arch = "wasm32"))] clock_alignment: Option<
+            crate::clock_sync::ClockAlignment,
+        >,
     ) -> RecordingStreamResult<Self> {
         let sink = store_info
             .store_id
@@ -1101,9 +1574,12 @@ impl RecordingStream {
         let stream = RecordingStreamInner::new(
             store_info,
             recording_info,
+            environment_properties,
             batcher_config,
             batcher_hooks,
             sink,
+            #[cfg(not(target_arch = "wasm32"))]
+            clock_alignment,
         )
         .map(|inner| Self {
             inner: Either::Left(Arc::new(Some(inner))),
@@ -1166,6 +1642,45 @@ impl RecordingStream {
         self.log_with_static(ent_path, false, as_components)
     }
 
+    /// Logs any [`serde::Serialize`] value to Rerun, so that it can be inspected in the dataframe view.
+    ///
+    /// Each field of a struct is logged as its own component, named after the field, with nested
+    /// structs, sequences, maps and enums mapped to their Arrow equivalents.
+    /// The Arrow datatypes are derived from the [`serde::Deserialize`] implementation of the type, so
+    /// that they are the same for every value logged.
+    /// See [`re_types::DynamicArchetype::from_serde`] for the details of the conversion.
+    ///
+    /// The data will be timestamped automatically based on the [`RecordingStream`]'s internal clock,
+    /// see [`Self::log`].
+    ///
+    /// # Example:
+    /// ```ignore
+    /// # use rerun;
+    /// # let (rec, storage) = rerun::RecordingStreamBuilder::new("rerun_example_log_serde").memory()?;
+    /// #[derive(serde::Serialize, serde::Deserialize)]
+    /// struct RobotState {
+    ///     battery: f32,
+    ///     joints: Vec<f64>,
+    /// }
+    ///
+    /// rec.log_serde(
+    ///     "robot/state",
+    ///     &RobotState {
+    ///         battery: 0.8,
+    ///         joints: vec![0.1, 0.2],
+    ///     },
+    /// )?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn log_serde<T: serde::Serialize + serde::de::DeserializeOwned>(
+        &self,
+        ent_path: impl Into<EntityPath>,
+        value: &T,
+    ) -> RecordingStreamResult<()> {
+        self.log(ent_path, &re_types::DynamicArchetype::from_serde(value)?)
+    }
+
     /// Lower-level logging API to provide data spanning multiple timepoints.
     ///
     /// Unlike the regular `log` API, which is row-oriented, this API lets you submit the data
@@ -1514,7 +2029,9 @@ fn forwarding_thread(
     mut sink: Box<dyn LogSink>,
     cmds_rx: Receiver<Command>,
     chunks: Receiver<Chunk>,
+    dropped_rows: Option<DroppedRows>,
     on_release: Option<ArrowRecordBatchReleaseCallback>,
+    #[cfg(not(target_arch = "wasm32"))] clock_alignment: crate::clock_sync::SharedClockAlignment,
 ) {
     /// Returns `true` to indicate that processing can continue; i.e. `false` means immediate
     /// shutdown.
@@ -1565,9 +2082,7 @@ fn forwarding_thread(
                 let result = sink.flush_blocking(timeout);
 
                 // Send back the result:
-                if let Err(crossbeam::channel::SendError(result)) = on_done.send(result)
-                    && let Err(err) = result
-                {
+                if let Err(Err(err)) = on_done.send(result) {
                     // There was an error, and nobody received it:
                     re_log::error!("Failed to flush sink: {err}");
                 }
@@ -1582,11 +2097,85 @@ fn forwarding_thread(
         true
     }
 
+    /// Logs the number of rows dropped so far for every entity whose count changed since last time.
+    fn send_dropped_rows(
+        store_info: &StoreInfo,
+        sink: &dyn LogSink,
+        dropped_rows: &DroppedRows,
+        reported: &mut IntMap<EntityPath, u64>,
+    ) {
+        let mut timepoint = TimePoint::default();
+        timepoint.insert_cell(TimelineName::log_time(), TimeCell::timestamp_now());
+        for (entity_path, num_dropped) in dropped_rows.counts() {
+            if reported.get(&entity_path) == Some(&num_dropped) {
+                continue;
+            }
+
+            let chunk = Chunk::builder(EntityPath::from(DROPPED_ROWS_PATH).join(&entity_path))
+                .with_archetype(
+                    RowId::new(),
+                    timepoint.clone(),
+                    &re_types::archetypes::Scalars::new([num_dropped as f64]),
+                )
+                .build();
+            match chunk.and_then(|chunk| chunk.to_arrow_msg()) {
+                Ok(msg) => sink.send(LogMsg::ArrowMsg(store_info.store_id.clone(), msg)),
+                Err(err) => {
+                    re_log::error!(%err, "couldn't serialize dropped rows diagnostics (this is a bug in Rerun!)");
+                }
+            }
+
+            reported.insert(entity_path, num_dropped);
+        }
+    }
+
+    /// Corrects the timestamps of `chunk` if clocks are being synchronized, logging the status of
+    /// the synchronization first if it changed.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn align_chunk(
+        store_info: &StoreInfo,
+        sink: &dyn LogSink,
+        clock_alignment: &Mutex<Option<crate::clock_sync::ClockAlignment>>,
+        chunk: Chunk,
+    ) -> Chunk {
+        let mut clock_alignment = clock_alignment.lock();
+        let Some(clock_alignment) = clock_alignment.as_mut() else {
+            return chunk;
+        };
+
+        if let Some(status) = clock_alignment.new_status_chunk() {
+            match status.and_then(|chunk| chunk.to_arrow_msg()) {
+                Ok(msg) => sink.send(LogMsg::ArrowMsg(store_info.store_id.clone(), msg)),
+                Err(err) => {
+                    re_log::error!(%err, "couldn't serialize clock sync diagnostics (this is a bug in Rerun!)");
+                }
+            }
+        }
+
+        match clock_alignment.align_chunk(&chunk) {
+            Ok(aligned) => aligned,
+            Err(err) => {
+                re_log::error!(%err, "couldn't align chunk timestamps; sent as is (this is a bug in Rerun!)");
+                chunk
+            }
+        }
+    }
+
+    let dropped_rows_tick = if dropped_rows.is_some() {
+        crossbeam::channel::tick(Duration::from_secs(1))
+    } else {
+        crossbeam::channel::never()
+    };
+    let mut reported_dropped_rows = IntMap::default();
+
     use crossbeam::select;
     loop {
         // NOTE: Always pop chunks first, this is what makes `Command::PopPendingChunks` possible,
         // which in turns makes `RecordingStream::flush_blocking` well defined.
         while let Ok(chunk) = chunks.try_recv() {
+            #[cfg(not(target_arch = "wasm32"))]
+            let chunk = align_chunk(&store_info, sink.as_ref(), &clock_alignment, chunk);
+
             let mut msg = match chunk.to_arrow_msg() {
                 Ok(chunk) => chunk,
                 Err(err) => {
@@ -1607,6 +2196,9 @@ fn forwarding_thread(
                     break;
                 };
 
+                #[cfg(not(target_arch = "wasm32"))]
+                let chunk = align_chunk(&store_info, sink.as_ref(), &clock_alignment, chunk);
+
                 let msg = match chunk.to_arrow_msg() {
                     Ok(chunk) => chunk,
                     Err(err) => {
@@ -1629,6 +2221,12 @@ fn forwarding_thread(
                     break; // shutdown
                 }
             }
+
+            recv(dropped_rows_tick) -> _ => {
+                if let Some(dropped_rows) = dropped_rows.as_ref() {
+                    send_dropped_rows(&store_info, sink.as_ref(), dropped_rows, &mut reported_dropped_rows);
+                }
+            }
         }
 
         // NOTE: The receiving end of the command stream is owned solely by this thread.
@@ -1637,6 +2235,18 @@ fn forwarding_thread(
 }
 
 impl RecordingStream {
+    /// The number of rows dropped so far because the pipeline could not keep up, per entity.
+    ///
+    /// Rows are only ever dropped if the batcher is configured with a non-blocking
+    /// [`ChunkBatcherConfig::backpressure`] policy, in which case these counters are also logged
+    /// as diagnostics under [`DROPPED_ROWS_PATH`], every second.
+    ///
+    /// Returns `None` if logging is disabled.
+    #[inline]
+    pub fn dropped_rows(&self) -> Option<IntMap<EntityPath, u64>> {
+        self.with(|inner| inner.batcher.dropped_rows().counts())
+    }
+
     /// Check if logging is enabled on this `RecordingStream`.
     ///
     /// If not, all recording calls will be ignored.
@@ -1853,6 +2463,11 @@ impl RecordingStream {
                 inner.batcher.update_config(batcher_config);
             }
 
+            inner.sink_runs_on_caller_runtime.store(
+                new_sink.runs_on_caller_runtime(),
+                std::sync::atomic::Ordering::Relaxed,
+            );
+
             // Swap the sink, which will internally make sure to re-ingest the backlog if needed
             inner
                 .cmds_tx
@@ -1922,10 +2537,37 @@ impl RecordingStream {
     ///
     /// See [`RecordingStream`] docs for ordering semantics and multithreading guarantees.
     fn flush(&self, timeout: Option<Duration>) -> Result<(), SinkFlushError> {
+        let (on_done, rx) = crossbeam::channel::bounded(1); // oneshot
+        match self.start_flush(FlushReply::Blocking(on_done)) {
+            Some(Ok(())) => {}
+            Some(Err(err)) => return Err(err),
+            None => {
+                re_log::warn_once!("Recording disabled - call to flush ignored");
+                return Ok(());
+            }
+        }
+
+        if let Some(timeout) = timeout {
+            rx.recv_timeout(timeout).map_err(|err| match err {
+                RecvTimeoutError::Timeout => SinkFlushError::Timeout,
+                RecvTimeoutError::Disconnected => SinkFlushError::failed(
+                    "Flush never finished. This is likely a bug in the Rerun SDK.",
+                ),
+            })??;
+        }
+
+        Ok(())
+    }
+
+    /// Flushes the batching pipeline, and has the sink flushed once everything that preceded has
+    /// been sent to it, replying through `on_done`.
+    ///
+    /// Returns `None` if the recording is disabled, in which case there will be no reply.
+    pub(crate) fn start_flush(&self, on_done: FlushReply) -> Option<Result<(), SinkFlushError>> {
         if self.is_forked_child() {
-            return Err(SinkFlushError::failed(
+            return Some(Err(SinkFlushError::failed(
                 "Fork detected during flush. cleanup_if_forked() should always be called after forking. This is likely a bug in the Rerun SDK.",
-            ));
+            )));
         }
 
         let f = move |inner: &RecordingStreamInner| -> Result<(), SinkFlushError> {
@@ -1953,33 +2595,18 @@ impl RecordingStream {
                 })?;
 
             // 3. Asynchronously flush everything down the sink
-            let (cmd, on_done) = Command::flush(Duration::MAX); // The background thread should block forever if necessary
+            let cmd = Command::Flush {
+                on_done,
+                timeout: Duration::MAX, // The background thread should block forever if necessary
+            };
             inner.cmds_tx.send(cmd).map_err(|_ignored| {
                 SinkFlushError::failed(
                     "Sink shut down prematurely. This is likely a bug in the Rerun SDK.",
                 )
-            })?;
-
-            if let Some(timeout) = timeout {
-                on_done.recv_timeout(timeout).map_err(|err| match err {
-                    RecvTimeoutError::Timeout => SinkFlushError::Timeout,
-                    RecvTimeoutError::Disconnected => SinkFlushError::failed(
-                        "Flush never finished. This is likely a bug in the Rerun SDK.",
-                    ),
-                })??;
-            }
-
-            Ok(())
+            })
         };
 
-        match self.with(f) {
-            Some(Ok(())) => Ok(()),
-            Some(Err(err)) => Err(err),
-            None => {
-                re_log::warn_once!("Recording disabled - call to flush ignored");
-                Ok(())
-            }
-        }
+        self.with(f)
     }
 }
 
@@ -2048,7 +2675,19 @@ impl RecordingStream {
     /// `flush_timeout` is the minimum time the [`GrpcSink`][`crate::log_sink::GrpcSink`] will
     /// wait during a flush before potentially dropping data. Note: Passing `None` here can cause a
     /// call to `flush` to block indefinitely if a connection cannot be established.
+    ///
+    /// If the `RERUN_SHARED_MEMORY` environment variable is set to `1` and `url` points to this
+    /// machine, the data is sent through shared memory instead whenever possible, see
+    /// [`crate::sink::SharedMemorySink`].
     pub fn connect_grpc_opts(&self, url: impl Into<String>) -> RecordingStreamResult<()> {
+        self.connect_proxy(url, shared_memory_from_env())
+    }
+
+    fn connect_proxy(
+        &self,
+        url: impl Into<String>,
+        shared_memory: bool,
+    ) -> RecordingStreamResult<()> {
         if forced_sink_path().is_some() {
             re_log::debug!("Ignored setting new GrpcSink since {ENV_FORCE_SAVE} is set");
             return Ok(());
@@ -2059,9 +2698,7 @@ impl RecordingStream {
             return Err(RecordingStreamError::NotAProxyEndpoint);
         };
 
-        let sink = crate::log_sink::GrpcSink::new(uri);
-
-        self.set_sink(Box::new(sink));
+        self.set_sink(proxy_sink(uri, shared_memory));
         Ok(())
     }
 
@@ -2155,7 +2792,10 @@ impl RecordingStream {
 
         crate::spawn(opts)?;
 
-        self.connect_grpc_opts(format!("rerun+http://{}/proxy", opts.connect_addr()))?;
+        self.connect_proxy(
+            format!("rerun+http://{}/proxy", opts.connect_addr()),
+            opts.shared_memory || shared_memory_from_env(),
+        )?;
 
         Ok(())
     }
@@ -2327,6 +2967,8 @@ impl fmt::Debug for RecordingStream {
                 batcher_to_sink_handle: _,
                 sink_dependent_batcher_config,
                 dataloader_handles,
+                #[cfg(not(target_arch = "wasm32"))]
+                    clock_alignment: _,
                 pid_at_creation,
             } = inner;
 
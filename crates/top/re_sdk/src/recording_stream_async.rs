@@ -0,0 +1,335 @@
+//! Async variants of the [`RecordingStream`] APIs, for use from within tokio applications.
+//!
+//! These run on the caller's runtime rather than spawning their own threads and runtimes, and
+//! never block it.
+//!
+//! Logging itself never performs any I/O, but it will block the calling thread if the pipeline is
+//! full and the batcher is configured with [`re_chunk::BackpressurePolicy::Block`] (the default).
+//! Async applications that log a lot of data should either use [`RecordingStream::send_columns_stream`],
+//! which awaits the sink rather than blocking, or configure a non-blocking
+//! [`re_chunk::BackpressurePolicy`].
+
+use futures_util::{Stream, StreamExt as _};
+
+use re_chunk::TimeColumn;
+use re_log_types::EntityPath;
+use re_types::SerializedComponentColumn;
+
+use crate::{
+    RecordingStream, RecordingStreamBuilder, RecordingStreamError, RecordingStreamResult,
+    forced_sink_path,
+    recording_stream::FlushReply,
+    sink::{GrpcSink, LogSink, SinkFlushError},
+};
+
+impl RecordingStreamBuilder {
+    /// Creates a new [`RecordingStream`] that is pre-configured to stream the data through to a
+    /// remote Rerun instance, and waits for the connection to be established.
+    ///
+    /// The connection runs on the current tokio runtime.
+    ///
+    /// Unlike [`Self::connect_grpc_opts`], this fails if the remote Rerun instance cannot be reached.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let rec = re_sdk::RecordingStreamBuilder::new("rerun_example_app")
+    ///     .connect_grpc_opts_async("rerun+http://127.0.0.1:9876/proxy")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn connect_grpc_opts_async(
+        self,
+        url: impl Into<String>,
+    ) -> RecordingStreamResult<RecordingStream> {
+        let (
+            enabled,
+            store_info,
+            properties,
+            environment_properties,
+            batcher_config,
+            batcher_hooks,
+        ) = self.into_args();
+        if enabled {
+            let sink = new_connected_grpc_sink(url.into()).await?;
+
+            RecordingStream::new(
+                store_info,
+                properties,
+                environment_properties,
+                batcher_config,
+                batcher_hooks,
+                Box::new(sink),
+            )
+        } else {
+            re_log::debug!("Rerun disabled - call to connect() ignored");
+            Ok(RecordingStream::disabled())
+        }
+    }
+
+    /// Spawns a new Rerun Viewer process from an executable available in PATH, then creates a new
+    /// [`RecordingStream`] that is pre-configured to stream the data through to that viewer over gRPC,
+    /// and waits for the connection to be established.
+    ///
+    /// This is the async version of [`Self::spawn_opts`], see [`Self::connect_grpc_opts_async`].
+    pub async fn spawn_opts_async(
+        self,
+        opts: &crate::SpawnOptions,
+    ) -> RecordingStreamResult<RecordingStream> {
+        if !self.is_enabled() {
+            re_log::debug!("Rerun disabled - call to spawn() ignored");
+            return Ok(RecordingStream::disabled());
+        }
+
+        let url = format!("rerun+http://{}/proxy", opts.connect_addr());
+
+        // NOTE: If `_RERUN_TEST_FORCE_SAVE` is set, all recording streams will write to disk no matter
+        // what, thus spawning a viewer is pointless (and probably not intended).
+        if forced_sink_path().is_none() {
+            spawn_viewer(opts).await?;
+        }
+
+        self.connect_grpc_opts_async(url).await
+    }
+}
+
+impl RecordingStream {
+    /// Swaps the underlying sink for a [`GrpcSink`] connected to the specified address, and waits
+    /// for the connection to be established.
+    ///
+    /// The connection runs on the current tokio runtime.
+    ///
+    /// This is the async version of [`Self::connect_grpc_opts`]: unlike it, this fails if the
+    /// remote Rerun instance cannot be reached, in which case the current sink is left untouched.
+    pub async fn connect_grpc_opts_async(
+        &self,
+        url: impl Into<String>,
+    ) -> RecordingStreamResult<()> {
+        if forced_sink_path().is_some() {
+            re_log::debug!("Ignored setting new GrpcSink since _RERUN_TEST_FORCE_SAVE is set");
+            return Ok(());
+        }
+
+        let sink = new_connected_grpc_sink(url.into()).await?;
+        self.set_sink_async(Box::new(sink)).await
+    }
+
+    /// Swaps the underlying sink for a [`crate::grpc_server::GrpcServerSink`] listening on
+    /// `rerun+http://{bind_ip}:{port}/proxy`.
+    ///
+    /// The gRPC server runs on the current tokio runtime.
+    ///
+    /// This is the async version of [`Self::serve_grpc_opts`].
+    #[cfg(feature = "server")]
+    pub async fn serve_grpc_opts_async(
+        &self,
+        bind_ip: impl AsRef<str>,
+        port: u16,
+        server_options: re_grpc_server::ServerOptions,
+    ) -> RecordingStreamResult<()> {
+        if forced_sink_path().is_some() {
+            re_log::debug!("Ignored setting GrpcServerSink since _RERUN_TEST_FORCE_SAVE is set");
+            return Ok(());
+        }
+
+        let sink = crate::grpc_server::GrpcServerSink::new_in_runtime(
+            bind_ip.as_ref(),
+            port,
+            server_options,
+            &tokio::runtime::Handle::current(),
+        )?;
+        self.set_sink_async(Box::new(sink)).await
+    }
+
+    /// Spawns a new Rerun Viewer process from an executable available in PATH, then swaps the
+    /// underlying sink for a [`GrpcSink`] connected to it, and waits for the connection to be
+    /// established.
+    ///
+    /// This is the async version of [`Self::spawn_opts`], see [`Self::connect_grpc_opts_async`].
+    pub async fn spawn_opts_async(&self, opts: &crate::SpawnOptions) -> RecordingStreamResult<()> {
+        if !self.is_enabled() {
+            re_log::debug!("Rerun disabled - call to spawn() ignored");
+            return Ok(());
+        }
+        if forced_sink_path().is_some() {
+            re_log::debug!("Ignored setting new GrpcSink since _RERUN_TEST_FORCE_SAVE is set");
+            return Ok(());
+        }
+
+        spawn_viewer(opts).await?;
+
+        self.connect_grpc_opts_async(format!("rerun+http://{}/proxy", opts.connect_addr()))
+            .await
+    }
+
+    /// Flushes the batching pipeline, and waits for it to propagate all the way through the sink.
+    ///
+    /// This is the async version of [`Self::flush_blocking`]. Use e.g. [`tokio::time::timeout`]
+    /// to bound how long to wait for.
+    ///
+    /// Awaiting this regularly is a simple way to apply backpressure: the caller never gets ahead
+    /// of what the sink can keep up with.
+    pub async fn flush_await(&self) -> Result<(), SinkFlushError> {
+        let (on_done, done) = tokio::sync::oneshot::channel();
+        match self.start_flush(FlushReply::Async(on_done)) {
+            Some(Ok(())) => {}
+            Some(Err(err)) => return Err(err),
+            None => {
+                re_log::warn_once!("Recording disabled - call to flush ignored");
+                return Ok(());
+            }
+        }
+
+        done.await.map_err(|_ignored| {
+            SinkFlushError::failed("Flush never finished. This is likely a bug in the Rerun SDK.")
+        })?
+    }
+
+    /// Sends the columns yielded by a [`Stream`] to the sink, see [`Self::send_columns`].
+    ///
+    /// Every item of the stream is sent as its own chunk, and the stream is only polled again
+    /// once that chunk has been flushed through the sink, so that slow sinks apply backpressure
+    /// to the stream instead of data piling up in memory.
+    pub async fn send_columns_stream(
+        &self,
+        ent_path: impl Into<EntityPath>,
+        columns: impl Stream<Item = (Vec<TimeColumn>, Vec<SerializedComponentColumn>)>,
+    ) -> RecordingStreamResult<()> {
+        let ent_path = ent_path.into();
+
+        let mut columns = std::pin::pin!(columns);
+        while let Some((indexes, components)) = columns.next().await {
+            self.send_columns(ent_path.clone(), indexes, components)?;
+            self.flush_await().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Swaps the underlying sink without blocking the runtime, see [`Self::set_sink`].
+    async fn set_sink_async(&self, sink: Box<dyn LogSink>) -> RecordingStreamResult<()> {
+        // Swapping sinks waits for the previous one to be flushed, which might need this runtime.
+        let rec = self.clone();
+        tokio::task::spawn_blocking(move || rec.set_sink(sink)).await?;
+        Ok(())
+    }
+}
+
+async fn new_connected_grpc_sink(url: String) -> RecordingStreamResult<GrpcSink> {
+    let re_uri::RedapUri::Proxy(uri) = url.as_str().parse()? else {
+        return Err(RecordingStreamError::NotAProxyEndpoint);
+    };
+
+    let sink = GrpcSink::new_in_runtime(uri, &tokio::runtime::Handle::current());
+    sink.wait_for_connection().await?;
+
+    Ok(sink)
+}
+
+async fn spawn_viewer(opts: &crate::SpawnOptions) -> RecordingStreamResult<()> {
+    // Spawning waits for the viewer to start listening.
+    let opts = opts.clone();
+    tokio::task::spawn_blocking(move || crate::spawn(&opts)).await??;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Runs the given future on a current-thread runtime, failing rather than hanging if it
+    /// deadlocks.
+    fn run_on_current_thread<F>(f: impl FnOnce() -> F + Send + 'static)
+    where
+        F: Future<Output = ()>,
+    {
+        let (done_tx, done_rx) = crossbeam::channel::bounded(1);
+        let thread = std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            runtime.block_on(f());
+            done_tx.send(()).ok();
+        });
+
+        match done_rx.recv_timeout(Duration::from_secs(30)) {
+            Ok(()) => {}
+            Err(crossbeam::channel::RecvTimeoutError::Timeout) => {
+                panic!("Deadlocked on a current-thread runtime");
+            }
+            Err(crossbeam::channel::RecvTimeoutError::Disconnected) => {
+                if let Err(err) = thread.join() {
+                    std::panic::resume_unwind(err);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn flush_await() {
+        run_on_current_thread(|| async {
+            let (rec, storage) = RecordingStreamBuilder::new("rerun_example_flush_await")
+                .enabled(true)
+                .batcher_config(re_chunk::ChunkBatcherConfig::NEVER)
+                .memory()
+                .unwrap();
+
+            rec.log("scalar", &re_types::archetypes::Scalars::new([1.0]))
+                .unwrap();
+            rec.flush_await().await.unwrap();
+
+            let has_scalar = storage.take().iter().any(|msg| {
+                let re_log_types::LogMsg::ArrowMsg(_, msg) = msg else {
+                    return false;
+                };
+                re_chunk::Chunk::from_arrow_msg(msg)
+                    .is_ok_and(|chunk| chunk.entity_path() == &EntityPath::from("scalar"))
+            });
+            assert!(has_scalar, "The flush didn't make it through the sink");
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn drop_on_current_thread_runtime() {
+        run_on_current_thread(|| async {
+            let port = std::net::TcpListener::bind("127.0.0.1:0")
+                .unwrap()
+                .local_addr()
+                .unwrap()
+                .port();
+
+            // Both the server and the client run on this very runtime.
+            let server = RecordingStreamBuilder::new("rerun_example_drop_on_current_thread")
+                .enabled(true)
+                .buffered()
+                .unwrap();
+            server
+                .serve_grpc_opts_async("127.0.0.1", port, Default::default())
+                .await
+                .unwrap();
+
+            let rec = RecordingStreamBuilder::new("rerun_example_drop_on_current_thread")
+                .enabled(true)
+                .connect_grpc_opts_async(format!("rerun+http://127.0.0.1:{port}/proxy"))
+                .await
+                .unwrap();
+
+            rec.log("scalar", &re_types::archetypes::Scalars::new([1.0]))
+                .unwrap();
+            rec.flush_await().await.unwrap();
+            drop(rec);
+
+            server
+                .log("scalar", &re_types::archetypes::Scalars::new([2.0]))
+                .unwrap();
+            server.flush_await().await.unwrap();
+            drop(server);
+        });
+    }
+}
@@ -0,0 +1,325 @@
+//! Replaying existing recordings through a [`RecordingStream`].
+
+use std::time::{Duration, Instant};
+
+use re_chunk::{Chunk, ChunkId, RowId, TimeColumn};
+use re_log_types::{LogMsg, TimelineName};
+
+use crate::{RecordingStream, RecordingStreamError, RecordingStreamResult};
+
+/// Options for [`RecordingStream::replay_rrd`].
+#[derive(Debug, Clone)]
+pub struct ReplayOptions {
+    /// If `true`, rows are sent at the pace at which they were originally logged, as given by
+    /// the gaps in their `log_time` timeline.
+    ///
+    /// Otherwise, chunks are sent as fast as possible.
+    ///
+    /// Defaults to `true`.
+    pub realtime: bool,
+
+    /// Multiplies the pace of a [`Self::realtime`] replay, e.g. `2.0` replays twice as fast.
+    ///
+    /// Also scales the gaps between rewritten `log_time`s, see [`Self::rewrite_log_time`].
+    ///
+    /// Must be a finite, positive number. Defaults to `1.0`.
+    pub speed: f64,
+
+    /// How many times to replay the recording, or `None` to replay it forever.
+    ///
+    /// Defaults to `Some(1)`.
+    pub num_loops: Option<u32>,
+
+    /// If `true`, the `log_time` timeline is shifted so that each replay starts at the current
+    /// time, as if the data was being logged live.
+    ///
+    /// Defaults to `true`.
+    pub rewrite_log_time: bool,
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        Self {
+            realtime: true,
+            speed: 1.0,
+            num_loops: Some(1),
+            rewrite_log_time: true,
+        }
+    }
+}
+
+/// Where the current loop of a replay started, both in wall-clock time and in recording time.
+struct ReplayOrigin {
+    instant: Instant,
+    now_nanos: i64,
+    log_time_nanos: i64,
+}
+
+impl RecordingStream {
+    /// Replays the recordings contained in an existing `.rrd` file through this stream.
+    ///
+    /// All recordings in the file are merged into this one, thereby taking its application and
+    /// recording ids. Blueprints are skipped.
+    ///
+    /// Every chunk is re-sent with new chunk and row ids, so that looping over the same file
+    /// doesn't produce duplicates. Timelines other than `log_time` are left untouched.
+    ///
+    /// This blocks the calling thread until the replay is over, see [`ReplayOptions`].
+    /// Like any other logging call, this doesn't wait for the data to be flushed.
+    pub fn replay_rrd(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        options: &ReplayOptions,
+    ) -> RecordingStreamResult<()> {
+        if !(options.speed.is_finite() && 0.0 < options.speed) {
+            return Err(RecordingStreamError::InvalidReplaySpeed(options.speed));
+        }
+
+        let path = path.as_ref();
+
+        let mut loop_index = 0;
+        while options
+            .num_loops
+            .is_none_or(|num_loops| loop_index < num_loops)
+        {
+            re_log::debug!(?path, loop_index, "Replaying recording");
+
+            let file =
+                std::fs::File::open(path).map_err(re_log_encoding::decoder::DecodeError::Read)?;
+            let decoder = re_log_encoding::decoder::Decoder::new(std::io::BufReader::new(file))?;
+
+            let mut origin = None;
+            for msg in decoder {
+                let LogMsg::ArrowMsg(store_id, arrow_msg) = msg? else {
+                    continue;
+                };
+                if store_id.is_blueprint() {
+                    continue;
+                }
+
+                let chunk = Chunk::from_arrow_msg(&arrow_msg)?;
+                if options.realtime {
+                    for chunk in split_by_log_time(&chunk) {
+                        self.send_chunk(replay_chunk(&chunk, &mut origin, options)?);
+                    }
+                } else {
+                    self.send_chunk(replay_chunk(&chunk, &mut origin, options)?);
+                }
+            }
+
+            loop_index += 1;
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits a chunk into runs of rows sharing the same `log_time`, in `log_time` order, so that
+/// each of them can be paced on its own.
+fn split_by_log_time(chunk: &Chunk) -> Vec<Chunk> {
+    let chunk = chunk.sorted_by_timeline_if_unsorted(&TimelineName::log_time());
+    let Some(log_time) = chunk.timelines().get(&TimelineName::log_time()) else {
+        return vec![chunk];
+    };
+
+    let runs = log_time
+        .times_raw()
+        .chunk_by(|a, b| a == b)
+        .map(<[i64]>::len)
+        .collect::<Vec<_>>();
+    if runs.len() <= 1 {
+        return vec![chunk];
+    }
+
+    let mut start = 0;
+    runs.into_iter()
+        .map(|len| {
+            let run = chunk.row_sliced(start, len);
+            start += len;
+            run
+        })
+        .collect()
+}
+
+/// Paces the replay of a chunk, then returns a copy of it ready to be sent.
+///
+/// The chunk is sent once its earliest `log_time` is due.
+fn replay_chunk(
+    chunk: &Chunk,
+    origin: &mut Option<ReplayOrigin>,
+    options: &ReplayOptions,
+) -> RecordingStreamResult<Chunk> {
+    let mut chunk = chunk.clone_as(ChunkId::new(), RowId::new());
+
+    let Some(log_time) = chunk.timelines().get(&TimelineName::log_time()).cloned() else {
+        // Static data, or data without any notion of wall-clock time: send it right away.
+        return Ok(chunk);
+    };
+
+    let start_nanos = log_time.time_range().min().as_i64();
+    let origin = origin.get_or_insert_with(|| ReplayOrigin {
+        instant: Instant::now(),
+        now_nanos: re_log_types::Timestamp::now().nanos_since_epoch(),
+        log_time_nanos: start_nanos,
+    });
+
+    // How far into the replay a given `log_time` is, in wall-clock time.
+    let replay_offset_nanos = |log_time_nanos: i64| {
+        (log_time_nanos.saturating_sub(origin.log_time_nanos) as f64 / options.speed) as i64
+    };
+
+    if options.realtime {
+        let target = Duration::from_nanos(replay_offset_nanos(start_nanos).max(0) as u64);
+        if let Some(wait) = target.checked_sub(origin.instant.elapsed()) {
+            std::thread::sleep(wait);
+        }
+    }
+
+    if options.rewrite_log_time {
+        let times = log_time
+            .times_raw()
+            .iter()
+            .map(|&time| origin.now_nanos.saturating_add(replay_offset_nanos(time)))
+            .collect::<Vec<_>>();
+
+        chunk.add_timeline(TimeColumn::new(
+            Some(log_time.is_sorted()),
+            *log_time.timeline(),
+            times.into(),
+        ))?;
+    }
+
+    Ok(chunk)
+}
+
+#[cfg(test)]
+mod tests {
+    use re_log_types::{EntityPath, TimeCell, Timeline};
+
+    use super::*;
+    use crate::RecordingStreamBuilder;
+
+    const BASE_NANOS: i64 = 1_700_000_000_000_000_000;
+    const MILLIS: i64 = 1_000_000;
+
+    /// Writes a recording made of a single chunk, whose rows were logged out of `log_time` order.
+    fn write_rrd(path: &std::path::Path) {
+        let rec = RecordingStreamBuilder::new("rerun_example_replay")
+            .enabled(true)
+            .save(path)
+            .unwrap();
+
+        let chunk = [200, 0, 100]
+            .into_iter()
+            .fold(Chunk::builder("scalar"), |builder, millis| {
+                builder.with_archetype(
+                    RowId::new(),
+                    [(
+                        Timeline::log_time(),
+                        TimeCell::from_timestamp_nanos_since_epoch(BASE_NANOS + millis * MILLIS),
+                    )],
+                    &re_types::archetypes::Scalars::new([millis as f64]),
+                )
+            })
+            .build()
+            .unwrap();
+        rec.send_chunk(chunk);
+        rec.flush_blocking().unwrap();
+    }
+
+    /// Replays the recording, and returns the `log_time`s of each of the resulting chunks.
+    fn replay(path: &std::path::Path, options: &ReplayOptions) -> Vec<Vec<i64>> {
+        let (rec, storage) = RecordingStreamBuilder::new("rerun_example_replay")
+            .enabled(true)
+            .memory()
+            .unwrap();
+        rec.replay_rrd(path, options).unwrap();
+        rec.flush_blocking().unwrap();
+
+        storage
+            .take()
+            .iter()
+            .filter_map(|msg| {
+                let LogMsg::ArrowMsg(_, msg) = msg else {
+                    return None;
+                };
+                let chunk = Chunk::from_arrow_msg(msg).ok()?;
+                if chunk.entity_path() != &EntityPath::from("scalar") {
+                    return None;
+                }
+                let log_time = chunk.timelines().get(&TimelineName::log_time())?;
+                Some(log_time.times_raw().to_vec())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn realtime() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recording.rrd");
+        write_rrd(&path);
+
+        let start = Instant::now();
+        let chunks = replay(
+            &path,
+            &ReplayOptions {
+                speed: 10.0,
+                ..Default::default()
+            },
+        );
+
+        // Every row is paced on its own, in `log_time` order.
+        assert!(Duration::from_millis(20) <= start.elapsed());
+        assert_eq!(3, chunks.len(), "{chunks:?}");
+        let times = chunks.concat();
+        assert_eq!(
+            vec![0, 10 * MILLIS, 20 * MILLIS],
+            times.iter().map(|time| time - times[0]).collect::<Vec<_>>()
+        );
+        assert!(BASE_NANOS < times[0]);
+    }
+
+    #[test]
+    fn as_fast_as_possible() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recording.rrd");
+        write_rrd(&path);
+
+        let chunks = replay(
+            &path,
+            &ReplayOptions {
+                realtime: false,
+                num_loops: Some(2),
+                rewrite_log_time: false,
+                ..Default::default()
+            },
+        );
+
+        let original = [200, 0, 100]
+            .map(|millis| BASE_NANOS + millis * MILLIS)
+            .to_vec();
+        assert_eq!(vec![original.clone(), original], chunks);
+    }
+
+    #[test]
+    fn invalid_speed() {
+        let (rec, _storage) = RecordingStreamBuilder::new("rerun_example_replay")
+            .enabled(true)
+            .memory()
+            .unwrap();
+
+        for speed in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let options = ReplayOptions {
+                speed,
+                ..Default::default()
+            };
+            assert!(
+                matches!(
+                    rec.replay_rrd("missing.rrd", &options),
+                    Err(RecordingStreamError::InvalidReplaySpeed(_))
+                ),
+                "{speed}"
+            );
+        }
+    }
+}
@@ -61,6 +61,16 @@ pub struct SpawnOptions {
 
     /// Detach Rerun Viewer process from the application process.
     pub detach_process: bool,
+
+    /// Send the data to the Rerun Viewer through shared memory rather than gRPC, if possible.
+    ///
+    /// This skips the socket and the HTTP/2 framing, but messages are still encoded and copied.
+    /// Falls back to gRPC if the Viewer doesn't accept a shared-memory connection in time.
+    /// See [`crate::sink::SharedMemorySink`].
+    ///
+    /// Defaults to `false`. Setting the `RERUN_SHARED_MEMORY` environment variable to `1` enables
+    /// it regardless.
+    pub shared_memory: bool,
 }
 
 // NOTE: No need for .exe extension on windows.
@@ -79,6 +89,7 @@ impl Default for SpawnOptions {
             extra_env: Vec::new(),
             hide_welcome_screen: false,
             detach_process: true,
+            shared_memory: false,
         }
     }
 }
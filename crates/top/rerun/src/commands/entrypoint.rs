@@ -106,6 +106,16 @@ Example: `16GB` or `50%` (of system total)."
     )]
     memory_limit: String,
 
+    #[clap(
+        long,
+        value_name = "[ENTITY:]TIMELINE=AMOUNT",
+        long_help = r"Only keep the most recent data of each recording on the given timeline, regardless of `--memory-limit`.
+Data is dropped as soon as it falls out of the retention window.
+Can be specified multiple times, and optionally scoped to an entity subtree.
+Example: `log_time=10min` or `/camera:frame=5000`."
+    )]
+    retention: Vec<re_chunk_store::RetentionPolicy>,
+
     #[clap(
         long,
         default_value = None,
@@ -965,6 +975,7 @@ fn native_startup_options_from_args(args: &Args) -> anyhow::Result<re_viewer::St
             re_memory::MemoryLimit::parse(&args.memory_limit)
                 .map_err(|err| anyhow::format_err!("Bad --memory-limit: {err}"))?
         },
+        retention: args.retention.clone(),
         persist_state: args.persist_state,
         is_in_notebook: false,
         screenshot_to_path_then_quit: args.screenshot_to.clone(),
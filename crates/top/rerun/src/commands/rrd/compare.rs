@@ -141,7 +141,7 @@ impl CompareCommand {
 /// `Chunk`s.
 ///
 /// Fails if there are more than one data recordings present in the rrd file.
-fn load_chunks(
+pub(super) fn load_chunks(
     path_to_rrd: &Path,
     ignore_chunks_without_components: bool,
 ) -> anyhow::Result<(re_log_types::ApplicationId, Vec<Arc<re_chunk::Chunk>>)> {
@@ -0,0 +1,737 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use arrow::{
+    array::{Array as _, ArrayRef, AsArray as _, Float64Array, ListArray, MutableArrayData},
+    buffer::OffsetBuffer,
+    datatypes::{DataType, Field, Float64Type},
+};
+
+use re_chunk::{Chunk, ChunkComponents, TimelineName};
+use re_log_types::{AbsoluteTimeRange, TimePoint};
+use re_sdk::{ComponentDescriptor, EntityPath};
+
+use super::compare::load_chunks;
+
+// ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum DiffFormat {
+    /// One line per difference.
+    Text,
+
+    /// A single JSON document.
+    Json,
+}
+
+#[derive(Debug, Clone, clap::Parser)]
+pub struct DiffCommand {
+    path_to_rrd1: String,
+    path_to_rrd2: String,
+
+    /// Maximum absolute difference between two numeric values for them to still be considered equal.
+    #[clap(long, default_value_t = 0.0)]
+    tolerance: f64,
+
+    /// Names of the timelines to ignore during the comparison.
+    #[clap(long = "ignore-timeline", default_values_t = [String::from("log_time")])]
+    ignored_timelines: Vec<String>,
+
+    /// How the report should be formatted.
+    #[clap(long, value_enum, default_value_t = DiffFormat::Text)]
+    format: DiffFormat,
+}
+
+impl DiffCommand {
+    /// Computes a per-entity/per-component report of all the differences between two .rrd files.
+    ///
+    /// Prints the report to standard output, and returns an error if any difference was found.
+    pub fn run(&self) -> anyhow::Result<()> {
+        let Self {
+            path_to_rrd1,
+            path_to_rrd2,
+            tolerance,
+            ignored_timelines,
+            format,
+        } = self;
+
+        re_log::debug!("Diffing {path_to_rrd1:?} against {path_to_rrd2:?}…");
+
+        let path_to_rrd1 = PathBuf::from(path_to_rrd1);
+        let path_to_rrd2 = PathBuf::from(path_to_rrd2);
+
+        let ignore_chunks_without_components = true;
+        let (app_id1, chunks1) = load_chunks(&path_to_rrd1, ignore_chunks_without_components)
+            .with_context(|| format!("path: {path_to_rrd1:?}"))?;
+        let (app_id2, chunks2) = load_chunks(&path_to_rrd2, ignore_chunks_without_components)
+            .with_context(|| format!("path: {path_to_rrd2:?}"))?;
+
+        let ignored_timelines: BTreeSet<TimelineName> = ignored_timelines
+            .iter()
+            .map(|timeline| TimelineName::new(timeline))
+            .collect();
+
+        let mut differences = Vec::new();
+
+        if app_id1 != app_id2 {
+            differences.push(Difference::ApplicationId {
+                lhs: app_id1.to_string(),
+                rhs: app_id2.to_string(),
+            });
+        }
+
+        let summary1 = RecordingSummary::new(&chunks1, &ignored_timelines);
+        let summary2 = RecordingSummary::new(&chunks2, &ignored_timelines);
+        diff_recordings(&summary1, &summary2, *tolerance, &mut differences);
+
+        let report = DiffReport {
+            lhs: path_to_rrd1.display().to_string(),
+            rhs: path_to_rrd2.display().to_string(),
+            differences,
+        };
+
+        match format {
+            DiffFormat::Text => print!("{report}"),
+            DiffFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        }
+
+        anyhow::ensure!(
+            report.differences.is_empty(),
+            "found {} difference(s) between {path_to_rrd1:?} and {path_to_rrd2:?}",
+            report.differences.len()
+        );
+
+        Ok(())
+    }
+}
+
+// ---
+
+#[derive(serde::Serialize)]
+struct DiffReport {
+    lhs: String,
+    rhs: String,
+    differences: Vec<Difference>,
+}
+
+/// A single difference between the left-hand side and the right-hand side recordings.
+#[derive(Debug, PartialEq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Difference {
+    /// The two recordings don't belong to the same application.
+    ApplicationId { lhs: String, rhs: String },
+
+    /// The entity only exists on the left-hand side.
+    MissingEntity { entity_path: String },
+
+    /// The entity only exists on the right-hand side.
+    ExtraEntity { entity_path: String },
+
+    /// The component only exists on the left-hand side.
+    MissingComponent {
+        entity_path: String,
+        component: String,
+    },
+
+    /// The component only exists on the right-hand side.
+    ExtraComponent {
+        entity_path: String,
+        component: String,
+    },
+
+    /// The component doesn't have the same number of (non-null) rows on both sides.
+    RowCount {
+        entity_path: String,
+        component: String,
+        lhs: usize,
+        rhs: usize,
+    },
+
+    /// The component doesn't cover the same time range on both sides.
+    TimeRange {
+        entity_path: String,
+        component: String,
+        timeline: String,
+        lhs: Option<[i64; 2]>,
+        rhs: Option<[i64; 2]>,
+    },
+
+    /// Some rows of the component hold different values.
+    Values {
+        entity_path: String,
+        component: String,
+        num_rows_differing: usize,
+        first_row_differing: usize,
+
+        /// Only set if the component holds numeric data.
+        max_abs_difference: Option<f64>,
+    },
+}
+
+impl std::fmt::Display for DiffReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            lhs,
+            rhs,
+            differences,
+        } = self;
+
+        writeln!(f, "--- {lhs}")?;
+        writeln!(f, "+++ {rhs}")?;
+
+        for difference in differences {
+            writeln!(f, "{difference}")?;
+        }
+
+        writeln!(
+            f,
+            "{} difference(s)",
+            re_format::format_uint(differences.len())
+        )
+    }
+}
+
+impl std::fmt::Display for Difference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let format_range = |range: &Option<[i64; 2]>| {
+            range.map_or_else(
+                || "<none>".to_owned(),
+                |[min, max]| format!("{min}..={max}"),
+            )
+        };
+
+        match self {
+            Self::ApplicationId { lhs, rhs } => {
+                write!(f, "~ application id: {lhs:?} vs. {rhs:?}")
+            }
+            Self::MissingEntity { entity_path } => write!(f, "- {entity_path}"),
+            Self::ExtraEntity { entity_path } => write!(f, "+ {entity_path}"),
+            Self::MissingComponent {
+                entity_path,
+                component,
+            } => write!(f, "- {entity_path}:{component}"),
+            Self::ExtraComponent {
+                entity_path,
+                component,
+            } => write!(f, "+ {entity_path}:{component}"),
+            Self::RowCount {
+                entity_path,
+                component,
+                lhs,
+                rhs,
+            } => write!(
+                f,
+                "~ {entity_path}:{component}: row count {} vs. {}",
+                re_format::format_uint(*lhs),
+                re_format::format_uint(*rhs),
+            ),
+            Self::TimeRange {
+                entity_path,
+                component,
+                timeline,
+                lhs,
+                rhs,
+            } => write!(
+                f,
+                "~ {entity_path}:{component}: time range on {timeline:?} {} vs. {}",
+                format_range(lhs),
+                format_range(rhs),
+            ),
+            Self::Values {
+                entity_path,
+                component,
+                num_rows_differing,
+                first_row_differing,
+                max_abs_difference,
+            } => {
+                write!(
+                    f,
+                    "~ {entity_path}:{component}: {} row(s) differ, starting at row #{first_row_differing}",
+                    re_format::format_uint(*num_rows_differing),
+                )?;
+                if let Some(max_abs_difference) = max_abs_difference {
+                    write!(f, " (max abs. difference: {max_abs_difference})")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// ---
+
+/// Everything we know about a single component column of a recording, all chunks included.
+#[derive(Default)]
+struct ComponentSummary {
+    time_ranges: BTreeMap<TimelineName, AbsoluteTimeRange>,
+
+    /// All non-null cells, sorted by their (non-ignored) timepoint.
+    cells: Vec<(TimePoint, ArrayRef)>,
+}
+
+struct RecordingSummary {
+    per_entity: BTreeMap<EntityPath, BTreeMap<String, ComponentSummary>>,
+}
+
+impl RecordingSummary {
+    fn new(chunks: &[Arc<Chunk>], ignored_timelines: &BTreeSet<TimelineName>) -> Self {
+        re_tracing::profile_function!();
+
+        let mut per_entity: BTreeMap<EntityPath, BTreeMap<String, ComponentSummary>> =
+            BTreeMap::new();
+
+        for chunk in chunks {
+            let per_component = per_entity.entry(chunk.entity_path().clone()).or_default();
+
+            let time_range_per_component = chunk.time_range_per_component();
+
+            for component_descr in chunk.component_descriptors() {
+                let summary = per_component
+                    .entry(component_name(&component_descr))
+                    .or_default();
+
+                for (timeline, per_component) in &time_range_per_component {
+                    if ignored_timelines.contains(timeline) {
+                        continue;
+                    }
+                    if let Some(range) = per_component.get(&component_descr) {
+                        summary
+                            .time_ranges
+                            .entry(*timeline)
+                            .and_modify(|cur| *cur = cur.union(*range))
+                            .or_insert(*range);
+                    }
+                }
+
+                let Some(values) = chunk.raw_component_array(&component_descr) else {
+                    continue;
+                };
+
+                summary.cells.extend(
+                    itertools::izip!(
+                        chunk.iter_component_timepoints(&component_descr),
+                        chunk.iter_component_offsets(&component_descr),
+                    )
+                    .map(|(mut timepoint, span)| {
+                        for timeline in ignored_timelines {
+                            timepoint.remove(timeline);
+                        }
+                        (timepoint, values.slice(span.start, span.len))
+                    }),
+                );
+            }
+        }
+
+        for summary in per_entity.values_mut().flat_map(|c| c.values_mut()) {
+            // Stable: rows at the same timepoint keep their original order.
+            summary.cells.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+        }
+
+        Self { per_entity }
+    }
+}
+
+fn component_name(component_descr: &ComponentDescriptor) -> String {
+    component_descr.component.as_str().to_owned()
+}
+
+fn diff_recordings(
+    lhs: &RecordingSummary,
+    rhs: &RecordingSummary,
+    tolerance: f64,
+    differences: &mut Vec<Difference>,
+) {
+    re_tracing::profile_function!();
+
+    let entity_paths: BTreeSet<&EntityPath> =
+        lhs.per_entity.keys().chain(rhs.per_entity.keys()).collect();
+
+    for entity_path in entity_paths {
+        let entity_path_str = entity_path.to_string();
+
+        let (lhs_components, rhs_components) = match (
+            lhs.per_entity.get(entity_path),
+            rhs.per_entity.get(entity_path),
+        ) {
+            (Some(lhs), Some(rhs)) => (lhs, rhs),
+            (Some(_), None) => {
+                differences.push(Difference::MissingEntity {
+                    entity_path: entity_path_str,
+                });
+                continue;
+            }
+            (None, Some(_)) => {
+                differences.push(Difference::ExtraEntity {
+                    entity_path: entity_path_str,
+                });
+                continue;
+            }
+            (None, None) => unreachable!("the entity path came from one of the two sides"),
+        };
+
+        let components: BTreeSet<&String> =
+            lhs_components.keys().chain(rhs_components.keys()).collect();
+
+        for component in components {
+            let (lhs, rhs) = match (lhs_components.get(component), rhs_components.get(component)) {
+                (Some(lhs), Some(rhs)) => (lhs, rhs),
+                (Some(_), None) => {
+                    differences.push(Difference::MissingComponent {
+                        entity_path: entity_path_str.clone(),
+                        component: component.clone(),
+                    });
+                    continue;
+                }
+                (None, Some(_)) => {
+                    differences.push(Difference::ExtraComponent {
+                        entity_path: entity_path_str.clone(),
+                        component: component.clone(),
+                    });
+                    continue;
+                }
+                (None, None) => unreachable!("the component came from one of the two sides"),
+            };
+
+            diff_components(
+                &entity_path_str,
+                component,
+                lhs,
+                rhs,
+                tolerance,
+                differences,
+            );
+        }
+    }
+}
+
+fn diff_components(
+    entity_path: &str,
+    component: &str,
+    lhs: &ComponentSummary,
+    rhs: &ComponentSummary,
+    tolerance: f64,
+    differences: &mut Vec<Difference>,
+) {
+    let timelines: BTreeSet<&TimelineName> = lhs
+        .time_ranges
+        .keys()
+        .chain(rhs.time_ranges.keys())
+        .collect();
+
+    for timeline in timelines {
+        let lhs_range = lhs.time_ranges.get(timeline);
+        let rhs_range = rhs.time_ranges.get(timeline);
+        if lhs_range != rhs_range {
+            let as_array = |range: Option<&AbsoluteTimeRange>| {
+                range.map(|range| [range.min().as_i64(), range.max().as_i64()])
+            };
+            differences.push(Difference::TimeRange {
+                entity_path: entity_path.to_owned(),
+                component: component.to_owned(),
+                timeline: timeline.to_string(),
+                lhs: as_array(lhs_range),
+                rhs: as_array(rhs_range),
+            });
+        }
+    }
+
+    if lhs.cells.len() != rhs.cells.len() {
+        differences.push(Difference::RowCount {
+            entity_path: entity_path.to_owned(),
+            component: component.to_owned(),
+            lhs: lhs.cells.len(),
+            rhs: rhs.cells.len(),
+        });
+
+        // Row-by-row comparisons would be meaningless at this point.
+        return;
+    }
+
+    let mut num_rows_differing = 0;
+    let mut first_row_differing = None;
+    let mut max_abs_difference: Option<f64> = None;
+
+    for (row, ((_, lhs_cell), (_, rhs_cell))) in lhs.cells.iter().zip(&rhs.cells).enumerate() {
+        if similar_cells(lhs_cell, rhs_cell) {
+            continue;
+        }
+
+        if let (Some(lhs_values), Some(rhs_values)) =
+            (flatten_numeric(lhs_cell), flatten_numeric(rhs_cell))
+            && lhs_values.len() == rhs_values.len()
+        {
+            let cell_max_abs_difference = lhs_values
+                .iter()
+                .zip(rhs_values.iter())
+                .map(|(lhs, rhs)| match (lhs, rhs) {
+                    (Some(lhs), Some(rhs)) if lhs.is_nan() && rhs.is_nan() => 0.0,
+                    (Some(lhs), Some(rhs)) => (lhs - rhs).abs(),
+                    (None, None) => 0.0,
+                    _ => f64::INFINITY,
+                })
+                .fold(0.0, f64::max);
+
+            max_abs_difference = Some(max_abs_difference.map_or(cell_max_abs_difference, |cur| {
+                cur.max(cell_max_abs_difference)
+            }));
+
+            if cell_max_abs_difference <= tolerance {
+                continue;
+            }
+        }
+
+        num_rows_differing += 1;
+        first_row_differing.get_or_insert(row);
+    }
+
+    if let Some(first_row_differing) = first_row_differing {
+        differences.push(Difference::Values {
+            entity_path: entity_path.to_owned(),
+            component: component.to_owned(),
+            num_rows_differing,
+            first_row_differing,
+            max_abs_difference,
+        });
+    }
+}
+
+/// Whether two cells are the same as far as `rerun rrd compare` is concerned, i.e. according to
+/// [`ChunkComponents::ensure_similar`]: this forgives binary widths and small floating point
+/// inaccuracies.
+fn similar_cells(lhs: &ArrayRef, rhs: &ArrayRef) -> bool {
+    if lhs.as_ref() == rhs.as_ref() {
+        return true;
+    }
+
+    // Any descriptor will do, as long as it's the same on both sides.
+    let descr = ComponentDescriptor::partial("cell");
+    let lhs = ChunkComponents::from_iter([(descr.clone(), standalone_list(lhs))]);
+    let rhs = ChunkComponents::from_iter([(descr, standalone_list(rhs))]);
+
+    ChunkComponents::ensure_similar(&lhs, &rhs).is_ok()
+}
+
+/// Wraps a cell into a list array of a single row, copying its data.
+///
+/// Cells are slices of the arrays of their chunks, whereas [`ChunkComponents::ensure_similar`]
+/// compares raw buffers: these must not contain anything but the cell itself.
+fn standalone_list(cell: &ArrayRef) -> ListArray {
+    let data = cell.to_data();
+    let mut copy = MutableArrayData::new(vec![&data], false, data.len());
+    copy.extend(0, 0, data.len());
+
+    ListArray::new(
+        Field::new_list_field(cell.data_type().clone(), true).into(),
+        OffsetBuffer::from_lengths([cell.len()]),
+        arrow::array::make_array(copy.freeze()),
+        None,
+    )
+}
+
+/// Flattens arbitrarily nested lists of numbers (e.g. `List<FixedSizeList<f32, 3>>`) into a
+/// single array of `f64`s.
+///
+/// Returns `None` for non-numeric data.
+fn flatten_numeric(array: &ArrayRef) -> Option<Float64Array> {
+    match array.data_type() {
+        DataType::List(_) => {
+            let list = array.as_list::<i32>();
+            let offsets = list.value_offsets();
+            let (start, end) = (*offsets.first()? as usize, *offsets.last()? as usize);
+            flatten_numeric(&list.values().slice(start, end - start))
+        }
+
+        DataType::LargeList(_) => {
+            let list = array.as_list::<i64>();
+            let offsets = list.value_offsets();
+            let (start, end) = (*offsets.first()? as usize, *offsets.last()? as usize);
+            flatten_numeric(&list.values().slice(start, end - start))
+        }
+
+        DataType::FixedSizeList(_, size) => {
+            let list = array.as_fixed_size_list();
+            let size = *size as usize;
+            flatten_numeric(&list.values().slice(list.offset() * size, list.len() * size))
+        }
+
+        datatype if datatype.is_numeric() => arrow::compute::cast(array, &DataType::Float64)
+            .ok()
+            .map(|array| array.as_primitive::<Float64Type>().clone()),
+
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use re_chunk::{RowId, Timeline};
+    use re_types::archetypes::{Points3D, Scalars};
+
+    use super::*;
+
+    /// A scalar per `(frame, value)`, logged at a `log_time` that depends on `log_time_offset`.
+    fn scalars(entity_path: &str, log_time_offset: i64, rows: &[(i64, f64)]) -> Arc<Chunk> {
+        let mut builder = Chunk::builder(entity_path);
+        for &(frame, value) in rows {
+            builder = builder.with_archetype(
+                RowId::new(),
+                [
+                    (Timeline::new_sequence("frame"), frame),
+                    (Timeline::log_time(), 1_000 * frame + log_time_offset),
+                ],
+                &Scalars::single(value),
+            );
+        }
+        Arc::new(builder.build().unwrap())
+    }
+
+    fn points(entity_path: &str, points: &Points3D) -> Arc<Chunk> {
+        Arc::new(
+            Chunk::builder(entity_path)
+                .with_archetype(RowId::new(), [(Timeline::new_sequence("frame"), 0)], points)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    fn diff(lhs: &[Arc<Chunk>], rhs: &[Arc<Chunk>], tolerance: f64) -> Vec<Difference> {
+        let ignored_timelines = std::iter::once(TimelineName::log_time()).collect();
+
+        let mut differences = Vec::new();
+        diff_recordings(
+            &RecordingSummary::new(lhs, &ignored_timelines),
+            &RecordingSummary::new(rhs, &ignored_timelines),
+            tolerance,
+            &mut differences,
+        );
+        differences
+    }
+
+    fn scalars_component() -> String {
+        component_name(&Scalars::descriptor_scalars())
+    }
+
+    #[test]
+    fn equal_recordings() {
+        let lhs = [
+            scalars("a", 0, &[(0, 1.0), (1, 2.0)]),
+            points("b", &Points3D::new([(1.0, 2.0, 3.0)]).with_radii([0.5])),
+        ];
+
+        // Different chunking and ignored timelines don't matter.
+        let rhs = [
+            scalars("a", 42, &[(1, 2.0)]),
+            scalars("a", 42, &[(0, 1.0)]),
+            points("b", &Points3D::new([(1.0, 2.0, 3.0)]).with_radii([0.5])),
+        ];
+
+        assert_eq!(Vec::<Difference>::new(), diff(&lhs, &rhs, 0.0));
+    }
+
+    #[test]
+    fn per_entity_and_component_report() {
+        let lhs = [
+            scalars("a", 0, &[(0, 1.0), (1, 2.0)]),
+            scalars("b", 0, &[(0, 1.0)]),
+            points("d", &Points3D::new([(1.0, 2.0, 3.0)]).with_radii([0.5])),
+            scalars("e", 0, &[(0, 1.0), (1, 2.0), (2, 3.0), (3, 4.0)]),
+        ];
+        let rhs = [
+            scalars("a", 0, &[(0, 1.0), (1, 2.0), (2, 3.0)]),
+            scalars("c", 0, &[(0, 1.0)]),
+            points(
+                "d",
+                &Points3D::new([(1.0, 2.0, 3.0)]).with_colors([0xFF0000FF]),
+            ),
+            scalars("e", 0, &[(0, 1.0), (1, 20.0), (2, 3.0), (3, 40.0)]),
+        ];
+
+        let expected = vec![
+            Difference::TimeRange {
+                entity_path: "/a".to_owned(),
+                component: scalars_component(),
+                timeline: "frame".to_owned(),
+                lhs: Some([0, 1]),
+                rhs: Some([0, 2]),
+            },
+            Difference::RowCount {
+                entity_path: "/a".to_owned(),
+                component: scalars_component(),
+                lhs: 2,
+                rhs: 3,
+            },
+            Difference::MissingEntity {
+                entity_path: "/b".to_owned(),
+            },
+            Difference::ExtraEntity {
+                entity_path: "/c".to_owned(),
+            },
+            Difference::ExtraComponent {
+                entity_path: "/d".to_owned(),
+                component: component_name(&Points3D::descriptor_colors()),
+            },
+            Difference::MissingComponent {
+                entity_path: "/d".to_owned(),
+                component: component_name(&Points3D::descriptor_radii()),
+            },
+            Difference::Values {
+                entity_path: "/e".to_owned(),
+                component: scalars_component(),
+                num_rows_differing: 2,
+                first_row_differing: 1,
+                max_abs_difference: Some(36.0),
+            },
+        ];
+
+        assert_eq!(expected, diff(&lhs, &rhs, 0.0));
+
+        let report = DiffReport {
+            lhs: "lhs.rrd".to_owned(),
+            rhs: "rhs.rrd".to_owned(),
+            differences: expected,
+        };
+        let text = report.to_string();
+        assert!(text.starts_with("--- lhs.rrd\n+++ rhs.rrd\n"), "{text}");
+        assert!(text.contains("\n- /b\n+ /c\n"), "{text}");
+        assert!(text.ends_with("7 difference(s)\n"), "{text}");
+    }
+
+    #[test]
+    fn tolerance() {
+        let lhs = [scalars("a", 0, &[(0, 1.0), (1, 2.0)])];
+        let rhs = [scalars("a", 0, &[(0, 1.001), (1, 2.0)])];
+
+        let differences = diff(&lhs, &rhs, 0.0);
+        let [
+            Difference::Values {
+                num_rows_differing: 1,
+                first_row_differing: 0,
+                max_abs_difference: Some(max_abs_difference),
+                ..
+            },
+        ] = differences.as_slice()
+        else {
+            panic!("unexpected differences: {differences:?}");
+        };
+        assert!((max_abs_difference - 0.001).abs() < 1e-9);
+
+        assert_eq!(Vec::<Difference>::new(), diff(&lhs, &rhs, 0.01));
+    }
+
+    #[test]
+    fn similar_cells_are_equal() {
+        // Same leniency as `rrd compare`: small relative differences between `f32`s are forgiven,
+        // even without any tolerance.
+        let lhs = [points("a", &Points3D::new([(1.0, 2.0, 3.0)]))];
+        let rhs = [points("a", &Points3D::new([(1.0, 2.0, 3.0001)]))];
+        assert_eq!(Vec::<Difference>::new(), diff(&lhs, &rhs, 0.0));
+
+        // Significant differences are not.
+        let rhs = [points("a", &Points3D::new([(1.0, 2.0, 4.0)]))];
+        assert_eq!(1, diff(&lhs, &rhs, 0.0).len());
+    }
+}
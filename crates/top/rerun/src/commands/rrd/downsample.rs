@@ -0,0 +1,790 @@
+use std::{collections::HashMap, str::FromStr};
+
+use anyhow::Context as _;
+use arrow::array::{Array as _, ArrayRef, BooleanArray, UInt32Array};
+use itertools::Itertools as _;
+
+use re_chunk::{
+    Chunk, ChunkComponents,
+    external::nohash_hasher::{IntMap, IntSet},
+};
+use re_log_types::{EntityPath, StoreId, TimeType, TimelineName};
+use re_sdk::{ComponentDescriptor, Loggable as _};
+use re_types::{
+    archetypes::{EncodedImage, Image, Points3D},
+    components,
+    datatypes::{ChannelDatatype, ColorModel},
+    external::image,
+};
+
+// ---
+
+/// Options for reducing the volume of the data, on top of compacting it.
+///
+/// Downsampling only ever applies to recordings: blueprints are always kept as-is.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct DownsampleArgs {
+    /// Only keep every Nth row of each entity, e.g. `--keep-every-nth 10` keeps 10% of the rows.
+    ///
+    /// Rows are counted per entity, in the order they appear in the input. Static data is
+    /// always kept.
+    #[arg(long = "keep-every-nth", value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    keep_every_nth: Option<u64>,
+
+    /// Only keep the first row of each entity within every time bucket of the given size.
+    ///
+    /// Syntax: `<timeline>=<size>`, e.g. `frame=10` or `log_time=100ms`.
+    /// Sizes are durations for temporal timelines, and plain integers for sequence timelines.
+    ///
+    /// Data that isn't indexed on that timeline (e.g. static data) is kept as-is.
+    #[arg(long = "time-bucket", value_name = "TIMELINE=SIZE")]
+    time_bucket: Option<TimeBucket>,
+
+    /// Downscale images (and JPEG-encoded images) so that neither side exceeds this many pixels.
+    ///
+    /// The aspect ratio is preserved.
+    #[arg(long = "image-max-size", value_name = "PIXELS", value_parser = clap::value_parser!(u32).range(1..))]
+    image_max_size: Option<u32>,
+
+    /// Transcode raw 8-bit images to JPEG, with the given quality (1-100).
+    ///
+    /// JPEG-encoded images are re-encoded with that quality too.
+    /// Images with an alpha channel are only downscaled, since JPEG cannot store transparency.
+    #[arg(long = "jpeg-quality", value_name = "QUALITY", value_parser = clap::value_parser!(u8).range(1..=100))]
+    jpeg_quality: Option<u8>,
+
+    /// Voxel-downsample `Points3D` batches, keeping a single point per voxel of the given size.
+    ///
+    /// All the other per-point components (colors, radii, labels, …) are downsampled accordingly.
+    #[arg(long = "voxel-size", value_name = "SIZE", value_parser = parse_voxel_size)]
+    voxel_size: Option<f32>,
+
+    /// Only voxel-downsample `Points3D` batches with at least this many points.
+    #[arg(long = "voxel-min-points", value_name = "N", default_value_t = 10_000)]
+    voxel_min_points: usize,
+}
+
+impl DownsampleArgs {
+    /// Returns `true` if none of the downsampling options are set.
+    pub fn is_noop(&self) -> bool {
+        let Self {
+            keep_every_nth,
+            time_bucket,
+            image_max_size,
+            jpeg_quality,
+            voxel_size,
+            voxel_min_points: _,
+        } = self;
+
+        keep_every_nth.is_none()
+            && time_bucket.is_none()
+            && image_max_size.is_none()
+            && jpeg_quality.is_none()
+            && voxel_size.is_none()
+    }
+}
+
+fn parse_voxel_size(voxel_size: &str) -> Result<f32, String> {
+    match voxel_size.parse::<f32>() {
+        Ok(voxel_size) if voxel_size.is_finite() && 0.0 < voxel_size => Ok(voxel_size),
+        Ok(_) => Err("must be a positive number".to_owned()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// A `<timeline>=<size>` argument, as used by e.g. `--time-bucket`.
+///
+/// The size is kept as a string, since how it should be parsed depends on the type of the
+/// timeline, which is only known once we encounter the data.
+#[derive(Debug, Clone)]
+pub struct TimeBucket {
+    timeline: TimelineName,
+    size: String,
+}
+
+impl FromStr for TimeBucket {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((timeline, size)) = s.split_once('=') else {
+            return Err(format!("expected `<timeline>=<size>`, got {s:?}"));
+        };
+
+        let bucket = Self {
+            timeline: TimelineName::new(timeline.trim()),
+            size: size.trim().to_owned(),
+        };
+
+        // Make sure the size makes sense for at least one kind of timeline.
+        let is_valid = [TimeType::Sequence, TimeType::DurationNs]
+            .into_iter()
+            .any(|typ| bucket.resolve(typ).is_ok());
+        if !is_valid {
+            return Err(format!("couldn't parse bucket size {size:?}"));
+        }
+
+        Ok(bucket)
+    }
+}
+
+impl TimeBucket {
+    pub fn timeline(&self) -> &TimelineName {
+        &self.timeline
+    }
+
+    /// The size of the bucket, in the units of a timeline of the given type.
+    pub fn resolve(&self, typ: TimeType) -> anyhow::Result<i64> {
+        let size = match typ {
+            TimeType::Sequence => re_format::parse_i64(&self.size),
+            TimeType::DurationNs | TimeType::TimestampNs => re_format::parse_i64(&self.size)
+                .or_else(|| {
+                    self.size
+                        .parse::<re_log_types::Duration>()
+                        .ok()
+                        .map(|duration| duration.as_nanos())
+                }),
+        };
+
+        match size {
+            Some(size) if size > 0 => Ok(size),
+            _ => anyhow::bail!("{:?} is not a valid {typ:?} bucket size", self.size),
+        }
+    }
+}
+
+// ---
+
+/// Applies [`DownsampleArgs`] to a stream of chunks.
+///
+/// Row decimation is stateful (rows are counted per entity across chunks), so the same
+/// [`Downsampler`] must be used for the whole input.
+pub struct Downsampler {
+    args: DownsampleArgs,
+
+    /// How many temporal rows we've encountered so far, per entity.
+    num_rows_seen: HashMap<(StoreId, EntityPath), u64>,
+
+    /// Which time buckets already have a row, per entity.
+    filled_buckets: HashMap<(StoreId, EntityPath), IntSet<i64>>,
+}
+
+impl Downsampler {
+    pub fn new(args: DownsampleArgs) -> Self {
+        Self {
+            args,
+            num_rows_seen: Default::default(),
+            filled_buckets: Default::default(),
+        }
+    }
+
+    /// Downsamples the chunk in the given message.
+    ///
+    /// Returns `None` if nothing is left of it.
+    pub fn apply(
+        &mut self,
+        store_id: &StoreId,
+        msg: &re_log_types::ArrowMsg,
+    ) -> anyhow::Result<Option<re_log_types::ArrowMsg>> {
+        re_tracing::profile_function!();
+
+        if !store_id.is_recording() {
+            return Ok(Some(msg.clone()));
+        }
+
+        let mut chunk = Chunk::from_arrow_msg(msg).context("corrupt chunk")?;
+
+        let key = (store_id.clone(), chunk.entity_path().clone());
+        if !chunk.is_static()
+            && let Some(filter) = self.rows_to_keep(&key, &chunk)?
+        {
+            match chunk.filtered(&filter) {
+                Some(filtered) if !filtered.is_empty() => chunk = filtered,
+                _ => return Ok(None),
+            }
+        }
+
+        if let Some(voxel_size) = self.args.voxel_size {
+            chunk = voxel_downsample_points(&chunk, voxel_size, self.args.voxel_min_points)?;
+        }
+
+        if self.args.image_max_size.is_some() || self.args.jpeg_quality.is_some() {
+            chunk = transcode_images(&chunk, self.args.image_max_size, self.args.jpeg_quality)?;
+        }
+
+        Ok(Some(
+            chunk.to_arrow_msg().context("couldn't re-encode chunk")?,
+        ))
+    }
+
+    /// Computes which rows of the chunk survive row decimation.
+    ///
+    /// Returns `None` if the chunk is unaffected.
+    fn rows_to_keep(
+        &mut self,
+        key: &(StoreId, EntityPath),
+        chunk: &Chunk,
+    ) -> anyhow::Result<Option<BooleanArray>> {
+        if self.args.keep_every_nth.is_none() && self.args.time_bucket.is_none() {
+            return Ok(None);
+        }
+
+        let mut keep = vec![true; chunk.num_rows()];
+
+        if let Some(time_bucket) = &self.args.time_bucket
+            && let Some(time_column) = chunk.timelines().get(&time_bucket.timeline)
+        {
+            let size = time_bucket.resolve(time_column.timeline().typ())?;
+            let filled_buckets = self.filled_buckets.entry(key.clone()).or_default();
+
+            for (keep, time) in keep.iter_mut().zip(time_column.times_raw()) {
+                *keep = filled_buckets.insert(time.div_euclid(size));
+            }
+        }
+
+        if let Some(n) = self.args.keep_every_nth {
+            let num_rows_seen = self.num_rows_seen.entry(key.clone()).or_default();
+
+            for keep in keep.iter_mut().filter(|keep| **keep) {
+                *keep = *num_rows_seen % n == 0;
+                *num_rows_seen += 1;
+            }
+        }
+
+        Ok(Some(BooleanArray::from(keep)))
+    }
+}
+
+// ---
+
+/// The component columns of a chunk, exploded into one optional array per row, for easy editing.
+///
+/// The row arrays are zero-copy slices of the original columns.
+struct ExplodedColumns {
+    num_rows: usize,
+    columns: IntMap<ComponentDescriptor, Vec<Option<ArrayRef>>>,
+}
+
+impl ExplodedColumns {
+    fn new(chunk: &Chunk) -> Self {
+        let columns = chunk
+            .components()
+            .iter()
+            .map(|(descr, list_array)| {
+                let rows = (0..list_array.len())
+                    .map(|row| list_array.is_valid(row).then(|| list_array.value(row)))
+                    .collect();
+                (descr.clone(), rows)
+            })
+            .collect();
+
+        Self {
+            num_rows: chunk.num_rows(),
+            columns,
+        }
+    }
+
+    fn get(&self, descr: &ComponentDescriptor, row: usize) -> Option<&ArrayRef> {
+        self.columns.get(descr)?.get(row)?.as_ref()
+    }
+
+    fn set(&mut self, descr: ComponentDescriptor, row: usize, array: Option<ArrayRef>) {
+        let num_rows = self.num_rows;
+        self.columns
+            .entry(descr)
+            .or_insert_with(|| vec![None; num_rows])[row] = array;
+    }
+
+    /// Re-assembles the columns into a chunk with the same ID, row IDs and timelines as `chunk`.
+    ///
+    /// Columns that ended up completely empty are dropped.
+    fn into_chunk(self, chunk: &Chunk) -> anyhow::Result<Chunk> {
+        let components: ChunkComponents = self
+            .columns
+            .into_iter()
+            .filter_map(|(descr, rows)| {
+                let rows = rows.iter().map(|row| row.as_deref()).collect_vec();
+                re_arrow_util::arrays_to_list_array_opt(&rows).map(|list_array| (descr, list_array))
+            })
+            .collect();
+
+        Ok(Chunk::new(
+            chunk.id(),
+            chunk.entity_path().clone(),
+            Some(chunk.is_sorted()),
+            chunk.row_ids_array().clone(),
+            chunk.timelines().clone(),
+            components,
+        )?)
+    }
+}
+
+// ---
+
+/// Keeps a single point per voxel in every `Points3D` batch with at least `min_points` points.
+///
+/// Every other component batch of the same length as the positions is filtered accordingly.
+fn voxel_downsample_points(
+    chunk: &Chunk,
+    voxel_size: f32,
+    min_points: usize,
+) -> anyhow::Result<Chunk> {
+    re_tracing::profile_function!();
+
+    let positions_descr = Points3D::descriptor_positions();
+    let Some(positions) = chunk.components().get(&positions_descr) else {
+        return Ok(chunk.clone());
+    };
+    if !positions.offsets().lengths().any(|len| len >= min_points) {
+        return Ok(chunk.clone());
+    }
+
+    let mut columns = ExplodedColumns::new(chunk);
+
+    for row in 0..chunk.num_rows() {
+        let Some(positions) = columns.get(&positions_descr, row) else {
+            continue;
+        };
+        let num_points = positions.len();
+        if num_points < min_points {
+            continue;
+        }
+
+        let positions = components::Position3D::from_arrow(positions)?;
+        let mut filled_voxels = std::collections::HashSet::new();
+        let indices: UInt32Array = positions
+            .iter()
+            .enumerate()
+            .filter(|(_, pos)| {
+                let voxel =
+                    [pos.x(), pos.y(), pos.z()].map(|coord| (coord / voxel_size).floor() as i64);
+                filled_voxels.insert(voxel)
+            })
+            .map(|(index, _)| index as u32)
+            .collect();
+
+        let descrs = columns.columns.keys().cloned().collect_vec();
+        for descr in descrs {
+            // Splats (and anything else that isn't per-point) are kept as-is.
+            let Some(array) = columns
+                .get(&descr, row)
+                .filter(|arr| arr.len() == num_points)
+            else {
+                continue;
+            };
+            let taken = arrow::compute::take(array, &indices, None)?;
+            columns.set(descr, row, Some(taken));
+        }
+    }
+
+    columns.into_chunk(chunk)
+}
+
+/// Downscales and/or JPEG-encodes the `Image`s and `EncodedImage`s in the chunk.
+///
+/// Raw images that end up JPEG-encoded are turned into `EncodedImage`s. Images that cannot be
+/// handled (e.g. non-8-bit, chroma-subsampled or non-JPEG encoded images) are kept as-is.
+fn transcode_images(
+    chunk: &Chunk,
+    max_size: Option<u32>,
+    jpeg_quality: Option<u8>,
+) -> anyhow::Result<Chunk> {
+    re_tracing::profile_function!();
+
+    let buffer_descr = Image::descriptor_buffer();
+    let format_descr = Image::descriptor_format();
+    let blob_descr = EncodedImage::descriptor_blob();
+    let media_type_descr = EncodedImage::descriptor_media_type();
+
+    let has_raw_images = chunk.components().contains_component(&buffer_descr)
+        && chunk.components().contains_component(&format_descr);
+    let has_encoded_images = chunk.components().contains_component(&blob_descr);
+    if !has_raw_images && !has_encoded_images {
+        return Ok(chunk.clone());
+    }
+
+    let mut columns = ExplodedColumns::new(chunk);
+    let mut num_transcoded = 0;
+
+    for row in 0..chunk.num_rows() {
+        if has_raw_images
+            && let Some(buffer) =
+                chunk.component_mono::<components::ImageBuffer>(&buffer_descr, row)
+            && let Some(format) =
+                chunk.component_mono::<components::ImageFormat>(&format_descr, row)
+        {
+            let (buffer, format) = (buffer?, format?);
+            let Some(image) = raw_to_dynamic_image(&buffer, &format) else {
+                continue;
+            };
+            let image = downscaled(image, max_size);
+
+            if let Some(quality) = jpeg_quality
+                && !image.color().has_alpha()
+            {
+                let blob = components::Blob::from(encode_jpeg(&image, quality)?);
+                columns.set(buffer_descr.clone(), row, None);
+                columns.set(format_descr.clone(), row, None);
+                columns.set(
+                    blob_descr.clone(),
+                    row,
+                    Some(components::Blob::to_arrow([blob])?),
+                );
+                columns.set(
+                    media_type_descr.clone(),
+                    row,
+                    Some(components::MediaType::to_arrow([
+                        components::MediaType::jpeg(),
+                    ])?),
+                );
+
+                // The remaining components of the archetype carry over as-is.
+                for (from, to) in [
+                    (
+                        Image::descriptor_opacity(),
+                        EncodedImage::descriptor_opacity(),
+                    ),
+                    (
+                        Image::descriptor_draw_order(),
+                        EncodedImage::descriptor_draw_order(),
+                    ),
+                ] {
+                    if let Some(array) = columns.get(&from, row).cloned() {
+                        columns.set(from, row, None);
+                        columns.set(to, row, Some(array));
+                    }
+                }
+            } else if max_size.is_some() {
+                let (buffer, format) = components::ImageBuffer::from_dynamic_image(image)?;
+                columns.set(
+                    buffer_descr.clone(),
+                    row,
+                    Some(components::ImageBuffer::to_arrow([buffer])?),
+                );
+                columns.set(
+                    format_descr.clone(),
+                    row,
+                    Some(components::ImageFormat::to_arrow([format])?),
+                );
+            } else {
+                continue;
+            }
+
+            num_transcoded += 1;
+        } else if has_encoded_images
+            && let Some(blob) = chunk.component_mono::<components::Blob>(&blob_descr, row)
+        {
+            let blob = blob?;
+            let bytes: &[u8] = &blob;
+            if image::guess_format(bytes).ok() != Some(image::ImageFormat::Jpeg) {
+                continue;
+            }
+            let image = match image::load_from_memory(bytes) {
+                Ok(image) => image,
+                Err(err) => {
+                    re_log::warn_once!("couldn't decode JPEG image, keeping it as-is: {err}");
+                    continue;
+                }
+            };
+
+            let is_too_large = max_size
+                .is_some_and(|max_size| image.width() > max_size || image.height() > max_size);
+            if !is_too_large && jpeg_quality.is_none() {
+                continue;
+            }
+
+            // NOTE: This is the default quality of the `image` crate.
+            let quality = jpeg_quality.unwrap_or(75);
+            let blob = components::Blob::from(encode_jpeg(&downscaled(image, max_size), quality)?);
+            columns.set(
+                blob_descr.clone(),
+                row,
+                Some(components::Blob::to_arrow([blob])?),
+            );
+            num_transcoded += 1;
+        }
+    }
+
+    if num_transcoded == 0 {
+        return Ok(chunk.clone());
+    }
+
+    re_log::debug!(entity = %chunk.entity_path(), num_transcoded, "transcoded images");
+
+    columns.into_chunk(chunk)
+}
+
+/// Only 8-bit grayscale, RGB and RGBA images are supported.
+fn raw_to_dynamic_image(
+    buffer: &components::ImageBuffer,
+    format: &components::ImageFormat,
+) -> Option<image::DynamicImage> {
+    if format.pixel_format.is_some() || format.datatype() != ChannelDatatype::U8 {
+        return None;
+    }
+
+    let (width, height) = (format.width, format.height);
+    let bytes = buffer.to_vec();
+
+    match format.color_model() {
+        ColorModel::L => {
+            image::GrayImage::from_raw(width, height, bytes).map(image::DynamicImage::ImageLuma8)
+        }
+        ColorModel::RGB => {
+            image::RgbImage::from_raw(width, height, bytes).map(image::DynamicImage::ImageRgb8)
+        }
+        ColorModel::RGBA => {
+            image::RgbaImage::from_raw(width, height, bytes).map(image::DynamicImage::ImageRgba8)
+        }
+        ColorModel::BGR | ColorModel::BGRA => None,
+    }
+}
+
+fn downscaled(image: image::DynamicImage, max_size: Option<u32>) -> image::DynamicImage {
+    match max_size {
+        Some(max_size) if image.width() > max_size || image.height() > max_size => {
+            image.resize(max_size, max_size, image::imageops::FilterType::Triangle)
+        }
+        _ => image,
+    }
+}
+
+fn encode_jpeg(image: &image::DynamicImage, quality: u8) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    image
+        .write_with_encoder(image::codecs::jpeg::JpegEncoder::new_with_quality(
+            &mut bytes, quality,
+        ))
+        .context("couldn't encode JPEG")?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+
+    use re_chunk::{RowId, Timeline};
+    use re_log_types::StoreKind;
+
+    use super::*;
+
+    #[derive(clap::Parser)]
+    struct Cli {
+        #[command(flatten)]
+        args: DownsampleArgs,
+    }
+
+    fn parse(args: &[&str]) -> Result<DownsampleArgs, clap::Error> {
+        Cli::try_parse_from(std::iter::once("compact").chain(args.iter().copied()))
+            .map(|cli| cli.args)
+    }
+
+    fn points_chunk(points: &Points3D) -> Chunk {
+        Chunk::builder("points")
+            .with_archetype(RowId::new(), [(Timeline::new_sequence("frame"), 0)], points)
+            .build()
+            .unwrap()
+    }
+
+    fn positions(chunk: &Chunk) -> Vec<[f32; 3]> {
+        chunk
+            .component_batch::<components::Position3D>(&Points3D::descriptor_positions(), 0)
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .map(|pos| [pos.x(), pos.y(), pos.z()])
+            .collect()
+    }
+
+    fn image_chunk(image: &impl re_types::AsComponents) -> Chunk {
+        Chunk::builder("image")
+            .with_archetype(RowId::new(), [(Timeline::new_sequence("frame"), 0)], image)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_args() {
+        let args = parse(&[]).unwrap();
+        assert!(args.is_noop());
+
+        let args = parse(&["--voxel-size", "0.5", "--keep-every-nth", "2"]).unwrap();
+        assert!(!args.is_noop());
+        assert_eq!(Some(0.5), args.voxel_size);
+        assert_eq!(Some(2), args.keep_every_nth);
+
+        for voxel_size in ["0", "-1", "inf", "NaN", "big"] {
+            assert!(
+                parse(&["--voxel-size", voxel_size]).is_err(),
+                "{voxel_size}"
+            );
+        }
+        assert!(parse(&["--keep-every-nth", "0"]).is_err());
+        assert!(parse(&["--image-max-size", "0"]).is_err());
+        assert!(parse(&["--jpeg-quality", "0"]).is_err());
+        assert!(parse(&["--jpeg-quality", "101"]).is_err());
+    }
+
+    #[test]
+    fn parse_time_bucket() {
+        let bucket: TimeBucket = "frame=10".parse().unwrap();
+        assert_eq!(&TimelineName::new("frame"), bucket.timeline());
+        assert_eq!(10, bucket.resolve(TimeType::Sequence).unwrap());
+
+        let bucket: TimeBucket = "log_time=100ms".parse().unwrap();
+        assert_eq!(100_000_000, bucket.resolve(TimeType::TimestampNs).unwrap());
+        assert!(bucket.resolve(TimeType::Sequence).is_err());
+
+        for bucket in ["frame", "frame=0", "frame=-1", "frame=soon"] {
+            assert!(bucket.parse::<TimeBucket>().is_err(), "{bucket}");
+        }
+    }
+
+    #[test]
+    fn decimate_rows() {
+        let args = parse(&["--keep-every-nth", "2", "--time-bucket", "frame=10"]).unwrap();
+        let mut downsampler = Downsampler::new(args);
+        let store_id = StoreId::random(StoreKind::Recording, "rerun_example_downsample");
+
+        let chunk = |frames: &[i64]| {
+            let mut builder = Chunk::builder("scalar");
+            for &frame in frames {
+                builder = builder.with_archetype(
+                    RowId::new(),
+                    [(Timeline::new_sequence("frame"), frame)],
+                    &re_types::archetypes::Scalars::single(frame as f64),
+                );
+            }
+            builder.build().unwrap().to_arrow_msg().unwrap()
+        };
+        let mut frames_kept = |frames: &[i64]| -> Vec<i64> {
+            downsampler
+                .apply(&store_id, &chunk(frames))
+                .unwrap()
+                .map(|msg| {
+                    Chunk::from_arrow_msg(&msg).unwrap().timelines()[&TimelineName::new("frame")]
+                        .times_raw()
+                        .to_vec()
+                })
+                .unwrap_or_default()
+        };
+
+        // Buckets: one row per 10 frames, negative frames included. Then every other row.
+        assert_eq!(vec![-15, 0], frames_kept(&[-15, -12, -5, 0, 5, 9]));
+
+        // The state carries over across chunks of the same entity.
+        assert_eq!(Vec::<i64>::new(), frames_kept(&[6, 7, 15]));
+        assert_eq!(vec![25], frames_kept(&[25, 30]));
+    }
+
+    #[test]
+    fn voxel_downsample() {
+        let chunk = points_chunk(
+            &Points3D::new([
+                (0.1, 0.1, 0.1),
+                (0.9, 0.9, 0.9),  // same voxel as the first point
+                (-0.1, 0.1, 0.1), // negative coordinates round down
+                (-0.9, 0.9, 0.9), // same voxel as the previous point
+                (-1.1, 0.1, 0.1), // next voxel down
+                (10.0, 10.0, 10.0),
+            ])
+            .with_colors([
+                0x000000FF, 0x111111FF, 0x222222FF, 0x333333FF, 0x444444FF, 0x555555FF,
+            ])
+            .with_radii([0.5]),
+        );
+
+        let downsampled = voxel_downsample_points(&chunk, 1.0, 0).unwrap();
+        assert_eq!(
+            vec![
+                [0.1, 0.1, 0.1],
+                [-0.1, 0.1, 0.1],
+                [-1.1, 0.1, 0.1],
+                [10.0, 10.0, 10.0]
+            ],
+            positions(&downsampled)
+        );
+
+        // Per-point components follow along, splats are kept as-is.
+        let colors = downsampled
+            .component_batch::<components::Color>(&Points3D::descriptor_colors(), 0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            vec![0x000000FF, 0x222222FF, 0x444444FF, 0x555555FF],
+            colors.iter().map(|color| color.0.0).collect_vec()
+        );
+        let radii = downsampled
+            .component_batch::<components::Radius>(&Points3D::descriptor_radii(), 0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(1, radii.len());
+
+        // Batches below the threshold are left untouched.
+        let untouched = voxel_downsample_points(&chunk, 1.0, 100).unwrap();
+        assert_eq!(6, positions(&untouched).len());
+
+        // So are chunks without points.
+        let scalars = Chunk::builder("scalar")
+            .with_archetype(
+                RowId::new(),
+                [(Timeline::new_sequence("frame"), 0)],
+                &re_types::archetypes::Scalars::single(1.0),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(scalars, voxel_downsample_points(&scalars, 1.0, 0).unwrap());
+    }
+
+    #[test]
+    fn transcode() {
+        let rgb = Image::from_rgb24(vec![128_u8; 64 * 32 * 3], [64, 32]);
+        let rgba = Image::from_rgba32(vec![128_u8; 64 * 32 * 4], [64, 32]);
+
+        // Downscaling keeps the image raw, and preserves the aspect ratio.
+        let downscaled = transcode_images(&image_chunk(&rgb), Some(16), None).unwrap();
+        let format = downscaled
+            .component_mono::<components::ImageFormat>(&Image::descriptor_format(), 0)
+            .unwrap()
+            .unwrap();
+        assert_eq!((16, 8), (format.width, format.height));
+
+        // JPEG-encoding turns it into an `EncodedImage`.
+        let encoded = transcode_images(&image_chunk(&rgb), Some(16), Some(80)).unwrap();
+        assert!(
+            encoded
+                .component_mono::<components::ImageBuffer>(&Image::descriptor_buffer(), 0)
+                .is_none()
+        );
+        let media_type = encoded
+            .component_mono::<components::MediaType>(&EncodedImage::descriptor_media_type(), 0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(components::MediaType::jpeg(), media_type);
+        let blob = encoded
+            .component_mono::<components::Blob>(&EncodedImage::descriptor_blob(), 0)
+            .unwrap()
+            .unwrap();
+        let jpeg = image::load_from_memory(&blob).unwrap();
+        assert_eq!((16, 8), (jpeg.width(), jpeg.height()));
+
+        // JPEG cannot store transparency.
+        let chunk = image_chunk(&rgba);
+        assert_eq!(chunk, transcode_images(&chunk, None, Some(80)).unwrap());
+
+        // Already encoded JPEGs are downscaled too.
+        let downscaled_jpeg = transcode_images(
+            &image_chunk(&EncodedImage::new(
+                encode_jpeg(&image::DynamicImage::new_rgb8(64, 32), 90).unwrap(),
+            )),
+            Some(16),
+            None,
+        )
+        .unwrap();
+        let blob = downscaled_jpeg
+            .component_mono::<components::Blob>(&EncodedImage::descriptor_blob(), 0)
+            .unwrap()
+            .unwrap();
+        let jpeg = image::load_from_memory(&blob).unwrap();
+        assert_eq!((16, 8), (jpeg.width(), jpeg.height()));
+    }
+}
@@ -1,16 +1,19 @@
-use std::{collections::HashSet, io::IsTerminal as _};
+use std::{collections::HashSet, io::IsTerminal as _, str::FromStr};
 
 use anyhow::Context as _;
-use arrow::array::RecordBatchOptions;
 use arrow::{
-    array::RecordBatch as ArrowRecordBatch,
+    array::{RecordBatch as ArrowRecordBatch, RecordBatchOptions},
     datatypes::{Field as ArrowField, Schema as ArrowSchema},
 };
 use itertools::Either;
 
 use re_build_info::CrateVersion;
-use re_chunk::external::crossbeam;
-use re_sdk::{EntityPath, external::arrow};
+use re_chunk::{
+    Chunk,
+    external::{crossbeam, nohash_hasher::IntSet},
+};
+use re_log_types::{AbsoluteTimeRange, TimeType, TimelineName, TimestampFormat};
+use re_sdk::{ComponentDescriptor, EntityPath, external::arrow};
 
 use crate::commands::read_rrd_streams_from_file_or_stdin;
 
@@ -30,9 +33,41 @@ pub struct FilterCommand {
     dropped_timelines: Vec<String>,
 
     /// Paths of the entities to be filtered out.
+    ///
+    /// Supports glob patterns: `*` matches a single path part, `**` matches any number of them
+    /// (e.g. `/world/debug/**`).
     #[clap(long = "drop-entity")]
     dropped_entity_paths: Vec<String>,
 
+    /// Paths of the entities to be kept. Everything else will be filtered out.
+    ///
+    /// Supports glob patterns: `*` matches a single path part, `**` matches any number of them
+    /// (e.g. `/tracks/*/bbox`).
+    ///
+    /// Keeps everything if unspecified. `--drop-entity` applies on top of this.
+    #[clap(long = "entity")]
+    kept_entity_paths: Vec<String>,
+
+    /// Components to be filtered out, while keeping the rest of the entity.
+    ///
+    /// Matches either the component identifier (e.g. `Image:buffer`) or the component type
+    /// (e.g. `ImageBuffer` or `rerun.components.ImageBuffer`).
+    #[clap(long = "drop-component")]
+    dropped_components: Vec<String>,
+
+    /// Only keep the data within the given range on the given timeline.
+    ///
+    /// Syntax: `<timeline>=<min>..<max>`, where either bound can be omitted, e.g.
+    /// `frame=100..500` or `log_time=2026-10-01T10:00..`.
+    /// Bounds are inclusive. Timestamps without a timezone are interpreted as UTC.
+    ///
+    /// Chunks straddling the boundaries are sliced accordingly. Data that isn't indexed on
+    /// that timeline (e.g. static data) is kept as-is.
+    ///
+    /// Can be specified more than once, in which case all ranges must match.
+    #[clap(long = "time-range")]
+    time_ranges: Vec<TimeRangeFilter>,
+
     /// If set, will try to proceed even in the face of IO and/or decoding errors in the input data.
     #[clap(long = "continue-on-error", default_value_t = false)]
     continue_on_error: bool,
@@ -45,6 +80,9 @@ impl FilterCommand {
             path_to_output_rrd,
             dropped_timelines,
             dropped_entity_paths,
+            kept_entity_paths,
+            dropped_components,
+            time_ranges,
             continue_on_error,
         } = self;
 
@@ -57,13 +95,26 @@ impl FilterCommand {
         }
 
         let now = std::time::Instant::now();
-        re_log::info!(srcs = ?path_to_input_rrds, ?dropped_timelines, "filter started");
+        re_log::info!(
+            srcs = ?path_to_input_rrds,
+            ?dropped_timelines,
+            ?dropped_entity_paths,
+            ?kept_entity_paths,
+            ?dropped_components,
+            ?time_ranges,
+            "filter started"
+        );
 
-        let dropped_timelines: HashSet<_> = dropped_timelines.iter().cloned().collect();
-        let dropped_entity_paths: HashSet<EntityPath> = dropped_entity_paths
-            .iter()
-            .map(|s| EntityPath::parse_forgiving(s))
-            .collect();
+        let filter = ChunkFilter {
+            dropped_timelines: dropped_timelines
+                .iter()
+                .map(|timeline| TimelineName::new(timeline))
+                .collect(),
+            dropped_entity_paths: parse_entity_path_globs(dropped_entity_paths)?,
+            kept_entity_paths: parse_entity_path_globs(kept_entity_paths)?,
+            dropped_components: dropped_components.iter().cloned().collect(),
+            time_ranges: time_ranges.clone(),
+        };
 
         let (rx_decoder, rx_size_bytes) = read_rrd_streams_from_file_or_stdin(path_to_input_rrds);
 
@@ -108,42 +159,15 @@ impl FilterCommand {
             match res {
                 Ok(msg) => {
                     let msg = match msg {
-                        re_log_types::LogMsg::ArrowMsg(store_id, mut msg) => {
-                            match re_sorbet::ChunkBatch::try_from(&msg.batch) {
-                                Ok(batch) => {
-                                    if !dropped_entity_paths.contains(batch.entity_path()) {
-                                        None
-                                    } else {
-                                        let (fields, columns): (Vec<_>, Vec<_>) = itertools::izip!(
-                                            &batch.schema().fields,
-                                            batch.columns()
-                                        )
-                                        .filter(|(field, _col)| {
-                                            !is_field_timeline_of(field, &dropped_timelines)
-                                        })
-                                        .map(|(field, col)| (field.clone(), col.clone()))
-                                        .unzip();
-
-                                        if let Ok(new_batch) =
-                                            ArrowRecordBatch::try_new_with_options(
-                                                ArrowSchema::new_with_metadata(
-                                                    fields,
-                                                    batch.schema().metadata().clone(),
-                                                )
-                                                .into(),
-                                                columns,
-                                                &RecordBatchOptions::default(),
-                                            )
-                                        {
-                                            msg.batch = new_batch;
-                                            Some(re_log_types::LogMsg::ArrowMsg(store_id, msg))
-                                        } else {
-                                            None // Probably failed because we filtered out everything
-                                        }
-                                    }
+                        re_log_types::LogMsg::ArrowMsg(store_id, msg) => {
+                            match filter.apply(msg) {
+                                Ok(Some(msg)) => {
+                                    Some(re_log_types::LogMsg::ArrowMsg(store_id, msg))
                                 }
+                                Ok(None) => None, // Filtered out entirely
                                 Err(err) => {
-                                    re_log::warn_once!("Failed to parse chunk schema: {err}");
+                                    re_log::error!(err = re_error::format(err));
+                                    is_success = false;
                                     None
                                 }
                             }
@@ -209,9 +233,230 @@ impl FilterCommand {
 
 // ---
 
+struct ChunkFilter {
+    dropped_timelines: IntSet<TimelineName>,
+    dropped_entity_paths: Vec<glob::Pattern>,
+    kept_entity_paths: Vec<glob::Pattern>,
+    dropped_components: HashSet<String>,
+    time_ranges: Vec<TimeRangeFilter>,
+}
+
+impl ChunkFilter {
+    /// Applies all filters to the chunk in the given message.
+    ///
+    /// Returns `None` if nothing is left of it, or if it cannot be decoded.
+    ///
+    /// The chunk is only decoded if it has to be sliced: entities and timelines are filtered
+    /// based on its schema alone.
+    fn apply(&self, msg: re_log_types::ArrowMsg) -> anyhow::Result<Option<re_log_types::ArrowMsg>> {
+        let batch = match re_sorbet::ChunkBatch::try_from(&msg.batch) {
+            Ok(batch) => batch,
+            Err(err) => {
+                re_log::warn_once!("Failed to parse chunk schema: {err}");
+                return Ok(None);
+            }
+        };
+
+        if !self.keeps_entity(batch.entity_path()) {
+            return Ok(None);
+        }
+
+        let slices_time = self.time_ranges.iter().any(|time_range| {
+            batch
+                .index_columns()
+                .any(|(descr, _)| descr.timeline_name() == time_range.timeline)
+        });
+        let slices_components = batch
+            .component_columns()
+            .any(|(descr, _)| self.drops_component(&descr.component_descriptor()));
+
+        if !slices_time && !slices_components {
+            return Ok(self.drop_timelines(msg));
+        }
+
+        let mut chunk = match Chunk::from_arrow_msg(&msg) {
+            Ok(chunk) => chunk,
+            Err(err) => {
+                re_log::warn_once!("Failed to decode chunk: {err}");
+                return Ok(None);
+            }
+        };
+
+        if !self.dropped_timelines.is_empty() {
+            let timelines_to_keep = chunk
+                .timelines()
+                .keys()
+                .filter(|timeline| !self.dropped_timelines.contains(timeline))
+                .copied()
+                .collect();
+            chunk = chunk.timelines_sliced(&timelines_to_keep);
+        }
+
+        for time_range in &self.time_ranges {
+            let Some(time_column) = chunk.timelines().get(&time_range.timeline) else {
+                continue; // Not indexed on that timeline: keep as-is.
+            };
+
+            let range = time_range.resolve(time_column.timeline().typ())?;
+            chunk = chunk.time_range_sliced(&time_range.timeline, range);
+
+            if chunk.is_empty() {
+                return Ok(None);
+            }
+        }
+
+        if !self.dropped_components.is_empty() && chunk.num_components() > 0 {
+            let components_to_keep = chunk
+                .component_descriptors()
+                .filter(|descr| !self.drops_component(descr))
+                .collect();
+            chunk = chunk.components_sliced(&components_to_keep);
+
+            if chunk.num_components() == 0 {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(
+            chunk.to_arrow_msg().context("couldn't re-encode chunk")?,
+        ))
+    }
+
+    /// Drops the timelines straight from the record batch, without decoding the chunk.
+    fn drop_timelines(&self, mut msg: re_log_types::ArrowMsg) -> Option<re_log_types::ArrowMsg> {
+        if self.dropped_timelines.is_empty() {
+            return Some(msg);
+        }
+
+        let batch = &msg.batch;
+        let (fields, columns): (Vec<_>, Vec<_>) =
+            itertools::izip!(&batch.schema().fields, batch.columns())
+                .filter(|(field, _col)| !is_field_timeline_of(field, &self.dropped_timelines))
+                .map(|(field, col)| (field.clone(), col.clone()))
+                .unzip();
+
+        msg.batch = ArrowRecordBatch::try_new_with_options(
+            ArrowSchema::new_with_metadata(fields, batch.schema().metadata().clone()).into(),
+            columns,
+            &RecordBatchOptions::default(),
+        )
+        .ok()?; // Probably failed because we filtered out everything
+
+        Some(msg)
+    }
+
+    fn keeps_entity(&self, entity_path: &EntityPath) -> bool {
+        let entity_path = entity_path.to_string();
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        let matches = |pattern: &glob::Pattern| pattern.matches_with(&entity_path, options);
+
+        (self.kept_entity_paths.is_empty() || self.kept_entity_paths.iter().any(matches))
+            && !self.dropped_entity_paths.iter().any(matches)
+    }
+
+    fn drops_component(&self, descr: &ComponentDescriptor) -> bool {
+        self.dropped_components.contains(descr.component.as_str())
+            || descr.component_type.is_some_and(|component_type| {
+                self.dropped_components
+                    .contains(component_type.short_name())
+                    || self.dropped_components.contains(component_type.full_name())
+            })
+    }
+}
+
 // Does the given field represent a timeline that is in the given set?
-fn is_field_timeline_of(field: &ArrowField, dropped_timelines: &HashSet<String>) -> bool {
+fn is_field_timeline_of(field: &ArrowField, dropped_timelines: &IntSet<TimelineName>) -> bool {
     re_sorbet::IndexColumnDescriptor::try_from(field)
         .ok()
-        .is_some_and(|schema| dropped_timelines.contains(schema.column_name()))
+        .is_some_and(|schema| dropped_timelines.contains(&schema.timeline_name()))
+}
+
+fn parse_entity_path_globs(patterns: &[String]) -> anyhow::Result<Vec<glob::Pattern>> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            let pattern = if pattern.starts_with('/') {
+                pattern.clone()
+            } else {
+                format!("/{pattern}")
+            };
+            glob::Pattern::new(&pattern).with_context(|| format!("invalid entity glob {pattern:?}"))
+        })
+        .collect()
+}
+
+/// A `--time-range` argument: `<timeline>=<min>..<max>`, where either bound can be omitted.
+///
+/// The bounds are kept as strings, since how they should be parsed depends on the type of the
+/// timeline, which is only known once we encounter the data.
+#[derive(Debug, Clone)]
+struct TimeRangeFilter {
+    timeline: TimelineName,
+    min: Option<String>,
+    max: Option<String>,
+}
+
+impl FromStr for TimeRangeFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((timeline, range)) = s.split_once('=') else {
+            return Err(format!("expected `<timeline>=<min>..<max>`, got {s:?}"));
+        };
+        let Some((min, max)) = range.split_once("..") else {
+            return Err(format!("expected `<min>..<max>`, got {range:?}"));
+        };
+
+        let bound = |s: &str| {
+            let s = s.trim();
+            (!s.is_empty()).then(|| s.to_owned())
+        };
+
+        let filter = Self {
+            timeline: TimelineName::new(timeline.trim()),
+            min: bound(min),
+            max: bound(max),
+        };
+
+        // Make sure the bounds make sense for at least one kind of timeline.
+        let is_valid = [
+            TimeType::Sequence,
+            TimeType::DurationNs,
+            TimeType::TimestampNs,
+        ]
+        .into_iter()
+        .any(|typ| filter.resolve(typ).is_ok());
+        if !is_valid {
+            return Err(format!("couldn't parse time range {range:?}"));
+        }
+
+        Ok(filter)
+    }
+}
+
+impl TimeRangeFilter {
+    fn resolve(&self, typ: TimeType) -> anyhow::Result<AbsoluteTimeRange> {
+        let parse = |s: &Option<String>, default| match s {
+            Some(s) => typ
+                .parse_time(s, TimestampFormat::utc())
+                .with_context(|| format!("{s:?} is not a valid {typ:?} value")),
+            None => Ok(default),
+        };
+
+        let min = parse(&self.min, re_log_types::TimeInt::MIN)?;
+        let max = parse(&self.max, re_log_types::TimeInt::MAX)?;
+
+        anyhow::ensure!(
+            min <= max,
+            "empty time range on {:?}: {:?}..{:?}",
+            self.timeline,
+            self.min,
+            self.max
+        );
+
+        Ok(AbsoluteTimeRange::new(min, max))
+    }
 }
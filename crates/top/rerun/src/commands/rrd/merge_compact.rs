@@ -10,6 +10,8 @@ use re_sdk::StoreKind;
 
 use crate::commands::read_rrd_streams_from_file_or_stdin;
 
+use super::downsample::{DownsampleArgs, Downsampler};
+
 // ---
 
 #[derive(Debug, Clone, clap::Parser)]
@@ -51,6 +53,7 @@ impl MergeCommand {
         merge_and_compact(
             num_passes,
             *continue_on_error,
+            None,
             &store_config,
             path_to_input_rrds,
             path_to_output_rrd.as_ref(),
@@ -107,6 +110,9 @@ pub struct CompactCommand {
     #[arg(long = "num-pass", default_value_t = 50)]
     num_extra_passes: u32,
 
+    #[command(flatten)]
+    downsample: DownsampleArgs,
+
     /// If set, will try to proceed even in the face of IO and/or decoding errors in the input data.
     #[clap(long = "continue-on-error", default_value_t = false)]
     continue_on_error: bool,
@@ -121,6 +127,7 @@ impl CompactCommand {
             max_rows,
             max_rows_if_unsorted,
             num_extra_passes,
+            downsample,
             continue_on_error,
         } = self;
 
@@ -146,9 +153,12 @@ impl CompactCommand {
             store_config.chunk_max_rows_if_unsorted = *max_rows_if_unsorted;
         }
 
+        let downsampler = (!downsample.is_noop()).then(|| Downsampler::new(downsample.clone()));
+
         merge_and_compact(
             *num_extra_passes,
             *continue_on_error,
+            downsampler,
             &store_config,
             path_to_input_rrds,
             path_to_output_rrd.as_ref(),
@@ -159,6 +169,7 @@ impl CompactCommand {
 fn merge_and_compact(
     num_passes: u32,
     continue_on_error: bool,
+    mut downsampler: Option<Downsampler>,
     store_config: &ChunkStoreConfig,
     path_to_input_rrds: &[String],
     path_to_output_rrd: Option<&String>,
@@ -192,15 +203,38 @@ fn merge_and_compact(
         match res {
             Ok(msg) => {
                 num_chunks_before += matches!(msg, re_log_types::LogMsg::ArrowMsg(_, _)) as u64;
-                if let Err(err) = entity_dbs
-                    .entry(msg.store_id().clone())
-                    .or_insert_with(|| {
-                        re_entity_db::EntityDb::with_store_config(
-                            msg.store_id().clone(),
-                            store_config.clone(),
-                        )
-                    })
-                    .add(&msg)
+
+                let msg = match (msg, downsampler.as_mut()) {
+                    (re_log_types::LogMsg::ArrowMsg(store_id, arrow_msg), Some(downsampler)) => {
+                        match downsampler.apply(&store_id, &arrow_msg) {
+                            Ok(Some(arrow_msg)) => {
+                                Some(re_log_types::LogMsg::ArrowMsg(store_id, arrow_msg))
+                            }
+                            Ok(None) => None, // Downsampled away entirely
+                            Err(err) => {
+                                re_log::error!(
+                                    err = re_error::format(err),
+                                    "couldn't downsample chunk"
+                                );
+                                is_success = false;
+                                None
+                            }
+                        }
+                    }
+
+                    (msg, _) => Some(msg),
+                };
+
+                if let Some(msg) = msg
+                    && let Err(err) = entity_dbs
+                        .entry(msg.store_id().clone())
+                        .or_insert_with(|| {
+                            re_entity_db::EntityDb::with_store_config(
+                                msg.store_id().clone(),
+                                store_config.clone(),
+                            )
+                        })
+                        .add(&msg)
                 {
                     re_log::error!(%err, "couldn't index corrupt chunk");
                     is_success = false;
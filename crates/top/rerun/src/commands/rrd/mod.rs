@@ -1,23 +1,37 @@
 mod compare;
+mod diff;
+mod downsample;
 mod filter;
 mod merge_compact;
 mod migrate;
 mod print;
+mod query;
+#[cfg(feature = "data_loaders")]
+mod replay;
 mod route;
+mod split;
 mod stats;
+#[cfg(feature = "data_loaders")]
+mod to_mcap;
 mod verify;
 
 use self::{
     compare::CompareCommand,
+    diff::DiffCommand,
     filter::FilterCommand,
     merge_compact::{CompactCommand, MergeCommand},
     migrate::MigrateCommand,
     print::PrintCommand,
+    query::QueryCommand,
     route::RouteCommand,
+    split::SplitCommand,
     stats::StatsCommand,
     verify::VerifyCommand,
 };
 
+#[cfg(feature = "data_loaders")]
+use self::{replay::ReplayCommand, to_mcap::ToMcapCommand};
+
 // ---
 
 use anyhow::Context as _;
@@ -37,6 +51,9 @@ pub enum RrdCommands {
     ///
     /// Unless explicit flags are passed, in which case they will override environment values.
     ///
+    /// The data can optionally be downsampled along the way (row decimation, image transcoding,
+    /// point cloud voxelization), e.g. to make a recording small enough for sharing.
+    ///
     /// ⚠️ This will automatically migrate the data to the latest version of the RRD protocol, if needed. ⚠️
     ///
     /// Examples:
@@ -44,6 +61,8 @@ pub enum RrdCommands {
     /// * `RERUN_CHUNK_MAX_ROWS=4096 RERUN_CHUNK_MAX_BYTES=1048576 rerun rrd compact /my/recordings/*.rrd -o output.rrd`
     ///
     /// * `rerun rrd compact --max-rows 4096 --max-bytes=1048576 /my/recordings/*.rrd > output.rrd`
+    ///
+    /// * `rerun rrd compact --time-bucket log_time=100ms --image-max-size 640 --jpeg-quality 80 --voxel-size 0.05 field.rrd -o review.rrd`
     Compact(CompactCommand),
 
     /// Compares the data between 2 .rrd files, returning a successful shell exit code if they
@@ -52,13 +71,29 @@ pub enum RrdCommands {
     /// This ignores the `log_time` timeline.
     Compare(CompareCommand),
 
+    /// Reports all the differences between 2 .rrd files, per entity and per component, returning
+    /// a successful shell exit code only if there are none.
+    ///
+    /// Reports missing/extra entities and components, row count changes, time range shifts, and
+    /// values that differ (beyond `--tolerance`, for numeric data).
+    ///
+    /// Rows are matched by timepoint, ignoring the `log_time` timeline by default.
+    ///
+    /// Example: `rerun rrd diff --tolerance 1e-6 --format json expected.rrd actual.rrd`
+    Diff(DiffCommand),
+
     /// Filters out data from .rrd/.rbl files/streams, and writes the result to standard output.
     ///
     /// Reads from standard input if no paths are specified.
     ///
-    /// This will not affect the chunking of the data in any way.
+    /// This will never merge nor split chunks, though chunks straddling the boundaries of a
+    /// `--time-range` will be sliced accordingly.
+    ///
+    /// Examples:
+    ///
+    /// * `rerun rrd filter --drop-timeline log_tick /my/recordings/*.rrd > output.rrd`
     ///
-    /// Example: `rerun rrd filter --drop-timeline log_tick /my/recordings/*.rrd > output.rrd`
+    /// * `rerun rrd filter --time-range frame=100..500 --entity "/world/**" --drop-component Image:buffer /my/recordings/*.rrd > output.rrd`
     Filter(FilterCommand),
 
     /// Merges the contents of multiple .rrd/.rbl files/streams, and writes the result to standard output.
@@ -83,6 +118,35 @@ pub enum RrdCommands {
     /// Example: `rerun rrd print /my/recordings/*.rrd`
     Print(PrintCommand),
 
+    /// Runs a SQL query over the contents of one or more .rrd files, and prints the results or
+    /// writes them to a Parquet file.
+    ///
+    /// Every recording is registered as a table, indexed on the timeline specified with `--index`.
+    /// Each row corresponds to a unique index value, and each entity/component pair to a column.
+    ///
+    /// Examples:
+    ///
+    /// * `rerun rrd query "SELECT * FROM recording WHERE frame_nr > 100" my_recording.rrd`
+    ///
+    /// * `rerun rrd query --index frame_nr "SELECT * FROM a JOIN b USING (frame_nr)" a=run1.rrd b=run2.rrd -o out.parquet`
+    Query(QueryCommand),
+
+    /// Replays the recordings of one or more .rrd files to a gRPC proxy, e.g. a running viewer,
+    /// as if they were being logged live.
+    ///
+    /// Data is sent at the pace at which it was originally logged, according to the `log_time`
+    /// timeline, which is shifted to the time of the replay.
+    /// The replay takes the application id of the first recording, and a new recording id,
+    /// unless specified otherwise.
+    ///
+    /// Examples:
+    ///
+    /// * `rerun rrd replay --connect rerun+http://127.0.0.1:9876/proxy my_recording.rrd`
+    ///
+    /// * `rerun rrd replay --speed 4 --loop --recording-id live field.rrd`
+    #[cfg(feature = "data_loaders")]
+    Replay(ReplayCommand),
+
     /// Manipulates the metadata of log message streams without decoding the payloads.
     ///
     /// This can be used to combine multiple .rrd files into a single recording.
@@ -91,6 +155,21 @@ pub enum RrdCommands {
     /// Note: Because the payload of the messages is never decoded, no migration or verification will performed.
     Route(RouteCommand),
 
+    /// Splits the contents of one or more .rrd files into several standalone .rrd files.
+    ///
+    /// The data can be split per recording, per top-level entity subtree, or per fixed-size time
+    /// window. Every output is a valid recording on its own: static data and blueprints are copied
+    /// into all of them.
+    ///
+    /// Reads from standard input if no paths are specified.
+    ///
+    /// Examples:
+    ///
+    /// * `rerun rrd split --by recording /my/recordings/*.rrd -o splits/`
+    ///
+    /// * `rerun rrd split --by time --window log_time=10min field.rrd -o splits/`
+    Split(SplitCommand),
+
     /// Compute important statistics for one or more .rrd/.rbl files/streams.
     ///
     /// Reads from standard input if no paths are specified.
@@ -98,6 +177,18 @@ pub enum RrdCommands {
     /// Example: `rerun rrd stats /my/recordings/*.rrd`
     Stats(StatsCommand),
 
+    /// Converts the contents of one or more .rrd files/streams to an .mcap file.
+    ///
+    /// Reads from standard input if no paths are specified.
+    ///
+    /// MCAP messages that were imported with the `raw` layer are written back out as-is.
+    /// Images, point clouds, transforms and scalars are written as ROS2 messages.
+    /// All other data is skipped.
+    ///
+    /// Example: `rerun rrd to-mcap /my/recordings/*.rrd -o output.mcap`
+    #[cfg(feature = "data_loaders")]
+    ToMcap(ToMcapCommand),
+
     /// Verify the that the .rrd file can be loaded and correctly interpreted.
     ///
     /// Can be used to ensure that the current Rerun version can load the data.
@@ -113,12 +204,19 @@ impl RrdCommands {
                     .with_context(|| format!("current directory {:?}", std::env::current_dir()))
             }
             Self::Compact(cmd) => cmd.run(),
+            Self::Diff(cmd) => cmd.run(),
             Self::Filter(cmd) => cmd.run(),
             Self::Merge(cmd) => cmd.run(),
             Self::Migrate(cmd) => cmd.run(),
             Self::Print(cmd) => cmd.run(),
+            Self::Query(cmd) => cmd.run(),
+            #[cfg(feature = "data_loaders")]
+            Self::Replay(cmd) => cmd.run(),
             Self::Route(cmd) => cmd.run(),
+            Self::Split(cmd) => cmd.run(),
             Self::Stats(cmd) => cmd.run(),
+            #[cfg(feature = "data_loaders")]
+            Self::ToMcap(cmd) => cmd.run(),
             Self::Verify(cmd) => cmd.run(),
         }
     }
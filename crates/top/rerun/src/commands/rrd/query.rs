@@ -0,0 +1,397 @@
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use arrow::array::RecordBatch;
+use datafusion::prelude::SessionContext;
+use itertools::Itertools as _;
+
+use re_dataframe::{
+    ChunkStoreConfig, EntityPathFilter, QueryEngine, QueryExpression, QuerySpec,
+    SparseFillStrategy, StorageEngine, TimelineName,
+};
+use re_datafusion::RecordingTableProvider;
+
+// ---
+
+#[derive(Debug, Clone, clap::Parser)]
+pub struct QueryCommand {
+    /// The SQL query to run, e.g. `SELECT * FROM recording WHERE log_time > '2025-10-01'`.
+    sql: String,
+
+    /// Paths to read from, optionally prefixed with the name of the table to register them as
+    /// (e.g. `left=a.rrd right=b.rrd`).
+    ///
+    /// Unnamed inputs are registered as `recording` if there is a single recording in total,
+    /// or as `recording_0`, `recording_1`, etc. otherwise.
+    /// Named inputs that contain more than one recording get the same numbered suffix.
+    #[clap(required = true)]
+    path_to_input_rrds: Vec<String>,
+
+    /// Path to a Parquet file to write the results to. Prints them to standard output if unspecified.
+    #[arg(short = 'o', long = "output", value_name = "dst.parquet")]
+    path_to_output_parquet: Option<String>,
+
+    /// The timeline used as the index of every table.
+    ///
+    /// Defaults to `log_time` if the recording has it, or to the first timeline in lexical order
+    /// otherwise.
+    #[clap(long)]
+    index: Option<String>,
+
+    /// Only include the entities matching this filter in the tables (e.g. `/world/**`).
+    #[clap(long = "entity")]
+    entity_path_filter: Option<String>,
+
+    /// If set, null values are filled using latest-at semantics.
+    #[clap(long, default_value_t = false)]
+    latest_at: bool,
+
+    /// The contents and settings of every table, using the same query syntax as the dataframe
+    /// view (e.g. `/world/points:[positions, radius] index=frame_nr range=10..20 fill=latest-at`).
+    ///
+    /// Cannot be combined with `--index`, `--entity` or `--latest-at`.
+    #[clap(long, value_name = "QUERY", conflicts_with_all = ["index", "entity_path_filter", "latest_at"])]
+    view: Option<String>,
+
+    /// Transpose record batches before printing them?
+    #[clap(long, default_value_t = false)]
+    transposed: bool,
+}
+
+/// A single recording, ready to be registered as a table.
+struct RecordingTable {
+    table_name: String,
+    engine: QueryEngine<StorageEngine>,
+}
+
+impl QueryCommand {
+    pub fn run(&self) -> anyhow::Result<()> {
+        let Self {
+            sql,
+            path_to_input_rrds,
+            path_to_output_parquet,
+            index: _,
+            entity_path_filter: _,
+            latest_at: _,
+            view: _,
+            transposed,
+        } = self;
+
+        let now = std::time::Instant::now();
+        re_log::info!(srcs = ?path_to_input_rrds, "query started");
+
+        let tables = load_recording_tables(path_to_input_rrds)?;
+        anyhow::ensure!(!tables.is_empty(), "no data recording found in inputs");
+
+        let ctx = self.session_context(tables)?;
+
+        // NOTE: The entrypoint enters the runtime but never blocks on it, so this is fine.
+        let batches = tokio::runtime::Handle::current()
+            .block_on(async {
+                let df = ctx.sql(sql).await?;
+                df.collect().await
+            })
+            .context("couldn't run query")?;
+
+        let num_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+
+        if let Some(path) = path_to_output_parquet {
+            write_parquet(path, &batches).with_context(|| format!("{path:?}"))?;
+        } else {
+            let format_options = re_format_arrow::RecordBatchFormatOpts {
+                transposed: *transposed,
+                width: None, // terminal width
+                include_metadata: false,
+                include_column_metadata: false,
+                ..Default::default()
+            };
+
+            for batch in &batches {
+                println!(
+                    "{}",
+                    re_format_arrow::format_record_batch_opts(batch, &format_options)
+                );
+            }
+        }
+
+        re_log::info!(
+            num_rows = %re_format::format_uint(num_rows),
+            time = ?now.elapsed(),
+            srcs = ?path_to_input_rrds,
+            "query finished"
+        );
+
+        Ok(())
+    }
+
+    /// Registers every recording as a table, queried according to the command's options.
+    fn session_context(&self, tables: Vec<RecordingTable>) -> anyhow::Result<SessionContext> {
+        let Self {
+            sql: _,
+            path_to_input_rrds: _,
+            path_to_output_parquet: _,
+            index,
+            entity_path_filter,
+            latest_at,
+            view,
+            transposed: _,
+        } = self;
+
+        let entity_path_filter = entity_path_filter
+            .as_deref()
+            .map(EntityPathFilter::parse_strict)
+            .transpose()
+            .context("invalid entity path filter")?;
+
+        let view = view
+            .as_deref()
+            .map(str::parse::<QuerySpec>)
+            .transpose()
+            .context("invalid view query")?;
+
+        let ctx = SessionContext::new();
+        for RecordingTable { table_name, engine } in tables {
+            let mut query_expression = if let Some(view) = &view {
+                view.to_query_expression(&engine)
+                    .with_context(|| format!("invalid view query for {table_name:?}"))?
+            } else {
+                let view_contents = entity_path_filter.as_ref().map(|filter| {
+                    engine
+                        .iter_entity_paths_sorted(filter)
+                        .map(|entity_path| (entity_path, None))
+                        .collect()
+                });
+
+                QueryExpression {
+                    view_contents,
+                    filtered_index: index.as_deref().map(TimelineName::new),
+                    sparse_fill_strategy: if *latest_at {
+                        SparseFillStrategy::LatestAtGlobal
+                    } else {
+                        SparseFillStrategy::None
+                    },
+                    ..Default::default()
+                }
+            };
+
+            let Some(filtered_index) = query_expression
+                .filtered_index
+                .or_else(|| default_index(&engine))
+            else {
+                re_log::warn!(%table_name, "recording has no timeline, skipping");
+                continue;
+            };
+            query_expression.filtered_index = Some(filtered_index);
+
+            re_log::debug!(%table_name, %filtered_index, "registering table");
+            ctx.register_table(
+                table_name.as_str(),
+                RecordingTableProvider::new(engine, query_expression).into_provider(),
+            )
+            .with_context(|| format!("couldn't register table {table_name:?}"))?;
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Loads all the data recordings found in the given `[name=]path` inputs.
+fn load_recording_tables(path_to_input_rrds: &[String]) -> anyhow::Result<Vec<RecordingTable>> {
+    let mut named_inputs = Vec::new();
+    let mut unnamed_engines = Vec::new();
+
+    for input in path_to_input_rrds {
+        let (table_name, path) = match input.split_once('=') {
+            Some((table_name, path)) if !table_name.is_empty() => {
+                (Some(table_name.to_owned()), PathBuf::from(path))
+            }
+            _ => (None, PathBuf::from(input)),
+        };
+
+        // NOTE: We're doing headless processing, there's no point in running subscribers, and we
+        // want the data exactly as it was written.
+        let engines = QueryEngine::from_rrd_filepath(&ChunkStoreConfig::ALL_DISABLED, &path)
+            .with_context(|| format!("couldn't load {path:?}"))?
+            .into_iter()
+            .filter(|(store_id, _)| store_id.is_recording())
+            .map(|(_, engine)| engine)
+            .collect_vec();
+
+        if let Some(table_name) = table_name {
+            named_inputs.push((table_name, engines));
+        } else {
+            unnamed_engines.extend(engines);
+        }
+    }
+
+    let mut tables = Vec::new();
+
+    for (table_name, engines) in named_inputs {
+        tables.extend(numbered_tables(&table_name, engines));
+    }
+    tables.extend(numbered_tables("recording", unnamed_engines));
+
+    let duplicated_names = tables
+        .iter()
+        .map(|table| table.table_name.as_str())
+        .duplicates()
+        .collect_vec();
+    anyhow::ensure!(
+        duplicated_names.is_empty(),
+        "the same table name was used more than once: {duplicated_names:?}"
+    );
+
+    Ok(tables)
+}
+
+/// Names the tables `name` if there's only one engine, or `name_0`, `name_1`, etc. otherwise.
+fn numbered_tables(
+    table_name: &str,
+    engines: Vec<QueryEngine<StorageEngine>>,
+) -> impl Iterator<Item = RecordingTable> + '_ {
+    let is_single = engines.len() == 1;
+    engines
+        .into_iter()
+        .enumerate()
+        .map(move |(i, engine)| RecordingTable {
+            table_name: if is_single {
+                table_name.to_owned()
+            } else {
+                format!("{table_name}_{i}")
+            },
+            engine,
+        })
+}
+
+/// `log_time` if present, the first timeline in lexical order otherwise.
+fn default_index(engine: &QueryEngine<StorageEngine>) -> Option<TimelineName> {
+    let timelines = engine
+        .schema()
+        .indices
+        .iter()
+        .map(|descr| descr.timeline_name())
+        .collect_vec();
+
+    timelines
+        .iter()
+        .find(|timeline| timeline.as_str() == "log_time")
+        .or_else(|| timelines.iter().min_by_key(|timeline| timeline.as_str()))
+        .copied()
+}
+
+fn write_parquet(path: &str, batches: &[RecordBatch]) -> anyhow::Result<()> {
+    let Some(schema) = batches.first().map(|batch| batch.schema()) else {
+        re_log::warn!("query returned no data, nothing to write");
+        return Ok(());
+    };
+
+    let file = std::fs::File::create(path)?;
+    let mut writer = parquet::arrow::ArrowWriter::try_new(file, schema, None)?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.close()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::AsArray as _;
+    use clap::Parser as _;
+
+    use re_chunk::{Chunk, RowId};
+    use re_chunk_store::ChunkStore;
+    use re_log_types::{EntityPath, StoreId, StoreKind, Timeline};
+
+    use super::*;
+
+    fn engine() -> QueryEngine<StorageEngine> {
+        let store = ChunkStore::new_handle(
+            StoreId::random(StoreKind::Recording, "rerun_example_query"),
+            ChunkStoreConfig::COMPACTION_DISABLED,
+        );
+
+        let frame_nr = Timeline::new_sequence("frame_nr");
+        let mut builder = Chunk::builder(EntityPath::from("scalar"));
+        for frame in 0..10_i64 {
+            builder = builder.with_archetype(
+                RowId::new(),
+                [(frame_nr, frame)],
+                &re_types::archetypes::Scalars::single(frame as f64),
+            );
+        }
+        store
+            .write()
+            .insert_chunk(&Arc::new(builder.build().unwrap()))
+            .unwrap();
+
+        QueryEngine::from_store(store)
+    }
+
+    fn query(sql: &str) -> Vec<i64> {
+        let cmd = QueryCommand::try_parse_from(["query", sql, "recording.rrd"]).unwrap();
+        let ctx = cmd
+            .session_context(vec![RecordingTable {
+                table_name: "recording".to_owned(),
+                engine: engine(),
+            }])
+            .unwrap();
+
+        let batches = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(async { ctx.sql(sql).await?.collect().await })
+            .unwrap();
+
+        batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column_by_name("frame_nr")
+                    .unwrap()
+                    .as_primitive::<arrow::datatypes::Int64Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn select() {
+        assert_eq!(
+            (0..10).collect::<Vec<_>>(),
+            query("SELECT frame_nr FROM recording ORDER BY frame_nr")
+        );
+    }
+
+    #[test]
+    fn index_range() {
+        assert_eq!(
+            vec![3, 4, 5],
+            query(
+                "SELECT frame_nr FROM recording WHERE frame_nr >= 3 AND frame_nr < 6 ORDER BY frame_nr"
+            )
+        );
+        assert_eq!(
+            vec![2, 7],
+            query(
+                "SELECT frame_nr FROM recording WHERE frame_nr = 2 OR frame_nr = 7 ORDER BY frame_nr"
+            )
+        );
+        assert_eq!(
+            Vec::<i64>::new(),
+            query("SELECT frame_nr FROM recording WHERE frame_nr > 5 AND frame_nr < 3")
+        );
+    }
+
+    #[test]
+    fn limit() {
+        assert_eq!(2, query("SELECT frame_nr FROM recording LIMIT 2").len());
+        assert_eq!(
+            vec![8, 9],
+            query("SELECT frame_nr FROM recording WHERE frame_nr >= 8 LIMIT 5")
+        );
+    }
+}
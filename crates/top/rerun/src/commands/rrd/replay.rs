@@ -0,0 +1,196 @@
+use anyhow::Context as _;
+
+use re_log_types::{LogMsg, StoreId};
+use re_sdk::{RecordingStreamBuilder, ReplayOptions};
+
+// ---
+
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ReplayCommand {
+    /// Paths of the .rrd files to replay, one after the other.
+    #[arg(required = true)]
+    path_to_input_rrds: Vec<String>,
+
+    /// URL of the gRPC proxy to stream the replayed data to, e.g. a running viewer.
+    #[clap(long, default_value = re_sdk::DEFAULT_CONNECT_URL)]
+    connect: String,
+
+    /// Multiplies the replay speed, e.g. `2.0` replays twice as fast as the original recording.
+    #[clap(long, default_value_t = 1.0, value_parser = parse_speed)]
+    speed: f64,
+
+    /// Send the data as fast as possible instead of respecting the gaps between `log_time`s.
+    #[clap(long = "as-fast-as-possible", default_value_t = false)]
+    as_fast_as_possible: bool,
+
+    /// Replay the files over and over, until interrupted.
+    #[clap(long = "loop", default_value_t = false)]
+    looping: bool,
+
+    /// Keep the original `log_time`s, instead of shifting them to the time of the replay.
+    #[clap(long = "keep-log-time", default_value_t = false)]
+    keep_log_time: bool,
+
+    /// The application id to replay as.
+    ///
+    /// Defaults to the application id of the first replayed recording.
+    #[clap(long = "application-id")]
+    application_id: Option<String>,
+
+    /// The recording id to replay as.
+    ///
+    /// Defaults to a new random recording id, so that the replay doesn't get mixed up with the
+    /// original recording.
+    #[clap(long = "recording-id")]
+    recording_id: Option<String>,
+}
+
+impl ReplayCommand {
+    pub fn run(&self) -> anyhow::Result<()> {
+        let Self {
+            path_to_input_rrds,
+            connect,
+            speed: _,
+            as_fast_as_possible: _,
+            looping,
+            keep_log_time: _,
+            application_id,
+            recording_id,
+        } = self;
+
+        let application_id = if let Some(application_id) = application_id {
+            application_id.clone()
+        } else {
+            let path = &path_to_input_rrds[0];
+            first_recording_id(path)
+                .with_context(|| format!("{path:?}"))?
+                .with_context(|| format!("{path:?} doesn't contain any recording"))?
+                .application_id()
+                .to_string()
+        };
+
+        // The replayed files already contain their recording properties.
+        let mut builder = RecordingStreamBuilder::new(application_id).send_properties(false);
+        if let Some(recording_id) = recording_id {
+            builder = builder.recording_id(recording_id.clone());
+        }
+        let rec = builder.connect_grpc_opts(connect.clone())?;
+
+        let options = self.replay_options();
+
+        re_log::info!(srcs = ?path_to_input_rrds, dst = %connect, "replay started");
+
+        loop {
+            for path in path_to_input_rrds {
+                let now = std::time::Instant::now();
+                rec.replay_rrd(path, &options)
+                    .with_context(|| format!("{path:?}"))?;
+                re_log::info!(src = ?path, time = ?now.elapsed(), "replayed");
+            }
+
+            if !looping {
+                break;
+            }
+        }
+
+        rec.flush_blocking()?;
+
+        Ok(())
+    }
+
+    /// The options to replay each file with.
+    ///
+    /// Looping is taken care of by [`Self::run`], since it goes over all the files.
+    fn replay_options(&self) -> ReplayOptions {
+        ReplayOptions {
+            realtime: !self.as_fast_as_possible,
+            speed: self.speed,
+            num_loops: Some(1),
+            rewrite_log_time: !self.keep_log_time,
+        }
+    }
+}
+
+/// Returns the id of the first recording in the given file.
+fn first_recording_id(path: &str) -> anyhow::Result<Option<StoreId>> {
+    let file = std::fs::File::open(path)?;
+    let decoder = re_log_encoding::decoder::Decoder::new(std::io::BufReader::new(file))?;
+
+    for msg in decoder {
+        if let LogMsg::SetStoreInfo(msg) = msg?
+            && msg.info.store_id.is_recording()
+        {
+            return Ok(Some(msg.info.store_id));
+        }
+    }
+
+    Ok(None)
+}
+
+fn parse_speed(speed: &str) -> Result<f64, String> {
+    match speed.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && 0.0 < speed => Ok(speed),
+        Ok(_) => Err("must be a positive number".to_owned()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<ReplayCommand, clap::Error> {
+        ReplayCommand::try_parse_from(std::iter::once("replay").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn defaults() {
+        let cmd = parse(&["a.rrd", "b.rrd"]).unwrap();
+        assert_eq!(vec!["a.rrd", "b.rrd"], cmd.path_to_input_rrds);
+        assert_eq!(re_sdk::DEFAULT_CONNECT_URL, cmd.connect);
+        assert!(!cmd.looping);
+
+        let options = cmd.replay_options();
+        assert!(options.realtime);
+        assert_eq!(1.0, options.speed);
+        assert_eq!(Some(1), options.num_loops);
+        assert!(options.rewrite_log_time);
+    }
+
+    #[test]
+    fn options() {
+        let cmd = parse(&[
+            "--speed",
+            "4",
+            "--as-fast-as-possible",
+            "--loop",
+            "--keep-log-time",
+            "--recording-id",
+            "live",
+            "a.rrd",
+        ])
+        .unwrap();
+        assert!(cmd.looping);
+        assert_eq!(Some("live"), cmd.recording_id.as_deref());
+
+        let options = cmd.replay_options();
+        assert!(!options.realtime);
+        assert_eq!(4.0, options.speed);
+        assert_eq!(Some(1), options.num_loops);
+        assert!(!options.rewrite_log_time);
+    }
+
+    #[test]
+    fn invalid_speed() {
+        for speed in ["0", "-1", "inf", "NaN", "fast"] {
+            assert!(parse(&["--speed", speed, "a.rrd"]).is_err(), "{speed}");
+        }
+    }
+
+    #[test]
+    fn missing_input() {
+        assert!(parse(&[]).is_err());
+    }
+}
@@ -0,0 +1,552 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use itertools::Itertools as _;
+
+use re_build_info::CrateVersion;
+use re_chunk::{Chunk, ChunkId};
+use re_chunk_store::ChunkStoreConfig;
+use re_entity_db::EntityDb;
+use re_log_types::{LogMsg, StoreId, TimelineName};
+use re_sdk::StoreKind;
+
+use crate::commands::read_rrd_streams_from_file_or_stdin;
+
+use super::downsample::TimeBucket;
+
+// ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum SplitBy {
+    /// One output per recording.
+    Recording,
+
+    /// One output per top-level entity subtree (e.g. `/world/**`, `/camera/**`), per recording.
+    Entity,
+
+    /// One output per fixed-size time window on the timeline specified with `--window`, per
+    /// recording.
+    Time,
+}
+
+#[derive(Debug, Clone, clap::Parser)]
+pub struct SplitCommand {
+    /// Paths to read from. Reads from standard input if none are specified.
+    path_to_input_rrds: Vec<String>,
+
+    /// Directory to write the resulting .rrd files to. Created if it doesn't exist yet.
+    #[arg(short = 'o', long = "output", value_name = "dst_dir")]
+    path_to_output_dir: PathBuf,
+
+    /// How to split the data.
+    #[clap(long = "by", value_enum)]
+    split_by: SplitBy,
+
+    /// The timeline and size of the time windows, when splitting by time.
+    ///
+    /// Syntax: `<timeline>=<size>`, e.g. `frame=1000` or `log_time=10min`.
+    /// Sizes are durations for temporal timelines, and plain integers for sequence timelines.
+    /// Windows are aligned on multiples of the size.
+    #[arg(
+        long = "window",
+        value_name = "TIMELINE=SIZE",
+        required_if_eq("split_by", "time")
+    )]
+    window: Option<TimeBucket>,
+
+    /// If set, will try to proceed even in the face of IO and/or decoding errors in the input data.
+    #[clap(long = "continue-on-error", default_value_t = false)]
+    continue_on_error: bool,
+}
+
+impl SplitCommand {
+    pub fn run(&self) -> anyhow::Result<()> {
+        let Self {
+            path_to_input_rrds,
+            path_to_output_dir,
+            split_by,
+            window,
+            continue_on_error,
+        } = self;
+
+        let now = std::time::Instant::now();
+        re_log::info!(srcs = ?path_to_input_rrds, ?split_by, "split started");
+
+        let entity_dbs = load_entity_dbs(path_to_input_rrds, *continue_on_error)?;
+
+        let (blueprints, recordings): (Vec<_>, Vec<_>) = entity_dbs
+            .values()
+            .partition(|entity_db| entity_db.store_kind() == StoreKind::Blueprint);
+        anyhow::ensure!(!recordings.is_empty(), "no data recording found in inputs");
+
+        std::fs::create_dir_all(path_to_output_dir)
+            .with_context(|| format!("{path_to_output_dir:?}"))?;
+
+        let mut num_outputs = 0;
+        let mut file_names = HashSet::new();
+        for recording in &recordings {
+            let store_id = recording.store_id();
+
+            let parts = match split_by {
+                SplitBy::Recording => vec![(None, sorted_chunks(recording))],
+                SplitBy::Entity => split_by_entity(recording),
+                SplitBy::Time => {
+                    let window = window
+                        .as_ref()
+                        .context("--window is required when splitting by time")?;
+                    split_by_time(recording, window)?
+                }
+            };
+
+            // Blueprints only ever apply to recordings of the same application.
+            let blueprints = blueprints
+                .iter()
+                .filter(|blueprint| {
+                    blueprint.store_id().application_id() == store_id.application_id()
+                })
+                .collect_vec();
+
+            for (part_name, chunks) in parts {
+                let file_name = match part_name {
+                    // Only disambiguate with the recording ID when needed.
+                    Some(part_name) if recordings.len() == 1 => part_name,
+                    Some(part_name) => format!("{}_{part_name}", store_id.recording_id()),
+                    None => store_id.recording_id().to_string(),
+                };
+                let file_name = unique_file_name(&file_name, &mut file_names);
+                let path = path_to_output_dir.join(format!("{file_name}.rrd"));
+
+                let size_bytes = write_rrd(&path, recording, &blueprints, &chunks)
+                    .with_context(|| format!("{path:?}"))?;
+                num_outputs += 1;
+
+                re_log::info!(
+                    dst = ?path,
+                    num_chunks = chunks.len(),
+                    dst_size_bytes = %re_format::format_bytes(size_bytes as _),
+                    "wrote split"
+                );
+            }
+        }
+
+        re_log::info!(
+            srcs = ?path_to_input_rrds,
+            time = ?now.elapsed(),
+            num_outputs,
+            "split finished"
+        );
+
+        Ok(())
+    }
+}
+
+fn load_entity_dbs(
+    path_to_input_rrds: &[String],
+    continue_on_error: bool,
+) -> anyhow::Result<BTreeMap<StoreId, EntityDb>> {
+    // NOTE #1: We're doing headless processing, there's no point in running subscribers, it will just
+    // (massively) slow us down.
+    // NOTE #2: We do not want to modify the configuration of the original data in any way
+    // (e.g. by recompacting it differently), so make sure to disable all these features.
+    let store_config = ChunkStoreConfig::ALL_DISABLED;
+
+    let (rx, _rx_size_bytes) = read_rrd_streams_from_file_or_stdin(path_to_input_rrds);
+
+    let mut entity_dbs: BTreeMap<StoreId, EntityDb> = Default::default();
+    for (_source, res) in rx {
+        let mut is_success = true;
+
+        match res {
+            Ok(msg) => {
+                if let Err(err) = entity_dbs
+                    .entry(msg.store_id().clone())
+                    .or_insert_with(|| {
+                        EntityDb::with_store_config(msg.store_id().clone(), store_config.clone())
+                    })
+                    .add(&msg)
+                {
+                    re_log::error!(%err, "couldn't index corrupt chunk");
+                    is_success = false;
+                }
+            }
+
+            Err(err) => {
+                re_log::error!(err = re_error::format(err));
+                is_success = false;
+            }
+        }
+
+        if !continue_on_error && !is_success {
+            anyhow::bail!(
+                "one or more IO and/or decoding failures in the input stream (check logs)"
+            )
+        }
+    }
+
+    Ok(entity_dbs)
+}
+
+/// All the chunks of the recording, roughly in the order they were originally logged.
+fn sorted_chunks(entity_db: &EntityDb) -> Vec<Arc<Chunk>> {
+    let mut chunks = entity_db
+        .storage_engine()
+        .store()
+        .iter_chunks()
+        .cloned() // refcount
+        .collect_vec();
+
+    // See https://github.com/rerun-io/rerun/issues/7175 for why.
+    chunks.sort_by_key(|chunk| chunk.row_id_range().map(|(min, _)| min));
+
+    chunks
+}
+
+/// Splits the recording per top-level entity.
+///
+/// Data logged at the root or in reserved namespaces (e.g. recording properties) is copied into
+/// every output.
+fn split_by_entity(entity_db: &EntityDb) -> Vec<(Option<String>, Vec<Arc<Chunk>>)> {
+    let mut shared = Vec::new();
+    let mut parts: BTreeMap<String, Vec<Arc<Chunk>>> = BTreeMap::new();
+
+    for chunk in sorted_chunks(entity_db) {
+        let entity_path = chunk.entity_path();
+        match entity_path.iter().next() {
+            Some(part) if !entity_path.is_reserved() => {
+                parts
+                    .entry(part.unescaped_str().to_owned())
+                    .or_default()
+                    .push(chunk);
+            }
+            _ => shared.push(chunk),
+        }
+    }
+
+    if parts.is_empty() {
+        return vec![(None, shared)];
+    }
+
+    parts
+        .into_iter()
+        .map(|(name, chunks)| {
+            let chunks = shared.iter().cloned().chain(chunks).collect();
+            (Some(name), chunks)
+        })
+        .collect()
+}
+
+/// Splits the recording into fixed-size time windows.
+///
+/// Chunks straddling window boundaries are sliced accordingly. Data that isn't indexed on the
+/// window's timeline (e.g. static data) is copied into every output.
+fn split_by_time(
+    entity_db: &EntityDb,
+    window: &TimeBucket,
+) -> anyhow::Result<Vec<(Option<String>, Vec<Arc<Chunk>>)>> {
+    let timeline = window.timeline();
+
+    let mut shared = Vec::new();
+    let mut windows: BTreeMap<i64, Vec<Arc<Chunk>>> = BTreeMap::new();
+
+    for chunk in sorted_chunks(entity_db) {
+        let Some(time_column) = chunk.timelines().get(timeline) else {
+            shared.push(chunk);
+            continue;
+        };
+
+        let size = window.resolve(time_column.timeline().typ())?;
+        for (window_index, chunk) in split_chunk_by_window(chunk, timeline, size) {
+            windows.entry(window_index).or_default().push(chunk);
+        }
+    }
+
+    if windows.is_empty() {
+        re_log::warn!(
+            store_id = ?entity_db.store_id(),
+            %timeline,
+            "recording has no data on the split timeline"
+        );
+        return Ok(vec![(None, shared)]);
+    }
+
+    Ok(windows
+        .into_values()
+        .enumerate()
+        .map(|(index, chunks)| {
+            let chunks = shared.iter().cloned().chain(chunks).collect();
+            (Some(format!("{timeline}_{index:04}")), chunks)
+        })
+        .collect())
+}
+
+/// Splits the chunk into the windows of the given size that its rows fall into, in order.
+///
+/// Only windows that actually contain rows are returned, however far apart they are.
+fn split_chunk_by_window(
+    chunk: Arc<Chunk>,
+    timeline: &TimelineName,
+    size: i64,
+) -> Vec<(i64, Arc<Chunk>)> {
+    let Some(time_column) = chunk.timelines().get(timeline) else {
+        return Vec::new();
+    };
+
+    let time_range = time_column.time_range();
+    let first_window = time_range.min.as_i64().div_euclid(size);
+    let last_window = time_range.max.as_i64().div_euclid(size);
+    if first_window == last_window {
+        return vec![(first_window, chunk)];
+    }
+
+    let sorted = chunk.sorted_by_timeline_if_unsorted(timeline);
+    let Some(time_column) = sorted.timelines().get(timeline) else {
+        return Vec::new();
+    };
+
+    // The rows are sorted, so each window is a contiguous run of rows.
+    let times = time_column.times_raw();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    while start < times.len() {
+        let window_index = times[start].div_euclid(size);
+        let len = times[start..].partition_point(|time| time.div_euclid(size) == window_index);
+        let sliced = sorted.row_sliced(start, len).with_id(ChunkId::new());
+        parts.push((window_index, Arc::new(sliced)));
+        start += len;
+    }
+
+    parts
+}
+
+/// Writes a standalone .rrd file with the given blueprints, followed by the recording's store
+/// info and the given chunks.
+fn write_rrd(
+    path: &std::path::Path,
+    recording: &EntityDb,
+    blueprints: &[&EntityDb],
+    chunks: &[Arc<Chunk>],
+) -> anyhow::Result<u64> {
+    use std::io::Write as _;
+
+    let store_id = recording.store_id().clone();
+
+    let mut rrd_out = std::io::BufWriter::new(std::fs::File::create(path)?);
+
+    // NOTE: We want to make sure all blueprints come first, so that the viewer can immediately
+    // set up the viewport correctly.
+    let messages_rbl = blueprints
+        .iter()
+        .flat_map(|blueprint| blueprint.to_messages(None /* time selection */));
+
+    let set_store_info = recording
+        .store_info_msg()
+        .map(|msg| Ok(LogMsg::SetStoreInfo(msg.clone())));
+    let messages_rrd = set_store_info.into_iter().chain(chunks.iter().map(|chunk| {
+        chunk
+            .to_arrow_msg()
+            .map(|msg| LogMsg::ArrowMsg(store_id.clone(), msg))
+    }));
+
+    // TODO(cmc): encoding options should match the original.
+    let encoding_options = re_log_encoding::EncodingOptions::PROTOBUF_COMPRESSED;
+    let version = recording
+        .store_info()
+        .and_then(|info| info.store_version)
+        .unwrap_or(CrateVersion::LOCAL);
+
+    let size_bytes = re_log_encoding::encoder::encode(
+        version,
+        encoding_options,
+        messages_rbl.chain(messages_rrd),
+        &mut rrd_out,
+    )
+    .context("couldn't encode messages")?;
+
+    rrd_out.flush().context("couldn't flush output")?;
+
+    Ok(size_bytes)
+}
+
+/// Sanitizes the given name so that it can be used as a file name, and disambiguates it with a
+/// numeric suffix if a previous output already used that file name.
+///
+/// `taken` holds the file names used so far, lowercased to account for case-insensitive file
+/// systems.
+fn unique_file_name(name: &str, taken: &mut HashSet<String>) -> String {
+    let sanitized = sanitize(name);
+
+    let mut file_name = sanitized.clone();
+    for suffix in 2.. {
+        if taken.insert(file_name.to_lowercase()) {
+            break;
+        }
+        file_name = format!("{sanitized}_{suffix}");
+    }
+
+    if file_name != sanitized {
+        re_log::warn!(
+            "{name:?} would have been written to the same file as a previous output, writing it to {file_name:?} instead"
+        );
+    }
+
+    file_name
+}
+
+/// Makes sure the given name can be used as a file name.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use re_chunk::{RowId, Timeline};
+    use re_types::archetypes::Scalars;
+
+    use super::*;
+
+    fn frame() -> Timeline {
+        Timeline::new_sequence("frame")
+    }
+
+    fn scalars(entity_path: &str, frames: &[i64]) -> Arc<Chunk> {
+        let mut builder = Chunk::builder(entity_path);
+        for &frame_nr in frames {
+            builder = builder.with_archetype(
+                RowId::new(),
+                [(frame(), frame_nr)],
+                &Scalars::single(frame_nr as f64),
+            );
+        }
+        Arc::new(builder.build().unwrap())
+    }
+
+    fn windows(frames: &[i64], size: i64) -> Vec<(i64, Vec<i64>)> {
+        split_chunk_by_window(scalars("scalar", frames), frame().name(), size)
+            .into_iter()
+            .map(|(window_index, chunk)| {
+                let times = chunk.timelines()[frame().name()].times_raw().to_vec();
+                (window_index, times)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn window_boundaries() {
+        assert_eq!(
+            vec![(0, vec![0, 9]), (1, vec![10, 19]), (2, vec![20])],
+            windows(&[0, 9, 10, 19, 20], 10)
+        );
+
+        // Unsorted chunks are sorted first.
+        assert_eq!(
+            vec![(0, vec![3]), (1, vec![12, 15])],
+            windows(&[15, 3, 12], 10)
+        );
+
+        // Chunks within a single window are kept as-is.
+        let chunk = scalars("scalar", &[10, 15, 19]);
+        let split = split_chunk_by_window(chunk.clone(), frame().name(), 10);
+        assert_eq!(1, split.len());
+        assert!(Arc::ptr_eq(&chunk, &split[0].1));
+    }
+
+    #[test]
+    fn negative_times() {
+        assert_eq!(
+            vec![(-2, vec![-11]), (-1, vec![-10, -1]), (0, vec![0])],
+            windows(&[-11, -10, -1, 0], 10)
+        );
+    }
+
+    #[test]
+    fn sparse_windows() {
+        // Only the windows that hold data are materialized, however many lie in between.
+        assert_eq!(
+            vec![
+                (-1_000_000_000_000, vec![-1_000_000_000_000]),
+                (0, vec![0]),
+                (1_000_000_000_000, vec![1_000_000_000_000])
+            ],
+            windows(&[1_000_000_000_000, -1_000_000_000_000, 0], 1)
+        );
+    }
+
+    #[test]
+    fn split_recording_by_time() {
+        let mut entity_db =
+            EntityDb::new(StoreId::random(StoreKind::Recording, "rerun_example_split"));
+        let static_chunk = Arc::new(
+            Chunk::builder("static")
+                .with_archetype(
+                    RowId::new(),
+                    re_log_types::TimePoint::default(),
+                    &Scalars::single(1.0),
+                )
+                .build()
+                .unwrap(),
+        );
+        entity_db.add_chunk(&static_chunk).unwrap();
+        entity_db.add_chunk(&scalars("a", &[5, 25])).unwrap();
+        entity_db.add_chunk(&scalars("b", &[-5])).unwrap();
+
+        let window: TimeBucket = "frame=10".parse().unwrap();
+        let parts = split_by_time(&entity_db, &window).unwrap();
+
+        let summary = parts
+            .iter()
+            .map(|(name, chunks)| {
+                let entities = chunks
+                    .iter()
+                    .map(|chunk| chunk.entity_path().to_string())
+                    .sorted()
+                    .collect_vec();
+                (name.clone().unwrap(), entities)
+            })
+            .collect_vec();
+        assert_eq!(
+            vec![
+                (
+                    "frame_0000".to_owned(),
+                    vec!["/b".to_owned(), "/static".to_owned()]
+                ),
+                (
+                    "frame_0001".to_owned(),
+                    vec!["/a".to_owned(), "/static".to_owned()]
+                ),
+                (
+                    "frame_0002".to_owned(),
+                    vec!["/a".to_owned(), "/static".to_owned()]
+                ),
+            ],
+            summary
+        );
+
+        // No data on that timeline: a single output with everything.
+        let window: TimeBucket = "other=10".parse().unwrap();
+        let parts = split_by_time(&entity_db, &window).unwrap();
+        assert_eq!(1, parts.len());
+        assert_eq!(None, parts[0].0);
+        assert_eq!(3, parts[0].1.len());
+    }
+
+    #[test]
+    fn unique_file_names() {
+        let mut taken = HashSet::new();
+        assert_eq!("a_b", unique_file_name("a/b", &mut taken));
+        assert_eq!("a_b_2", unique_file_name("a_b", &mut taken));
+        assert_eq!("A_B_3", unique_file_name("A:B", &mut taken));
+        assert_eq!("c", unique_file_name("c", &mut taken));
+    }
+}
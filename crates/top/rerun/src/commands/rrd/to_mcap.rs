@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+
+use re_chunk::Chunk;
+use re_log_types::LogMsg;
+use re_mcap::McapWriter;
+
+use crate::commands::read_rrd_streams_from_file_or_stdin;
+
+// ---
+
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ToMcapCommand {
+    /// Paths to read from. Reads from standard input if none are specified.
+    path_to_input_rrds: Vec<String>,
+
+    /// Path to write the resulting .mcap file to.
+    #[arg(short = 'o', long = "output", value_name = "dst.mcap")]
+    path_to_output_mcap: PathBuf,
+
+    /// If set, will try to proceed even in the face of IO and/or decoding errors in the input data.
+    #[clap(long = "continue-on-error", default_value_t = false)]
+    continue_on_error: bool,
+}
+
+impl ToMcapCommand {
+    pub fn run(&self) -> anyhow::Result<()> {
+        let Self {
+            path_to_input_rrds,
+            path_to_output_mcap,
+            continue_on_error,
+        } = self;
+
+        let now = std::time::Instant::now();
+        re_log::info!(srcs = ?path_to_input_rrds, dst = ?path_to_output_mcap, "to-mcap started");
+
+        let file = std::fs::File::create(path_to_output_mcap)
+            .with_context(|| format!("{path_to_output_mcap:?}"))?;
+        let mut writer = McapWriter::new(std::io::BufWriter::new(file))?;
+
+        let (rx, _rx_size_bytes) = read_rrd_streams_from_file_or_stdin(path_to_input_rrds);
+
+        let mut num_chunks = 0;
+        for (_source, res) in rx {
+            let mut is_success = true;
+
+            match res {
+                Ok(LogMsg::ArrowMsg(store_id, msg)) if !store_id.is_blueprint() => {
+                    match Chunk::from_arrow_msg(&msg) {
+                        Ok(chunk) => {
+                            writer.add_chunk(&chunk)?;
+                            num_chunks += 1;
+                        }
+
+                        Err(err) => {
+                            re_log::error!(%err, "couldn't decode corrupt chunk");
+                            is_success = false;
+                        }
+                    }
+                }
+
+                Ok(_) => {}
+
+                Err(err) => {
+                    re_log::error!(err = re_error::format(err));
+                    is_success = false;
+                }
+            }
+
+            if !continue_on_error && !is_success {
+                anyhow::bail!(
+                    "one or more IO and/or decoding failures in the input stream (check logs)"
+                )
+            }
+        }
+
+        writer.finish()?;
+
+        let dst_size_bytes = std::fs::metadata(path_to_output_mcap)
+            .map(|metadata| metadata.len())
+            .unwrap_or_default();
+
+        re_log::info!(
+            srcs = ?path_to_input_rrds,
+            dst = ?path_to_output_mcap,
+            time = ?now.elapsed(),
+            num_chunks,
+            dst_size_bytes = %re_format::format_bytes(dst_size_bytes as _),
+            "to-mcap finished"
+        );
+
+        Ok(())
+    }
+}
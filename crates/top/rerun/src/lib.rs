@@ -101,6 +101,9 @@
 //! ## Forwarding text log events to Rerun
 //! See [`Logger`].
 //!
+//! For the `tracing` crate, see `TracingLayer` (requires the `tracing` feature): it also records
+//! span durations.
+//!
 
 #![warn(missing_docs)] // Let's keep the this crate well-documented!
 
@@ -129,6 +132,12 @@ pub use re_log::default_log_filter;
 #[cfg(feature = "log")]
 pub use log_integration::Logger;
 
+#[cfg(feature = "tracing")]
+pub mod tracing_integration;
+
+#[cfg(feature = "tracing")]
+pub use tracing_integration::TracingLayer;
+
 #[cfg(feature = "run")]
 pub use commands::{CallSource, run};
 
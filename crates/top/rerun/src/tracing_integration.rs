@@ -0,0 +1,394 @@
+//! Integrates the Rerun SDK with the [`tracing`] crate.
+
+use std::{
+    cell::Cell,
+    sync::Arc,
+    time::{Instant, SystemTime},
+};
+
+use arrow::array::{ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray, UInt64Array};
+use tracing::{
+    Event, Subscriber,
+    field::{Field, Visit},
+    span,
+};
+use tracing_subscriber::{Layer, layer::Context, registry::LookupSpan};
+
+use re_chunk::PendingRow;
+use re_log_types::{EntityPath, EntityPathPart, TimeCell, TimePoint, TimelineName};
+use re_types::{
+    AnyValues, AsComponents,
+    archetypes::{Scalars, TextLog},
+    components::TextLogLevel,
+};
+
+use crate::RecordingStream;
+
+// ---
+
+/// How span durations are laid out in the entity hierarchy, see [`TracingLayer::with_span_layout`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SpanLayout {
+    /// One entity per span name, e.g. `spans/query_db`.
+    ByName,
+
+    /// One entity per call path, mirroring the span hierarchy, e.g. `spans/handle_request/query_db`.
+    ///
+    /// Combined with the fact that durations are logged at the time their span was entered, this
+    /// gives a flame graph.
+    #[default]
+    ByScope,
+}
+
+/// Implements a [`tracing_subscriber::Layer`] that forwards events and span durations to the Rerun SDK.
+///
+/// * Events are logged as [`TextLog`]s at `<prefix>/<target>`. Their fields are part of the text,
+///   and are also logged as separate components, for easy querying.
+/// * Every time a span is exited, the time spent in it is logged as [`Scalars`] (in seconds)
+///   at `<prefix>/spans/…`, see [`SpanLayout`].
+///
+/// Everything is logged on the `log_time` timeline only.
+///
+/// Events and spans coming from Rerun itself (i.e. whose target is a `re_*` or `rerun*` crate)
+/// are ignored: logging them would emit more of them, from whichever thread the SDK happens to
+/// be running on (e.g. the batcher's), forever.
+///
+/// ```
+/// use tracing_subscriber::layer::SubscriberExt as _;
+///
+/// let rec = rerun::RecordingStreamBuilder::new("rerun_example_app").buffered()?;
+///
+/// let layer = rerun::TracingLayer::new(rec.clone()) // recording streams are ref-counted
+///     .with_path_prefix("logs");
+/// let subscriber = tracing_subscriber::registry().with(layer);
+/// tracing::subscriber::set_global_default(subscriber)?;
+///
+/// tracing::info!(answer = 42, "This INFO event got added through the tracing interface");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct TracingLayer {
+    rec: RecordingStream,
+    path_prefix: Option<String>,
+    span_layout: SpanLayout,
+}
+
+impl Drop for TracingLayer {
+    fn drop(&mut self) {
+        self.rec.flush_blocking().ok();
+    }
+}
+
+impl TracingLayer {
+    /// Returns a new [`TracingLayer`] that forwards all events and spans to the specified [`RecordingStream`].
+    pub fn new(rec: RecordingStream) -> Self {
+        Self {
+            rec,
+            path_prefix: None,
+            span_layout: SpanLayout::default(),
+        }
+    }
+
+    /// Configures the [`TracingLayer`] to prefix the specified `path_prefix` to all entity paths.
+    #[inline]
+    pub fn with_path_prefix(mut self, path_prefix: impl Into<String>) -> Self {
+        self.path_prefix = Some(path_prefix.into());
+        self
+    }
+
+    /// Configures how span durations are laid out in the entity hierarchy.
+    ///
+    /// Defaults to [`SpanLayout::ByScope`].
+    #[inline]
+    pub fn with_span_layout(mut self, span_layout: SpanLayout) -> Self {
+        self.span_layout = span_layout;
+        self
+    }
+
+    fn entity_path(&self, parts: impl IntoIterator<Item = EntityPathPart>) -> EntityPath {
+        let path = EntityPath::new(parts.into_iter().collect());
+        if let Some(path_prefix) = self.path_prefix.as_deref() {
+            EntityPath::from(path_prefix).join(&path)
+        } else {
+            path
+        }
+    }
+
+    fn log_at(&self, entity_path: EntityPath, time: SystemTime, as_components: &dyn AsComponents) {
+        thread_local! {
+            /// Guards against infinite recursion, in case logging itself emits tracing events
+            /// from outside of Rerun's crates (see [`is_rerun_target`]).
+            static IS_LOGGING: Cell<bool> = const { Cell::new(false) };
+        }
+
+        if IS_LOGGING.get() {
+            return;
+        }
+
+        let Ok(time) = TimeCell::try_from(time) else {
+            return;
+        };
+
+        let components = as_components
+            .as_serialized_batches()
+            .into_iter()
+            .map(|batch| (batch.descriptor, batch.array))
+            .collect();
+
+        let mut timepoint = TimePoint::default();
+        timepoint.insert_cell(TimelineName::log_time(), time);
+
+        IS_LOGGING.set(true);
+        self.rec
+            .record_row(entity_path, PendingRow::new(timepoint, components), false);
+        IS_LOGGING.set(false);
+    }
+}
+
+/// Keeps track of when a span was entered, stored in the span's extensions.
+///
+/// This is a stack, since a span can be re-entered before it is exited (e.g. recursion).
+#[derive(Default)]
+struct SpanEntries(Vec<(Instant, SystemTime)>);
+
+impl<S> Layer<S> for TracingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if is_rerun_target(metadata.target()) {
+            return;
+        }
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let mut body = visitor.message.unwrap_or_default();
+        let mut any_values = AnyValues::default();
+        for (name, value) in visitor.fields {
+            if !body.is_empty() {
+                body.push(' ');
+            }
+            body.push_str(&format!("{name}={}", value.display));
+            any_values = any_values.with_component_from_data(name, value.array);
+        }
+
+        let entity_path = self.entity_path(metadata.target().split("::").map(EntityPathPart::new));
+        let level = tracing_level_to_rerun_level(*metadata.level());
+
+        self.log_at(
+            entity_path,
+            SystemTime::now(),
+            &[
+                &TextLog::new(body).with_level(level) as &dyn AsComponents,
+                &any_values,
+            ],
+        );
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        if is_rerun_target(span.metadata().target()) {
+            return; // Never entered as far as we're concerned, so never logged on exit either.
+        }
+
+        let mut extensions = span.extensions_mut();
+        let entry = (Instant::now(), SystemTime::now());
+        if let Some(entries) = extensions.get_mut::<SpanEntries>() {
+            entries.0.push(entry);
+        } else {
+            extensions.insert(SpanEntries(vec![entry]));
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let Some((entered_at, entered_at_wall)) = span
+            .extensions_mut()
+            .get_mut::<SpanEntries>()
+            .and_then(|entries| entries.0.pop())
+        else {
+            return;
+        };
+        let duration = entered_at.elapsed();
+
+        let span_path: Vec<_> = match self.span_layout {
+            SpanLayout::ByName => vec![EntityPathPart::new(span.name())],
+            SpanLayout::ByScope => span
+                .scope()
+                .from_root()
+                .map(|span| EntityPathPart::new(span.name()))
+                .collect(),
+        };
+        let entity_path =
+            self.entity_path(std::iter::once(EntityPathPart::new("spans")).chain(span_path));
+
+        self.log_at(
+            entity_path,
+            entered_at_wall,
+            &Scalars::new([duration.as_secs_f64()]),
+        );
+    }
+}
+
+// ---
+
+/// A single field of an event, both as text and as data.
+struct FieldValue {
+    display: String,
+    array: ArrayRef,
+}
+
+impl FieldValue {
+    fn new(display: impl ToString, array: impl arrow::array::Array + 'static) -> Self {
+        Self {
+            display: display.to_string(),
+            array: Arc::new(array),
+        }
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: Option<String>,
+    fields: Vec<(&'static str, FieldValue)>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        let value = FieldValue::new(value, Float64Array::from(vec![value]));
+        self.fields.push((field.name(), value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        let value = FieldValue::new(value, Int64Array::from(vec![value]));
+        self.fields.push((field.name(), value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        let value = FieldValue::new(value, UInt64Array::from(vec![value]));
+        self.fields.push((field.name(), value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        let value = FieldValue::new(value, BooleanArray::from(vec![value]));
+        self.fields.push((field.name(), value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = Some(value.to_owned());
+        } else {
+            let display = format!("{value:?}");
+            let value = FieldValue::new(display, StringArray::from(vec![value]));
+            self.fields.push((field.name(), value));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        let value = format!("{value:?}");
+        if field.name() == "message" {
+            self.message = Some(value);
+        } else {
+            let array = StringArray::from(vec![value.as_str()]);
+            self.fields
+                .push((field.name(), FieldValue::new(value, array)));
+        }
+    }
+}
+
+/// Does this target belong to one of Rerun's own crates?
+fn is_rerun_target(target: &str) -> bool {
+    let crate_name = target.split("::").next().unwrap_or_default();
+    crate_name.starts_with("re_") || crate_name.starts_with("rerun")
+}
+
+fn tracing_level_to_rerun_level(lvl: tracing::Level) -> TextLogLevel {
+    match lvl {
+        tracing::Level::ERROR => TextLogLevel::ERROR,
+        tracing::Level::WARN => TextLogLevel::WARN,
+        tracing::Level::INFO => TextLogLevel::INFO,
+        tracing::Level::DEBUG => TextLogLevel::DEBUG,
+        _ => TextLogLevel::TRACE,
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use tracing_subscriber::layer::SubscriberExt as _;
+
+    use re_chunk::Chunk;
+    use re_log_types::LogMsg;
+
+    use super::*;
+
+    #[test]
+    fn ignore_rerun_targets() {
+        let (rec, storage) = crate::RecordingStreamBuilder::new("rerun_example_test")
+            .memory()
+            .unwrap();
+
+        let dispatch = tracing::Dispatch::new(
+            tracing_subscriber::registry().with(TracingLayer::new(rec).with_path_prefix("logs")),
+        );
+
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::info!(target: "my_app::db", "kept");
+            tracing::info!(target: "re_sdk::recording_stream", "ignored");
+            tracing::info_span!(target: "rerun::sink", "ignored_span").in_scope(|| {});
+            tracing::info_span!(target: "my_app", "kept_span").in_scope(|| {});
+        });
+
+        // Rerun emits events from its own threads too, where the recursion guard can't help.
+        std::thread::spawn({
+            let dispatch = dispatch.clone();
+            move || {
+                tracing::dispatcher::with_default(&dispatch, || {
+                    tracing::debug!(target: "re_chunk::batcher", "ignored");
+                });
+            }
+        })
+        .join()
+        .unwrap();
+
+        drop(dispatch); // Flushes the recording.
+
+        let entity_paths: BTreeSet<_> = storage
+            .take()
+            .iter()
+            .filter_map(|msg| match msg {
+                LogMsg::ArrowMsg(_, msg) => Some(Chunk::from_arrow_msg(msg).unwrap()),
+                _ => None,
+            })
+            .map(|chunk| chunk.entity_path().to_string())
+            .filter(|entity_path| entity_path.starts_with("/logs"))
+            .collect();
+
+        assert_eq!(
+            BTreeSet::from([
+                "/logs/my_app/db".to_owned(),
+                "/logs/spans/kept_span".to_owned()
+            ]),
+            entity_paths
+        );
+    }
+
+    #[test]
+    fn rerun_targets() {
+        assert!(is_rerun_target("re_sdk"));
+        assert!(is_rerun_target("re_chunk::batcher"));
+        assert!(is_rerun_target("rerun"));
+        assert!(is_rerun_target("rerun_bindings::python_bridge"));
+        assert!(!is_rerun_target("my_app::rerun"));
+        assert!(!is_rerun_target("tokio"));
+    }
+}
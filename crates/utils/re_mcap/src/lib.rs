@@ -5,10 +5,12 @@ pub mod layers;
 
 pub(crate) mod parsers;
 pub(crate) mod util;
+pub mod writer;
 
 pub use error::Error;
 pub use layers::{Layer, LayerIdentifier, LayerRegistry, MessageLayer, SelectedLayers};
 pub use parsers::{MessageParser, ParserContext, cdr};
+pub use writer::McapWriter;
 
 // TODO(grtlr): We should expose an `Mcap` object that internally holds the summary + a reference to the bytes.
 pub use util::read_summary;
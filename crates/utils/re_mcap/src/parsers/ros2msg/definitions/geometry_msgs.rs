@@ -4,6 +4,8 @@
 //!
 use serde::{Deserialize, Serialize};
 
+use super::std_msgs::Header;
+
 /// This represents a vector in free space.
 ///
 /// This is semantically different than a point.
@@ -39,3 +41,18 @@ pub struct Pose {
     pub position: Point,
     pub orientation: Quaternion,
 }
+
+/// The transform between two coordinate frames.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Transform {
+    pub translation: Vector3,
+    pub rotation: Quaternion,
+}
+
+/// A transform from coordinate frame `header.frame_id` to coordinate frame `child_frame_id`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransformStamped {
+    pub header: Header,
+    pub child_frame_id: String,
+    pub transform: Transform,
+}
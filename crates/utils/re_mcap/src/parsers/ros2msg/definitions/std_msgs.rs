@@ -29,6 +29,13 @@ pub struct StringMessage {
     pub data: String,
 }
 
+/// A double-precision floating point value.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Float64 {
+    /// The value.
+    pub data: f64,
+}
+
 /// Standard metadata for higher-level stamped data types.
 ///
 /// This is generally used to communicate timestamped data
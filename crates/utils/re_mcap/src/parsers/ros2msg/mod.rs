@@ -1,6 +1,6 @@
 use crate::parsers::MessageParser;
 
-mod definitions;
+pub(crate) mod definitions;
 
 pub mod rcl_interfaces;
 pub mod scalar_parser;
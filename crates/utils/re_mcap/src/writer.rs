@@ -0,0 +1,897 @@
+//! Writes Rerun data out as MCAP, see [`McapWriter`].
+
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    io::{Seek, Write},
+};
+
+use ahash::HashMap;
+use re_chunk::{Chunk, EntityPath, TimelineName};
+use re_types::{
+    archetypes::{
+        DepthImage, EncodedImage, Image, McapChannel, McapMessage, McapSchema, Points3D, Scalars,
+        Transform3D,
+    },
+    components,
+    datatypes::{ChannelDatatype, ColorModel},
+};
+use serde::Serialize;
+
+use crate::{
+    Error,
+    parsers::{
+        dds::RepresentationIdentifier,
+        ros2msg::definitions::{
+            builtin_interfaces::Time,
+            geometry_msgs::{Quaternion, Transform, TransformStamped, Vector3},
+            sensor_msgs::{
+                CompressedImage, Image as Ros2Image, PointCloud2, PointField, PointFieldDatatype,
+            },
+            std_msgs::{Float64, Header},
+            tf2_msgs::TFMessage,
+        },
+    },
+};
+
+/// Writes Rerun [`Chunk`]s out as an MCAP file.
+///
+/// * Messages that were loaded with the `raw` layer ([`McapMessage`]) are written back out
+///   verbatim, on the channel described by the [`McapChannel`] and [`McapSchema`] that the
+///   `schema` layer logged at the same entity.
+/// * Standard archetypes are encoded as ROS2 messages, on a topic named after their entity:
+///   - [`Image`] and [`DepthImage`] as `sensor_msgs/msg/Image`,
+///   - [`EncodedImage`] (JPEG and PNG only) as `sensor_msgs/msg/CompressedImage`,
+///   - [`Points3D`] as `sensor_msgs/msg/PointCloud2` (positions only),
+///   - [`Transform3D`] as `tf2_msgs/msg/TFMessage` on `/tf`, or `/tf_static` for static data
+///     (translation and rotation only), using entity paths as frame ids,
+///   - [`Scalars`] as `std_msgs/msg/Float64`, with one topic per scalar if there are several.
+/// * Everything else is skipped.
+///
+/// Messages are timestamped using the `log_time` and `publish_time` timelines if present, and
+/// the time at which their row was created otherwise.
+///
+/// Nothing is readable until [`Self::finish`] has been called.
+pub struct McapWriter<W: Write + Seek> {
+    writer: mcap::Writer<W>,
+
+    /// The channels of raw messages, per entity.
+    raw_channels: HashMap<EntityPath, RawChannel>,
+
+    /// The channels of ROS2 messages, per topic and schema.
+    ros2_channels: HashMap<(String, &'static str), u16>,
+
+    ros2_schemas: HashMap<&'static str, u16>,
+    sequences: HashMap<u16, u32>,
+}
+
+#[derive(Default)]
+struct RawChannel {
+    /// Only known once the [`McapChannel`] of the entity was seen.
+    info: Option<RawChannelInfo>,
+
+    /// Only assigned once the first message was written, so that channels without raw messages
+    /// (e.g. those that were decoded into standard archetypes) don't end up in the output.
+    channel_id: Option<u16>,
+
+    /// The messages that came before their [`McapChannel`].
+    pending: Vec<(Timestamps, Vec<u8>)>,
+}
+
+struct RawChannelInfo {
+    topic: String,
+    message_encoding: String,
+    metadata: BTreeMap<String, String>,
+
+    /// Name, encoding and data.
+    schema: Option<(String, String, Vec<u8>)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Timestamps {
+    log_time: u64,
+    publish_time: u64,
+}
+
+impl<W: Write + Seek> McapWriter<W> {
+    pub fn new(writer: W) -> Result<Self, Error> {
+        Ok(Self {
+            writer: mcap::Writer::new(writer)?,
+            raw_channels: Default::default(),
+            ros2_channels: Default::default(),
+            ros2_schemas: Default::default(),
+            sequences: Default::default(),
+        })
+    }
+
+    /// Writes all the messages that can be extracted from this chunk, see [`McapWriter`].
+    pub fn add_chunk(&mut self, chunk: &Chunk) -> Result<(), Error> {
+        re_tracing::profile_function!();
+
+        let timestamps = row_timestamps(chunk);
+
+        if chunk
+            .components()
+            .contains_component(&McapChannel::descriptor_topic())
+        {
+            self.add_raw_channel(chunk)?;
+        }
+
+        if chunk
+            .components()
+            .contains_component(&McapMessage::descriptor_data())
+        {
+            return self.add_raw_messages(chunk, &timestamps);
+        }
+
+        if chunk
+            .components()
+            .contains_component(&Image::descriptor_buffer())
+        {
+            self.add_images(
+                chunk,
+                &timestamps,
+                &Image::descriptor_buffer(),
+                &Image::descriptor_format(),
+            )?;
+        }
+        if chunk
+            .components()
+            .contains_component(&DepthImage::descriptor_buffer())
+        {
+            self.add_images(
+                chunk,
+                &timestamps,
+                &DepthImage::descriptor_buffer(),
+                &DepthImage::descriptor_format(),
+            )?;
+        }
+        if chunk
+            .components()
+            .contains_component(&EncodedImage::descriptor_blob())
+        {
+            self.add_encoded_images(chunk, &timestamps)?;
+        }
+        if chunk
+            .components()
+            .contains_component(&Points3D::descriptor_positions())
+        {
+            self.add_point_clouds(chunk, &timestamps)?;
+        }
+        if [
+            Transform3D::descriptor_translation(),
+            Transform3D::descriptor_quaternion(),
+            Transform3D::descriptor_rotation_axis_angle(),
+        ]
+        .iter()
+        .any(|descr| chunk.components().contains_component(descr))
+        {
+            self.add_transforms(chunk, &timestamps)?;
+        }
+        if chunk
+            .components()
+            .contains_component(&Scalars::descriptor_scalars())
+        {
+            self.add_scalars(chunk, &timestamps)?;
+        }
+
+        Ok(())
+    }
+
+    /// Finishes any pending MCAP chunk, and flushes it to the underlying writer.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes the MCAP summary.
+    ///
+    /// Raw messages whose [`McapChannel`] was never seen are written to a channel named after
+    /// their entity, without schema nor encoding.
+    pub fn finish(mut self) -> Result<(), Error> {
+        let entity_paths = self
+            .raw_channels
+            .iter()
+            .filter(|(_, channel)| channel.info.is_none() && !channel.pending.is_empty())
+            .map(|(entity_path, _)| entity_path.clone())
+            .collect::<Vec<_>>();
+
+        for entity_path in entity_paths {
+            re_log::warn!(
+                "Found raw MCAP messages without channel information at {entity_path}, writing them without schema"
+            );
+
+            let channel_id =
+                self.writer
+                    .add_channel(0, &entity_path.to_string(), "", &BTreeMap::new())?;
+            self.open_raw_channel(&entity_path, channel_id)?;
+        }
+
+        self.writer.finish()?;
+        Ok(())
+    }
+
+    // ---
+
+    fn add_raw_channel(&mut self, chunk: &Chunk) -> Result<(), Error> {
+        let entity_path = chunk.entity_path();
+
+        let Some(row) = (0..chunk.num_rows()).rev().find(|&row| {
+            chunk
+                .component_mono::<components::Text>(&McapChannel::descriptor_topic(), row)
+                .is_some()
+        }) else {
+            return Ok(());
+        };
+
+        let text = |descr| {
+            chunk
+                .component_mono::<components::Text>(&descr, row)
+                .and_then(Result::ok)
+                .map(|text| text.as_str().to_owned())
+        };
+
+        let metadata = chunk
+            .component_mono::<components::KeyValuePairs>(&McapChannel::descriptor_metadata(), row)
+            .and_then(Result::ok)
+            .map(|pairs| {
+                pairs
+                    .0
+                    .iter()
+                    .map(|pair| {
+                        (
+                            pair.first.as_str().to_owned(),
+                            pair.second.as_str().to_owned(),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let schema = text(McapSchema::descriptor_name()).map(|name| {
+            let encoding = text(McapSchema::descriptor_encoding()).unwrap_or_default();
+            let data = chunk
+                .component_mono::<components::Blob>(&McapSchema::descriptor_data(), row)
+                .and_then(Result::ok)
+                .map(|blob| blob.0.0.to_vec())
+                .unwrap_or_default();
+            (name, encoding, data)
+        });
+
+        let info = RawChannelInfo {
+            topic: text(McapChannel::descriptor_topic()).unwrap_or_else(|| entity_path.to_string()),
+            message_encoding: text(McapChannel::descriptor_message_encoding()).unwrap_or_default(),
+            metadata,
+            schema,
+        };
+
+        let raw_channel = self.raw_channels.entry(entity_path.clone()).or_default();
+        if raw_channel.info.is_some() {
+            return Ok(());
+        }
+        raw_channel.info = Some(info);
+
+        if !raw_channel.pending.is_empty() {
+            let channel_id = self.add_raw_mcap_channel(entity_path)?;
+            self.open_raw_channel(entity_path, channel_id)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_raw_mcap_channel(&mut self, entity_path: &EntityPath) -> Result<u16, Error> {
+        let Some(info) = self
+            .raw_channels
+            .get(entity_path)
+            .and_then(|raw_channel| raw_channel.info.as_ref())
+        else {
+            return Err(Error::Other(anyhow::anyhow!(
+                "Missing channel information for {entity_path}"
+            )));
+        };
+
+        let schema_id = if let Some((name, encoding, data)) = &info.schema {
+            self.writer.add_schema(name, encoding, data)?
+        } else {
+            0 // No schema
+        };
+
+        Ok(self.writer.add_channel(
+            schema_id,
+            &info.topic,
+            &info.message_encoding,
+            &info.metadata,
+        )?)
+    }
+
+    /// Assigns the channel of an entity, and writes out its pending messages.
+    fn open_raw_channel(&mut self, entity_path: &EntityPath, channel_id: u16) -> Result<(), Error> {
+        let raw_channel = self.raw_channels.entry(entity_path.clone()).or_default();
+        raw_channel.channel_id = Some(channel_id);
+        let pending = std::mem::take(&mut raw_channel.pending);
+
+        for (timestamps, data) in pending {
+            self.write(channel_id, timestamps, &data)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_raw_messages(&mut self, chunk: &Chunk, timestamps: &[Timestamps]) -> Result<(), Error> {
+        let entity_path = chunk.entity_path();
+        let raw_channel = self.raw_channels.entry(entity_path.clone()).or_default();
+
+        let channel_id = match (raw_channel.channel_id, raw_channel.info.is_some()) {
+            (Some(channel_id), _) => Some(channel_id),
+            (None, true) => {
+                let channel_id = self.add_raw_mcap_channel(entity_path)?;
+                self.open_raw_channel(entity_path, channel_id)?;
+                Some(channel_id)
+            }
+            (None, false) => None,
+        };
+
+        for (row, timestamps) in timestamps.iter().enumerate() {
+            let Some(Ok(data)) =
+                chunk.component_mono::<components::Blob>(&McapMessage::descriptor_data(), row)
+            else {
+                continue;
+            };
+
+            if let Some(channel_id) = channel_id {
+                self.write(channel_id, *timestamps, &data.0.0)?;
+            } else {
+                self.raw_channels
+                    .entry(entity_path.clone())
+                    .or_default()
+                    .pending
+                    .push((*timestamps, data.0.0.to_vec()));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_images(
+        &mut self,
+        chunk: &Chunk,
+        timestamps: &[Timestamps],
+        buffer_descr: &re_types::ComponentDescriptor,
+        format_descr: &re_types::ComponentDescriptor,
+    ) -> Result<(), Error> {
+        let topic = chunk.entity_path().to_string();
+
+        for (row, timestamps) in timestamps.iter().enumerate() {
+            let (Some(Ok(buffer)), Some(Ok(format))) = (
+                chunk.component_mono::<components::ImageBuffer>(buffer_descr, row),
+                chunk.component_mono::<components::ImageFormat>(format_descr, row),
+            ) else {
+                continue;
+            };
+
+            let Some(encoding) = ros2_image_encoding(&format) else {
+                re_log::warn_once!(
+                    "Images of format {} cannot be written to MCAP, skipping {topic}",
+                    *format
+                );
+                continue;
+            };
+
+            let msg = Ros2Image {
+                header: header(chunk.entity_path(), *timestamps),
+                height: format.height,
+                width: format.width,
+                encoding: encoding.to_owned(),
+                is_bigendian: 0,
+                step: (buffer.0.0.len() as u32)
+                    .checked_div(format.height)
+                    .unwrap_or_default(),
+                data: Cow::Borrowed(&buffer.0.0),
+            };
+
+            self.write_ros2(&topic, &ros2_schemas::IMAGE, *timestamps, &msg)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_encoded_images(
+        &mut self,
+        chunk: &Chunk,
+        timestamps: &[Timestamps],
+    ) -> Result<(), Error> {
+        let topic = chunk.entity_path().to_string();
+
+        for (row, timestamps) in timestamps.iter().enumerate() {
+            let Some(Ok(blob)) =
+                chunk.component_mono::<components::Blob>(&EncodedImage::descriptor_blob(), row)
+            else {
+                continue;
+            };
+
+            let media_type = chunk
+                .component_mono::<components::MediaType>(
+                    &EncodedImage::descriptor_media_type(),
+                    row,
+                )
+                .and_then(Result::ok)
+                .or_else(|| components::MediaType::guess_from_data(&blob.0.0));
+            let format = match media_type.as_ref().map(|media_type| media_type.as_str()) {
+                Some(components::MediaType::JPEG) => "jpeg",
+                Some(components::MediaType::PNG) => "png",
+                _ => {
+                    re_log::warn_once!(
+                        "Only JPEG and PNG encoded images can be written to MCAP, skipping {topic}"
+                    );
+                    continue;
+                }
+            };
+
+            let msg = CompressedImage {
+                header: header(chunk.entity_path(), *timestamps),
+                format: format.to_owned(),
+                data: Cow::Borrowed(&blob.0.0),
+            };
+
+            self.write_ros2(&topic, &ros2_schemas::COMPRESSED_IMAGE, *timestamps, &msg)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_point_clouds(&mut self, chunk: &Chunk, timestamps: &[Timestamps]) -> Result<(), Error> {
+        const POINT_STEP: u32 = 3 * size_of::<f32>() as u32;
+
+        let topic = chunk.entity_path().to_string();
+
+        for (row, timestamps) in timestamps.iter().enumerate() {
+            let Some(Ok(positions)) = chunk
+                .component_batch::<components::Position3D>(&Points3D::descriptor_positions(), row)
+            else {
+                continue;
+            };
+
+            let fields = ["x", "y", "z"]
+                .into_iter()
+                .zip((0..).step_by(size_of::<f32>()))
+                .map(|(name, offset)| PointField {
+                    name: name.to_owned(),
+                    offset,
+                    datatype: PointFieldDatatype::Float32,
+                    count: 1,
+                })
+                .collect();
+
+            let msg = PointCloud2 {
+                header: header(chunk.entity_path(), *timestamps),
+                height: 1,
+                width: positions.len() as u32,
+                fields,
+                is_bigendian: false,
+                point_step: POINT_STEP,
+                row_step: POINT_STEP * positions.len() as u32,
+                data: positions
+                    .iter()
+                    .flat_map(|position| position.0.0)
+                    .flat_map(f32::to_le_bytes)
+                    .collect(),
+                is_dense: true,
+            };
+
+            self.write_ros2(&topic, &ros2_schemas::POINT_CLOUD_2, *timestamps, &msg)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_transforms(&mut self, chunk: &Chunk, timestamps: &[Timestamps]) -> Result<(), Error> {
+        let topic = if chunk.is_static() {
+            "/tf_static"
+        } else {
+            "/tf"
+        };
+
+        for (row, timestamps) in timestamps.iter().enumerate() {
+            let translation = chunk
+                .component_mono::<components::Translation3D>(
+                    &Transform3D::descriptor_translation(),
+                    row,
+                )
+                .and_then(Result::ok);
+            let quaternion = chunk
+                .component_mono::<components::RotationQuat>(
+                    &Transform3D::descriptor_quaternion(),
+                    row,
+                )
+                .and_then(Result::ok)
+                .map(|quaternion| quaternion.0.0)
+                .or_else(|| {
+                    chunk
+                        .component_mono::<components::RotationAxisAngle>(
+                            &Transform3D::descriptor_rotation_axis_angle(),
+                            row,
+                        )
+                        .and_then(Result::ok)
+                        .map(|rotation| axis_angle_to_quaternion(&rotation))
+                });
+
+            if translation.is_none() && quaternion.is_none() {
+                continue;
+            }
+
+            let [x, y, z] = translation.map_or([0.0; 3], |translation| translation.0.0);
+            let [qx, qy, qz, qw] = quaternion.unwrap_or([0.0, 0.0, 0.0, 1.0]);
+
+            let entity_path = chunk.entity_path();
+            let msg = TFMessage {
+                transforms: vec![TransformStamped {
+                    header: header(
+                        &entity_path.parent().unwrap_or_else(EntityPath::root),
+                        *timestamps,
+                    ),
+                    child_frame_id: frame_id(entity_path),
+                    transform: Transform {
+                        translation: Vector3 {
+                            x: x as f64,
+                            y: y as f64,
+                            z: z as f64,
+                        },
+                        rotation: Quaternion {
+                            x: qx as f64,
+                            y: qy as f64,
+                            z: qz as f64,
+                            w: qw as f64,
+                        },
+                    },
+                }],
+            };
+
+            self.write_ros2(topic, &ros2_schemas::TF_MESSAGE, *timestamps, &msg)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_scalars(&mut self, chunk: &Chunk, timestamps: &[Timestamps]) -> Result<(), Error> {
+        let topic = chunk.entity_path().to_string();
+
+        for (row, timestamps) in timestamps.iter().enumerate() {
+            let Some(Ok(scalars)) =
+                chunk.component_batch::<components::Scalar>(&Scalars::descriptor_scalars(), row)
+            else {
+                continue;
+            };
+
+            for (index, scalar) in scalars.iter().enumerate() {
+                let topic = if scalars.len() == 1 {
+                    topic.clone()
+                } else {
+                    format!("{topic}/{index}")
+                };
+
+                let msg = Float64 { data: scalar.0.0 };
+                self.write_ros2(&topic, &ros2_schemas::FLOAT_64, *timestamps, &msg)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // ---
+
+    fn write_ros2(
+        &mut self,
+        topic: &str,
+        schema: &ros2_schemas::Schema,
+        timestamps: Timestamps,
+        msg: &impl Serialize,
+    ) -> Result<(), Error> {
+        let channel_id =
+            if let Some(channel_id) = self.ros2_channels.get(&(topic.to_owned(), schema.name)) {
+                *channel_id
+            } else {
+                let schema_id = if let Some(schema_id) = self.ros2_schemas.get(schema.name) {
+                    *schema_id
+                } else {
+                    let schema_id = self.writer.add_schema(
+                        schema.name,
+                        ros2_schemas::ENCODING,
+                        schema.definition.as_bytes(),
+                    )?;
+                    self.ros2_schemas.insert(schema.name, schema_id);
+                    schema_id
+                };
+
+                let channel_id =
+                    self.writer
+                        .add_channel(schema_id, topic, "cdr", &BTreeMap::new())?;
+                self.ros2_channels
+                    .insert((topic.to_owned(), schema.name), channel_id);
+                channel_id
+            };
+
+        let mut data = vec![
+            0x00,
+            RepresentationIdentifier::CdrLittleEndian as u8,
+            0x00,
+            0x00,
+        ];
+        data.extend(
+            cdr_encoding::to_vec::<_, byteorder::LittleEndian>(msg)
+                .map_err(|err| Error::Other(err.into()))?,
+        );
+
+        self.write(channel_id, timestamps, &data)
+    }
+
+    fn write(&mut self, channel_id: u16, timestamps: Timestamps, data: &[u8]) -> Result<(), Error> {
+        let sequence = self.sequences.entry(channel_id).or_default();
+        *sequence = sequence.wrapping_add(1);
+
+        self.writer.write_to_known_channel(
+            &mcap::records::MessageHeader {
+                channel_id,
+                sequence: *sequence,
+                log_time: timestamps.log_time,
+                publish_time: timestamps.publish_time,
+            },
+            data,
+        )?;
+
+        Ok(())
+    }
+}
+
+fn row_timestamps(chunk: &Chunk) -> Vec<Timestamps> {
+    let times = |timeline: TimelineName| {
+        chunk
+            .timelines()
+            .get(&timeline)
+            .map(|time_column| time_column.times_raw())
+    };
+    let log_times = times(TimelineName::log_time());
+    let publish_times = times(TimelineName::new("publish_time"));
+
+    chunk
+        .row_ids()
+        .enumerate()
+        .map(|(row, row_id)| {
+            let log_time = log_times.map_or(row_id.as_tuid().nanos_since_epoch(), |times| {
+                times[row].max(0) as u64
+            });
+            let publish_time = publish_times.map_or(log_time, |times| times[row].max(0) as u64);
+
+            Timestamps {
+                log_time,
+                publish_time,
+            }
+        })
+        .collect()
+}
+
+/// Entity paths are used as frame ids, without their leading slash.
+fn frame_id(entity_path: &EntityPath) -> String {
+    entity_path.to_string().trim_start_matches('/').to_owned()
+}
+
+fn header(entity_path: &EntityPath, timestamps: Timestamps) -> Header {
+    let nanos = timestamps.log_time;
+    Header {
+        stamp: Time {
+            sec: (nanos / 1_000_000_000) as i32,
+            nanosec: (nanos % 1_000_000_000) as u32,
+        },
+        frame_id: frame_id(entity_path),
+    }
+}
+
+fn ros2_image_encoding(format: &components::ImageFormat) -> Option<&'static str> {
+    if format.pixel_format.is_some() {
+        return None;
+    }
+
+    Some(
+        match (
+            format.color_model.unwrap_or(ColorModel::L),
+            format.channel_datatype?,
+        ) {
+            (ColorModel::L, ChannelDatatype::U8) => "mono8",
+            (ColorModel::L, ChannelDatatype::U16) => "mono16",
+            (ColorModel::L, ChannelDatatype::I8) => "8SC1",
+            (ColorModel::L, ChannelDatatype::I16) => "16SC1",
+            (ColorModel::L, ChannelDatatype::I32) => "32SC1",
+            (ColorModel::L, ChannelDatatype::F32) => "32FC1",
+            (ColorModel::L, ChannelDatatype::F64) => "64FC1",
+            (ColorModel::RGB, ChannelDatatype::U8) => "rgb8",
+            (ColorModel::RGB, ChannelDatatype::U16) => "rgb16",
+            (ColorModel::RGBA, ChannelDatatype::U8) => "rgba8",
+            (ColorModel::RGBA, ChannelDatatype::U16) => "rgba16",
+            (ColorModel::BGR, ChannelDatatype::U8) => "bgr8",
+            (ColorModel::BGR, ChannelDatatype::U16) => "bgr16",
+            (ColorModel::BGRA, ChannelDatatype::U8) => "bgra8",
+            (ColorModel::BGRA, ChannelDatatype::U16) => "bgra16",
+            _ => return None,
+        },
+    )
+}
+
+/// Returns an `xyzw` quaternion.
+fn axis_angle_to_quaternion(rotation: &components::RotationAxisAngle) -> [f32; 4] {
+    let [x, y, z] = rotation.axis.0;
+    let norm = (x * x + y * y + z * z).sqrt();
+    if norm == 0.0 {
+        return [0.0, 0.0, 0.0, 1.0];
+    }
+
+    let half_angle = rotation.angle.radians / 2.0;
+    let scale = half_angle.sin() / norm;
+    [x * scale, y * scale, z * scale, half_angle.cos()]
+}
+
+/// The `ros2msg` definitions of the messages we write, including their dependencies.
+mod ros2_schemas {
+    pub const ENCODING: &str = "ros2msg";
+
+    pub struct Schema {
+        pub name: &'static str,
+        pub definition: &'static str,
+    }
+
+    macro_rules! dependency {
+        ($name:literal, $definition:literal) => {
+            concat!(
+                "\n================================================================================\nMSG: ",
+                $name,
+                "\n",
+                $definition
+            )
+        };
+    }
+
+    macro_rules! header {
+        () => {
+            concat!(
+                dependency!(
+                    "std_msgs/Header",
+                    "builtin_interfaces/Time stamp\nstring frame_id\n"
+                ),
+                dependency!("builtin_interfaces/Time", "int32 sec\nuint32 nanosec\n"),
+            )
+        };
+    }
+
+    pub const IMAGE: Schema = Schema {
+        name: "sensor_msgs/msg/Image",
+        definition: concat!(
+            "std_msgs/Header header\nuint32 height\nuint32 width\nstring encoding\nuint8 is_bigendian\nuint32 step\nuint8[] data\n",
+            header!(),
+        ),
+    };
+
+    pub const COMPRESSED_IMAGE: Schema = Schema {
+        name: "sensor_msgs/msg/CompressedImage",
+        definition: concat!(
+            "std_msgs/Header header\nstring format\nuint8[] data\n",
+            header!(),
+        ),
+    };
+
+    pub const POINT_CLOUD_2: Schema = Schema {
+        name: "sensor_msgs/msg/PointCloud2",
+        definition: concat!(
+            "std_msgs/Header header\nuint32 height\nuint32 width\nsensor_msgs/PointField[] fields\nbool is_bigendian\nuint32 point_step\nuint32 row_step\nuint8[] data\nbool is_dense\n",
+            header!(),
+            dependency!(
+                "sensor_msgs/PointField",
+                "uint8 INT8=1\nuint8 UINT8=2\nuint8 INT16=3\nuint8 UINT16=4\nuint8 INT32=5\nuint8 UINT32=6\nuint8 FLOAT32=7\nuint8 FLOAT64=8\nstring name\nuint32 offset\nuint8 datatype\nuint32 count\n"
+            ),
+        ),
+    };
+
+    pub const TF_MESSAGE: Schema = Schema {
+        name: "tf2_msgs/msg/TFMessage",
+        definition: concat!(
+            "geometry_msgs/TransformStamped[] transforms\n",
+            dependency!(
+                "geometry_msgs/TransformStamped",
+                "std_msgs/Header header\nstring child_frame_id\ngeometry_msgs/Transform transform\n"
+            ),
+            header!(),
+            dependency!(
+                "geometry_msgs/Transform",
+                "geometry_msgs/Vector3 translation\ngeometry_msgs/Quaternion rotation\n"
+            ),
+            dependency!("geometry_msgs/Vector3", "float64 x\nfloat64 y\nfloat64 z\n"),
+            dependency!(
+                "geometry_msgs/Quaternion",
+                "float64 x\nfloat64 y\nfloat64 z\nfloat64 w\n"
+            ),
+        ),
+    };
+
+    pub const FLOAT_64: Schema = Schema {
+        name: "std_msgs/msg/Float64",
+        definition: "float64 data\n",
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use re_chunk::{RowId, TimePoint, Timeline};
+    use re_types::{AsComponents as _, components::KeyValuePairs};
+
+    use super::*;
+
+    #[test]
+    fn raw_roundtrip_and_scalars() -> anyhow::Result<()> {
+        let log_time = Timeline::new_timestamp("log_time");
+        let at = |nanos: i64| TimePoint::default().with(log_time, nanos);
+
+        let chunks = [
+            // Messages may come before their channel.
+            Chunk::builder("/camera/raw")
+                .with_archetype(RowId::new(), at(1_000), &McapMessage::new(b"abc".to_vec()))
+                .with_archetype(RowId::new(), at(2_000), &McapMessage::new(b"def".to_vec()))
+                .build()?,
+            Chunk::builder("/camera/raw")
+                .with_serialized_batches(
+                    RowId::new(),
+                    TimePoint::STATIC,
+                    McapChannel::new(1, "/camera/raw", "json")
+                        .with_metadata(KeyValuePairs::default())
+                        .as_serialized_batches()
+                        .into_iter()
+                        .chain(
+                            McapSchema::new(1, "my_schema", "jsonschema", b"{}".to_vec())
+                                .as_serialized_batches(),
+                        )
+                        .collect(),
+                )
+                .build()?,
+            Chunk::builder("/robot/speed")
+                .with_archetype(RowId::new(), at(3_000), &Scalars::new([4.2]))
+                .build()?,
+        ];
+
+        let mut bytes = Cursor::new(Vec::new());
+        let mut writer = McapWriter::new(&mut bytes)?;
+        for chunk in &chunks {
+            writer.add_chunk(chunk)?;
+        }
+        writer.finish()?;
+        let bytes = bytes.into_inner();
+
+        let messages = mcap::MessageStream::new(&bytes)?.collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(3, messages.len());
+
+        let raw = messages
+            .iter()
+            .filter(|msg| msg.channel.topic == "/camera/raw")
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![(1_000, b"abc".as_slice()), (2_000, b"def".as_slice())],
+            raw.iter()
+                .map(|msg| (msg.log_time, &*msg.data))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!("json", raw[0].channel.message_encoding);
+        let schema = raw[0].channel.schema.as_ref().expect("schema");
+        assert_eq!(
+            ("my_schema", "jsonschema"),
+            (schema.name.as_str(), schema.encoding.as_str())
+        );
+        assert_eq!(b"{}", &*schema.data);
+
+        let speed = messages
+            .iter()
+            .find(|msg| msg.channel.topic == "/robot/speed")
+            .expect("speed");
+        assert_eq!(3_000, speed.log_time);
+        let decoded: Float64 = crate::cdr::try_decode_message(&speed.data)?;
+        assert_eq!(Float64 { data: 4.2 }, decoded);
+
+        Ok(())
+    }
+}
@@ -2,10 +2,11 @@ use jiff::SignedDuration;
 use jiff::fmt::friendly::{FractionalUnit, SpanPrinter};
 
 use re_byte_size::SizeBytes as _;
-use re_chunk_store::ChunkStoreConfig;
+use re_chunk_store::{ChunkStoreConfig, LatestAtQuery};
 use re_entity_db::EntityDb;
-use re_log_types::StoreKind;
+use re_log_types::{EntityPath, StoreKind, TimelineName};
 use re_smart_channel::SmartChannelSource;
+use re_types::reflection::ComponentDescriptorExt as _;
 use re_ui::UiExt as _;
 use re_viewer_context::{UiLayout, ViewerContext};
 
@@ -135,6 +136,8 @@ impl crate::DataUi for EntityDb {
                     chunk_max_bytes,
                     chunk_max_rows,
                     chunk_max_rows_if_unsorted,
+                    spill_directory: _,
+                    compute_statistics: _,
                 } = self.storage_engine().store().config();
 
                 ui.grid_left_hand_label("Compaction");
@@ -187,6 +190,10 @@ impl crate::DataUi for EntityDb {
                 data_source_button_ui(ctx, ui, data_source);
                 ui.end_row();
             }
+
+            if self.store_kind() == StoreKind::Recording {
+                environment_properties_ui(ctx, ui, self);
+            }
         });
 
         let hub = ctx.storage_context.hub;
@@ -245,3 +252,36 @@ impl crate::DataUi for EntityDb {
         }
     }
 }
+
+/// Shows the properties sent with `RecordingStreamBuilder::with_environment_properties`, if any,
+/// as rows of the surrounding grid.
+fn environment_properties_ui(ctx: &ViewerContext<'_>, ui: &mut egui::Ui, entity_db: &EntityDb) {
+    let entity_path = EntityPath::environment_properties();
+
+    let Some(components) = entity_db
+        .storage_engine()
+        .store()
+        .all_components_for_entity_sorted(&entity_path)
+    else {
+        return;
+    };
+
+    let results = entity_db.latest_at(
+        &LatestAtQuery::latest(TimelineName::log_tick()),
+        &entity_path,
+        &components,
+    );
+
+    for component_descr in &components {
+        if let Some(array) = results.component_batch_raw(component_descr) {
+            ui.grid_left_hand_label(component_descr.archetype_field_name());
+            re_arrow_ui::arrow_ui(
+                ui,
+                UiLayout::List,
+                ctx.app_options().timestamp_format,
+                array.as_ref(),
+            );
+            ui.end_row();
+        }
+    }
+}
@@ -541,7 +541,10 @@ pub fn build_density_graph<'a>(
         }
 
         for (chunk, time_range, num_events_in_chunk) in chunk_ranges {
+            // NOTE: Chunks that were spilled to disk only retain their index columns, which are not
+            // enough to count individual events.
             let should_render_individual_events = can_render_individual_events
+                && chunk.num_components() > 0
                 && if chunk.is_timeline_sorted(timeline) {
                     num_events_in_chunk < config.max_events_in_sorted_chunk
                 } else {
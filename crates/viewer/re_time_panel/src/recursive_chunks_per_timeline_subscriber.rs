@@ -12,6 +12,7 @@ use re_log_types::{AbsoluteTimeRange, EntityPath, EntityPathHash, StoreId, Timel
 /// Cached information about a chunk in the context of a given timeline.
 #[derive(Debug, Clone)]
 pub struct ChunkTimelineInfo {
+    /// Only contains the index columns if the chunk was spilled to disk.
     pub chunk: Arc<Chunk>,
     pub num_events: u64,
     pub resolved_time_range: AbsoluteTimeRange,
@@ -104,6 +105,36 @@ impl PathRecursiveChunksPerTimelineStoreSubscriber {
         }
     }
 
+    /// Only keeps the index columns of a chunk that was offloaded to disk, so that its data can
+    /// actually be freed while it still shows up in the time panel.
+    fn spill_chunk(&mut self, chunk: &Chunk) {
+        re_tracing::profile_function!();
+
+        let index_only_chunk = Arc::new(chunk.clone().components_removed());
+
+        #[expect(clippy::iter_over_hash_type)]
+        for timeline in chunk.timelines().keys() {
+            let Some(chunks_per_entities) = self.chunks_per_timeline_per_entity.get_mut(timeline)
+            else {
+                continue;
+            };
+
+            let mut next_path = Some(chunk.entity_path().clone());
+            while let Some(path) = next_path {
+                if let Some(chunk_info) =
+                    chunks_per_entities
+                        .get_mut(&path.hash())
+                        .and_then(|chunks_per_entity| {
+                            chunks_per_entity.recursive_chunks_info.get_mut(&chunk.id())
+                        })
+                {
+                    chunk_info.chunk = Arc::clone(&index_only_chunk);
+                }
+                next_path = path.parent();
+            }
+        }
+    }
+
     fn remove_chunk(&mut self, chunk: &Chunk) {
         re_tracing::profile_function!();
 
@@ -169,6 +200,9 @@ impl PerStoreChunkSubscriber for PathRecursiveChunksPerTimelineStoreSubscriber {
                     re_chunk_store::ChunkStoreDiffKind::Deletion => {
                         self.remove_chunk(&event.chunk);
                     }
+                    re_chunk_store::ChunkStoreDiffKind::Spill => {
+                        self.spill_chunk(&event.chunk);
+                    }
                 }
             }
         }
@@ -1,11 +1,15 @@
 use std::collections::HashSet;
 
-use re_chunk_store::ColumnDescriptor;
-use re_log_types::{AbsoluteTimeRange, Timeline, TimelineName};
+use itertools::Itertools as _;
+
+use re_chunk_store::{ColumnDescriptor, SparseFillStrategy};
+use re_dataframe::{ContentsRule, IndexRangeSpec, QuerySpec};
+use re_log_types::{AbsoluteTimeRange, EntityPathSubs, TimeType, Timeline, TimelineName};
 use re_sorbet::{ColumnSelector, ComponentColumnSelector};
 use re_types::blueprint::archetypes::DataframeQuery;
 use re_types::blueprint::{components, datatypes};
 use re_viewer_context::{ViewSystemExecutionError, ViewerContext};
+use re_viewport_blueprint::ViewBlueprint;
 
 use crate::dataframe_ui::HideColumnAction;
 use crate::view_query::Query;
@@ -188,6 +192,164 @@ impl Query {
         );
     }
 
+    /// The view's contents and query, expressed using the textual query syntax.
+    ///
+    /// See [`QuerySpec`].
+    pub fn query_spec(
+        &self,
+        ctx: &ViewerContext<'_>,
+        view_blueprint: &ViewBlueprint,
+    ) -> Result<QuerySpec, ViewSystemExecutionError> {
+        let index_type = self
+            .timeline(ctx)?
+            .map_or(TimeType::Sequence, |timeline| timeline.typ());
+
+        Ok(QuerySpec {
+            contents: ContentsRule::from_entity_path_filter(
+                &view_blueprint.contents.entity_path_filter().unresolved(),
+            ),
+            filtered_index: Some(self.timeline_name(ctx)?),
+            filtered_index_range: Some(self.filter_by_range()?)
+                .filter(|range| *range != AbsoluteTimeRange::EVERYTHING)
+                .map(|range| IndexRangeSpec::from_range(&range, index_type)),
+            secondary_indices: Vec::new(),
+            filtered_is_not_null: self.filter_is_not_null()?,
+            filter: None,
+            sparse_fill_strategy: if self.latest_at_enabled()? {
+                SparseFillStrategy::LatestAtGlobal
+            } else {
+                SparseFillStrategy::None
+            },
+            resampling: None,
+        })
+    }
+
+    /// Applies a query expressed using the textual query syntax to the view.
+    ///
+    /// Fails without modifying anything if the query uses features that the dataframe view
+    /// doesn't support.
+    pub fn save_query_spec(
+        &self,
+        ctx: &ViewerContext<'_>,
+        view_blueprint: &ViewBlueprint,
+        spec: &QuerySpec,
+    ) -> Result<(), String> {
+        let QuerySpec {
+            contents,
+            filtered_index,
+            filtered_index_range,
+            secondary_indices,
+            filtered_is_not_null,
+            filter,
+            sparse_fill_strategy,
+            resampling,
+        } = spec;
+
+        if let Some(rule) = contents.iter().find(|rule| rule.components.is_some()) {
+            return Err(format!(
+                "Component lists are not supported by the dataframe view: `{rule}`"
+            ));
+        }
+
+        if !secondary_indices.is_empty() {
+            return Err(format!(
+                "Composite indices are not supported by the dataframe view: `index={}`",
+                filtered_index
+                    .iter()
+                    .chain(secondary_indices.iter().map(|secondary| &secondary.index))
+                    .join(",")
+            ));
+        }
+
+        if let Some(filter) = filter {
+            return Err(format!(
+                "Row filters are not supported by the dataframe view: `filter=[{filter}]`"
+            ));
+        }
+
+        if let Some(resampling) = resampling {
+            return Err(format!(
+                "Resampling is not supported by the dataframe view: `resample={resampling}`"
+            ));
+        }
+
+        let latest_at = match sparse_fill_strategy {
+            SparseFillStrategy::None => false,
+            SparseFillStrategy::LatestAtGlobal => true,
+            strategy => {
+                return Err(format!(
+                    "This fill strategy is not supported by the dataframe view: {strategy}"
+                ));
+            }
+        };
+
+        // The range is resolved according to the type of the timeline it applies to.
+        let timeline_name = self.timeline_name(ctx).map_err(|err| err.to_string())?;
+        let index_type = ctx
+            .recording()
+            .timelines()
+            .get(filtered_index.as_ref().unwrap_or(&timeline_name))
+            .map_or(TimeType::Sequence, |timeline| timeline.typ());
+        let filtered_index_range = filtered_index_range
+            .as_ref()
+            .map(|range| range.resolve(index_type))
+            .transpose()
+            .map_err(|err| err.to_string())?;
+
+        if let Some(filter) = spec.entity_path_filter() {
+            let subst_env = EntityPathSubs::new_with_origin(&view_blueprint.space_origin);
+            let filter = filter.resolve_forgiving(&subst_env);
+            if &filter != view_blueprint.contents.entity_path_filter() {
+                view_blueprint.contents.set_entity_path_filter(ctx, filter);
+            }
+        }
+
+        // NOTE: Saving the timeline resets the range, so this must come first.
+        if let Some(filtered_index) = filtered_index
+            && *filtered_index != timeline_name
+        {
+            self.save_timeline_name(ctx, filtered_index);
+        }
+
+        self.save_filter_by_range(
+            ctx,
+            filtered_index_range.unwrap_or(AbsoluteTimeRange::EVERYTHING),
+        );
+
+        match filtered_is_not_null {
+            Some(column) => self.save_filter_is_not_null(
+                ctx,
+                &components::FilterIsNotNull::new(
+                    true,
+                    &column.entity_path,
+                    column.component.clone(),
+                ),
+            ),
+
+            None => {
+                // Keep the column around, so it's still there when re-enabling the filter.
+                if let Some(filter) = self
+                    .filter_is_not_null_raw()
+                    .map_err(|err| err.to_string())?
+                    .filter(|filter| filter.active())
+                {
+                    self.save_filter_is_not_null(
+                        ctx,
+                        &components::FilterIsNotNull::new(
+                            false,
+                            &filter.entity_path(),
+                            filter.column_selector().component,
+                        ),
+                    );
+                }
+            }
+        }
+
+        self.save_latest_at_enabled(ctx, latest_at);
+
+        Ok(())
+    }
+
     /// Given some view columns, list the columns that should be visible (aka "selected columns"),
     /// according to the blueprint.
     ///
@@ -50,6 +50,8 @@ impl Query {
         self.column_visibility_ui(ctx, ui, timeline.as_ref(), view_columns)?;
         ui.separator();
         self.latest_at_ui(ctx, ui)?;
+        ui.separator();
+        self.query_syntax_ui(ctx, ui, view_id)?;
 
         Ok(())
     }
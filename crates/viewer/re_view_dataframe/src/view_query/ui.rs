@@ -10,6 +10,7 @@ use re_types::blueprint::components;
 use re_ui::list_item::ListItemContentButtonsExt as _;
 use re_ui::{TimeDragValue, UiExt as _, list_item};
 use re_viewer_context::{ViewId, ViewSystemExecutionError, ViewerContext};
+use re_viewport_blueprint::ViewBlueprint;
 use std::collections::{BTreeSet, HashSet};
 
 // UI implementation
@@ -522,6 +523,75 @@ impl Query {
 
         Ok(())
     }
+
+    /// Shows the whole query using the textual query syntax, so that it can be copied to/from the
+    /// CLI and the SDKs.
+    pub(super) fn query_syntax_ui(
+        &self,
+        ctx: &ViewerContext<'_>,
+        ui: &mut egui::Ui,
+        view_id: ViewId,
+    ) -> Result<(), ViewSystemExecutionError> {
+        let Some(view_blueprint) =
+            ViewBlueprint::try_from_db(view_id, ctx.blueprint_db(), ctx.blueprint_query)
+        else {
+            return Ok(());
+        };
+
+        let query = self.query_spec(ctx, &view_blueprint)?.to_string();
+
+        ui.horizontal(|ui| {
+            ui.label("Query:");
+            if ui
+                .small_icon_button(&re_ui::icons::COPY, "Copy query")
+                .clicked()
+            {
+                ui.ctx().copy_text(query.clone());
+            }
+        });
+
+        // We store the string we are temporarily editing in the `Ui`'s temporary data storage.
+        // This is so it can contain an invalid query while the user edits it, and it's only
+        // applied when they press enter.
+        let query_text_id = ui.id().with("dataframe_view_query_text");
+        let query_error_id = query_text_id.with("error");
+
+        let mut query_text = ui.data_mut(|data| {
+            data.get_temp_mut_or_insert_with::<String>(query_text_id, || query.clone())
+                .clone()
+        });
+
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut query_text)
+                .code_editor()
+                .desired_width(ui.available_width()),
+        );
+
+        if response.has_focus() {
+            ui.data_mut(|data| data.insert_temp::<String>(query_text_id, query_text));
+        } else {
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                let result = query_text
+                    .parse::<re_dataframe::QuerySpec>()
+                    .map_err(|err| err.to_string())
+                    .and_then(|spec| self.save_query_spec(ctx, &view_blueprint, &spec));
+
+                match result {
+                    Ok(()) => ui.data_mut(|data| data.remove::<String>(query_error_id)),
+                    Err(err) => ui.data_mut(|data| data.insert_temp(query_error_id, err)),
+                }
+            }
+
+            // Reconstruct it from the blueprint next frame.
+            ui.data_mut(|data| data.remove::<String>(query_text_id));
+        }
+
+        if let Some(err) = ui.data(|data| data.get_temp::<String>(query_error_id)) {
+            ui.error_label(err);
+        }
+
+        Ok(())
+    }
 }
 
 /// Gather all entities that can meaningfully be used as point-of-view for this view.
@@ -2733,6 +2733,8 @@ impl eframe::App for App {
 
         self.check_keyboard_shortcuts(egui_ctx);
 
+        store_hub.purge_expired_data(&self.startup_options.retention);
+        store_hub.compact_recordings(re_entity_db::DEFAULT_COMPACTION_TIME_BUDGET);
         self.purge_memory_if_needed(&mut store_hub);
 
         // In some (rare) circumstances we run two egui passes in a single frame.
@@ -7,6 +7,10 @@ pub struct StartupOptions {
     /// When the total process RAM reaches this limit, we GC old data.
     pub memory_limit: re_memory::MemoryLimit,
 
+    /// Recording data that falls outside of these retention policies is dropped as soon as it
+    /// expires, regardless of [`Self::memory_limit`].
+    pub retention: Vec<re_chunk_store::RetentionPolicy>,
+
     pub persist_state: bool,
 
     /// Whether or not the app is running in the context of a Jupyter Notebook.
@@ -132,6 +136,7 @@ impl Default for StartupOptions {
     fn default() -> Self {
         Self {
             memory_limit: re_memory::MemoryLimit::from_fraction_of_total(0.75),
+            retention: Vec::new(),
             persist_state: true,
             is_in_notebook: false,
 
@@ -695,6 +695,10 @@ impl Cache for VideoStreamCache {
                             }
                         }
                     }
+                    re_chunk_store::ChunkStoreDiffKind::Spill => {
+                        // The decoder can't deal with gaps in the middle of the stream, so the
+                        // samples of spilled chunks are kept around.
+                    }
                 }
             }
         }
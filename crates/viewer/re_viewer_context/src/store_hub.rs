@@ -7,7 +7,7 @@ use nohash_hasher::IntMap;
 
 use re_chunk_store::{
     ChunkStoreConfig, ChunkStoreGeneration, ChunkStoreStats, GarbageCollectionOptions,
-    GarbageCollectionTarget,
+    GarbageCollectionTarget, RetentionPolicy,
 };
 use re_entity_db::{EntityDb, StoreBundle};
 use re_global_context::RecordingOrTable;
@@ -66,6 +66,9 @@ pub struct StoreHub {
 
     /// The [`ChunkStoreGeneration`] from when the [`EntityDb`] was last garbage collected
     blueprint_last_gc: HashMap<StoreId, ChunkStoreGeneration>,
+
+    /// The [`ChunkStoreGeneration`] from when the recording's expired data was last purged
+    recording_last_retention: HashMap<StoreId, ChunkStoreGeneration>,
 }
 
 /// Load a blueprint from persisted storage, e.g. disk.
@@ -176,6 +179,7 @@ impl StoreHub {
             caches_per_recording: Default::default(),
             blueprint_last_save: Default::default(),
             blueprint_last_gc: Default::default(),
+            recording_last_retention: Default::default(),
 
             table_stores: TableStores::default(),
         }
@@ -838,6 +842,7 @@ impl StoreHub {
                     protect_latest: 1, // keep the latest instance of everything, or we will forget things that haven't changed in a while
                     time_budget: re_entity_db::DEFAULT_GC_TIME_BUDGET,
                     protected_time_ranges,
+                    retention: Vec::new(),
                 });
                 if !store_events.is_empty() {
                     re_log::debug!("Garbage-collected blueprint store");
@@ -852,6 +857,60 @@ impl StoreHub {
         }
     }
 
+    /// Forget all the recording data that has expired according to the given retention policies.
+    ///
+    /// Recordings that haven't changed since the last call are skipped.
+    pub fn purge_expired_data(&mut self, retention: &[RetentionPolicy]) {
+        re_tracing::profile_function!();
+
+        if retention.is_empty() {
+            return;
+        }
+
+        for entity_db in self.store_bundle.entity_dbs_mut() {
+            if entity_db.store_kind() != StoreKind::Recording {
+                continue;
+            }
+
+            let store_id = entity_db.store_id().clone();
+            if self.recording_last_retention.get(&store_id) == Some(&entity_db.generation()) {
+                continue; // no change since last purge
+            }
+
+            let store_events = entity_db.purge_expired(retention);
+            if !store_events.is_empty() {
+                re_log::debug!(num_events = store_events.len(), "Purged expired data");
+                if let Some(caches) = self.caches_per_recording.get_mut(&store_id) {
+                    caches.on_store_events(&store_events);
+                }
+            }
+
+            self.recording_last_retention
+                .insert(store_id, entity_db.generation());
+        }
+    }
+
+    /// Incrementally re-compacts the data of all recordings, within the given time budget.
+    ///
+    /// See [`EntityDb::compact`].
+    pub fn compact_recordings(&mut self, time_budget: std::time::Duration) {
+        re_tracing::profile_function!();
+
+        for entity_db in self.store_bundle.entity_dbs_mut() {
+            if entity_db.store_kind() != StoreKind::Recording {
+                continue;
+            }
+
+            let store_events = entity_db.compact(time_budget);
+            if !store_events.is_empty() {
+                re_log::trace!(num_events = store_events.len(), "Re-compacted data");
+                if let Some(caches) = self.caches_per_recording.get_mut(entity_db.store_id()) {
+                    caches.on_store_events(&store_events);
+                }
+            }
+        }
+    }
+
     /// See [`crate::Caches::begin_frame`].
     pub fn begin_frame_caches(&mut self) {
         self.caches_per_recording.retain(|store_id, caches| {
@@ -957,6 +1016,7 @@ impl StoreHub {
             caches_per_recording,
             blueprint_last_save: _,
             blueprint_last_gc: _,
+            recording_last_retention: _,
         } = self;
 
         let mut store_stats = BTreeMap::new();
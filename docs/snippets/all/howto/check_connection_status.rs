@@ -19,10 +19,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         });
 
         if let Ok(status) = rx.recv_timeout(std::time::Duration::from_secs(1)) {
-            println!("Connection status: {status:?}");
+            println!(
+                "Connection status: {:?}, {} messages waiting to be sent",
+                status.connection, status.backlog.num_messages
+            );
 
             if matches!(
-                status,
+                status.connection,
                 rerun::sink::GrpcSinkConnectionState::Disconnected(_)
             ) {
                 println!("Connection lost, exiting");
@@ -398,6 +398,39 @@ class RecordingView:
 
         """
 
+    def resample(self, interval: int, aggregation: str = "last") -> RecordingView:
+        """
+        Resample the view at a fixed rate on the index, aggregating the values within each bucket.
+
+        The index is split into contiguous buckets of `interval` index units (i64 sequence counts
+        or nanoseconds), aligned on multiples of `interval`. The output view will contain exactly
+        one row per bucket, and the index value of each row is the start of its bucket.
+
+        Only floating-point scalar components can be aggregated using `mean`, `min` and `max`: all
+        other components (e.g. colors, class IDs or positions) use the `last` value of the bucket
+        instead. Counting turns every component column into a column of counts.
+
+        Intervals yielding more than 10 million buckets are rejected: the view will be empty.
+
+        This has no effect if [`.using_index_values()`][rerun.dataframe.RecordingView.using_index_values]
+        is used.
+
+        Parameters
+        ----------
+        interval : int
+            The size of each bucket, in index units. Must be strictly positive.
+        aggregation : str
+            How to combine the values within a bucket: one of `mean`, `min`, `max`, `last` or `count`.
+
+        Returns
+        -------
+        RecordingView
+            A new view containing one row per bucket.
+
+            The original view will not be modified.
+
+        """
+
     def fill_latest_at(self) -> RecordingView:
         """
         Populate any null values in a row with the latest valid data according to the index.
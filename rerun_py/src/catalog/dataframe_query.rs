@@ -72,9 +72,12 @@ impl PyDataframeQueryView {
                 },
                 filtered_index: index.map(Into::into),
                 filtered_index_range: None,
+                secondary_indices: Vec::new(),
                 filtered_index_values: None,
                 using_index_values: None,
+                resampling: None,
                 filtered_is_not_null: None,
+                filter: None,
                 sparse_fill_strategy: SparseFillStrategy::None,
                 selection: None,
             },
@@ -228,9 +228,12 @@ impl PyRecording {
             },
             filtered_index,
             filtered_index_range: None,
+            secondary_indices: Vec::new(),
             filtered_index_values: None,
             using_index_values: None,
+            resampling: None,
             filtered_is_not_null: None,
+            filter: None,
             sparse_fill_strategy: SparseFillStrategy::None,
             selection: None,
         };
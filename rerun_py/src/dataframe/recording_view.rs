@@ -7,7 +7,7 @@ use pyo3::prelude::{PyAnyMethods as _, PyTupleMethods as _};
 use pyo3::types::PyTuple;
 use pyo3::{Bound, PyRef, PyResult, Python, pyclass, pymethods};
 
-use re_chunk_store::{QueryExpression, SparseFillStrategy};
+use re_chunk_store::{AggregationFunction, QueryExpression, Resampling, SparseFillStrategy};
 use re_log_types::AbsoluteTimeRange;
 use re_sorbet::{ColumnDescriptor, ColumnSelector};
 
@@ -526,6 +526,65 @@ impl PyRecordingView {
         })
     }
 
+    #[allow(rustdoc::private_doc_tests)]
+    /// Resample the view at a fixed rate on the index, aggregating the values within each bucket.
+    ///
+    /// The index is split into contiguous buckets of `interval` index units (i64 sequence counts
+    /// or nanoseconds), aligned on multiples of `interval`. The output view will contain exactly
+    /// one row per bucket, and the index value of each row is the start of its bucket.
+    ///
+    /// Only floating-point scalar components can be aggregated using `mean`, `min` and `max`: all
+    /// other components (e.g. colors, class IDs or positions) use the `last` value of the bucket
+    /// instead. Counting turns every component column into a column of counts.
+    ///
+    /// Intervals yielding more than 10 million buckets are rejected: the view will be empty.
+    ///
+    /// This has no effect if [`.using_index_values()`][rerun.dataframe.RecordingView.using_index_values]
+    /// is used.
+    ///
+    /// Parameters
+    /// ----------
+    /// interval : int
+    ///     The size of each bucket, in index units. Must be strictly positive.
+    /// aggregation : str
+    ///     How to combine the values within a bucket: one of `mean`, `min`, `max`, `last` or `count`.
+    ///
+    /// Returns
+    /// -------
+    /// RecordingView
+    ///     A new view containing one row per bucket.
+    ///
+    ///     The original view will not be modified.
+    #[pyo3(signature = (interval, aggregation = "last"))]
+    fn resample(&self, interval: u64, aggregation: &str) -> PyResult<Self> {
+        let interval = std::num::NonZeroU64::new(interval)
+            .ok_or_else(|| PyValueError::new_err("interval must be strictly positive"))?;
+
+        let aggregation = match aggregation {
+            "mean" => AggregationFunction::Mean,
+            "min" => AggregationFunction::Min,
+            "max" => AggregationFunction::Max,
+            "last" => AggregationFunction::Last,
+            "count" => AggregationFunction::Count,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown aggregation {aggregation:?}, expected one of: mean, min, max, last, count"
+                )));
+            }
+        };
+
+        let mut query_expression = self.query_expression.clone();
+        query_expression.resampling = Some(Resampling {
+            interval,
+            aggregation,
+        });
+
+        Ok(Self {
+            recording: self.recording.clone(),
+            query_expression,
+        })
+    }
+
     #[allow(rustdoc::private_doc_tests)]
     /// Populate any null values in a row with the latest valid data according to the index.
     ///
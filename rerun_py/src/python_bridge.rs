@@ -875,6 +875,8 @@ fn spawn(
         executable_path,
         extra_args,
         extra_env,
+        // Only relevant when connecting, which happens separately.
+        shared_memory: false,
     };
 
     re_sdk::spawn(&spawn_opts).map_err(|err| PyRuntimeError::new_err(err.to_string()))